@@ -15,7 +15,7 @@ fn main() -> Result<(), meadow::Error> {
         use meadow::host::generate_certs;
         use meadow::host::quic::QuicCertGenConfig;
 
-        generate_certs(QuicCertGenConfig::default());
+        generate_certs(QuicCertGenConfig::default())?;
     }
 
     // Configure the Host with logging
@@ -30,13 +30,11 @@ fn main() -> Result<(), meadow::Error> {
             .path(format!("./logs/{}", stamp))
             // If we wanted to keep the logs, we'd make this `false`
             .temporary(true);
-        let mut config = HostConfig::default().with_sled_config(sled_cfg);
+        let config = HostConfig::default().with_sled_config(sled_cfg);
         #[cfg(feature = "quic")]
-        {
-            config = config
-                .with_udp_config(None)
-                .with_quic_config(Some(QuicConfig::default()))
-        }
+        let config = config
+            .with_udp_config(None)
+            .with_quic_config(Some(QuicConfig::default()));
         config.build()?
     };
 
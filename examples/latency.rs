@@ -0,0 +1,80 @@
+use meadow::prelude::*;
+use rand::Rng;
+
+/// Messages published on each transport for each payload size
+const MSGS: usize = 1000;
+/// Payload sizes, in number of `f32`s, which fit within the default buffer sizes
+const SIZES: [usize; 3] = [1, 32, 128];
+
+/// Publish and request `MSGS` messages with `len` random `f32`s between two Nodes, returning the
+/// latencies the requester received them with
+macro_rules! measure {
+    ($interface:ty, $network_cfg:expr, $len:expr) => {{
+        let tracker = LatencyTracker::new();
+        let topic = format!("latency_{}", $len);
+        let node = || {
+            NodeConfig::<Blocking, $interface, Vec<f32>>::new(topic.clone())
+                .with_config($network_cfg)
+                .with_latency_tracker(tracker.clone())
+                .build()?
+                .activate()
+        };
+        let (publisher, requester) = (node()?, node()?);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..MSGS {
+            let payload: Vec<f32> = (0..$len).map(|_| rng.gen()).collect();
+            publisher.publish(payload)?;
+            requester.request()?;
+        }
+        requester.latency().unwrap_or_default()
+    }};
+}
+
+fn main() -> Result<(), meadow::Error> {
+    let mut host = HostConfig::default()
+        .with_sled_config(SledConfig::default().temporary(true))
+        .with_udp_config(Some(UdpConfig::default("lo")))
+        .build()?;
+    host.start()?;
+    let handle = host.in_process_handle();
+
+    println!(
+        "{:<12}{:>8}{:>8}{:>14}{:>14}{:>14}",
+        "transport", "floats", "msgs", "p50", "p99", "max"
+    );
+    for len in SIZES {
+        let reports = [
+            (
+                "tcp",
+                measure!(Tcp, NetworkConfig::<Blocking, Tcp>::default(), len),
+            ),
+            (
+                "udp",
+                measure!(Udp, NetworkConfig::<Blocking, Udp>::default(), len),
+            ),
+            (
+                "in-process",
+                measure!(
+                    InProcess,
+                    NetworkConfig::<Blocking, InProcess>::default().set_host_handle(handle.clone()),
+                    len
+                ),
+            ),
+        ];
+        for (transport, report) in reports {
+            let latency = &report.end_to_end;
+            println!(
+                "{:<12}{:>8}{:>8}{:>14?}{:>14?}{:>14?}",
+                transport,
+                len,
+                latency.count(),
+                latency.p50(),
+                latency.p99(),
+                latency.max()
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+#[cfg(feature = "tls")]
+fn main() -> Result<(), meadow::Error> {
+    use meadow::host::quic::QuicCertGenConfig;
+    use meadow::prelude::*;
+    use std::thread;
+    use std::time::Duration;
+
+    // TLS uses the same certificate and private key files as QUIC
+    generate_certs(QuicCertGenConfig::default())?;
+    let mut host: Host = HostConfig::default()
+        .with_tls_config(Some(TlsConfig::default()))
+        .build()?;
+    host.start()?;
+
+    // Get the writer up and running
+    let node = NodeConfig::<Blocking, Tls, usize>::new("pose")
+        .build()?
+        .activate()?;
+
+    // Create a subscription node with a query rate of 20 Hz
+    let reader = NodeConfig::<Blocking, Tls, usize>::new("pose")
+        .build()?
+        .subscribe(Duration::from_millis(50))?;
+
+    for i in 0..5 {
+        node.publish(i)?;
+        println!("Published {}", i);
+        let value = node.request()?;
+        assert_eq!(i, value.data);
+        println!("TLS request received with value {:?}", value);
+        thread::sleep(Duration::from_millis(100));
+        println!("Received reply: {:?}", reader.get_subscribed_data());
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "tls"))]
+fn main() {
+    panic!("Must enable the \"tls\" feature to run");
+}
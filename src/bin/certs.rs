@@ -1,11 +1,277 @@
+//! Certificate management for QUIC and TLS Hosts and Nodes
+//!
+//! Running `certs` without a command generates the default self-signed certificate used by the
+//! examples and tests. See `certs help` for the full set of commands.
+
 #[cfg(feature = "quic")]
-use meadow::host::quic::generate_certs;
+const USAGE: &str = "\
+Usage: certs [COMMAND] [OPTIONS]
+
+Commands:
+  self-signed            Generate a self-signed certificate (the default command)
+  ca                     Create a local certificate authority
+  issue                  Issue a Host or Node certificate signed by a certificate authority
+  inspect <FILE>...      Print the details of every certificate in PEM files
+  validate <FILE>        Check that a certificate is current and, optionally, trusted
+  help                   Print this message
+
+Options:
+  --out-dir <DIR>        Output directory [default: target]
+  --name <NAME>          Common name; names Nodes verified over mutual TLS
+  --dns <NAME>           DNS subject alternative name, may be repeated
+  --ip <ADDR>            IP address subject alternative name, may be repeated
+  --days <DAYS>          Number of days the certificate is valid for
+  --ca-cert <FILE>       Certificate authority certificate [default: <out-dir>/ca.pem]
+  --ca-key <FILE>        Certificate authority private key [default: <out-dir>/ca_key.pem]
+  --ca <FILE>            (validate) Certificate authority the chain must be signed by
+  --server-name <NAME>   (validate) DNS name Nodes use to verify the Host
+  --min-days <DAYS>      (validate) Fail if the certificate expires within this many days
+";
+
 #[cfg(feature = "quic")]
-use meadow::host::quic::QuicCertGenConfig;
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(e) = cli::run(args) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
 
+#[cfg(not(feature = "quic"))]
 fn main() {
-    #[cfg(feature = "quic")]
-    generate_certs(QuicCertGenConfig::default());
-    #[cfg(not(feature = "quic"))]
     panic!("Must enable the \"quic\" feature to run");
 }
+
+#[cfg(feature = "quic")]
+mod cli {
+    use super::USAGE;
+    use meadow::host::pki::{self, CertificateAuthority, CertificateInfo};
+    use meadow::host::quic::{generate_certs, QuicCertGenConfig};
+
+    use std::collections::HashMap;
+    use std::fs;
+    use std::net::IpAddr;
+    use std::path::{Path, PathBuf};
+
+    /// Command-line options, as `--key value` pairs and positional arguments
+    struct Args {
+        options: HashMap<String, Vec<String>>,
+        positional: Vec<String>,
+    }
+
+    impl Args {
+        fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+            let mut options: HashMap<String, Vec<String>> = HashMap::new();
+            let mut positional = Vec::new();
+            let mut args = args.into_iter();
+            while let Some(arg) = args.next() {
+                match arg.strip_prefix("--") {
+                    Some(key) => {
+                        let value = args
+                            .next()
+                            .ok_or_else(|| format!("missing value for --{}", key))?;
+                        options.entry(key.to_string()).or_default().push(value);
+                    }
+                    None => positional.push(arg),
+                }
+            }
+            Ok(Args {
+                options,
+                positional,
+            })
+        }
+
+        fn all(&self, key: &str) -> Vec<String> {
+            self.options.get(key).cloned().unwrap_or_default()
+        }
+
+        fn get(&self, key: &str) -> Option<&str> {
+            self.options
+                .get(key)
+                .and_then(|values| values.last())
+                .map(|value| value.as_str())
+        }
+
+        fn parsed<T: std::str::FromStr>(&self, key: &str) -> Result<Option<T>, String> {
+            self.get(key)
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|_e| format!("invalid value for --{}: {}", key, value))
+                })
+                .transpose()
+        }
+
+        fn out_dir(&self) -> Result<PathBuf, String> {
+            let dir = PathBuf::from(self.get("out-dir").unwrap_or("target"));
+            fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+            Ok(dir)
+        }
+    }
+
+    pub fn run(args: Vec<String>) -> Result<(), String> {
+        let (command, rest) = match args.split_first() {
+            Some((command, rest)) if !command.starts_with("--") => (command.as_str(), rest),
+            _ => ("self-signed", &args[..]),
+        };
+        let args = Args::parse(rest.to_vec())?;
+
+        match command {
+            "self-signed" => self_signed(&args),
+            "ca" => ca(&args),
+            "issue" => issue(&args),
+            "inspect" => inspect(&args),
+            "validate" => validate(&args),
+            "help" | "-h" => {
+                print!("{}", USAGE);
+                Ok(())
+            }
+            _ => Err(format!("unknown command \"{}\"\n\n{}", command, USAGE)),
+        }
+    }
+
+    /// Certificate configuration shared by the `self-signed` and `issue` commands
+    fn cert_config(args: &Args, default_dns: Vec<String>) -> Result<QuicCertGenConfig, String> {
+        let mut config = QuicCertGenConfig::default().with_dns_names(default_dns);
+        let dns_names = args.all("dns");
+        if !dns_names.is_empty() {
+            config = config.with_dns_names(dns_names);
+        }
+        for ip in args.all("ip") {
+            let ip: IpAddr = ip
+                .parse()
+                .map_err(|_e| format!("invalid IP address: {}", ip))?;
+            config = config.with_ip_address(ip);
+        }
+        if let Some(name) = args.get("name") {
+            config = config.with_common_name(name);
+        }
+        if let Some(days) = args.parsed("days")? {
+            config = config.with_validity_days(days);
+        }
+        Ok(config)
+    }
+
+    fn self_signed(args: &Args) -> Result<(), String> {
+        let out_dir = args.out_dir()?;
+        let config = cert_config(args, vec!["localhost".into()])?
+            .with_cert_path(out_dir.join("cert.pem"))
+            .with_key_path(out_dir.join("priv_key.pem"));
+        generate_certs(config.clone()).map_err(|e| e.to_string())?;
+        println!(
+            "Wrote self-signed certificate to {} and private key to {}",
+            config.cert_pem_path.display(),
+            config.priv_key_pem_path.display()
+        );
+        Ok(())
+    }
+
+    fn ca(args: &Args) -> Result<(), String> {
+        let out_dir = args.out_dir()?;
+        let name = args.get("name").unwrap_or("meadow-ca");
+        let ca = CertificateAuthority::generate(name, args.parsed("days")?)
+            .map_err(|e| e.to_string())?;
+
+        let (cert_path, key_path) = (out_dir.join("ca.pem"), out_dir.join("ca_key.pem"));
+        ca.write(&cert_path, &key_path).map_err(|e| e.to_string())?;
+        println!(
+            "Wrote certificate authority \"{}\" to {} and private key to {}",
+            name,
+            cert_path.display(),
+            key_path.display()
+        );
+        Ok(())
+    }
+
+    fn issue(args: &Args) -> Result<(), String> {
+        let out_dir = args.out_dir()?;
+        let name = args
+            .get("name")
+            .ok_or("issuing a certificate requires --name")?;
+        let ca_cert = args
+            .get("ca-cert")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| out_dir.join("ca.pem"));
+        let ca_key = args
+            .get("ca-key")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| out_dir.join("ca_key.pem"));
+        let ca = CertificateAuthority::load(&ca_cert, &ca_key).map_err(|e| e.to_string())?;
+
+        let config = cert_config(args, vec![name.to_string()])?
+            .with_cert_path(out_dir.join(format!("{}.pem", name)))
+            .with_key_path(out_dir.join(format!("{}_key.pem", name)));
+        ca.issue(&config).map_err(|e| e.to_string())?;
+        println!(
+            "Wrote certificate chain for \"{}\" to {} and private key to {}",
+            name,
+            config.cert_pem_path.display(),
+            config.priv_key_pem_path.display()
+        );
+        println!(
+            "Use these as a Host's `QuicConfig` or a Node's `NetworkConfig` cert_path and key_path; Hosts verify Nodes against {} with `QuicConfig::with_client_ca`",
+            ca_cert.display()
+        );
+        Ok(())
+    }
+
+    fn inspect(args: &Args) -> Result<(), String> {
+        if args.positional.is_empty() {
+            return Err("inspect requires at least one certificate file".into());
+        }
+        for path in &args.positional {
+            let certs = pki::inspect(path).map_err(|e| format!("{}: {}", path, e))?;
+            for (i, info) in certs.iter().enumerate() {
+                println!("{} [{}]", path, i);
+                print_info(info);
+            }
+        }
+        Ok(())
+    }
+
+    fn validate(args: &Args) -> Result<(), String> {
+        let path = match args.positional.as_slice() {
+            [path] => Path::new(path),
+            _ => return Err("validate requires exactly one certificate file".into()),
+        };
+        let ca = args.get("ca").map(PathBuf::from);
+        let info = pki::validate(path, ca, args.get("server-name"))
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        if let Some(min_days) = args.parsed::<i64>("min-days")? {
+            if info.expires_in() < chrono::Duration::days(min_days) {
+                return Err(format!(
+                    "{}: certificate expires on {}, within {} days",
+                    path.display(),
+                    info.not_after,
+                    min_days
+                ));
+            }
+        }
+        println!("{}: valid", path.display());
+        print_info(&info);
+        Ok(())
+    }
+
+    fn print_info(info: &CertificateInfo) {
+        println!("\tSubject:      {}", info.subject);
+        println!("\tIssuer:       {}", info.issuer);
+        println!("\tCA:           {}", info.is_ca);
+        if !info.dns_names.is_empty() {
+            println!("\tDNS names:    {}", info.dns_names.join(", "));
+        }
+        if !info.ip_addresses.is_empty() {
+            let ips: Vec<String> = info.ip_addresses.iter().map(|ip| ip.to_string()).collect();
+            println!("\tIP addresses: {}", ips.join(", "));
+        }
+        println!("\tFingerprint:  {}", info.fingerprint);
+        println!("\tNot before:   {}", info.not_before);
+        println!("\tNot after:    {}", info.not_after);
+        let days = info.expires_in().num_days();
+        if days < 0 {
+            println!("\tExpired {} days ago", -days);
+        } else {
+            println!("\tExpires in {} days", days);
+        }
+    }
+}
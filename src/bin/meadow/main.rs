@@ -0,0 +1,639 @@
+//! Command-line introspection of the topics on a Meadow Host
+//!
+//! Messages are handled as raw bytes, so the tool doesn't need to be compiled with the data types
+//! used on the Host. Payloads are decoded to JSON using a schema given with `--schema`, or using
+//! the message's data type name when it describes a standard type, e.g. `f32` or
+//! `alloc::string::String`. See `meadow help` for the full set of commands.
+
+#[cfg(feature = "cli")]
+mod schema;
+
+#[cfg(feature = "cli")]
+const USAGE: &str = "\
+Usage: meadow <COMMAND> [OPTIONS]
+
+Commands:
+  list                   List the Host's topics
+  echo <TOPIC>           Print the topic's latest message, followed by each new message
+  pub <TOPIC> <JSON>     Publish a message to the topic
+  hz <TOPIC>             Report the rate at which messages are published to the topic
+  bw <TOPIC>             Report the bandwidth used by the topic
+  history <TOPIC>        Print the topic's most recent messages, oldest first
+  info <TOPIC>           Print the data type, size, and rate of the topic's messages
+  help                   Print this message
+
+Options:
+  --transport <NAME>     tcp, udp, quic, tls, unix, or serial [default: tcp]
+  --host <ADDR>          Address of the Host [default: 127.0.0.1:25000, or :25001 for TLS]
+  --socket <PATH>        (unix) Path of the Host's socket
+  --device <PATH>        (serial) Serial device linked to the Host
+  --baud <RATE>          (serial) Baud rate of the serial device
+  --cert <FILE>          (quic, tls) Certificate used to verify the Host
+  --server-name <NAME>   (quic, tls) Name used to verify the Host's certificate
+  --identity <NAME>      Identity presented to a Host that requires authentication
+  --token <TOKEN>        Token presented along with the identity
+  --buffer <BYTES>       Max size of the Host's responses
+  --schema <SCHEMA>      Layout of the topic's messages, e.g. \"{x: f32, y: f32}\", or @<FILE>
+  --type <NAME>          (pub) Data type name of the published message [default: the schema]
+  --rate <HZ>            (pub) Publish the message repeatedly at this rate
+  -n <N>                 (history) Number of messages to print [default: 10]
+  --window <N>           (hz, bw, info) Number of recent messages to measure [default: 100]
+  --interval <MS>        (echo, hz, bw) Time between polls of the Host [default: 10]
+  --count <N>            (echo, pub, hz, bw) Exit after this many messages or reports
+
+Messages are published as raw bytes when no schema is given, in which case <JSON> must be an array
+of bytes, e.g. [1, 2, 3].
+";
+
+#[cfg(feature = "cli")]
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(e) = cli::run(args) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(not(feature = "cli"))]
+fn main() {
+    panic!("Must enable the \"cli\" feature to run");
+}
+
+#[cfg(feature = "cli")]
+mod cli {
+    use super::schema::Schema;
+    use super::USAGE;
+    use meadow::node::{Active, Node};
+    use meadow::prelude::*;
+
+    use serde_json::{json, Value};
+    use std::collections::{HashMap, VecDeque};
+    use std::net::SocketAddr;
+    #[cfg(feature = "quic")]
+    use std::path::PathBuf;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    /// Command-line options, as `--key value` or `-k value` pairs and positional arguments
+    struct Args {
+        options: HashMap<String, Vec<String>>,
+        positional: Vec<String>,
+    }
+
+    impl Args {
+        fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+            let mut options: HashMap<String, Vec<String>> = HashMap::new();
+            let mut positional = Vec::new();
+            let mut args = args.into_iter();
+            while let Some(arg) = args.next() {
+                // Short options must start with a letter, so negative numbers are positional
+                let key = match arg.strip_prefix("--") {
+                    Some(key) => Some(key),
+                    None => arg
+                        .strip_prefix('-')
+                        .filter(|key| key.starts_with(|c: char| c.is_ascii_alphabetic())),
+                };
+                match key {
+                    Some(key) => {
+                        let value = args
+                            .next()
+                            .ok_or_else(|| format!("missing value for {}", arg))?;
+                        options.entry(key.to_string()).or_default().push(value);
+                    }
+                    None => positional.push(arg),
+                }
+            }
+            Ok(Args {
+                options,
+                positional,
+            })
+        }
+
+        fn get(&self, key: &str) -> Option<&str> {
+            self.options
+                .get(key)
+                .and_then(|values| values.last())
+                .map(|value| value.as_str())
+        }
+
+        fn parsed<T: std::str::FromStr>(&self, key: &str) -> Result<Option<T>, String> {
+            self.get(key)
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|_e| format!("invalid value for {}: {}", key, value))
+                })
+                .transpose()
+        }
+
+        fn topic(&self, command: &str) -> Result<&str, String> {
+            match self.positional.first() {
+                Some(topic) => Ok(topic),
+                None => Err(format!("{} requires a topic", command)),
+            }
+        }
+
+        fn schema(&self) -> Result<Option<Schema>, String> {
+            let description = match self.get("schema") {
+                Some(schema) => match schema.strip_prefix('@') {
+                    Some(path) => std::fs::read_to_string(path)
+                        .map_err(|e| format!("unable to read {}: {}", path, e))?,
+                    None => schema.to_string(),
+                },
+                None => return Ok(None),
+            };
+            Schema::parse(&description).map(Some)
+        }
+
+        fn interval(&self) -> Result<Duration, String> {
+            Ok(Duration::from_millis(
+                self.parsed("interval")?.unwrap_or(10),
+            ))
+        }
+    }
+
+    pub fn run(args: Vec<String>) -> Result<(), String> {
+        let (command, rest) = match args.split_first() {
+            Some((command, rest)) => (command.as_str(), rest),
+            None => ("help", &args[..]),
+        };
+        let args = Args::parse(rest.to_vec())?;
+
+        match command {
+            "list" => list(&args),
+            "echo" => echo(&args),
+            "pub" => publish(&args),
+            "hz" => hz(&args),
+            "bw" => bw(&args),
+            "history" => history(&args),
+            "info" => info(&args),
+            "help" | "-h" | "--help" => {
+                print!("{}", USAGE);
+                Ok(())
+            }
+            _ => Err(format!("unknown command \"{}\"\n\n{}", command, USAGE)),
+        }
+    }
+
+    /// Node connected to the Host over the transport chosen on the command line
+    enum Client {
+        Tcp(Node<Blocking, Tcp, Active, RawData>),
+        Udp(Node<Blocking, Udp, Active, RawData>),
+        #[cfg(feature = "quic")]
+        Quic(Node<Blocking, Quic, Active, RawData>),
+        #[cfg(feature = "tls")]
+        Tls(Node<Blocking, Tls, Active, RawData>),
+        #[cfg(unix)]
+        Unix(Node<Blocking, Unix, Active, RawData>),
+        #[cfg(feature = "serial")]
+        Serial(Node<Blocking, Serial, Active, RawData>),
+    }
+
+    /// Call the same method on the Node, whichever transport it uses
+    macro_rules! dispatch {
+        ($client:expr, $node:ident => $call:expr) => {
+            match $client {
+                Client::Tcp($node) => $call,
+                Client::Udp($node) => $call,
+                #[cfg(feature = "quic")]
+                Client::Quic($node) => $call,
+                #[cfg(feature = "tls")]
+                Client::Tls($node) => $call,
+                #[cfg(unix)]
+                Client::Unix($node) => $call,
+                #[cfg(feature = "serial")]
+                Client::Serial($node) => $call,
+            }
+        };
+    }
+
+    /// Build a Node for `topic` and connect it to the Host
+    macro_rules! activate {
+        ($args:expr, $topic:expr, $network_cfg:expr) => {{
+            let mut network_cfg = $network_cfg;
+            if let Some(max_buffer_size) = $args.parsed("buffer")? {
+                network_cfg.max_buffer_size = max_buffer_size;
+            }
+            let mut node_cfg = NodeConfig::new($topic).with_config(network_cfg);
+            if let Some(identity) = $args.get("identity") {
+                let token = $args.get("token").unwrap_or_default();
+                node_cfg = node_cfg.with_credentials(Credentials::new(identity, token));
+            }
+            node_cfg
+                .build()
+                .and_then(|node| node.activate())
+                .map_err(|e| format!("unable to connect to the Host: {}", e))?
+        }};
+    }
+
+    impl Client {
+        fn connect(args: &Args, topic: &str) -> Result<Client, String> {
+            let host_addr: Option<SocketAddr> = args.parsed("host")?;
+            let client = match args.get("transport").unwrap_or("tcp") {
+                "tcp" => {
+                    let mut network_cfg = NetworkConfig::<Blocking, Tcp>::default();
+                    if let Some(addr) = host_addr {
+                        network_cfg = network_cfg.set_host_addr(addr);
+                    }
+                    Client::Tcp(activate!(args, topic, network_cfg))
+                }
+                "udp" => {
+                    let mut network_cfg = NetworkConfig::<Blocking, Udp>::default();
+                    if let Some(addr) = host_addr {
+                        network_cfg = network_cfg.set_host_addr(addr);
+                    }
+                    Client::Udp(activate!(args, topic, network_cfg))
+                }
+                #[cfg(feature = "quic")]
+                "quic" => {
+                    let mut network_cfg = NetworkConfig::<Blocking, Quic>::default();
+                    if let Some(addr) = host_addr {
+                        network_cfg = network_cfg.set_host_addr(addr);
+                    }
+                    if let Some(cert) = args.get("cert") {
+                        // A Node's own certificate only identifies it alongside its key
+                        network_cfg = network_cfg.set_cert_path(Some(PathBuf::from(cert)));
+                    }
+                    if let Some(server_name) = args.get("server-name") {
+                        network_cfg = network_cfg.set_server_name(server_name);
+                    }
+                    Client::Quic(activate!(args, topic, network_cfg))
+                }
+                #[cfg(feature = "tls")]
+                "tls" => {
+                    let mut network_cfg = NetworkConfig::<Blocking, Tls>::default();
+                    if let Some(addr) = host_addr {
+                        network_cfg = network_cfg.set_host_addr(addr);
+                    }
+                    if let Some(cert) = args.get("cert") {
+                        network_cfg = network_cfg.set_cert_path(Some(PathBuf::from(cert)));
+                    }
+                    if let Some(server_name) = args.get("server-name") {
+                        network_cfg = network_cfg.set_server_name(server_name);
+                    }
+                    Client::Tls(activate!(args, topic, network_cfg))
+                }
+                #[cfg(unix)]
+                "unix" => {
+                    let mut network_cfg = NetworkConfig::<Blocking, Unix>::default();
+                    if let Some(path) = args.get("socket") {
+                        network_cfg = network_cfg.set_socket_path(path);
+                    }
+                    Client::Unix(activate!(args, topic, network_cfg))
+                }
+                #[cfg(feature = "serial")]
+                "serial" => {
+                    let device = args.get("device").ok_or("serial requires a --device")?;
+                    let mut serial_cfg = SerialConfig::new(device);
+                    if let Some(baud_rate) = args.parsed("baud")? {
+                        serial_cfg = serial_cfg.set_baud_rate(baud_rate);
+                    }
+                    let network_cfg =
+                        NetworkConfig::<Blocking, Serial>::default().set_serial_config(serial_cfg);
+                    Client::Serial(activate!(args, topic, network_cfg))
+                }
+                transport => {
+                    return Err(format!(
+                        "unsupported transport \"{}\"; it may need to be enabled as a feature",
+                        transport
+                    ))
+                }
+            };
+            Ok(client)
+        }
+
+        fn topics(&self) -> Result<Vec<String>, String> {
+            let topics = dispatch!(self, node => node.topics()).map_err(|e| e.to_string())?;
+            Ok(topics.data)
+        }
+
+        fn publish_msg(&self, msg: Msg<RawData>) -> Result<(), String> {
+            dispatch!(self, node => node.publish_msg(msg)).map_err(|e| e.to_string())
+        }
+
+        /// Request the topic's `n`th most recent message, or `None` if it doesn't have one
+        fn request_nth_back(&self, n: usize) -> Result<Option<Msg<RawData>>, String> {
+            match dispatch!(self, node => node.request_nth_back(n)) {
+                Ok(msg) => Ok(Some(msg)),
+                // Hosts reply to requests for missing messages with errors, which TCP and UDP
+                // Hosts send as text that doesn't decode as a message
+                Err(Error::NonExistentTopic(_))
+                | Err(Error::NoNthValue)
+                | Err(Error::Postcard(_)) => Ok(None),
+                Err(e) => Err(e.to_string()),
+            }
+        }
+
+        /// Request up to `n` of the topic's most recent messages, oldest first
+        fn history(&self, n: usize) -> Result<Vec<Msg<RawData>>, String> {
+            let mut msgs = Vec::new();
+            for i in 0..n {
+                match self.request_nth_back(i)? {
+                    Some(msg) => msgs.push(msg),
+                    None => break,
+                }
+            }
+            msgs.reverse();
+            Ok(msgs)
+        }
+    }
+
+    /// Finds the messages set on a topic since it was last polled
+    struct Poller {
+        client: Client,
+        last: Option<DateTime<Utc>>,
+        /// Max number of messages returned by each poll
+        window: usize,
+    }
+
+    impl Poller {
+        fn new(client: Client, window: usize) -> Self {
+            Poller {
+                client,
+                last: None,
+                window,
+            }
+        }
+
+        /// New messages on the topic, oldest first; the first poll only returns the latest one
+        fn poll(&mut self) -> Result<Vec<Msg<RawData>>, String> {
+            let mut msgs = Vec::new();
+            for n in 0..self.window {
+                let msg = match self.client.request_nth_back(n)? {
+                    Some(msg) => msg,
+                    None => break,
+                };
+                if matches!(self.last, Some(last) if msg.timestamp <= last) {
+                    break;
+                }
+                msgs.push(msg);
+                if self.last.is_none() {
+                    break;
+                }
+            }
+            msgs.reverse();
+            if let Some(msg) = msgs.last() {
+                self.last = Some(msg.timestamp);
+            }
+            Ok(msgs)
+        }
+    }
+
+    /// Decodes message payloads to JSON, falling back to their bytes if they can't be decoded
+    struct Decoder {
+        schema: Option<Schema>,
+        /// Schemas parsed from data type names, or `None` for names that aren't schemas
+        type_schemas: HashMap<String, Option<Schema>>,
+    }
+
+    impl Decoder {
+        fn new(args: &Args) -> Result<Self, String> {
+            Ok(Decoder {
+                schema: args.schema()?,
+                type_schemas: HashMap::new(),
+            })
+        }
+
+        fn decode(&mut self, msg: &Msg<RawData>) -> Value {
+            let bytes = &msg.data.0;
+            if let Some(schema) = &self.schema {
+                return match schema.decode(bytes) {
+                    Ok(value) => value,
+                    Err(e) => json!({ "bytes": bytes, "error": e }),
+                };
+            }
+            let schema = self
+                .type_schemas
+                .entry(msg.data_type.clone())
+                .or_insert_with(|| Schema::parse(&msg.data_type).ok());
+            schema
+                .as_ref()
+                .and_then(|schema| schema.decode(bytes).ok())
+                .unwrap_or_else(|| json!(bytes))
+        }
+
+        fn print(&mut self, msg: &Msg<RawData>) {
+            let line = json!({
+                "timestamp": msg.timestamp.to_rfc3339(),
+                "data_type": msg.data_type,
+                "data": self.decode(msg),
+            });
+            println!("{}", line);
+        }
+    }
+
+    fn list(args: &Args) -> Result<(), String> {
+        let client = Client::connect(args, "")?;
+        let mut topics = client.topics()?;
+        topics.sort();
+        for topic in topics {
+            println!("{}", topic);
+        }
+        Ok(())
+    }
+
+    fn echo(args: &Args) -> Result<(), String> {
+        let topic = args.topic("echo")?;
+        let count: Option<usize> = args.parsed("count")?;
+        let interval = args.interval()?;
+        let mut decoder = Decoder::new(args)?;
+        let mut poller = Poller::new(
+            Client::connect(args, topic)?,
+            args.parsed("window")?.unwrap_or(100),
+        );
+        let mut printed = 0;
+        loop {
+            for msg in poller.poll()? {
+                decoder.print(&msg);
+                printed += 1;
+                if Some(printed) == count {
+                    return Ok(());
+                }
+            }
+            thread::sleep(interval);
+        }
+    }
+
+    fn publish(args: &Args) -> Result<(), String> {
+        let topic = args.topic("pub")?;
+        let json = args
+            .positional
+            .get(1)
+            .ok_or("pub requires a JSON message")?;
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| format!("invalid JSON: {}", e))?;
+        let (data, default_type) = match args.schema()? {
+            Some(schema) => (
+                schema.encode(&value)?,
+                args.get("schema").unwrap_or_default(),
+            ),
+            None => {
+                let bytes = serde_json::from_value::<Vec<u8>>(value)
+                    .map_err(|_e| "a message without a schema must be an array of bytes")?;
+                (bytes, "bytes")
+            }
+        };
+        let data_type = args.get("type").unwrap_or(default_type).to_string();
+        let client = Client::connect(args, topic)?;
+
+        let rate: Option<f64> = args.parsed("rate")?;
+        let count: Option<usize> = args.parsed("count")?;
+        let count = match rate {
+            Some(_) => count,
+            None => Some(1),
+        };
+        let start = Instant::now();
+        let mut published = 0;
+        while Some(published) != count {
+            if let Some(rate) = rate {
+                let next = Duration::try_from_secs_f64(published as f64 / rate).unwrap_or_default();
+                thread::sleep(next.saturating_sub(start.elapsed()));
+            }
+            let mut msg = Msg::new(MsgType::Set, topic, RawData(data.clone()));
+            msg.data_type = data_type.clone();
+            client.publish_msg(msg)?;
+            published += 1;
+        }
+        Ok(())
+    }
+
+    /// Statistics over a window of recent values
+    struct Stats {
+        mean: f64,
+        min: f64,
+        max: f64,
+        std_dev: f64,
+    }
+
+    impl Stats {
+        fn of(values: impl Iterator<Item = f64> + Clone) -> Option<Stats> {
+            let n = values.clone().count();
+            if n == 0 {
+                return None;
+            }
+            let mean = values.clone().sum::<f64>() / n as f64;
+            let variance = values.clone().map(|v| (v - mean).powi(2)).sum::<f64>() / n as f64;
+            Some(Stats {
+                mean,
+                min: values.clone().fold(f64::INFINITY, f64::min),
+                max: values.fold(f64::NEG_INFINITY, f64::max),
+                std_dev: variance.sqrt(),
+            })
+        }
+    }
+
+    /// Seconds between the timestamps of consecutive messages
+    fn intervals<'a>(msgs: impl IntoIterator<Item = &'a Msg<RawData>>) -> Vec<f64> {
+        let timestamps: Vec<_> = msgs.into_iter().map(|msg| msg.timestamp).collect();
+        timestamps
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).num_microseconds().unwrap_or(i64::MAX) as f64 / 1e6)
+            .collect()
+    }
+
+    /// Poll the topic, printing a report on the window of recent messages once per second
+    fn report(
+        args: &Args,
+        command: &str,
+        mut print: impl FnMut(&VecDeque<Msg<RawData>>),
+    ) -> Result<(), String> {
+        let topic = args.topic(command)?;
+        let count: Option<usize> = args.parsed("count")?;
+        let interval = args.interval()?;
+        let window = args.parsed("window")?.unwrap_or(100);
+        let mut poller = Poller::new(Client::connect(args, topic)?, window);
+        let mut msgs = VecDeque::with_capacity(window);
+        let mut reports = 0;
+        let mut next_report = Instant::now() + Duration::from_secs(1);
+        loop {
+            for msg in poller.poll()? {
+                if msgs.len() == window {
+                    msgs.pop_front();
+                }
+                msgs.push_back(msg);
+            }
+            if Instant::now() >= next_report {
+                next_report += Duration::from_secs(1);
+                print(&msgs);
+                reports += 1;
+                if Some(reports) == count {
+                    return Ok(());
+                }
+            }
+            thread::sleep(interval);
+        }
+    }
+
+    fn hz(args: &Args) -> Result<(), String> {
+        report(args, "hz", |msgs| {
+            match Stats::of(intervals(msgs).into_iter()) {
+                Some(stats) => println!(
+                    "average rate: {:.3}\n\tmin: {:.3}s max: {:.3}s std dev: {:.5}s window: {}",
+                    1.0 / stats.mean,
+                    stats.min,
+                    stats.max,
+                    stats.std_dev,
+                    msgs.len()
+                ),
+                None => println!("no new messages"),
+            }
+        })
+    }
+
+    fn bw(args: &Args) -> Result<(), String> {
+        report(args, "bw", |msgs| {
+            let sizes = msgs.iter().map(|msg| msg.data.0.len() as f64);
+            let span: f64 = intervals(msgs).iter().sum();
+            match Stats::of(sizes.clone()) {
+                Some(stats) if span > 0.0 => println!(
+                    "average: {}/s\n\tmean: {} min: {} max: {} window: {}",
+                    bytes(sizes.sum::<f64>() / span),
+                    bytes(stats.mean),
+                    bytes(stats.min),
+                    bytes(stats.max),
+                    msgs.len()
+                ),
+                _ => println!("no new messages"),
+            }
+        })
+    }
+
+    /// Human-readable byte count
+    fn bytes(n: f64) -> String {
+        match n {
+            n if n >= 1e6 => format!("{:.2}MB", n / 1e6),
+            n if n >= 1e3 => format!("{:.2}KB", n / 1e3),
+            n => format!("{:.2}B", n),
+        }
+    }
+
+    fn history(args: &Args) -> Result<(), String> {
+        let topic = args.topic("history")?;
+        let n = args.parsed("n")?.unwrap_or(10);
+        let mut decoder = Decoder::new(args)?;
+        let client = Client::connect(args, topic)?;
+        for msg in client.history(n)? {
+            decoder.print(&msg);
+        }
+        Ok(())
+    }
+
+    fn info(args: &Args) -> Result<(), String> {
+        let topic = args.topic("info")?;
+        let client = Client::connect(args, topic)?;
+        let msgs = client.history(args.parsed("window")?.unwrap_or(100))?;
+        let latest = match msgs.last() {
+            Some(msg) => msg,
+            None => return Err(format!("topic \"{}\" has no messages", topic)),
+        };
+        println!("topic: {}", topic);
+        println!("data type: {}", latest.data_type);
+        println!("latest: {}", latest.timestamp.to_rfc3339());
+        println!("size: {}", bytes(latest.data.0.len() as f64));
+        match Stats::of(intervals(&msgs).into_iter()) {
+            Some(stats) => println!("rate: {:.3} over {} messages", 1.0 / stats.mean, msgs.len()),
+            None => println!("rate: unknown over 1 message"),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,716 @@
+//! Schemas describing the layout of postcard-encoded messages, for converting them to and from
+//! JSON without their Rust types
+//!
+//! Schemas are written in a Rust-like syntax, e.g. `{x: f32, y: f32}` for a struct,
+//! `[u8]` or `Vec<u8>` for a sequence, `[f32; 3]` for an array, `(u8, String)` for a tuple,
+//! `Option<T>`, `Map<K, V>`, and `enum {Idle, Move(f32), Goto {x: f32, y: f32}}`. Type names are
+//! case-insensitive and may be prefixed with a path, so most data type names reported by
+//! `std::any::type_name` for standard types are also valid schemas.
+
+use serde_json::{Map, Number, Value};
+use std::convert::TryFrom;
+
+/// Layout of a postcard-encoded value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schema {
+    Unit,
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    F32,
+    F64,
+    Char,
+    String,
+    Option(Box<Schema>),
+    Seq(Box<Schema>),
+    Array(Box<Schema>, usize),
+    Map(Box<Schema>, Box<Schema>),
+    Tuple(Vec<Schema>),
+    Struct(Vec<(String, Schema)>),
+    /// Variants, in declaration order, with their contents if they aren't unit variants
+    Enum(Vec<(String, Option<Schema>)>),
+}
+
+impl Schema {
+    /// Parse a schema from its Rust-like description
+    pub fn parse(description: &str) -> Result<Schema, String> {
+        let mut parser = Parser {
+            tokens: tokenize(description)?,
+            pos: 0,
+        };
+        let schema = parser.schema()?;
+        match parser.next() {
+            None => Ok(schema),
+            Some(token) => Err(format!("unexpected \"{}\" in schema", token)),
+        }
+    }
+
+    /// Decode a postcard-encoded value, which must use all of `bytes`
+    pub fn decode(&self, bytes: &[u8]) -> Result<Value, String> {
+        let mut reader = Reader { bytes, pos: 0 };
+        let value = self.read(&mut reader)?;
+        match bytes.len() - reader.pos {
+            0 => Ok(value),
+            n => Err(format!("{} bytes left over after decoding", n)),
+        }
+    }
+
+    /// Encode a JSON value as postcard
+    pub fn encode(&self, value: &Value) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::new();
+        self.write(value, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn read(&self, reader: &mut Reader) -> Result<Value, String> {
+        Ok(match self {
+            Schema::Unit => Value::Null,
+            Schema::Bool => match reader.byte()? {
+                0 => Value::Bool(false),
+                1 => Value::Bool(true),
+                b => return Err(format!("invalid bool {}", b)),
+            },
+            Schema::U8 => reader.byte()?.into(),
+            Schema::I8 => (reader.byte()? as i8).into(),
+            Schema::U16 | Schema::U32 | Schema::U64 => {
+                let v = reader.varint()?;
+                if v > u64::MAX as u128 {
+                    return Err("integer out of range".into());
+                }
+                (v as u64).into()
+            }
+            Schema::U128 => {
+                let v = reader.varint()?;
+                u64::try_from(v).map_or_else(|_e| Value::String(v.to_string()), Value::from)
+            }
+            Schema::I16 | Schema::I32 | Schema::I64 => (unzigzag(reader.varint()?) as i64).into(),
+            Schema::I128 => {
+                let v = unzigzag(reader.varint()?);
+                i64::try_from(v).map_or_else(|_e| Value::String(v.to_string()), Value::from)
+            }
+            Schema::F32 => float(f32::from_le_bytes(reader.array()?) as f64),
+            Schema::F64 => float(f64::from_le_bytes(reader.array()?)),
+            Schema::Char => {
+                let s = reader.string()?;
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(_), None) => Value::String(s),
+                    _ => return Err(format!("invalid char \"{}\"", s)),
+                }
+            }
+            Schema::String => Value::String(reader.string()?),
+            Schema::Option(inner) => match reader.byte()? {
+                0 => Value::Null,
+                1 => inner.read(reader)?,
+                b => return Err(format!("invalid option tag {}", b)),
+            },
+            Schema::Seq(inner) => {
+                let len = reader.len()?;
+                let items = (0..len)
+                    .map(|_| inner.read(reader))
+                    .collect::<Result<_, _>>()?;
+                Value::Array(items)
+            }
+            Schema::Array(inner, len) => {
+                let items = (0..*len)
+                    .map(|_| inner.read(reader))
+                    .collect::<Result<_, _>>()?;
+                Value::Array(items)
+            }
+            Schema::Map(key, value) => {
+                let len = reader.len()?;
+                let mut map = Map::new();
+                for _ in 0..len {
+                    let k = match key.read(reader)? {
+                        Value::String(s) => s,
+                        k => k.to_string(),
+                    };
+                    map.insert(k, value.read(reader)?);
+                }
+                Value::Object(map)
+            }
+            Schema::Tuple(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| item.read(reader))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Schema::Struct(fields) => {
+                let mut map = Map::new();
+                for (name, field) in fields {
+                    map.insert(name.clone(), field.read(reader)?);
+                }
+                Value::Object(map)
+            }
+            Schema::Enum(variants) => {
+                let index = reader.varint()?;
+                let (name, contents) = usize::try_from(index)
+                    .ok()
+                    .and_then(|index| variants.get(index))
+                    .ok_or_else(|| format!("invalid variant index {}", index))?;
+                match contents {
+                    None => Value::String(name.clone()),
+                    Some(contents) => {
+                        let mut map = Map::new();
+                        map.insert(name.clone(), contents.read(reader)?);
+                        Value::Object(map)
+                    }
+                }
+            }
+        })
+    }
+
+    fn write(&self, value: &Value, out: &mut Vec<u8>) -> Result<(), String> {
+        let mismatch = || format!("expected {}, found {}", self.describe(), value);
+        match self {
+            Schema::Unit => match value {
+                Value::Null => {}
+                _ => return Err(mismatch()),
+            },
+            Schema::Bool => out.push(value.as_bool().ok_or_else(mismatch)? as u8),
+            Schema::U8 => out.push(unsigned(value, u8::MAX as u128).ok_or_else(mismatch)? as u8),
+            Schema::U16 => {
+                write_varint(unsigned(value, u16::MAX as u128).ok_or_else(mismatch)?, out)
+            }
+            Schema::U32 => {
+                write_varint(unsigned(value, u32::MAX as u128).ok_or_else(mismatch)?, out)
+            }
+            Schema::U64 => {
+                write_varint(unsigned(value, u64::MAX as u128).ok_or_else(mismatch)?, out)
+            }
+            Schema::U128 => write_varint(unsigned(value, u128::MAX).ok_or_else(mismatch)?, out),
+            Schema::I8 => out.push(signed(value, i8::MAX as i128).ok_or_else(mismatch)? as u8),
+            Schema::I16 => write_varint(
+                zigzag(signed(value, i16::MAX as i128).ok_or_else(mismatch)?),
+                out,
+            ),
+            Schema::I32 => write_varint(
+                zigzag(signed(value, i32::MAX as i128).ok_or_else(mismatch)?),
+                out,
+            ),
+            Schema::I64 => write_varint(
+                zigzag(signed(value, i64::MAX as i128).ok_or_else(mismatch)?),
+                out,
+            ),
+            Schema::I128 => {
+                write_varint(zigzag(signed(value, i128::MAX).ok_or_else(mismatch)?), out)
+            }
+            Schema::F32 => {
+                let v = value.as_f64().ok_or_else(mismatch)? as f32;
+                out.extend_from_slice(&v.to_le_bytes());
+            }
+            Schema::F64 => {
+                out.extend_from_slice(&value.as_f64().ok_or_else(mismatch)?.to_le_bytes())
+            }
+            Schema::Char => {
+                let s = value.as_str().ok_or_else(mismatch)?;
+                if s.chars().count() != 1 {
+                    return Err(mismatch());
+                }
+                write_bytes(s.as_bytes(), out);
+            }
+            Schema::String => write_bytes(value.as_str().ok_or_else(mismatch)?.as_bytes(), out),
+            Schema::Option(inner) => match value {
+                Value::Null => out.push(0),
+                value => {
+                    out.push(1);
+                    inner.write(value, out)?;
+                }
+            },
+            Schema::Seq(inner) => {
+                let items = value.as_array().ok_or_else(mismatch)?;
+                write_varint(items.len() as u128, out);
+                for item in items {
+                    inner.write(item, out)?;
+                }
+            }
+            Schema::Array(inner, len) => {
+                let items = value.as_array().ok_or_else(mismatch)?;
+                if items.len() != *len {
+                    return Err(mismatch());
+                }
+                for item in items {
+                    inner.write(item, out)?;
+                }
+            }
+            Schema::Map(key, inner) => {
+                let map = value.as_object().ok_or_else(mismatch)?;
+                write_varint(map.len() as u128, out);
+                for (k, v) in map {
+                    // Keys that aren't strings are written in JSON objects as their JSON text
+                    let k = match **key {
+                        Schema::String | Schema::Char => Value::String(k.clone()),
+                        _ => serde_json::from_str(k).map_err(|_e| format!("invalid key {}", k))?,
+                    };
+                    key.write(&k, out)?;
+                    inner.write(v, out)?;
+                }
+            }
+            Schema::Tuple(items) => {
+                let values = value.as_array().ok_or_else(mismatch)?;
+                if values.len() != items.len() {
+                    return Err(mismatch());
+                }
+                for (item, value) in items.iter().zip(values) {
+                    item.write(value, out)?;
+                }
+            }
+            Schema::Struct(fields) => {
+                let map = value.as_object().ok_or_else(mismatch)?;
+                if let Some(name) = map.keys().find(|k| !fields.iter().any(|(f, _)| f == *k)) {
+                    return Err(format!("unknown field \"{}\"", name));
+                }
+                for (name, field) in fields {
+                    // Missing fields are only allowed for options, as with serde
+                    let value = match (map.get(name), field) {
+                        (Some(value), _) => value,
+                        (None, Schema::Option(_)) => &Value::Null,
+                        (None, _) => return Err(format!("missing field \"{}\"", name)),
+                    };
+                    field.write(value, out)?;
+                }
+            }
+            Schema::Enum(variants) => {
+                // Variants are externally tagged, as serde_json does by default
+                let (name, contents) = match value {
+                    Value::String(name) => (name, None),
+                    Value::Object(map) if map.len() == 1 => {
+                        let (name, contents) = map.iter().next().unwrap();
+                        (name, Some(contents))
+                    }
+                    _ => return Err(mismatch()),
+                };
+                let index = variants
+                    .iter()
+                    .position(|(variant, _)| variant == name)
+                    .ok_or_else(|| format!("unknown variant \"{}\"", name))?;
+                write_varint(index as u128, out);
+                match (&variants[index].1, contents) {
+                    (None, None) => {}
+                    (Some(schema), Some(contents)) => schema.write(contents, out)?,
+                    _ => return Err(mismatch()),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn describe(&self) -> &'static str {
+        match self {
+            Schema::Unit => "null",
+            Schema::Bool => "a bool",
+            Schema::U8
+            | Schema::U16
+            | Schema::U32
+            | Schema::U64
+            | Schema::U128
+            | Schema::I8
+            | Schema::I16
+            | Schema::I32
+            | Schema::I64
+            | Schema::I128 => "an integer in range",
+            Schema::F32 | Schema::F64 => "a number",
+            Schema::Char => "a single character",
+            Schema::String => "a string",
+            Schema::Option(_) => "an option",
+            Schema::Seq(_) => "an array",
+            Schema::Array(_, _) | Schema::Tuple(_) => "an array of fixed length",
+            Schema::Map(_, _) | Schema::Struct(_) => "an object",
+            Schema::Enum(_) => "a variant",
+        }
+    }
+}
+
+/// JSON number for floats, which are `null` if they aren't finite
+fn float(v: f64) -> Value {
+    Number::from_f64(v)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
+
+fn unsigned(value: &Value, max: u128) -> Option<u128> {
+    let v = match value {
+        Value::Number(n) => n.as_u64().map(u128::from),
+        // Integers too large for JSON numbers may be passed as strings
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }?;
+    (v <= max).then_some(v)
+}
+
+fn signed(value: &Value, max: i128) -> Option<i128> {
+    let v = match value {
+        Value::Number(n) => n.as_i64().map(i128::from),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }?;
+    (v <= max && v >= -max - 1).then_some(v)
+}
+
+fn zigzag(v: i128) -> u128 {
+    ((v << 1) ^ (v >> 127)) as u128
+}
+
+fn unzigzag(v: u128) -> i128 {
+    ((v >> 1) as i128) ^ -((v & 1) as i128)
+}
+
+fn write_varint(mut v: u128, out: &mut Vec<u8>) {
+    loop {
+        let byte = (v & 0x7f) as u8;
+        v >>= 7;
+        if v == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn write_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    write_varint(bytes.len() as u128, out);
+    out.extend_from_slice(bytes);
+}
+
+/// Cursor over a postcard-encoded message
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or("message ended unexpectedly")?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn varint(&mut self) -> Result<u128, String> {
+        let mut v = 0u128;
+        for shift in (0..128).step_by(7) {
+            let byte = self.byte()?;
+            v |= ((byte & 0x7f) as u128) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(v);
+            }
+        }
+        Err("invalid varint".into())
+    }
+
+    fn len(&mut self) -> Result<usize, String> {
+        usize::try_from(self.varint()?).map_err(|_e| "invalid length".to_string())
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let len = self.len()?;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_e| "invalid UTF-8 string".to_string())
+    }
+}
+
+fn tokenize(description: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = description.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_alphanumeric() || c == '_' {
+            let mut token = String::new();
+            while let Some(&c) = chars.peek() {
+                if !(c.is_alphanumeric() || c == '_') {
+                    break;
+                }
+                token.push(c);
+                chars.next();
+            }
+            tokens.push(token);
+        } else if c == ':' {
+            chars.next();
+            if chars.peek() == Some(&':') {
+                chars.next();
+                tokens.push("::".into());
+            } else {
+                tokens.push(":".into());
+            }
+        } else if "{}[]()<>,;&'".contains(c) {
+            chars.next();
+            tokens.push(c.to_string());
+        } else {
+            return Err(format!("unexpected \"{}\" in schema", c));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(|token| token.as_str())
+    }
+
+    fn next(&mut self) -> Option<String> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(format!("expected \"{}\", found \"{}\"", expected, token)),
+            None => Err(format!("expected \"{}\" at end of schema", expected)),
+        }
+    }
+
+    /// Parse items up to the closing delimiter, allowing a trailing comma
+    fn list<T>(
+        &mut self,
+        close: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T, String>,
+    ) -> Result<Vec<T>, String> {
+        let mut items = Vec::new();
+        loop {
+            if self.peek() == Some(close) {
+                self.next();
+                return Ok(items);
+            }
+            items.push(item(self)?);
+            if self.peek() != Some(close) {
+                self.expect(",")?;
+            }
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(token) if token.starts_with(|c: char| c.is_alphabetic() || c == '_') => Ok(token),
+            Some(token) => Err(format!("expected a name, found \"{}\"", token)),
+            None => Err("expected a name at end of schema".into()),
+        }
+    }
+
+    fn fields(&mut self) -> Result<Vec<(String, Schema)>, String> {
+        self.list("}", |parser| {
+            let name = parser.ident()?;
+            parser.expect(":")?;
+            Ok((name, parser.schema()?))
+        })
+    }
+
+    fn schema(&mut self) -> Result<Schema, String> {
+        let token = self.next().ok_or("unexpected end of schema")?;
+        match token.as_str() {
+            "{" => Ok(Schema::Struct(self.fields()?)),
+            "(" => {
+                let mut items = self.list(")", Parser::schema)?;
+                Ok(match items.len() {
+                    0 => Schema::Unit,
+                    1 => items.remove(0),
+                    _ => Schema::Tuple(items),
+                })
+            }
+            "[" => {
+                let inner = Box::new(self.schema()?);
+                if self.peek() == Some(";") {
+                    self.next();
+                    let len = self.next().ok_or("expected an array length")?;
+                    let len = len
+                        .parse()
+                        .map_err(|_e| format!("invalid array length \"{}\"", len))?;
+                    self.expect("]")?;
+                    Ok(Schema::Array(inner, len))
+                } else {
+                    self.expect("]")?;
+                    Ok(Schema::Seq(inner))
+                }
+            }
+            "&" => {
+                // References, e.g. `&str`, are encoded the same as the values they point to
+                if self.peek() == Some("'") {
+                    self.next();
+                    self.ident()?;
+                }
+                self.schema()
+            }
+            _ => {
+                let mut name = token;
+                // Only the last segment of a path names the type
+                while self.peek() == Some("::") {
+                    self.next();
+                    name = self.ident()?;
+                }
+                self.named(&name)
+            }
+        }
+    }
+
+    fn generics(&mut self, n: usize) -> Result<Vec<Schema>, String> {
+        self.expect("<")?;
+        let generics = self.list(">", Parser::schema)?;
+        match generics.len() == n {
+            true => Ok(generics),
+            false => Err(format!("expected {} type parameters", n)),
+        }
+    }
+
+    fn named(&mut self, name: &str) -> Result<Schema, String> {
+        Ok(match name.to_lowercase().as_str() {
+            "bool" => Schema::Bool,
+            "u8" => Schema::U8,
+            "u16" => Schema::U16,
+            "u32" => Schema::U32,
+            "u64" | "usize" => Schema::U64,
+            "u128" => Schema::U128,
+            "i8" => Schema::I8,
+            "i16" => Schema::I16,
+            "i32" => Schema::I32,
+            "i64" | "isize" => Schema::I64,
+            "i128" => Schema::I128,
+            "f32" => Schema::F32,
+            "f64" => Schema::F64,
+            "char" => Schema::Char,
+            "string" | "str" => Schema::String,
+            "bytes" => Schema::Seq(Box::new(Schema::U8)),
+            "option" => Schema::Option(Box::new(self.generics(1)?.remove(0))),
+            "vec" | "vecdeque" => Schema::Seq(Box::new(self.generics(1)?.remove(0))),
+            "map" | "hashmap" | "btreemap" => {
+                let mut generics = self.generics(2)?;
+                let value = generics.remove(1);
+                Schema::Map(Box::new(generics.remove(0)), Box::new(value))
+            }
+            "enum" => {
+                self.expect("{")?;
+                let variants = self.list("}", |parser| {
+                    let name = parser.ident()?;
+                    let contents = match parser.peek() {
+                        Some("(") => Some(parser.schema()?),
+                        Some("{") => {
+                            parser.next();
+                            Some(Schema::Struct(parser.fields()?))
+                        }
+                        _ => None,
+                    };
+                    Ok((name, contents))
+                })?;
+                Schema::Enum(variants)
+            }
+            _ => return Err(format!("unknown type \"{}\"", name)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Schema;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Command {
+        Stop,
+        Speed(f32),
+        Turn(i16, i16),
+        Goto { x: f64, y: f64 },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Status {
+        name: String,
+        id: u32,
+        offset: i64,
+        flags: [bool; 2],
+        history: Vec<Command>,
+        label: Option<char>,
+        pair: (u8, i8),
+        large: u128,
+    }
+
+    const STATUS: &str = "{name: String, id: u32, offset: i64, flags: [bool; 2], \
+        history: Vec<enum {Stop, Speed(f32), Turn(i16, i16), Goto {x: f64, y: f64}}>, \
+        label: Option<char>, pair: (u8, i8), large: u128,}";
+
+    #[test]
+    fn schema_matches_postcard() {
+        let status = Status {
+            name: "rover".into(),
+            id: 300,
+            offset: -70_000,
+            flags: [true, false],
+            history: vec![
+                Command::Stop,
+                Command::Speed(1.5),
+                Command::Turn(-3, 4),
+                Command::Goto { x: 1.0, y: -2.0 },
+            ],
+            label: Some('é'),
+            pair: (255, -128),
+            large: u128::MAX,
+        };
+        let bytes = postcard::to_allocvec(&status).unwrap();
+        let schema = Schema::parse(STATUS).unwrap();
+        let value = schema.decode(&bytes).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "name": "rover",
+                "id": 300,
+                "offset": -70_000,
+                "flags": [true, false],
+                "history": ["Stop", {"Speed": 1.5}, {"Turn": [-3, 4]}, {"Goto": {"x": 1.0, "y": -2.0}}],
+                "label": "é",
+                "pair": [255, -128],
+                "large": u128::MAX.to_string(),
+            })
+        );
+        assert_eq!(schema.encode(&value).unwrap(), bytes);
+    }
+
+    #[test]
+    fn type_names_are_schemas() {
+        let name = std::any::type_name::<Vec<Option<(String, f32)>>>();
+        let schema = Schema::parse(name).unwrap();
+        let value = json!([["a", 1.0], null]);
+        let bytes = postcard::to_allocvec(&vec![Some(("a".to_string(), 1.0f32)), None]).unwrap();
+        assert_eq!(schema.encode(&value).unwrap(), bytes);
+        assert_eq!(schema.decode(&bytes).unwrap(), value);
+        assert!(Schema::parse("my_crate::Pose").is_err());
+    }
+
+    #[test]
+    fn invalid_values() {
+        let schema = Schema::parse("{x: u8, y: Option<f32>}").unwrap();
+        assert_eq!(schema.encode(&json!({"x": 1})).unwrap(), vec![1, 0]);
+        assert!(schema.encode(&json!({"x": 256})).is_err());
+        assert!(schema.encode(&json!({"y": 1.0})).is_err());
+        assert!(schema.encode(&json!({"x": 1, "z": 1})).is_err());
+        assert!(schema.decode(&[1]).is_err());
+        assert!(schema.decode(&[1, 0, 0]).is_err());
+    }
+}
@@ -0,0 +1,185 @@
+use crate::networks::get_ip;
+use crate::Error;
+
+use serde::{Deserialize, Serialize};
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::net::UdpSocket;
+use tokio::time::{sleep, timeout_at, Instant};
+
+use tracing::*;
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::time::Duration;
+
+/// Transport protocol a Host accepts Node connections over
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum Transport {
+    Tcp,
+    Udp,
+    Quic,
+    Tls,
+    /// Unix domain sockets, which are only reachable from the Host's own machine and so are
+    /// never announced
+    Unix,
+    /// Nodes sharing the Host's process, which are never announced
+    InProcess,
+    /// Serial links such as UARTs and telemetry radios, which are never announced
+    Serial,
+    /// User-supplied streams, which are never announced
+    Stream,
+}
+
+/// Announcement periodically multicast by a Host so that Nodes can find it by name
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Beacon {
+    /// Name of the announcing Host
+    pub name: String,
+    /// Address the Host accepts connections on for each of its transports
+    pub transports: Vec<(Transport, SocketAddr)>,
+    /// SHA-256 fingerprint of the certificate the Host presents over QUIC and TLS, if any
+    pub fingerprint: Option<String>,
+}
+
+impl Beacon {
+    /// Address the Host accepts connections on for the specified transport
+    pub fn addr(&self, transport: Transport) -> Option<SocketAddr> {
+        self.transports
+            .iter()
+            .find(|(t, _addr)| *t == transport)
+            .map(|(_t, addr)| *addr)
+    }
+}
+
+/// Configuration of the multicast group that Hosts announce themselves on and Nodes listen to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveryConfig {
+    /// Network interface beacons are sent and received on
+    pub interface: String,
+    /// Multicast group address and port of the beacons
+    pub group: SocketAddrV4,
+    /// Time between each of the Host's beacons
+    pub interval: Duration,
+    /// How long a Node waits for a beacon from its Host before giving up
+    pub timeout: Duration,
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        DiscoveryConfig::new("lo")
+    }
+}
+
+impl DiscoveryConfig {
+    /// Create a new `DiscoveryConfig` on a defined network interface, using multicast group
+    /// `239.255.25.0:25_099`
+    pub fn new(interface: impl Into<String>) -> Self {
+        DiscoveryConfig {
+            interface: interface.into(),
+            group: SocketAddrV4::new(Ipv4Addr::new(239, 255, 25, 0), 25_099),
+            interval: Duration::from_secs(1),
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    /// Set the multicast group address and port of the beacons
+    pub fn with_group(mut self, group: SocketAddrV4) -> Self {
+        self.group = group;
+        self
+    }
+
+    /// Set the time between each of the Host's beacons
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Set how long a Node waits for a beacon from its Host
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+/// Bind the socket a Host sends its beacons from
+pub(crate) fn beacon_socket(cfg: &DiscoveryConfig) -> Result<std::net::UdpSocket, Error> {
+    let ip = get_ip(&cfg.interface)?;
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_multicast_if_v4(&ip)?;
+    socket.set_multicast_loop_v4(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&SocketAddr::new(IpAddr::V4(ip), 0).into())?;
+    Ok(socket.into())
+}
+
+/// Periodically multicast the Host's beacon until the task is aborted
+pub(crate) async fn announce(
+    socket: std::net::UdpSocket,
+    beacon: Beacon,
+    cfg: DiscoveryConfig,
+) -> Result<(), Error> {
+    let socket = UdpSocket::from_std(socket)?;
+    let bytes = postcard::to_allocvec(&beacon)?;
+    debug!("Announcing Host \"{}\" on {}", beacon.name, cfg.group);
+    loop {
+        if let Err(e) = socket.send_to(&bytes, cfg.group).await {
+            warn!("Unable to send discovery beacon: {}", e);
+        }
+        sleep(cfg.interval).await;
+    }
+}
+
+/// Wait for a beacon from the named Host
+///
+/// Hosts listening on an unspecified address are announced with it, so these are replaced with
+/// the address the beacon was received from.
+#[tracing::instrument(skip(cfg))]
+pub async fn discover(name: &str, cfg: &DiscoveryConfig) -> Result<Beacon, Error> {
+    discover_trusted(name, cfg, |_beacon| true).await
+}
+
+/// Wait for a beacon from the named Host that is `trusted`, ignoring any others sent in its name
+pub(crate) async fn discover_trusted(
+    name: &str,
+    cfg: &DiscoveryConfig,
+    trusted: impl Fn(&Beacon) -> bool,
+) -> Result<Beacon, Error> {
+    let ip = get_ip(&cfg.interface)?;
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    // Multiple Nodes on the same machine listen to the same group
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), cfg.group.port()).into())?;
+    socket.join_multicast_v4(cfg.group.ip(), &ip)?;
+    let socket = UdpSocket::from_std(socket.into())?;
+
+    let deadline = Instant::now() + cfg.timeout;
+    let mut buf = vec![0u8; 2048];
+    loop {
+        let (n, source) = match timeout_at(deadline, socket.recv_from(&mut buf)).await {
+            Ok(received) => received?,
+            Err(_elapsed) => return Err(Error::HostNotFound(name.to_string())),
+        };
+        let mut beacon: Beacon = match postcard::from_bytes(&buf[..n]) {
+            Ok(beacon) => beacon,
+            Err(_e) => continue,
+        };
+        if beacon.name != name {
+            continue;
+        }
+        if !trusted(&beacon) {
+            warn!(
+                "Ignoring beacon for Host \"{}\" from {}, as it doesn't announce a trusted certificate",
+                name, source
+            );
+            continue;
+        }
+
+        for (_transport, addr) in beacon.transports.iter_mut() {
+            if addr.ip().is_unspecified() {
+                addr.set_ip(source.ip());
+            }
+        }
+        debug!("Discovered Host \"{}\" from {}", name, source);
+        return Ok(beacon);
+    }
+}
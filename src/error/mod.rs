@@ -66,10 +66,56 @@ pub enum Error {
     /// Topic does not have value at specific n'th position
     #[error("Topic does not have value at specific n'th position")]
     NoNthValue,
+    /// Node credentials were missing or rejected by the Host
+    #[error("Node credentials were missing or rejected by the Host")]
+    Unauthenticated,
+    /// Node identity is not permitted to perform an operation on a topic
+    #[error("`{identity}` is not permitted to {permission} on topic `{topic}`")]
+    PermissionDenied {
+        identity: String,
+        permission: crate::host::Permission,
+        topic: String,
+    },
+    /// Message payload could not be sealed or opened with the Node's key
+    #[error("Message payload could not be sealed or opened with the Node's key")]
+    Crypto,
+    /// Message payload was not signed by a trusted publisher
+    #[error("Message payload was not signed by a trusted publisher")]
+    UntrustedPublisher,
+    /// No beacon was received from the named Host before the discovery timeout
+    #[error("No Host named `{0}` was discovered")]
+    HostNotFound(String),
+    /// Serial frame did not match its checksum
+    #[error("Serial frame did not match its checksum")]
+    CorruptFrame,
+    /// Operation can't be served over the transport it was requested on
+    #[error("Operation isn't supported over this transport")]
+    UnsupportedOperation,
+    /// Recording could not be read as an MCAP file or sled log
+    #[error("Unable to read recording: {0}")]
+    InvalidRecording(String),
+    /// Trace context could not be parsed as a W3C `traceparent`
+    #[error("Invalid traceparent `{0}`")]
+    InvalidTraceContext(String),
     #[error("Undefined error")]
     Undefined,
 }
 
+impl Error {
+    /// Whether the Host rejected the Node's credentials or denied it access to a topic
+    pub fn is_access_denied(&self) -> bool {
+        matches!(
+            self,
+            Error::Unauthenticated | Error::PermissionDenied { .. }
+        )
+    }
+
+    /// Whether the connection to the Host was lost, so that the Node should reconnect
+    pub fn is_connection_lost(&self) -> bool {
+        matches!(self, Error::StreamConnection | Error::Io { .. })
+    }
+}
+
 /// This is the Result type used by meadow.
 pub type Result<T> = ::core::result::Result<T, Error>;
 
@@ -121,12 +167,6 @@ impl From<std::io::Error> for Error {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct IoError {
-    kind: String,
-    raw_os_error: i32,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum SledError {
     CollectionNotFound(Vec<u8>),
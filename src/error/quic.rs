@@ -34,6 +34,15 @@ pub enum Quic {
     /// Transparent `rustls::Error`
     #[error("`rustls::Error`-based error")]
     RustlsError,
+    /// Server name used to verify the Host's certificate is not a valid DNS name
+    #[error("Server name used to verify the Host's certificate is not a valid DNS name")]
+    InvalidDnsName,
+    /// Error generating a certificate or private key
+    #[error("Error generating certificate: {0}")]
+    CertificateGeneration(String),
+    /// Certificate could not be parsed or failed validation
+    #[error("Invalid certificate: {0}")]
+    InvalidCertificate(String),
 }
 
 // ===== quinn::ConnectError =====
@@ -112,3 +121,17 @@ impl From<rustls::Error> for crate::Error {
         Error::Quic(error.into())
     }
 }
+
+// ===== rcgen::RcgenError =====
+
+impl From<rcgen::RcgenError> for Quic {
+    fn from(error: rcgen::RcgenError) -> Self {
+        Quic::CertificateGeneration(error.to_string())
+    }
+}
+
+impl From<rcgen::RcgenError> for crate::Error {
+    fn from(error: rcgen::RcgenError) -> Self {
+        Error::Quic(error.into())
+    }
+}
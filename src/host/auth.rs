@@ -0,0 +1,283 @@
+use crate::error::Error;
+use crate::msg::MsgType;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+
+/// Topic operations that can be granted to a Node identity
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum Permission {
+    /// Permission to SET values on a topic
+    Publish,
+    /// Permission to GET or GET_NTH values from a topic, or its statistics
+    Request,
+    /// Permission to start a subscription on a topic
+    Subscribe,
+}
+
+impl Permission {
+    /// All available permissions
+    pub const ALL: [Permission; 3] = [
+        Permission::Publish,
+        Permission::Request,
+        Permission::Subscribe,
+    ];
+
+    /// Permission required for the Host to service a message, if any
+    pub(crate) fn required_for(msg_type: &MsgType) -> Option<Permission> {
+        match msg_type {
+            MsgType::Set => Some(Permission::Publish),
+            // Replicating a Host requires access to all of its topics, as `*`
+            MsgType::Get | MsgType::GetNth(_) | MsgType::Replicate | MsgType::Stats => {
+                Some(Permission::Request)
+            }
+            MsgType::Subscribe => Some(Permission::Subscribe),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Permission {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Permission::Publish => write!(f, "publish"),
+            Permission::Request => write!(f, "request"),
+            Permission::Subscribe => write!(f, "subscribe"),
+        }
+    }
+}
+
+/// Credentials presented by a Node to the Host during the connection handshake
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Credentials {
+    pub identity: String,
+    pub token: String,
+}
+
+impl Credentials {
+    /// Create a new set of credentials from a Node identity and its pre-shared token
+    pub fn new(identity: impl Into<String>, token: impl Into<String>) -> Self {
+        Credentials {
+            identity: identity.into(),
+            token: token.into(),
+        }
+    }
+}
+
+impl Debug for Credentials {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("identity", &self.identity)
+            .field("token", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Grants a set of permissions on all topics matching a glob pattern
+///
+/// Patterns support `*`, which matches any run of characters, and `?`, which
+/// matches exactly one character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AclRule {
+    pub pattern: String,
+    pub permissions: Vec<Permission>,
+}
+
+impl AclRule {
+    /// Whether this rule grants `permission` on `topic`
+    pub fn grants(&self, permission: Permission, topic: &str) -> bool {
+        self.permissions.contains(&permission) && glob_match(&self.pattern, topic)
+    }
+}
+
+/// Pre-shared token and topic access rules for a single Node identity
+#[derive(Clone, PartialEq, Eq)]
+pub struct Identity {
+    pub name: String,
+    token: String,
+    pub rules: Vec<AclRule>,
+}
+
+impl Identity {
+    /// Create a new identity without any topic permissions
+    pub fn new(name: impl Into<String>, token: impl Into<String>) -> Self {
+        Identity {
+            name: name.into(),
+            token: token.into(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Grant `permissions` on all topics matching the glob `pattern`
+    pub fn allow(mut self, pattern: impl Into<String>, permissions: &[Permission]) -> Self {
+        self.rules.push(AclRule {
+            pattern: pattern.into(),
+            permissions: permissions.to_vec(),
+        });
+        self
+    }
+
+    /// Grant every permission on all topics matching the glob `pattern`
+    pub fn allow_all(self, pattern: impl Into<String>) -> Self {
+        self.allow(pattern, &Permission::ALL)
+    }
+
+    /// Check a presented token against this identity's pre-shared token
+    fn verify(&self, token: &str) -> bool {
+        // Compare every byte so the time taken doesn't leak the matching prefix length
+        let (a, b) = (self.token.as_bytes(), token.as_bytes());
+        let mut diff = a.len() ^ b.len();
+        for (x, y) in a.iter().zip(b.iter()) {
+            diff |= (x ^ y) as usize;
+        }
+        diff == 0
+    }
+}
+
+impl Debug for Identity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Identity")
+            .field("name", &self.name)
+            .field("token", &"<redacted>")
+            .field("rules", &self.rules)
+            .finish()
+    }
+}
+
+/// Authentication and per-topic access control configuration for the Host
+///
+/// When assigned to a `HostConfig`, every Node must present valid `Credentials`
+/// during its handshake, and each publish, request, and subscribe operation is
+/// checked against the rules of the Node's identity.
+///
+/// UDP datagrams are only tied to a Node's identity by their source address,
+/// which can be spoofed, so publishes over UDP are refused altogether; UDP
+/// Nodes can still request and subscribe.
+#[derive(Debug, Clone, Default)]
+pub struct AuthConfig {
+    identities: HashMap<String, Identity>,
+}
+
+impl AuthConfig {
+    /// Create an empty configuration, which rejects all Nodes
+    pub fn new() -> Self {
+        AuthConfig::default()
+    }
+
+    /// Add a Node identity to the configuration, replacing any with the same name
+    pub fn with_identity(mut self, identity: Identity) -> Self {
+        self.identities.insert(identity.name.clone(), identity);
+        self
+    }
+
+    /// Verify a Node's credentials, returning the authenticated identity name
+    pub fn authenticate(&self, credentials: Option<&Credentials>) -> Result<String, Error> {
+        let credentials = credentials.ok_or(Error::Unauthenticated)?;
+        match self.identities.get(&credentials.identity) {
+            Some(identity) if identity.verify(&credentials.token) => Ok(identity.name.clone()),
+            _ => Err(Error::Unauthenticated),
+        }
+    }
+
+    /// Check whether an authenticated identity holds `permission` on `topic`
+    pub fn authorize(
+        &self,
+        identity: &str,
+        permission: Permission,
+        topic: &str,
+    ) -> Result<(), Error> {
+        let identity = self
+            .identities
+            .get(identity)
+            .ok_or(Error::Unauthenticated)?;
+        if identity
+            .rules
+            .iter()
+            .any(|rule| rule.grants(permission, topic))
+        {
+            Ok(())
+        } else {
+            Err(Error::PermissionDenied {
+                identity: identity.name.clone(),
+                permission,
+                topic: topic.to_string(),
+            })
+        }
+    }
+}
+
+/// Check a connection's identity against the Host's access rules for a given message
+///
+/// Hosts without an `AuthConfig` allow every operation.
+pub(crate) fn authorize_msg(
+    auth: Option<&AuthConfig>,
+    identity: Option<&str>,
+    msg_type: &MsgType,
+    topic: &str,
+) -> Result<(), Error> {
+    let auth = match auth {
+        Some(auth) => auth,
+        None => return Ok(()),
+    };
+    match Permission::required_for(msg_type) {
+        Some(permission) => {
+            let identity = identity.ok_or(Error::Unauthenticated)?;
+            auth.authorize(identity, permission, topic)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Match `text` against a glob `pattern` supporting `*` and `?` wildcards
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let (p, t): (Vec<char>, Vec<char>) = (pattern.chars().collect(), text.chars().collect());
+    let (mut pi, mut ti) = (0, 0);
+    // Position of the last `*` in the pattern, and the text position it was matched against
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            backtrack = Some((pi, ti));
+            pi += 1;
+        } else if let Some((star, matched)) = backtrack {
+            pi = star + 1;
+            ti = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+#[test]
+fn acl_glob_matching() {
+    assert!(glob_match("*", "anything/at/all"));
+    assert!(glob_match("motor/*", "motor/left"));
+    assert!(!glob_match("motor/*", "sensors/imu"));
+    assert!(glob_match("motor/?", "motor/1"));
+    assert!(!glob_match("motor/?", "motor/12"));
+    assert!(glob_match("*/imu", "front/imu"));
+    assert!(glob_match("pose", "pose"));
+    assert!(!glob_match("pose", "poses"));
+
+    let auth = AuthConfig::new()
+        .with_identity(Identity::new("planner", "secret").allow("motor/*", &[Permission::Publish]));
+    let name = auth
+        .authenticate(Some(&Credentials::new("planner", "secret")))
+        .unwrap();
+    assert!(auth
+        .authorize(&name, Permission::Publish, "motor/left")
+        .is_ok());
+    assert!(auth
+        .authorize(&name, Permission::Request, "motor/left")
+        .is_err());
+    assert_eq!(
+        auth.authenticate(Some(&Credentials::new("planner", "wrong"))),
+        Err(Error::Unauthenticated)
+    );
+}
@@ -0,0 +1,66 @@
+use chrono::Utc;
+use tracing::*;
+
+use std::borrow::Cow;
+
+use crate::msg::{GenericMsg, Msg, MsgType};
+
+/// Bytes to store for a message received as `bytes`, re-encoded to carry the Host's receive
+/// timestamp and trace context
+///
+/// The receive timestamp is only carried along with the message; it's still stored under its
+/// publisher's timestamp, as explained in `Dispatcher::handle`.
+pub(crate) fn stored<'a>(msg: &GenericMsg, bytes: &'a [u8]) -> Cow<'a, [u8]> {
+    match msg.as_bytes() {
+        Ok(bytes) => Cow::Owned(bytes),
+        Err(e) => {
+            error!("Unable to re-encode message on {}: {}", msg.topic, e);
+            Cow::Borrowed(bytes)
+        }
+    }
+}
+
+/// Reply to a `MsgType::Clock` request
+///
+/// The reply's data is the time the Host received the request, and its timestamp the time the
+/// reply was sent.
+pub(crate) fn reply(msg: &GenericMsg) -> GenericMsg {
+    let received = msg.received.unwrap_or_else(Utc::now);
+    match Msg::new(MsgType::Clock, "", received).to_generic() {
+        Ok(reply) => reply,
+        Err(e) => GenericMsg::error(e),
+    }
+}
+
+/// Stamp a message the Host is sending on to a Node with the time it was forwarded, if its
+/// latency is being tracked
+pub(crate) fn forward(mut msg: GenericMsg) -> GenericMsg {
+    if let Some(latency) = &mut msg.latency {
+        latency.forwarded = Some(Utc::now());
+    }
+    msg
+}
+
+/// Bytes to send on to a Node for a message stored as `bytes`, which are re-encoded with the time
+/// they were forwarded if the message's latency is being tracked
+///
+/// Messages on topics whose latency isn't `tracked` are sent on as they were stored, without
+/// being decoded.
+pub(crate) fn forwarded(bytes: &[u8], tracked: bool) -> Cow<'_, [u8]> {
+    if !tracked {
+        return Cow::Borrowed(bytes);
+    }
+    match postcard::from_bytes::<GenericMsg>(bytes) {
+        Ok(msg) if msg.latency.is_some() => {
+            let msg = forward(msg);
+            match msg.as_bytes() {
+                Ok(bytes) => Cow::Owned(bytes),
+                Err(e) => {
+                    error!("Unable to re-encode message on {}: {}", msg.topic, e);
+                    Cow::Borrowed(bytes)
+                }
+            }
+        }
+        _ => Cow::Borrowed(bytes),
+    }
+}
@@ -7,6 +7,8 @@ use tokio::sync::Mutex; // as TokioMutex;
 use std::sync::Arc;
 use std::sync::Mutex as StdMutex;
 // Misc other imports
+use crate::discovery::DiscoveryConfig;
+use crate::host::stats::StatsTracker;
 use crate::prelude::*;
 use std::result::Result;
 
@@ -21,6 +23,35 @@ pub struct HostConfig {
     pub udp_cfg: Option<host::UdpConfig>,
     #[cfg(feature = "quic")]
     pub quic_cfg: Option<host::QuicConfig>,
+    #[cfg(feature = "tls")]
+    pub tls_cfg: Option<host::TlsConfig>,
+    #[cfg(unix)]
+    pub unix_cfg: Option<host::UnixConfig>,
+    #[cfg(feature = "serial")]
+    pub serial_cfg: Option<crate::serial::SerialConfig>,
+    #[cfg(feature = "foxglove")]
+    pub foxglove_cfg: Option<host::FoxgloveConfig>,
+    #[cfg(feature = "http")]
+    pub http_cfg: Option<host::HttpConfig>,
+    #[cfg(feature = "mqtt")]
+    pub mqtt_cfg: Option<host::MqttConfig>,
+    /// Prometheus metrics endpoint the Host serves, if any
+    #[cfg(feature = "metrics")]
+    pub metrics_cfg: Option<host::MetricsConfig>,
+    /// MCAP file the Host records its topics to, if any
+    #[cfg(feature = "mcap")]
+    pub recorder_cfg: Option<host::RecorderConfig>,
+    /// Recording the Host plays back once started, if any
+    #[cfg(feature = "mcap")]
+    pub player_cfg: Option<host::PlayerConfig>,
+    /// Peer Hosts whose topics are mirrored to or from this one
+    pub peers: Vec<host::PeerConfig>,
+    /// Primary Host this one replicates as its standby, if any
+    pub replication_cfg: Option<host::ReplicationConfig>,
+    pub auth_cfg: Option<host::AuthConfig>,
+    /// Name the Host announces itself with when discovery is enabled
+    pub name: String,
+    pub discovery_cfg: Option<DiscoveryConfig>,
 }
 
 impl Default for HostConfig {
@@ -37,22 +68,39 @@ impl Default for HostConfig {
             .path(format!("./logs/{}.sled", stamp))
             .temporary(true);
 
-        #[cfg(feature = "quic")]
-        {
-            return HostConfig {
-                sled_cfg,
-                tcp_cfg: Some(host::TcpConfig::default("lo")),
-                udp_cfg: None,
-                quic_cfg: Some(host::QuicConfig::default()),
-            };
-        }
-        #[cfg(not(feature = "quic"))]
-        {
-            return HostConfig {
-                sled_cfg,
-                tcp_cfg: Some(host::TcpConfig::default("lo")),
-                udp_cfg: Some(host::UdpConfig::default("lo")),
-            };
+        HostConfig {
+            sled_cfg,
+            tcp_cfg: Some(host::TcpConfig::default("lo")),
+            // QUIC is served in place of UDP when it's enabled
+            #[cfg(feature = "quic")]
+            udp_cfg: None,
+            #[cfg(not(feature = "quic"))]
+            udp_cfg: Some(host::UdpConfig::default("lo")),
+            #[cfg(feature = "quic")]
+            quic_cfg: Some(host::QuicConfig::default()),
+            #[cfg(feature = "tls")]
+            tls_cfg: None,
+            #[cfg(unix)]
+            unix_cfg: None,
+            #[cfg(feature = "serial")]
+            serial_cfg: None,
+            #[cfg(feature = "foxglove")]
+            foxglove_cfg: None,
+            #[cfg(feature = "http")]
+            http_cfg: None,
+            #[cfg(feature = "mqtt")]
+            mqtt_cfg: None,
+            #[cfg(feature = "metrics")]
+            metrics_cfg: None,
+            #[cfg(feature = "mcap")]
+            recorder_cfg: None,
+            #[cfg(feature = "mcap")]
+            player_cfg: None,
+            peers: Vec::new(),
+            replication_cfg: None,
+            auth_cfg: None,
+            name: "meadow".into(),
+            discovery_cfg: None,
         }
     }
 }
@@ -83,14 +131,132 @@ impl HostConfig {
         self
     }
 
+    /// Assign a configuration to the Host's TLS-encrypted `TcpListener`
+    #[cfg(feature = "tls")]
+    pub fn with_tls_config(mut self, tls_cfg: Option<host::TlsConfig>) -> HostConfig {
+        self.tls_cfg = tls_cfg;
+        self
+    }
+
+    /// Assign a configuration to the Host's Unix domain socket `UnixListener`
+    #[cfg(unix)]
+    pub fn with_unix_config(mut self, unix_cfg: Option<host::UnixConfig>) -> HostConfig {
+        self.unix_cfg = unix_cfg;
+        self
+    }
+
+    /// Assign a serial device to the Host, on which it serves the Node at the other end of the link
+    #[cfg(feature = "serial")]
+    pub fn with_serial_config(
+        mut self,
+        serial_cfg: Option<crate::serial::SerialConfig>,
+    ) -> HostConfig {
+        self.serial_cfg = serial_cfg;
+        self
+    }
+
+    /// Serve the Host's topics to Foxglove Studio over WebSocket, for live visualization
+    ///
+    /// Foxglove clients can't present credentials, so Hosts with an `AuthConfig` can't serve them.
+    #[cfg(feature = "foxglove")]
+    pub fn with_foxglove_config(
+        mut self,
+        foxglove_cfg: Option<host::FoxgloveConfig>,
+    ) -> HostConfig {
+        self.foxglove_cfg = foxglove_cfg;
+        self
+    }
+
+    /// Serve the Host's topics as JSON over HTTP, for web dashboards and scripts
+    #[cfg(feature = "http")]
+    pub fn with_http_config(mut self, http_cfg: Option<host::HttpConfig>) -> HostConfig {
+        self.http_cfg = http_cfg;
+        self
+    }
+
+    /// Mirror topics between the Host and an MQTT broker
+    #[cfg(feature = "mqtt")]
+    pub fn with_mqtt_config(mut self, mqtt_cfg: Option<host::MqttConfig>) -> HostConfig {
+        self.mqtt_cfg = mqtt_cfg;
+        self
+    }
+
+    /// Serve the Host's metrics for Prometheus to scrape
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics_config(mut self, metrics_cfg: Option<host::MetricsConfig>) -> HostConfig {
+        self.metrics_cfg = metrics_cfg;
+        self
+    }
+
+    /// Record the Host's topics to an MCAP file while it runs
+    #[cfg(feature = "mcap")]
+    pub fn with_recorder_config(
+        mut self,
+        recorder_cfg: Option<host::RecorderConfig>,
+    ) -> HostConfig {
+        self.recorder_cfg = recorder_cfg;
+        self
+    }
+
+    /// Play a recording back on the Host once it's started
+    #[cfg(feature = "mcap")]
+    pub fn with_player_config(mut self, player_cfg: Option<host::PlayerConfig>) -> HostConfig {
+        self.player_cfg = player_cfg;
+        self
+    }
+
+    /// Link the Host to a peer Host, mirroring topics between them
+    pub fn with_peer(mut self, peer: host::PeerConfig) -> HostConfig {
+        self.peers.push(peer);
+        self
+    }
+
+    /// Run the Host as a standby, which replicates a primary Host and takes over if it's lost
+    pub fn with_replication_config(
+        mut self,
+        replication_cfg: Option<host::ReplicationConfig>,
+    ) -> HostConfig {
+        self.replication_cfg = replication_cfg;
+        self
+    }
+
+    /// Require Node authentication and enforce per-topic access control on the Host
+    pub fn with_auth_config(mut self, auth_cfg: Option<host::AuthConfig>) -> HostConfig {
+        self.auth_cfg = auth_cfg;
+        self
+    }
+
+    /// Set the name the Host announces itself with
+    pub fn with_name(mut self, name: impl Into<String>) -> HostConfig {
+        self.name = name.into();
+        self
+    }
+
+    /// Periodically announce the Host on a multicast group, so Nodes can discover it by name
+    pub fn with_discovery_config(mut self, discovery_cfg: Option<DiscoveryConfig>) -> HostConfig {
+        self.discovery_cfg = discovery_cfg;
+        self
+    }
+
     /// Construct a Host based on the `HostConfig`'s parameters
+    ///
+    /// Returns `Error::UnsupportedOperation` if the Host has both an `AuthConfig` and a
+    /// `FoxgloveConfig`, as Foxglove clients would bypass its access control.
     pub fn build(self) -> Result<Host, Error> {
+        #[cfg(feature = "foxglove")]
+        if self.foxglove_cfg.is_some() && self.auth_cfg.is_some() {
+            return Err(Error::UnsupportedOperation);
+        }
         let runtime = match tokio::runtime::Runtime::new() {
             Ok(runtime) => runtime,
             Err(_e) => return Err(Error::RuntimeCreation),
         };
 
         let connections = Arc::new(StdMutex::new(Vec::new()));
+        let role = match self.replication_cfg {
+            Some(_) => host::HostRole::Standby,
+            None => host::HostRole::Primary,
+        };
         let store: sled::Db = self.sled_cfg.open()?;
 
         Ok(Host {
@@ -101,7 +267,37 @@ impl HostConfig {
             task_listen_udp: None,
             #[cfg(feature = "quic")]
             task_listen_quic: None,
+            #[cfg(feature = "tls")]
+            task_listen_tls: None,
+            #[cfg(unix)]
+            task_listen_unix: None,
+            #[cfg(feature = "serial")]
+            task_listen_serial: None,
+            #[cfg(feature = "foxglove")]
+            task_listen_foxglove: None,
+            #[cfg(feature = "foxglove")]
+            foxglove_addr: None,
+            #[cfg(feature = "http")]
+            task_listen_http: None,
+            #[cfg(feature = "http")]
+            http_addr: None,
+            #[cfg(feature = "mqtt")]
+            task_mqtt: None,
+            #[cfg(feature = "metrics")]
+            task_listen_metrics: None,
+            #[cfg(feature = "metrics")]
+            metrics_addr: None,
+            #[cfg(feature = "mcap")]
+            task_recorder: None,
+            #[cfg(feature = "mcap")]
+            task_player: None,
+            tasks_peers: Vec::new(),
+            task_replication: None,
+            role: Arc::new(StdMutex::new(role)),
+            task_beacon: None,
+            local_addrs: Vec::new(),
             store,
+            stats: StatsTracker::default(),
         })
     }
 }
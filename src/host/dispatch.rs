@@ -0,0 +1,489 @@
+use chrono::Utc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::time::{sleep, Duration};
+use tracing::*;
+
+use postcard::*;
+use std::convert::TryInto;
+use std::future::Future;
+use std::result::Result;
+use std::sync::Arc;
+
+use crate::discovery::Transport;
+use crate::error::{Error, HostError, HostOperation};
+use crate::host::auth::authorize_msg;
+use crate::host::clock;
+use crate::host::stats::{ConnectionGuard, RequestGuard, StatsTracker, SubscriberGuard};
+use crate::host::{AuthConfig, Credentials, Store};
+use crate::msg::{GenericMsg, Msg, MsgType};
+use crate::stream::AsyncStream;
+use crate::trace::host_span;
+
+/// Connection to a Node that carries a single serialized `GenericMsg` in each frame
+pub(crate) trait Frames: Send {
+    /// Wait for the next frame from the Node
+    ///
+    /// Returns `Error::StreamConnection` once the Node disconnects.
+    fn read_frame(&mut self) -> impl Future<Output = Result<Vec<u8>, Error>> + Send;
+
+    /// Send a complete frame to the Node
+    fn write_frame(&mut self, bytes: &[u8]) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Wait out the interval between a subscription's messages, returning any frame the Node sent
+    /// meanwhile to end the subscription
+    ///
+    /// Nodes end their subscriptions by disconnecting unless the connection says otherwise.
+    fn wait(
+        &mut self,
+        rate: Duration,
+    ) -> impl Future<Output = Option<Result<Vec<u8>, Error>>> + Send {
+        async move {
+            sleep(rate).await;
+            None
+        }
+    }
+}
+
+/// Stream that carries a single message in each read, like the Host's TCP connections
+pub(crate) struct Unframed<S> {
+    stream: S,
+    buf: Vec<u8>,
+}
+
+impl<S: AsyncStream> Unframed<S> {
+    pub(crate) fn new(stream: S, max_buffer_size: usize) -> Self {
+        Unframed {
+            stream,
+            buf: vec![0u8; max_buffer_size],
+        }
+    }
+
+    /// Hand back the stream, such as once the Node's handshake is done
+    pub(crate) fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S: AsyncStream> Frames for Unframed<S> {
+    async fn read_frame(&mut self) -> Result<Vec<u8>, Error> {
+        match self.stream.read(&mut self.buf).await? {
+            0 => Err(Error::StreamConnection),
+            n => Ok(self.buf[..n].to_vec()),
+        }
+    }
+
+    async fn write_frame(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.stream.write_all(bytes).await?;
+        self.stream.flush().await?;
+        Ok(())
+    }
+}
+
+/// Check the credentials carried by a Node's `MsgType::Auth` message
+fn authenticate(auth: Option<&AuthConfig>, msg: &GenericMsg) -> Result<Option<String>, Error> {
+    let credentials: Option<Credentials> = from_bytes(&msg.data)?;
+    match auth {
+        Some(auth) => auth.authenticate(credentials.as_ref()).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Initiate a connection with a Node, which opens with its name and any credentials
+///
+/// Returns the Node's name along with its identity if the Host requires authentication.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn handshake<F: Frames>(
+    frames: &mut F,
+    max_name_size: usize,
+    auth: Option<&AuthConfig>,
+) -> Result<(String, Option<String>), Error> {
+    let msg: GenericMsg = from_bytes(&frames.read_frame().await?)?;
+    if msg.msg_type != MsgType::Auth {
+        return Err(Error::HostOperation(HostError::ConnectionError));
+    }
+    let mut name = msg.topic.clone();
+    name.truncate(max_name_size);
+
+    let (reply, identity) = match authenticate(auth, &msg) {
+        Err(e) => (GenericMsg::error(e.clone()), Err(e)),
+        Ok(identity) => (GenericMsg::auth(&name, None)?, Ok(identity)),
+    };
+    frames.write_frame(&reply.as_bytes()?).await?;
+
+    Ok((name, identity?))
+}
+
+/// Subscription of a Node to a topic, which is counted on the topic until it's dropped
+#[derive(Debug)]
+pub(crate) struct Subscription {
+    topic: String,
+    rate: Duration,
+    _subscriber: SubscriberGuard,
+}
+
+/// Outcome of a Node's request, before it's encoded for the Node's connection
+#[derive(Debug)]
+pub(crate) enum Reply {
+    /// Message to send to the Node
+    Msg(GenericMsg),
+    /// Message stored on a topic, as it was stored, and whether the topic's latency is tracked
+    Stored { bytes: sled::IVec, tracked: bool },
+    /// Outcome of storing a published message
+    Set(HostOperation),
+    /// Start sending the latest message on a topic to the Node
+    Subscribe(Subscription),
+    /// Nothing to send to the Node
+    None,
+}
+
+impl Reply {
+    /// Message to hand to an in-process Node, which skips encoding the reply
+    pub(crate) fn into_generic(self) -> Result<GenericMsg, Error> {
+        match self {
+            Reply::Msg(GenericMsg {
+                msg_type: MsgType::Error(e),
+                ..
+            }) => Err(e),
+            Reply::Msg(msg) => Ok(msg),
+            Reply::Stored { bytes, .. } => Ok(clock::forward(from_bytes(&bytes)?)),
+            Reply::Set(_) | Reply::Subscribe(_) | Reply::None => Err(Error::UnsupportedOperation),
+        }
+    }
+}
+
+/// Host side of a Node's connection, which handles the Node's requests against the Host's store
+///
+/// The connection is counted on its transport until the `Dispatcher` is dropped.
+#[derive(Debug)]
+pub(crate) struct Dispatcher {
+    db: sled::Db,
+    auth: Option<Arc<AuthConfig>>,
+    stats: StatsTracker,
+    transport: Transport,
+    identity: Option<String>,
+    /// Name the Node's messages are recorded as published by, if it isn't authenticated
+    peer: String,
+    /// Longest Node name kept when the Node re-authenticates, for links that carry one Node at a
+    /// time and so treat each `MsgType::Auth` as a new handshake
+    reauthenticate: Option<usize>,
+    _connection: ConnectionGuard,
+}
+
+impl Dispatcher {
+    pub(crate) fn new(
+        db: sled::Db,
+        auth: Option<Arc<AuthConfig>>,
+        stats: StatsTracker,
+        transport: Transport,
+        identity: Option<String>,
+        peer: impl Into<String>,
+    ) -> Self {
+        let _connection = stats.connect(transport);
+        Dispatcher {
+            db,
+            auth,
+            stats,
+            transport,
+            identity,
+            peer: peer.into(),
+            reauthenticate: None,
+            _connection,
+        }
+    }
+
+    /// Authenticate the Node again on each `MsgType::Auth`, keeping up to `max_name_size` of its
+    /// name
+    #[cfg(feature = "serial")]
+    pub(crate) fn reauthenticating(mut self, max_name_size: usize) -> Self {
+        self.reauthenticate = Some(max_name_size);
+        self
+    }
+
+    /// Name the Node's messages are recorded as published by
+    fn publisher(&self) -> &str {
+        self.identity.as_deref().unwrap_or(&self.peer)
+    }
+
+    /// Deserialize a message received from the Node, counting any that can't be decoded
+    fn decode(&self, bytes: &[u8]) -> Option<GenericMsg> {
+        match from_bytes(bytes) {
+            Ok(msg) => Some(msg),
+            Err(e) => {
+                error!(
+                    "Had received Msg of {} bytes: {:?}, Error: {}",
+                    bytes.len(),
+                    bytes,
+                    e
+                );
+                self.stats.decode_failed(self.transport);
+                None
+            }
+        }
+    }
+
+    /// Stamp a message with the time the Host received it, and time its request until the
+    /// returned guard is dropped once the request has been replied to
+    pub(crate) fn receive(&self, msg: &mut GenericMsg) -> RequestGuard {
+        info!("{:?}", msg.msg_type);
+        msg.received = Some(Utc::now());
+        self.stats.time_request(&msg.msg_type)
+    }
+
+    /// Handle a request received from the Node
+    pub(crate) fn handle(&self, mut msg: GenericMsg) -> Reply {
+        let _span = host_span(&mut msg).entered();
+
+        if let Err(e) = authorize_msg(
+            self.auth.as_deref(),
+            self.identity.as_deref(),
+            &msg.msg_type,
+            &msg.topic,
+        ) {
+            warn!("{}", e);
+            return Reply::Msg(GenericMsg::error(e));
+        }
+        self.stats
+            .count_request(&self.db, &msg.msg_type, &msg.topic);
+
+        match &msg.msg_type {
+            MsgType::Subscribe => match TryInto::<Msg<Duration>>::try_into(msg.clone()) {
+                Ok(specialized) => Reply::Subscribe(Subscription {
+                    _subscriber: self.stats.subscribe(&self.db, &msg.topic),
+                    topic: msg.topic,
+                    rate: specialized.data,
+                }),
+                Err(e) => Reply::Msg(GenericMsg::error(e)),
+            },
+            MsgType::Set => {
+                // Messages are keyed by their publisher's timestamp rather than the time the Host
+                // received them, so a topic's history stays in the order it was published even
+                // when messages are delayed in transit, and stays keyed the same when it's
+                // replicated, bridged or replayed. Nodes that need the Host's clock publish
+                // corrected timestamps `with_clock_correction`.
+                let stored = msg.as_bytes().map_err(Error::from).and_then(|bytes| {
+                    let tree = self.db.open_tree(msg.topic.as_bytes())?;
+                    tree.insert(msg.timestamp.to_string().as_bytes(), bytes)?;
+                    Ok(())
+                });
+                match stored {
+                    Ok(()) => {
+                        self.stats.record(&msg, self.publisher());
+                        Reply::Set(HostOperation::SUCCESS)
+                    }
+                    Err(e) => {
+                        error!("{:?}", e);
+                        Reply::Set(HostOperation::FAILURE)
+                    }
+                }
+            }
+            MsgType::Get => self.latest(&msg.topic),
+            MsgType::GetNth(n) => match self
+                .db
+                .open_tree(msg.topic.as_bytes())
+                .map(|tree| tree.iter().nth_back(*n))
+            {
+                Ok(Some(Ok((_timestamp, bytes)))) => Reply::Stored {
+                    bytes,
+                    tracked: self.stats.tracks_latency(&msg.topic),
+                },
+                _ => Reply::Msg(GenericMsg::error(Error::NoNthValue)),
+            },
+            MsgType::Stats => Reply::Msg(self.stats.reply(&self.db, &msg.topic)),
+            MsgType::Clock => Reply::Msg(clock::reply(&msg)),
+            MsgType::Topics => {
+                let topics = self.db.topics().and_then(|topics| {
+                    let mut packet = GenericMsg::topics();
+                    packet.set_data(to_allocvec(&topics)?);
+                    Ok(packet)
+                });
+                match topics {
+                    Ok(packet) => Reply::Msg(packet),
+                    Err(e) => Reply::Msg(GenericMsg::error(e)),
+                }
+            }
+            // Nodes authenticate during the handshake, so only acknowledge the message
+            MsgType::Auth => match GenericMsg::auth(&msg.topic, None) {
+                Ok(ack) => Reply::Msg(ack),
+                Err(e) => Reply::Msg(GenericMsg::error(e)),
+            },
+            // Standbys only replicate Hosts over TCP
+            MsgType::Replicate => Reply::Msg(GenericMsg::error(Error::UnsupportedOperation)),
+            MsgType::Error(e) => {
+                error!("Received {}", e);
+                Reply::None
+            }
+        }
+    }
+
+    /// Authenticate the Node again from its `MsgType::Auth` message
+    ///
+    /// A Node that fails to authenticate loses the identity it had authenticated as before.
+    fn authenticate(&mut self, msg: &GenericMsg, max_name_size: usize) -> Reply {
+        let mut name = msg.topic.clone();
+        name.truncate(max_name_size);
+        debug!(
+            "Host received {:?} connection from {:?}",
+            self.transport, &name
+        );
+        match authenticate(self.auth.as_deref(), msg) {
+            Ok(identity) => {
+                self.identity = identity;
+                self.stats
+                    .count_request(&self.db, &msg.msg_type, &msg.topic);
+                match GenericMsg::auth(&name, None) {
+                    Ok(ack) => Reply::Msg(ack),
+                    Err(e) => Reply::Msg(GenericMsg::error(e)),
+                }
+            }
+            Err(e) => {
+                warn!("{:?} handshake from {} failed: {}", self.transport, name, e);
+                self.stats.handshake_failed(self.transport);
+                self.identity = None;
+                Reply::Msg(GenericMsg::error(e))
+            }
+        }
+    }
+
+    /// Last message on a topic, for a request or subscription that has already been authorized
+    fn latest(&self, topic: &str) -> Reply {
+        match self.db.open_tree(topic.as_bytes()).map(|tree| tree.last()) {
+            Ok(Ok(Some((_timestamp, bytes)))) => Reply::Stored {
+                bytes,
+                tracked: self.stats.tracks_latency(topic),
+            },
+            _ => Reply::Msg(GenericMsg::error(Error::NonExistentTopic(
+                topic.to_string(),
+            ))),
+        }
+    }
+
+    /// Serialize a reply for the Node's connection, if there's anything to send
+    fn encode(&self, reply: Reply) -> Option<Vec<u8>> {
+        let bytes = match reply {
+            Reply::Msg(msg) => msg.as_bytes().map_err(Error::from),
+            Reply::Stored { bytes, tracked } => Ok(clock::forwarded(&bytes, tracked).into_owned()),
+            Reply::Set(operation) => to_allocvec(&operation).map_err(Error::from),
+            Reply::Subscribe(_) | Reply::None => return None,
+        };
+        match bytes {
+            Ok(bytes) => Some(bytes),
+            Err(e) => {
+                error!("{:?}", e);
+                None
+            }
+        }
+    }
+
+    /// Store a message published by an in-process Node
+    pub(crate) fn publish(&self, mut msg: GenericMsg) -> Result<(), Error> {
+        let _request = self.receive(&mut msg);
+        match self.handle(msg) {
+            Reply::Set(HostOperation::SUCCESS) => Ok(()),
+            Reply::Set(HostOperation::FAILURE) => Err(Error::HostOperation(HostError::SetFailure)),
+            reply => reply.into_generic().map(drop),
+        }
+    }
+
+    /// Reply to an in-process Node's request
+    pub(crate) fn request(&self, mut msg: GenericMsg) -> Result<GenericMsg, Error> {
+        let _request = self.receive(&mut msg);
+        self.handle(msg).into_generic()
+    }
+
+    /// Subscribe an in-process Node to a topic, which it then polls with `Dispatcher::next`
+    pub(crate) fn subscribe(&self, mut msg: GenericMsg) -> Result<Subscription, Error> {
+        let _request = self.receive(&mut msg);
+        match self.handle(msg) {
+            Reply::Subscribe(subscription) => Ok(subscription),
+            reply => reply.into_generic().and(Err(Error::UnsupportedOperation)),
+        }
+    }
+
+    /// Latest message for an in-process Node's subscription
+    pub(crate) fn next(&self, subscription: &Subscription) -> Result<GenericMsg, Error> {
+        self.latest(&subscription.topic).into_generic()
+    }
+}
+
+/// Serve a Node's requests over its connection until it disconnects
+#[tracing::instrument(skip_all)]
+pub(crate) async fn serve<F: Frames>(mut frames: F, mut dispatcher: Dispatcher) {
+    // Frame that ended a subscription, which still needs a reply
+    let mut next: Option<Vec<u8>> = None;
+    loop {
+        let bytes = match next.take() {
+            Some(bytes) => bytes,
+            None => match frames.read_frame().await {
+                Ok(bytes) => bytes,
+                Err(Error::StreamConnection) => break,
+                Err(e) => {
+                    error!(
+                        "Error reading from {:?} connection: {:?}",
+                        dispatcher.transport, e
+                    );
+                    break;
+                }
+            },
+        };
+
+        let mut msg = match dispatcher.decode(&bytes) {
+            Some(msg) => msg,
+            None => continue,
+        };
+        let _request = dispatcher.receive(&mut msg);
+
+        let reply = match dispatcher.reauthenticate {
+            Some(max_name_size) if msg.msg_type == MsgType::Auth => {
+                dispatcher.authenticate(&msg, max_name_size)
+            }
+            _ => dispatcher.handle(msg),
+        };
+        let reply = match reply {
+            Reply::Subscribe(subscription) => {
+                match run_subscription(&mut frames, &dispatcher, subscription).await {
+                    Ok(frame) => {
+                        next = Some(frame);
+                        continue;
+                    }
+                    Err(Error::StreamConnection) => break,
+                    Err(e) => {
+                        error!(
+                            "Error on {:?} connection during subscription: {:?}",
+                            dispatcher.transport, e
+                        );
+                        break;
+                    }
+                }
+            }
+            reply => dispatcher.encode(reply),
+        };
+
+        if let Some(bytes) = reply {
+            if let Err(e) = frames.write_frame(&bytes).await {
+                error!(
+                    "Error sending data back on {:?} connection: {:?}",
+                    dispatcher.transport, e
+                );
+                break;
+            }
+        }
+    }
+}
+
+/// Send the latest message on a topic to the Node at the requested rate
+///
+/// The subscription lasts until the Node sends another frame, which is returned to be handled.
+async fn run_subscription<F: Frames>(
+    frames: &mut F,
+    dispatcher: &Dispatcher,
+    subscription: Subscription,
+) -> Result<Vec<u8>, Error> {
+    loop {
+        if let Some(bytes) = dispatcher.encode(dispatcher.latest(&subscription.topic)) {
+            frames.write_frame(&bytes).await?;
+        }
+        if let Some(frame) = frames.wait(subscription.rate).await {
+            return frame;
+        }
+    }
+}
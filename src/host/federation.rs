@@ -0,0 +1,337 @@
+use postcard::from_bytes;
+use tokio::net::TcpStream;
+use tokio::sync::watch;
+use tokio::task::JoinSet;
+use tokio::time::{sleep, timeout, Duration};
+use tracing::*;
+
+use std::net::SocketAddr;
+use std::result::Result;
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::host::host::GenericStore;
+use crate::node::check_publish_ack;
+use crate::node::tcp::{exchange, handshake};
+use crate::prelude::*;
+
+/// Direction messages are mirrored in across a peer link
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BridgeDirection {
+    /// Messages set on the peer's topic are set on the local Host
+    Pull,
+    /// Messages set on the local Host's topic are set on the peer
+    Push,
+}
+
+/// Pairing of a local topic with a topic on a peer Host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicBridge {
+    pub local_topic: String,
+    pub remote_topic: String,
+    pub direction: BridgeDirection,
+    /// Shortest time between mirrored messages; messages set in between are skipped, apart from
+    /// the latest, which is mirrored once the interval has elapsed
+    pub min_interval: Duration,
+}
+
+impl TopicBridge {
+    /// Mirror messages set on the peer's `remote_topic` to the local `local_topic`
+    pub fn pull(remote_topic: impl Into<String>, local_topic: impl Into<String>) -> Self {
+        TopicBridge {
+            local_topic: local_topic.into(),
+            remote_topic: remote_topic.into(),
+            direction: BridgeDirection::Pull,
+            min_interval: Duration::ZERO,
+        }
+    }
+
+    /// Mirror messages set on the local `local_topic` to the peer's `remote_topic`
+    pub fn push(local_topic: impl Into<String>, remote_topic: impl Into<String>) -> Self {
+        TopicBridge {
+            local_topic: local_topic.into(),
+            remote_topic: remote_topic.into(),
+            direction: BridgeDirection::Push,
+            min_interval: Duration::ZERO,
+        }
+    }
+
+    /// Limit the rate at which the bridge mirrors messages
+    pub fn set_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+}
+
+/// Configuration of the Host's link to a peer Host, over which it mirrors selected topics
+///
+/// The link connects to the peer's TCP listener like a Node, and reconnects whenever either Host
+/// restarts. Mirrored messages keep their original timestamps, so a message that comes back
+/// around a cycle of bridges is recognised and isn't mirrored again.
+#[derive(Debug, Clone)]
+pub struct PeerConfig {
+    /// Address of the peer Host's TCP listener
+    pub host_addr: SocketAddr,
+    /// Name the link presents to the peer Host
+    pub name: String,
+    /// Prefix added to the destination topic of every bridge, as `{namespace}/{topic}`
+    pub namespace: Option<String>,
+    /// Interval at which pulled topics are checked for new messages
+    pub poll_interval: Duration,
+    /// Longest wait for the peer to reply before the connection is considered lost
+    pub timeout: Duration,
+    /// How long the link waits before reconnecting to an unreachable peer
+    pub retry_interval: Duration,
+    /// Largest message mirrored from the peer, in bytes
+    pub max_buffer_size: usize,
+    credentials: Option<Credentials>,
+    bridges: Vec<TopicBridge>,
+}
+
+impl PeerConfig {
+    /// Link to the Host listening for TCP connections at the given address
+    pub fn new(host_addr: impl Into<SocketAddr>) -> Self {
+        PeerConfig {
+            host_addr: host_addr.into(),
+            name: "federation".into(),
+            namespace: None,
+            poll_interval: Duration::from_millis(50),
+            timeout: Duration::from_secs(5),
+            retry_interval: Duration::from_secs(1),
+            max_buffer_size: 10_000,
+            credentials: None,
+            bridges: Vec::new(),
+        }
+    }
+
+    /// Set the name the link presents to the peer Host
+    pub fn set_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Prefix the destination topic of every bridge with a namespace
+    pub fn set_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Set the interval at which pulled topics are checked for new messages
+    pub fn set_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Set the longest wait for the peer to reply before the connection is considered lost
+    pub fn set_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set how long the link waits before reconnecting to an unreachable peer
+    pub fn set_retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = retry_interval;
+        self
+    }
+
+    /// Set the largest message mirrored from the peer, in bytes
+    pub fn set_max_buffer_size(mut self, max_buffer_size: usize) -> Self {
+        self.max_buffer_size = max_buffer_size;
+        self
+    }
+
+    /// Present credentials to a peer Host that requires authentication
+    pub fn set_credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Mirror a topic across the link
+    pub fn with_bridge(mut self, bridge: TopicBridge) -> Self {
+        self.bridges.push(bridge);
+        self
+    }
+
+    /// Name of a bridge's destination topic, including the link's namespace
+    fn destination(&self, bridge: &TopicBridge) -> String {
+        let topic = match bridge.direction {
+            BridgeDirection::Pull => &bridge.local_topic,
+            BridgeDirection::Push => &bridge.remote_topic,
+        };
+        match &self.namespace {
+            Some(namespace) => format!("{}/{}", namespace, topic),
+            None => topic.clone(),
+        }
+    }
+}
+
+/// Run the link to a peer Host, with each bridge on its own connection
+pub(crate) async fn link(db: sled::Db, cfg: Arc<PeerConfig>) {
+    // Dropped along with the link, which stops the bridges
+    let mut tasks = JoinSet::new();
+    for bridge in &cfg.bridges {
+        let (db, cfg, bridge) = (db.clone(), cfg.clone(), bridge.clone());
+        match bridge.direction {
+            BridgeDirection::Pull => tasks.spawn(pull(db, cfg, bridge)),
+            BridgeDirection::Push => tasks.spawn(push(db, cfg, bridge)),
+        };
+    }
+    while tasks.join_next().await.is_some() {}
+}
+
+/// Connect and authenticate with the peer, retrying until it's reachable
+async fn connect(cfg: &PeerConfig) -> TcpStream {
+    loop {
+        let connection = async {
+            let stream = TcpStream::connect(cfg.host_addr).await?;
+            handshake(stream, cfg.name.clone(), cfg.credentials.as_ref()).await
+        };
+        match timeout(cfg.timeout, connection).await {
+            Ok(Ok(stream)) => {
+                info!("Connected to peer Host at {}", cfg.host_addr);
+                return stream;
+            }
+            Ok(Err(e)) => debug!("Unable to connect to peer {}: {}", cfg.host_addr, e),
+            Err(_elapsed) => debug!("Timed out connecting to peer {}", cfg.host_addr),
+        }
+        sleep(cfg.retry_interval).await;
+    }
+}
+
+/// Send a request to the peer and wait for its reply
+async fn request(
+    cfg: &PeerConfig,
+    stream: &TcpStream,
+    msg: &GenericMsg,
+    buf: &mut [u8],
+) -> Result<usize, Error> {
+    let packet = msg.as_bytes()?;
+    match timeout(cfg.timeout, exchange(stream, &packet, buf)).await {
+        Ok(result) => result,
+        Err(_elapsed) => Err(Error::StreamConnection),
+    }
+}
+
+/// Mirror new messages on the peer's topic to the local Host, by polling for its latest message
+async fn pull(db: sled::Db, cfg: Arc<PeerConfig>, bridge: TopicBridge) {
+    let destination = cfg.destination(&bridge);
+    let tree = match db.open_tree(destination.as_bytes()) {
+        Ok(tree) => tree,
+        Err(e) => return error!("Unable to pull into {}: {}", destination, e),
+    };
+    let interval = cfg.poll_interval.max(bridge.min_interval);
+    let mut buf = vec![0u8; cfg.max_buffer_size];
+    loop {
+        let stream = connect(&cfg).await;
+        loop {
+            let msg = GenericMsg::get::<()>(bridge.remote_topic.clone());
+            let n = match request(&cfg, &stream, &msg, &mut buf).await {
+                Ok(n) => n,
+                Err(e) => {
+                    warn!("Lost connection to peer {}: {}", cfg.host_addr, e);
+                    break;
+                }
+            };
+            // Topics the peer doesn't have yet aren't replied to with a message
+            match from_bytes::<GenericMsg>(&buf[..n]) {
+                Ok(GenericMsg {
+                    msg_type: MsgType::Error(e),
+                    ..
+                }) => {
+                    warn!("Peer refused to share {}: {}", bridge.remote_topic, e);
+                    sleep(cfg.retry_interval).await;
+                    continue;
+                }
+                Ok(mut generic) => {
+                    // Messages the Host already holds have been mirrored before, or originated
+                    // here and came back around a cycle of bridges
+                    let key = generic.timestamp.to_string();
+                    if !matches!(tree.contains_key(key.as_bytes()), Ok(true)) {
+                        generic.msg_type = MsgType::Set;
+                        generic.topic = destination.clone();
+                        if let Err(e) = db.clone().insert_generic(generic) {
+                            error!("Unable to pull into {}: {}", destination, e);
+                        }
+                    }
+                }
+                Err(_) => (),
+            }
+            sleep(interval).await;
+        }
+        sleep(cfg.retry_interval).await;
+    }
+}
+
+/// Mirror messages set on the local Host's topic to the peer, as they're set
+async fn push(db: sled::Db, cfg: Arc<PeerConfig>, bridge: TopicBridge) {
+    let tree = match db.open_tree(bridge.local_topic.as_bytes()) {
+        Ok(tree) => tree,
+        Err(e) => return error!("Unable to push {}: {}", bridge.local_topic, e),
+    };
+
+    // Only the latest message waiting to be pushed is kept, so that neither a slow peer nor the
+    // rate limit holds up the Host's writes
+    let (tx, mut rx) = watch::channel::<Option<sled::IVec>>(None);
+    let mut subscriber = tree.watch_prefix(vec![]);
+    // Dropped along with the bridge, which stops the watcher
+    let mut watcher = JoinSet::new();
+    watcher.spawn(async move {
+        while let Some(event) = (&mut subscriber).await {
+            if let sled::Event::Insert { value, .. } = event {
+                tx.send_replace(Some(value));
+            }
+        }
+    });
+
+    let destination = cfg.destination(&bridge);
+    let mut buf = vec![0u8; 1024];
+    loop {
+        let stream = connect(&cfg).await;
+        // The latest message is pushed on every connection, in case the peer restarted without it
+        // or the connection was lost while pushing it
+        let mut pending = tree.last().ok().flatten().map(|(_key, value)| value);
+        let mut last_pushed = None;
+        loop {
+            let value = match pending.take() {
+                Some(value) => value,
+                None => {
+                    if rx.changed().await.is_err() {
+                        return;
+                    }
+                    match rx.borrow_and_update().clone() {
+                        Some(value) => value,
+                        None => continue,
+                    }
+                }
+            };
+            let mut generic = match from_bytes::<GenericMsg>(&value) {
+                Ok(generic) => generic,
+                Err(e) => {
+                    warn!("Unable to push {}: {}", bridge.local_topic, e);
+                    continue;
+                }
+            };
+            // Messages that come back around a cycle of bridges aren't pushed again
+            if matches!(last_pushed, Some(last) if generic.timestamp <= last) {
+                continue;
+            }
+            let timestamp = generic.timestamp;
+            generic.msg_type = MsgType::Set;
+            generic.topic = destination.clone();
+            match request(&cfg, &stream, &generic, &mut buf).await {
+                Ok(n) => {
+                    if let Err(e) = check_publish_ack(&buf[..n]) {
+                        warn!("Peer refused {}: {}", destination, e);
+                    }
+                    last_pushed = Some(timestamp);
+                }
+                Err(e) => {
+                    warn!("Lost connection to peer {}: {}", cfg.host_addr, e);
+                    break;
+                }
+            }
+            sleep(bridge.min_interval).await;
+        }
+        sleep(cfg.retry_interval).await;
+    }
+}
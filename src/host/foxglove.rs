@@ -0,0 +1,445 @@
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration};
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use tokio_tungstenite::tungstenite::http::header::SEC_WEBSOCKET_PROTOCOL;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::WebSocketStream;
+use tracing::*;
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::result::Result;
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::host::host::GenericStore;
+use crate::host::json::{json_error, msg_json, JsonCodec};
+use crate::host::Store;
+use crate::prelude::*;
+
+/// WebSocket subprotocol spoken by Foxglove Studio
+const SUBPROTOCOL: &str = "foxglove.websocket.v1";
+/// How often the Host's topics are checked for new channels to advertise
+const ADVERTISE_INTERVAL: Duration = Duration::from_millis(500);
+/// Schema of the JSON sent for topics without a registered schema
+const RAW_SCHEMA: &str = r#"{"type":"object","properties":{"topic":{"type":"string"},"timestamp":{"type":"string"},"data_type":{"type":"string"},"data":{"type":"array","items":{"type":"integer"}}}}"#;
+const RAW_SCHEMA_NAME: &str = "meadow.GenericMsg";
+/// Opcode of binary messages carrying message data, in both directions
+const MESSAGE_DATA: u8 = 0x01;
+
+/// JSON schema of a topic, and conversions between its messages and JSON
+#[derive(Debug, Clone)]
+struct Schema {
+    name: String,
+    schema: String,
+    codec: JsonCodec,
+}
+
+/// Configuration of the Host's Foxglove WebSocket server, for live visualization in Foxglove
+/// Studio
+///
+/// Every topic on the Host is advertised to connected clients. Messages on topics with a
+/// registered schema are decoded to JSON, while others are sent as their raw bytes. Clients
+/// aren't authenticated, so Hosts with an `AuthConfig` refuse to serve them.
+#[derive(Debug, Clone)]
+pub struct FoxgloveConfig {
+    /// Address the WebSocket server listens on
+    pub bind_addr: SocketAddr,
+    /// Name the server reports to clients
+    pub name: String,
+    /// Whether clients may publish to topics with a registered schema
+    pub client_publish: bool,
+    schemas: HashMap<String, Schema>,
+}
+
+impl Default for FoxgloveConfig {
+    /// Listen on `127.0.0.1:8765`, Foxglove Studio's default, without client publishing
+    fn default() -> Self {
+        FoxgloveConfig {
+            bind_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8765),
+            name: "meadow".into(),
+            client_publish: false,
+            schemas: HashMap::new(),
+        }
+    }
+}
+
+impl FoxgloveConfig {
+    /// Set the address the WebSocket server listens on
+    pub fn set_bind_addr(mut self, bind_addr: impl Into<SocketAddr>) -> Self {
+        self.bind_addr = bind_addr.into();
+        self
+    }
+
+    /// Set the name the server reports to clients
+    pub fn set_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Allow clients to publish to topics with a registered schema
+    pub fn set_client_publish(mut self, client_publish: bool) -> Self {
+        self.client_publish = client_publish;
+        self
+    }
+
+    /// Register the type of a topic's messages, with its JSON schema, so they can be decoded
+    /// to JSON
+    pub fn with_schema<T: Message + 'static>(
+        mut self,
+        topic: impl Into<String>,
+        schema_name: impl Into<String>,
+        json_schema: impl Into<String>,
+    ) -> Self {
+        let schema = Schema {
+            name: schema_name.into(),
+            schema: json_schema.into(),
+            codec: JsonCodec::new::<T>(),
+        };
+        self.schemas.insert(topic.into(), schema);
+        self
+    }
+}
+
+fn ws_error(e: tokio_tungstenite::tungstenite::Error) -> Error {
+    std::io::Error::other(e).into()
+}
+
+/// Accept Foxglove clients on the Host's WebSocket listener
+pub(crate) async fn serve(listener: TcpListener, db: sled::Db, cfg: Arc<FoxgloveConfig>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, addr)) => {
+                debug!("Host received Foxglove connection from {}", addr);
+                let (db, cfg) = (db.clone(), cfg.clone());
+                tokio::spawn(async move {
+                    if let Err(e) = process_client(stream, db, cfg).await {
+                        warn!("Foxglove client {} disconnected: {}", addr, e);
+                    }
+                });
+            }
+            Err(e) => error!("Error accepting Foxglove connection: {}", e),
+        }
+    }
+}
+
+// The handshake callback's error type is fixed by tungstenite
+#[allow(clippy::result_large_err)]
+async fn process_client(
+    stream: TcpStream,
+    db: sled::Db,
+    cfg: Arc<FoxgloveConfig>,
+) -> Result<(), Error> {
+    let mut ws =
+        tokio_tungstenite::accept_hdr_async(stream, |request: &Request, mut response: Response| {
+            let offered = request
+                .headers()
+                .get(SEC_WEBSOCKET_PROTOCOL)
+                .and_then(|protocols| protocols.to_str().ok())
+                .map(|protocols| protocols.split(',').any(|p| p.trim() == SUBPROTOCOL))
+                .unwrap_or(false);
+            if offered {
+                response.headers_mut().insert(
+                    SEC_WEBSOCKET_PROTOCOL,
+                    HeaderValue::from_static(SUBPROTOCOL),
+                );
+            }
+            Ok(response)
+        })
+        .await
+        .map_err(ws_error)?;
+
+    let mut session = Session::new(db, cfg);
+    send(&mut ws, session.server_info()).await?;
+
+    let (tx, mut rx) = mpsc::channel::<(u32, sled::IVec)>(1024);
+    let mut advertise = interval(ADVERTISE_INTERVAL);
+    loop {
+        let replies = tokio::select! {
+            msg = ws.next() => match msg {
+                None | Some(Ok(WsMessage::Close(_))) => break,
+                Some(Err(e)) => return Err(ws_error(e)),
+                Some(Ok(WsMessage::Text(text))) => session.handle_text(&text, &tx),
+                Some(Ok(WsMessage::Binary(bytes))) => session.handle_binary(&bytes),
+                Some(Ok(_)) => Vec::new(),
+            },
+            Some((subscription_id, value)) = rx.recv() => {
+                session.message_data(subscription_id, &value).into_iter().collect()
+            }
+            _ = advertise.tick() => session.advertise()?,
+        };
+        for reply in replies {
+            send(&mut ws, reply).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn send(ws: &mut WebSocketStream<TcpStream>, msg: WsMessage) -> Result<(), Error> {
+    ws.send(msg).await.map_err(ws_error)
+}
+
+fn status(level: u8, message: impl Into<String>) -> WsMessage {
+    let message: String = message.into();
+    warn!("Foxglove client: {}", message);
+    WsMessage::Text(json!({"op": "status", "level": level, "message": message}).to_string())
+}
+
+/// State of a single Foxglove client connection
+struct Session {
+    db: sled::Db,
+    cfg: Arc<FoxgloveConfig>,
+    /// Channel advertised for each topic
+    channels: HashMap<String, u32>,
+    next_channel_id: u32,
+    /// Topic and forwarding task of each of the client's subscriptions
+    subscriptions: HashMap<u32, (String, JoinHandle<()>)>,
+    /// Topic of each channel the client has advertised for publishing
+    client_channels: HashMap<u32, String>,
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        for (_topic, task) in self.subscriptions.values() {
+            task.abort();
+        }
+    }
+}
+
+impl Session {
+    fn new(db: sled::Db, cfg: Arc<FoxgloveConfig>) -> Self {
+        Session {
+            db,
+            cfg,
+            channels: HashMap::new(),
+            next_channel_id: 1,
+            subscriptions: HashMap::new(),
+            client_channels: HashMap::new(),
+        }
+    }
+
+    fn server_info(&self) -> WsMessage {
+        let capabilities: Vec<&str> = match self.cfg.client_publish {
+            true => vec!["clientPublish"],
+            false => vec![],
+        };
+        WsMessage::Text(
+            json!({
+                "op": "serverInfo",
+                "name": self.cfg.name,
+                "capabilities": capabilities,
+                "supportedEncodings": ["json"],
+                "metadata": {},
+                "sessionId": chrono::Utc::now().timestamp_millis().to_string(),
+            })
+            .to_string(),
+        )
+    }
+
+    /// Advertise topics that have appeared on the Host since the last check, and unadvertise
+    /// any that have been removed
+    fn advertise(&mut self) -> Result<Vec<WsMessage>, Error> {
+        let topics = self.db.topics()?;
+        let mut replies = Vec::new();
+
+        let mut channels = Vec::new();
+        for topic in &topics {
+            if self.channels.contains_key(topic) {
+                continue;
+            }
+            let id = self.next_channel_id;
+            self.next_channel_id += 1;
+            self.channels.insert(topic.clone(), id);
+            let (schema_name, schema) = match self.cfg.schemas.get(topic) {
+                Some(schema) => (schema.name.as_str(), schema.schema.as_str()),
+                None => (RAW_SCHEMA_NAME, RAW_SCHEMA),
+            };
+            channels.push(json!({
+                "id": id,
+                "topic": topic,
+                "encoding": "json",
+                "schemaName": schema_name,
+                "schema": schema,
+                "schemaEncoding": "jsonschema",
+            }));
+        }
+        if !channels.is_empty() {
+            replies.push(WsMessage::Text(
+                json!({"op": "advertise", "channels": channels}).to_string(),
+            ));
+        }
+
+        let removed: Vec<String> = self
+            .channels
+            .keys()
+            .filter(|topic| !topics.contains(topic))
+            .cloned()
+            .collect();
+        if !removed.is_empty() {
+            let ids: Vec<u32> = removed
+                .iter()
+                .filter_map(|topic| self.channels.remove(topic))
+                .collect();
+            replies.push(WsMessage::Text(
+                json!({"op": "unadvertise", "channelIds": ids}).to_string(),
+            ));
+        }
+        Ok(replies)
+    }
+
+    fn handle_text(&mut self, text: &str, tx: &mpsc::Sender<(u32, sled::IVec)>) -> Vec<WsMessage> {
+        let request: Value = match serde_json::from_str(text) {
+            Ok(request) => request,
+            Err(e) => return vec![status(2, format!("Invalid JSON: {}", e))],
+        };
+        let ids = |key: &str| -> Vec<u32> {
+            request[key]
+                .as_array()
+                .map(|ids| {
+                    ids.iter()
+                        .filter_map(|id| id.as_u64().map(|id| id as u32))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let mut replies = Vec::new();
+        match request["op"].as_str() {
+            Some("subscribe") => {
+                for subscription in request["subscriptions"].as_array().into_iter().flatten() {
+                    let (id, channel_id) = match (
+                        subscription["id"].as_u64(),
+                        subscription["channelId"].as_u64(),
+                    ) {
+                        (Some(id), Some(channel_id)) => (id as u32, channel_id as u32),
+                        _ => continue,
+                    };
+                    match self.subscribe(id, channel_id, tx) {
+                        Ok(()) => (),
+                        Err(e) => replies.push(status(2, e.to_string())),
+                    }
+                }
+            }
+            Some("unsubscribe") => {
+                for id in ids("subscriptionIds") {
+                    if let Some((_topic, task)) = self.subscriptions.remove(&id) {
+                        task.abort();
+                    }
+                }
+            }
+            Some("advertise") if !self.cfg.client_publish => {
+                replies.push(status(2, "Client publishing is not enabled on this Host"));
+            }
+            Some("advertise") => {
+                for channel in request["channels"].as_array().into_iter().flatten() {
+                    let (id, topic) = match (channel["id"].as_u64(), channel["topic"].as_str()) {
+                        (Some(id), Some(topic)) => (id as u32, topic),
+                        _ => continue,
+                    };
+                    if channel["encoding"].as_str() != Some("json") {
+                        replies.push(status(2, format!("Channel {} must be JSON-encoded", id)));
+                        continue;
+                    }
+                    self.client_channels.insert(id, topic.to_string());
+                }
+            }
+            Some("unadvertise") => {
+                for id in ids("channelIds") {
+                    self.client_channels.remove(&id);
+                }
+            }
+            op => replies.push(status(1, format!("Unsupported operation {:?}", op))),
+        }
+        replies
+    }
+
+    /// Forward each message `Set` on a channel's topic to the client
+    fn subscribe(
+        &mut self,
+        id: u32,
+        channel_id: u32,
+        tx: &mpsc::Sender<(u32, sled::IVec)>,
+    ) -> Result<(), Error> {
+        let topic = match self.channels.iter().find(|(_topic, c)| **c == channel_id) {
+            Some((topic, _channel_id)) => topic.clone(),
+            None => return Err(Error::NonExistentTopic(format!("channel {}", channel_id))),
+        };
+
+        let mut subscriber = self.db.open_tree(topic.as_bytes())?.watch_prefix(vec![]);
+        let tx = tx.clone();
+        let task = tokio::spawn(async move {
+            while let Some(event) = (&mut subscriber).await {
+                if let sled::Event::Insert { value, .. } = event {
+                    // Drop messages for slow clients rather than holding up the Host's writes
+                    if tx.try_send((id, value)).is_err() {
+                        debug!("Dropped Foxglove message for subscription {}", id);
+                    }
+                }
+            }
+        });
+        if let Some((_topic, previous)) = self.subscriptions.insert(id, (topic, task)) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    /// Binary message carrying a stored message to the client, decoded to JSON
+    fn message_data(&self, subscription_id: u32, value: &[u8]) -> Option<WsMessage> {
+        let (topic, _task) = self.subscriptions.get(&subscription_id)?;
+        let generic: GenericMsg = postcard::from_bytes(value).ok()?;
+        let timestamp = generic.timestamp.timestamp_nanos_opt().unwrap_or(0) as u64;
+
+        let payload = match self.cfg.schemas.get(topic) {
+            Some(schema) => schema.codec.decode(generic),
+            None => msg_json(generic, None),
+        };
+        let payload = match payload {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!("Unable to decode message on {} to JSON: {}", topic, e);
+                return None;
+            }
+        };
+
+        let mut frame = vec![MESSAGE_DATA];
+        frame.extend_from_slice(&subscription_id.to_le_bytes());
+        frame.extend_from_slice(&timestamp.to_le_bytes());
+        frame.extend_from_slice(payload.to_string().as_bytes());
+        Some(WsMessage::Binary(frame))
+    }
+
+    /// Store a message published by the client on one of its advertised channels
+    fn handle_binary(&mut self, bytes: &[u8]) -> Vec<WsMessage> {
+        if bytes.len() < 5 || bytes[0] != MESSAGE_DATA {
+            return vec![status(2, "Unsupported binary message")];
+        }
+        let channel_id = u32::from_le_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]);
+        let topic = match self.client_channels.get(&channel_id) {
+            Some(topic) => topic,
+            None => {
+                return vec![status(
+                    2,
+                    format!("Channel {} is not advertised", channel_id),
+                )]
+            }
+        };
+        let schema = match self.cfg.schemas.get(topic) {
+            Some(schema) => schema,
+            None => return vec![status(2, format!("No schema is registered for {}", topic))],
+        };
+
+        let result = serde_json::from_slice(&bytes[5..])
+            .map_err(json_error)
+            .and_then(|value| schema.codec.encode(topic, value))
+            .and_then(|generic| self.db.clone().insert_generic(generic));
+        match result {
+            Ok(()) => Vec::new(),
+            Err(e) => vec![status(2, format!("Unable to publish to {}: {}", topic, e))],
+        }
+    }
+}
@@ -7,13 +7,14 @@ use tokio::net::UdpSocket;
 use tokio::runtime::Runtime;
 use tokio::sync::Mutex; // as TokioMutex;
 use tokio::task::JoinHandle;
+use tokio::time::timeout;
 // QUIC requirements
 #[cfg(feature = "quic")]
 use futures_util::StreamExt;
 #[cfg(feature = "quic")]
 use quinn::Connection as QuicConnection;
 #[cfg(feature = "quic")]
-use quinn::{Endpoint, ServerConfig};
+use quinn::{Endpoint, EndpointConfig, ServerConfig, TokioRuntime};
 #[cfg(feature = "quic")]
 use std::{fs::File, io::BufReader};
 
@@ -30,20 +31,55 @@ use std::result::Result;
 
 #[cfg(feature = "quic")]
 use crate::error::Quic::*;
+use crate::host::federation;
+#[cfg(feature = "foxglove")]
+use crate::host::foxglove;
+#[cfg(feature = "http")]
+use crate::host::http;
+#[cfg(feature = "mcap")]
+use crate::host::mcap;
+#[cfg(feature = "metrics")]
+use crate::host::metrics;
+#[cfg(feature = "mqtt")]
+use crate::host::mqtt;
 #[cfg(feature = "quic")]
 use crate::host::quic::*;
+use crate::host::replication::{self, HostRole};
+#[cfg(feature = "serial")]
+use crate::host::serial::process_serial;
+use crate::host::stats::StatsTracker;
+#[cfg(feature = "tls")]
+use crate::host::tls::{generate_acceptor, process_tls};
+#[cfg(unix)]
+use crate::host::unix::process_unix;
+#[cfg(feature = "serial")]
+use crate::serial::SerialPort;
+#[cfg(unix)]
+use tokio::net::UnixListener;
 
+use crate::discovery::{announce, beacon_socket, Beacon, Transport};
+use crate::host::dispatch;
+use crate::host::dispatch::Unframed;
+use crate::host::in_process::InProcessHandle;
+use crate::host::stream::{process_stream, STREAM_MAX_FRAME_SIZE, STREAM_MAX_NAME_SIZE};
 use crate::host::tcp::*;
 use crate::host::udp::*;
+use crate::networks::{bind_tcp, bind_udp};
 use crate::prelude::*;
 use crate::prelude::*;
+use crate::stream::{AsyncStream, FramedStream};
 use crate::*;
 
+/// Longest a Node may take to complete its handshake before the Host drops the connection
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Named task handle for each Hosted connection
 #[derive(Debug)]
 pub struct Connection {
     handle: JoinHandle<()>,
-    stream_addr: SocketAddr,
+    /// Socket address of the Node, the socket file path for Unix domain socket connections, or the
+    /// label of a user-supplied stream
+    stream_addr: String,
     name: String,
 }
 
@@ -57,7 +93,37 @@ pub struct Host {
     pub(crate) task_listen_udp: Option<JoinHandle<()>>,
     #[cfg(feature = "quic")]
     pub(crate) task_listen_quic: Option<JoinHandle<()>>,
+    #[cfg(feature = "tls")]
+    pub(crate) task_listen_tls: Option<JoinHandle<()>>,
+    #[cfg(unix)]
+    pub(crate) task_listen_unix: Option<JoinHandle<()>>,
+    #[cfg(feature = "serial")]
+    pub(crate) task_listen_serial: Option<JoinHandle<()>>,
+    #[cfg(feature = "foxglove")]
+    pub(crate) task_listen_foxglove: Option<JoinHandle<()>>,
+    #[cfg(feature = "foxglove")]
+    pub(crate) foxglove_addr: Option<SocketAddr>,
+    #[cfg(feature = "http")]
+    pub(crate) task_listen_http: Option<JoinHandle<()>>,
+    #[cfg(feature = "http")]
+    pub(crate) http_addr: Option<SocketAddr>,
+    #[cfg(feature = "metrics")]
+    pub(crate) task_listen_metrics: Option<JoinHandle<()>>,
+    #[cfg(feature = "metrics")]
+    pub(crate) metrics_addr: Option<SocketAddr>,
+    #[cfg(feature = "mqtt")]
+    pub(crate) task_mqtt: Option<JoinHandle<()>>,
+    #[cfg(feature = "mcap")]
+    pub(crate) task_recorder: Option<JoinHandle<()>>,
+    #[cfg(feature = "mcap")]
+    pub(crate) task_player: Option<JoinHandle<()>>,
+    pub(crate) tasks_peers: Vec<JoinHandle<()>>,
+    pub(crate) task_replication: Option<JoinHandle<()>>,
+    pub(crate) role: Arc<StdMutex<HostRole>>,
+    pub(crate) task_beacon: Option<JoinHandle<()>>,
+    pub(crate) local_addrs: Vec<(Transport, SocketAddr)>,
     pub(crate) store: sled::Db,
+    pub(crate) stats: StatsTracker,
 }
 
 pub trait Store {
@@ -94,7 +160,8 @@ pub(crate) trait GenericStore {
 
 impl GenericStore for sled::Db {
     #[tracing::instrument]
-    fn insert_generic(&mut self, msg: GenericMsg) -> Result<(), crate::Error> {
+    fn insert_generic(&mut self, mut msg: GenericMsg) -> Result<(), crate::Error> {
+        msg.received = Some(Utc::now());
         let bytes = msg.as_bytes()?;
         let tree = self.open_tree(msg.topic.as_bytes())?;
         tree.insert(msg.timestamp.to_string().as_bytes(), bytes)?;
@@ -209,7 +276,7 @@ impl Store for Host {
     /// Insert a raw `Msg<T>`
     #[inline]
     fn insert_msg<T: Message>(&mut self, msg: Msg<T>) -> Result<(), crate::Error> {
-        self.db().insert_msg(msg)
+        Host::insert_msg(self, msg)
     }
 
     /// Insert a value using a default `Msg`
@@ -219,7 +286,7 @@ impl Store for Host {
         topic: impl Into<String> + std::fmt::Debug,
         data: T,
     ) -> Result<(), crate::Error> {
-        self.db().insert(topic, data)
+        Host::insert(self, topic, data)
     }
 
     /// Retrieve last message on a given topic
@@ -262,6 +329,69 @@ impl Drop for Host {
             task.abort();
             self.task_listen_quic = None;
         }
+        #[cfg(feature = "tls")]
+        if let Some(task) = &self.task_listen_tls {
+            task.abort();
+            self.task_listen_tls = None;
+        }
+        #[cfg(unix)]
+        if let Some(task) = &self.task_listen_unix {
+            task.abort();
+            self.task_listen_unix = None;
+            // Only the socket file this Host bound is removed
+            if let Some(unix_cfg) = &self.cfg.unix_cfg {
+                if let Err(e) = std::fs::remove_file(&unix_cfg.path) {
+                    warn!("Unable to remove Unix socket {:?}: {}", unix_cfg.path, e);
+                }
+            }
+        }
+        #[cfg(feature = "serial")]
+        if let Some(task) = &self.task_listen_serial {
+            task.abort();
+            self.task_listen_serial = None;
+        }
+        #[cfg(feature = "foxglove")]
+        if let Some(task) = &self.task_listen_foxglove {
+            task.abort();
+            self.task_listen_foxglove = None;
+        }
+        #[cfg(feature = "http")]
+        if let Some(task) = &self.task_listen_http {
+            task.abort();
+            self.task_listen_http = None;
+        }
+        #[cfg(feature = "metrics")]
+        if let Some(task) = &self.task_listen_metrics {
+            task.abort();
+            self.task_listen_metrics = None;
+        }
+        #[cfg(feature = "mqtt")]
+        if let Some(task) = &self.task_mqtt {
+            task.abort();
+            self.task_mqtt = None;
+        }
+        #[cfg(feature = "mcap")]
+        if let Some(task) = &self.task_player {
+            task.abort();
+            self.task_player = None;
+        }
+        // The recording is finished once the recorder is dropped along with the runtime
+        #[cfg(feature = "mcap")]
+        if let Some(task) = &self.task_recorder {
+            task.abort();
+            self.task_recorder = None;
+        }
+        for task in self.tasks_peers.drain(..) {
+            task.abort();
+        }
+        if let Some(task) = &self.task_replication {
+            task.abort();
+            self.task_replication = None;
+        }
+        if let Some(task) = &self.task_beacon {
+            task.abort();
+            self.task_beacon = None;
+        }
         if let Ok(mut connections) = self.connections.lock() {
             for connection in &mut *connections {
                 connection.handle.abort();
@@ -288,11 +418,13 @@ impl Host {
 
     /// Insert a raw `Msg<T>`
     pub fn insert_msg<T: Message>(&mut self, msg: Msg<T>) -> Result<(), crate::Error> {
-        let generic: GenericMsg = msg.try_into()?;
+        let mut generic: GenericMsg = msg.try_into()?;
+        generic.received = Some(Utc::now());
         let bytes = to_allocvec(&generic)?;
 
         let tree = self.db().open_tree(generic.topic.as_bytes())?;
         tree.insert(generic.timestamp.to_string().as_bytes(), bytes)?;
+        self.stats.record(&generic, "host");
 
         Ok(())
     }
@@ -329,29 +461,38 @@ impl Host {
         let connections = self.connections.clone();
 
         let db = self.store.clone();
+        let auth = self.cfg.auth_cfg.clone().map(Arc::new);
+        let stats = self.stats.clone();
+        // Listeners are bound before starting, so that failures are returned to the caller
+        let mut local_addrs = Vec::new();
 
         // Start up the UDP process
         match &self.config().udp_cfg {
             None => warn!("Host has no UDP configuration"),
             Some(udp_cfg) => {
-                let ip = match get_ip(&udp_cfg.interface) {
-                    Ok(ip) => ip,
-                    Err(_e) => return Err(Error::InvalidInterface),
-                };
-
-                let addr = SocketAddr::new(IpAddr::V4(ip), udp_cfg.socket_num);
+                let socket = bind_udp(udp_cfg.socket_addr()?)?;
+                local_addrs.push((Transport::Udp, socket.local_addr()?));
 
                 let db = db.clone();
+                let auth = auth.clone();
+                let stats = stats.clone();
 
                 // Start the UDP listening socket
                 let (max_buffer_size_udp, _max_name_size_udp) =
                     (udp_cfg.max_buffer_size, udp_cfg.max_name_size);
                 let rt_handle = self.runtime.handle().clone();
                 let task_listen_udp = self.runtime.spawn(async move {
-                    match UdpSocket::bind(addr).await {
+                    match UdpSocket::from_std(socket) {
                         Ok(socket) => {
-                            process_udp(rt_handle.clone(), socket, db.clone(), max_buffer_size_udp)
-                                .await
+                            process_udp(
+                                rt_handle.clone(),
+                                socket,
+                                db.clone(),
+                                max_buffer_size_udp,
+                                auth,
+                                stats,
+                            )
+                            .await
                         }
                         Err(e) => {
                             error!("{}", e);
@@ -366,50 +507,79 @@ impl Host {
         match &self.config().tcp_cfg {
             None => warn!("Host has no TCP configuration"),
             Some(tcp_cfg) => {
-                let ip = match get_ip(&tcp_cfg.interface) {
-                    Ok(ip) => ip,
-                    Err(_e) => return Err(Error::InvalidInterface),
-                };
-
-                let addr = SocketAddr::new(IpAddr::V4(ip), tcp_cfg.socket_num);
+                let listener = bind_tcp(tcp_cfg.socket_addr()?)?;
+                local_addrs.push((Transport::Tcp, listener.local_addr()?));
 
                 let (max_buffer_size_tcp, max_name_size_tcp) =
                     (tcp_cfg.max_buffer_size, tcp_cfg.max_name_size);
                 let db = db.clone();
+                let auth = auth.clone();
                 let connections = Arc::clone(&connections);
+                let stats = stats.clone();
 
                 let task_listen_tcp = self.runtime.spawn(async move {
-                    if let Ok(listener) = TcpListener::bind(addr).await {
+                    if let Ok(listener) = TcpListener::from_std(listener) {
                         let connections = Arc::clone(&connections.clone());
 
                         loop {
                             if let Ok((stream, stream_addr)) = listener.accept().await {
-                                let (stream, name) = match crate::host::tcp::handshake(
-                                    stream,
-                                    max_buffer_size_tcp,
-                                    max_name_size_tcp,
-                                )
-                                .await
-                                {
-                                    Ok((stream, name)) => (stream, name),
-                                    Err(_e) => continue,
-                                };
-                                debug!("Host received connection from {:?}", &name);
-
                                 let connections = Arc::clone(&connections.clone());
                                 let db = db.clone();
+                                let auth = auth.clone();
+                                let stats = stats.clone();
 
-                                let handle = tokio::spawn(async move {
-                                    process_tcp(stream, db, max_buffer_size_tcp).await;
-                                });
-                                let connection = Connection {
-                                    handle,
-                                    stream_addr,
-                                    name,
-                                };
+                                // Each connection is set up in its own task, so that a Node which
+                                // stalls its handshake doesn't hold up the others
+                                tokio::spawn(async move {
+                                    let mut stream = Unframed::new(stream, max_buffer_size_tcp);
+                                    let handshake = dispatch::handshake(
+                                        &mut stream,
+                                        max_name_size_tcp,
+                                        auth.as_deref(),
+                                    );
+                                    let (name, identity) =
+                                        match timeout(HANDSHAKE_TIMEOUT, handshake).await {
+                                            Ok(Ok(handshake)) => handshake,
+                                            Ok(Err(e)) => {
+                                                warn!(
+                                                    "TCP handshake from {} failed: {}",
+                                                    stream_addr, e
+                                                );
+                                                stats.handshake_failed(Transport::Tcp);
+                                                return;
+                                            }
+                                            Err(_elapsed) => {
+                                                warn!(
+                                                    "TCP handshake from {} timed out",
+                                                    stream_addr
+                                                );
+                                                stats.handshake_failed(Transport::Tcp);
+                                                return;
+                                            }
+                                        };
+                                    debug!("Host received connection from {:?}", &name);
 
-                                // TO_DO: We should re-evaluate how connections are stored
-                                connections.lock().unwrap().push(connection);
+                                    let stream = stream.into_inner();
+                                    let handle = tokio::spawn(async move {
+                                        process_tcp(
+                                            stream,
+                                            db,
+                                            max_buffer_size_tcp,
+                                            auth,
+                                            identity,
+                                            stats,
+                                        )
+                                        .await;
+                                    });
+                                    let connection = Connection {
+                                        handle,
+                                        stream_addr: stream_addr.to_string(),
+                                        name,
+                                    };
+
+                                    // TO_DO: We should re-evaluate how connections are stored
+                                    connections.lock().unwrap().push(connection);
+                                });
                             }
                         }
                     }
@@ -419,18 +589,325 @@ impl Host {
             }
         }
 
+        // Start the TLS process
+        #[cfg(feature = "tls")]
+        match &self.config().tls_cfg {
+            None => warn!("Host has no TLS configuration"),
+            Some(tls_cfg) => {
+                let listener = bind_tcp(tls_cfg.network_cfg.socket_addr()?)?;
+                local_addrs.push((Transport::Tls, listener.local_addr()?));
+                let acceptor = generate_acceptor(&tls_cfg.cert_path, &tls_cfg.key_path)?;
+                debug!("Successfully read in TLS certs");
+
+                let (max_buffer_size_tls, max_name_size_tls) = (
+                    tls_cfg.network_cfg.max_buffer_size,
+                    tls_cfg.network_cfg.max_name_size,
+                );
+                let db = db.clone();
+                let auth = auth.clone();
+                let connections = Arc::clone(&connections);
+                let stats = stats.clone();
+
+                let task_listen_tls = self.runtime.spawn(async move {
+                    if let Ok(listener) = TcpListener::from_std(listener) {
+                        loop {
+                            if let Ok((stream, stream_addr)) = listener.accept().await {
+                                let acceptor = acceptor.clone();
+                                let db = db.clone();
+                                let auth = auth.clone();
+                                let connections = Arc::clone(&connections);
+                                let stats = stats.clone();
+
+                                // Each connection is set up in its own task, so that a Node which
+                                // stalls its handshake doesn't hold up the others
+                                tokio::spawn(async move {
+                                    let handshake = async {
+                                        let stream = acceptor.accept(stream).await?;
+                                        let mut stream = Unframed::new(stream, max_buffer_size_tls);
+                                        let (name, identity) = dispatch::handshake(
+                                            &mut stream,
+                                            max_name_size_tls,
+                                            auth.as_deref(),
+                                        )
+                                        .await?;
+                                        Ok::<_, Error>((stream, name, identity))
+                                    };
+                                    let (stream, name, identity) =
+                                        match timeout(HANDSHAKE_TIMEOUT, handshake).await {
+                                            Ok(Ok(handshake)) => handshake,
+                                            Ok(Err(e)) => {
+                                                warn!(
+                                                    "TLS handshake from {} failed: {}",
+                                                    stream_addr, e
+                                                );
+                                                stats.handshake_failed(Transport::Tls);
+                                                return;
+                                            }
+                                            Err(_elapsed) => {
+                                                warn!(
+                                                    "TLS handshake from {} timed out",
+                                                    stream_addr
+                                                );
+                                                stats.handshake_failed(Transport::Tls);
+                                                return;
+                                            }
+                                        };
+                                    debug!("Host received TLS connection from {:?}", &name);
+
+                                    let handle = tokio::spawn(async move {
+                                        process_tls(stream, db, auth, identity, stats, stream_addr)
+                                            .await;
+                                    });
+                                    let connection = Connection {
+                                        handle,
+                                        stream_addr: stream_addr.to_string(),
+                                        name,
+                                    };
+
+                                    connections.lock().unwrap().push(connection);
+                                });
+                            }
+                        }
+                    }
+                });
+
+                self.task_listen_tls = Some(task_listen_tls);
+            }
+        }
+
+        // Start the Unix domain socket process
+        #[cfg(unix)]
+        match &self.config().unix_cfg {
+            None => warn!("Host has no Unix domain socket configuration"),
+            Some(unix_cfg) => {
+                let listener = crate::host::unix::bind(unix_cfg)?;
+                let path = unix_cfg.path.display().to_string();
+
+                let (max_buffer_size_unix, max_name_size_unix) =
+                    (unix_cfg.max_buffer_size, unix_cfg.max_name_size);
+                let db = db.clone();
+                let auth = auth.clone();
+                let connections = Arc::clone(&connections);
+                let stats = stats.clone();
+
+                let task_listen_unix = self.runtime.spawn(async move {
+                    if let Ok(listener) = UnixListener::from_std(listener) {
+                        loop {
+                            if let Ok((stream, _addr)) = listener.accept().await {
+                                let db = db.clone();
+                                let auth = auth.clone();
+                                let connections = Arc::clone(&connections);
+                                let stats = stats.clone();
+                                let path = path.clone();
+
+                                // Each connection is set up in its own task, so that a Node which
+                                // stalls its handshake doesn't hold up the others
+                                tokio::spawn(async move {
+                                    let mut stream = Unframed::new(stream, max_buffer_size_unix);
+                                    let handshake = dispatch::handshake(
+                                        &mut stream,
+                                        max_name_size_unix,
+                                        auth.as_deref(),
+                                    );
+                                    let (name, identity) =
+                                        match timeout(HANDSHAKE_TIMEOUT, handshake).await {
+                                            Ok(Ok(handshake)) => handshake,
+                                            Ok(Err(e)) => {
+                                                warn!("Unix handshake on {} failed: {}", path, e);
+                                                stats.handshake_failed(Transport::Unix);
+                                                return;
+                                            }
+                                            Err(_elapsed) => {
+                                                warn!("Unix handshake on {} timed out", path);
+                                                stats.handshake_failed(Transport::Unix);
+                                                return;
+                                            }
+                                        };
+                                    debug!("Host received Unix connection from {:?}", &name);
+
+                                    let socket_path = path.clone();
+                                    let handle = tokio::spawn(async move {
+                                        process_unix(
+                                            stream,
+                                            db,
+                                            auth,
+                                            identity,
+                                            stats,
+                                            socket_path,
+                                        )
+                                        .await;
+                                    });
+                                    let connection = Connection {
+                                        handle,
+                                        stream_addr: path,
+                                        name,
+                                    };
+
+                                    connections.lock().unwrap().push(connection);
+                                });
+                            }
+                        }
+                    }
+                });
+
+                self.task_listen_unix = Some(task_listen_unix);
+            }
+        }
+
+        // Start the serial link process
+        #[cfg(feature = "serial")]
+        match &self.config().serial_cfg {
+            None => warn!("Host has no serial configuration"),
+            Some(serial_cfg) => {
+                // The device is registered with the Host's runtime when it is opened
+                let port = {
+                    let _guard = self.runtime.enter();
+                    SerialPort::open(serial_cfg)?
+                };
+                debug!("Host opened serial device {:?}", serial_cfg.path);
+
+                let max_name_size_serial = serial_cfg.max_name_size;
+                let device = serial_cfg.path.display().to_string();
+                let db = db.clone();
+                let auth = auth.clone();
+                let stats = stats.clone();
+
+                let task_listen_serial = self.runtime.spawn(async move {
+                    process_serial(port, db, max_name_size_serial, auth, stats, device).await;
+                });
+
+                self.task_listen_serial = Some(task_listen_serial);
+            }
+        }
+
+        // Start the Foxglove WebSocket server
+        #[cfg(feature = "foxglove")]
+        match self.config().foxglove_cfg.clone() {
+            None => warn!("Host has no Foxglove configuration"),
+            Some(foxglove_cfg) => {
+                let listener = bind_tcp(foxglove_cfg.bind_addr)?;
+                self.foxglove_addr = Some(listener.local_addr()?);
+                let foxglove_cfg = Arc::new(foxglove_cfg);
+                let db = db.clone();
+
+                let task_listen_foxglove = self.runtime.spawn(async move {
+                    match TcpListener::from_std(listener) {
+                        Ok(listener) => foxglove::serve(listener, db, foxglove_cfg).await,
+                        Err(e) => error!("Unable to listen for Foxglove clients: {}", e),
+                    }
+                });
+
+                self.task_listen_foxglove = Some(task_listen_foxglove);
+            }
+        }
+
+        // Start the HTTP gateway
+        #[cfg(feature = "http")]
+        match self.config().http_cfg.clone() {
+            None => warn!("Host has no HTTP configuration"),
+            Some(http_cfg) => {
+                let listener = bind_tcp(http_cfg.bind_addr)?;
+                self.http_addr = Some(listener.local_addr()?);
+                let http_cfg = Arc::new(http_cfg);
+                let db = db.clone();
+                let auth = auth.clone();
+                let stats = stats.clone();
+
+                let task_listen_http = self.runtime.spawn(async move {
+                    match TcpListener::from_std(listener) {
+                        Ok(listener) => http::serve(listener, db, http_cfg, auth, stats).await,
+                        Err(e) => error!("Unable to listen for HTTP clients: {}", e),
+                    }
+                });
+
+                self.task_listen_http = Some(task_listen_http);
+            }
+        }
+
+        // Start the Prometheus metrics endpoint
+        #[cfg(feature = "metrics")]
+        match &self.config().metrics_cfg {
+            None => warn!("Host has no metrics configuration"),
+            Some(metrics_cfg) => {
+                let listener = bind_tcp(metrics_cfg.bind_addr)?;
+                self.metrics_addr = Some(listener.local_addr()?);
+                let db = db.clone();
+                let stats = stats.clone();
+
+                let task_listen_metrics = self.runtime.spawn(async move {
+                    match TcpListener::from_std(listener) {
+                        Ok(listener) => metrics::serve(listener, db, stats).await,
+                        Err(e) => error!("Unable to listen for metrics scrapes: {}", e),
+                    }
+                });
+
+                self.task_listen_metrics = Some(task_listen_metrics);
+            }
+        }
+
+        // Start the MQTT bridge
+        #[cfg(feature = "mqtt")]
+        match self.config().mqtt_cfg.clone() {
+            None => warn!("Host has no MQTT configuration"),
+            Some(mqtt_cfg) => {
+                let db = db.clone();
+                let task_mqtt = self.runtime.spawn(async move {
+                    mqtt::bridge(db, Arc::new(mqtt_cfg)).await;
+                });
+                self.task_mqtt = Some(task_mqtt);
+            }
+        }
+
+        // Record the Host's topics
+        #[cfg(feature = "mcap")]
+        if let Some(recorder_cfg) = self.config().recorder_cfg.clone() {
+            let db = db.clone();
+            let task_recorder = self.runtime.spawn(async move {
+                mcap::record(db, Arc::new(recorder_cfg)).await;
+            });
+            self.task_recorder = Some(task_recorder);
+        }
+
+        // Play back a recording
+        #[cfg(feature = "mcap")]
+        if let Some(player_cfg) = self.config().player_cfg.clone() {
+            let db = db.clone();
+            let task_player = self.runtime.spawn(async move {
+                if let Err(e) = mcap::play(db, Arc::new(player_cfg)).await {
+                    error!("Unable to play recording: {}", e);
+                }
+            });
+            self.task_player = Some(task_player);
+        }
+
+        // Link to each peer Host
+        for peer in self.config().peers.clone() {
+            let db = db.clone();
+            let task_peer = self.runtime.spawn(async move {
+                federation::link(db, Arc::new(peer)).await;
+            });
+            self.tasks_peers.push(task_peer);
+        }
+
+        // Replicate the primary Host, if this one is its standby
+        if let Some(replication_cfg) = self.config().replication_cfg.clone() {
+            if self.role() == HostRole::Standby {
+                let (db, role) = (db.clone(), Arc::clone(&self.role));
+                let task_replication = self.runtime.spawn(async move {
+                    replication::replicate(db, Arc::new(replication_cfg), role).await;
+                });
+                self.task_replication = Some(task_replication);
+            }
+        }
+
         // Start the QUIC process
         #[cfg(feature = "quic")]
         match &self.config().quic_cfg {
             None => warn!("Host has no QUIC configuration"),
             Some(quic_cfg) => {
-                let ip = match get_ip(&quic_cfg.network_cfg.interface) {
-                    Ok(ip) => ip,
-                    Err(_e) => return Err(Error::InvalidInterface),
-                };
-
-                let addr = SocketAddr::new(IpAddr::V4(ip), quic_cfg.network_cfg.socket_num);
-                let (certs, key) = read_certs_from_file(&quic_cfg.cert_path, &quic_cfg.key_path)?;
+                let socket = bind_udp(quic_cfg.network_cfg.socket_addr()?)?;
+                local_addrs.push((Transport::Quic, socket.local_addr()?));
+                let server_config = generate_server_config(quic_cfg)?;
 
                 debug!("Successfully read in QUIC certs");
 
@@ -438,70 +915,143 @@ impl Host {
                     quic_cfg.network_cfg.max_buffer_size,
                     quic_cfg.network_cfg.max_name_size,
                 );
-                let server_config = ServerConfig::with_single_cert(certs, key)?;
 
                 let task_listen_quic = self.runtime.spawn(async move {
-                    if let Ok(endpoint) = Endpoint::server(server_config, addr) {
+                    let endpoint = Endpoint::new(
+                        EndpointConfig::default(),
+                        Some(server_config),
+                        socket,
+                        TokioRuntime,
+                    );
+                    if let Ok(endpoint) = endpoint {
                         debug!(
                             "Waiting for incoming QUIC connection on {:?}",
                             endpoint.local_addr()
                         );
-                        let connections = Arc::clone(&connections.clone());
-                        loop {
-                            if let Some(conn) = endpoint.accept().await {
-                                if let Ok(connection) = conn.await {
-                                    let db = db.clone();
-                                    let remote_addr = connection.remote_address();
-
-                                    debug!(
-                                        "Received QUIC connection from {:?}",
-                                        &connection.remote_address()
-                                    );
+                        while let Some(conn) = endpoint.accept().await {
+                            let remote_addr = conn.remote_address();
+                            let db = db.clone();
+                            let auth = auth.clone();
+                            let stats = stats.clone();
+                            let connections = Arc::clone(&connections);
 
-                                    let handle = tokio::spawn(async move {
-                                        loop {
-                                            let db = db.clone();
-                                            // TO_DO: Instead of having these buffers, is there a way that we can just use sled 
-                                            // to hold our buffer space instead, removing the additional allocation?
-                                            let mut buf = vec![0u8; max_buffer_size_quic];
-                                            match connection.accept_bi().await {
-                                                Ok((send, recv)) => {
-                                                    debug!("Host successfully received bi-directional stream from {}",connection.remote_address());
-                                                    tokio::spawn(async move {
-                                                        process_quic(
-                                                            (send, recv),
-                                                            db.clone(),
-                                                            &mut buf,
-                                                        )
-                                                        .await;
-                                                    });
-                                                }
-                                                Err(_e) => {}
+                            // Each connection is set up in its own task, so that a Node which
+                            // stalls its handshake doesn't hold up the others
+                            tokio::spawn(async move {
+                                let accepted = timeout(
+                                    HANDSHAKE_TIMEOUT,
+                                    crate::host::quic::accept(
+                                        conn,
+                                        auth.as_deref(),
+                                        max_buffer_size_quic,
+                                    ),
+                                )
+                                .await;
+                                let (connection, name, identity) = match accepted {
+                                    Ok(Ok(accepted)) => accepted,
+                                    Ok(Err(e)) => {
+                                        warn!("QUIC handshake from {} failed: {}", remote_addr, e);
+                                        stats.handshake_failed(Transport::Quic);
+                                        return;
+                                    }
+                                    Err(_elapsed) => {
+                                        warn!("QUIC handshake from {} timed out", remote_addr);
+                                        stats.handshake_failed(Transport::Quic);
+                                        return;
+                                    }
+                                };
+
+                                let handle = tokio::spawn(async move {
+                                    let _connection = stats.connect(Transport::Quic);
+                                    loop {
+                                        let db = db.clone();
+                                        // TO_DO: Instead of having these buffers, is there a way that we can just use sled
+                                        // to hold our buffer space instead, removing the additional allocation?
+                                        let mut buf = vec![0u8; max_buffer_size_quic];
+                                        match connection.accept_bi().await {
+                                            Ok((send, recv)) => {
+                                                debug!(
+                                                    "Host successfully received bi-directional stream from {}",
+                                                    remote_addr
+                                                );
+                                                let (auth, identity) = (auth.clone(), identity.clone());
+                                                let stats = stats.clone();
+                                                let publisher = identity
+                                                    .clone()
+                                                    .unwrap_or_else(|| remote_addr.to_string());
+                                                tokio::spawn(async move {
+                                                    process_quic(
+                                                        (send, recv),
+                                                        db.clone(),
+                                                        &mut buf,
+                                                        auth,
+                                                        identity,
+                                                        stats,
+                                                        publisher,
+                                                    )
+                                                    .await;
+                                                });
+                                            }
+                                            // The Node has closed its connection
+                                            Err(e) => {
+                                                debug!("QUIC connection from {} closed: {}", remote_addr, e);
+                                                break;
                                             }
                                         }
-                                        });
-                                        let connection = Connection {
-                                            handle,
-                                            stream_addr: remote_addr,
-                                            name: "TO_DO: temp".to_string(),
-                                        };
-
-                                        connections.lock().unwrap().push(connection);
-                                }
+                                    }
+                                });
+                                let connection = Connection {
+                                    handle,
+                                    stream_addr: remote_addr.to_string(),
+                                    name,
+                                };
 
-                            }
+                                connections.lock().unwrap().push(connection);
+                            });
                         }
                     }
-
                 });
 
                 self.task_listen_quic = Some(task_listen_quic);
             }
         }
 
+        self.local_addrs = local_addrs;
+
+        // Announce the Host so that Nodes can discover it by name
+        if let Some(discovery_cfg) = &self.cfg.discovery_cfg {
+            let socket = beacon_socket(discovery_cfg)?;
+            let beacon = Beacon {
+                name: self.cfg.name.clone(),
+                transports: self.local_addrs.clone(),
+                fingerprint: self.certificate_fingerprint()?,
+            };
+            let discovery_cfg = discovery_cfg.clone();
+            let task_beacon = self.runtime.spawn(async move {
+                if let Err(e) = announce(socket, beacon, discovery_cfg).await {
+                    error!("Discovery beacon failed: {}", e);
+                }
+            });
+            self.task_beacon = Some(task_beacon);
+        }
+
         Ok(())
     }
 
+    /// Fingerprint of the certificate presented to QUIC and TLS Nodes, if either is enabled
+    fn certificate_fingerprint(&self) -> Result<Option<String>, crate::Error> {
+        #[cfg(feature = "quic")]
+        {
+            let cert_path = self.cfg.quic_cfg.as_ref().map(|cfg| &cfg.cert_path);
+            #[cfg(feature = "tls")]
+            let cert_path = cert_path.or(self.cfg.tls_cfg.as_ref().map(|cfg| &cfg.cert_path));
+            if let Some(cert_path) = cert_path {
+                return Ok(Some(crate::host::pki::fingerprint(cert_path)?));
+            }
+        }
+        Ok(None)
+    }
+
     /// Shuts down all networking connections and releases Host object handle
     /// This also makes sure that temporary sled::Db's built are also dropped
     /// following the shutdown of a Host
@@ -537,6 +1087,128 @@ impl Host {
         strings
     }
 
+    /// Statistics on the messages set on each topic and its subscribers, sorted by topic
+    ///
+    /// Topics stored on the Host that haven't been set since it started are included with empty
+    /// statistics.
+    pub fn topic_stats(&self) -> Vec<TopicStats> {
+        self.stats.all_topic_stats(self.topics())
+    }
+
+    /// Handle that Nodes in the same process connect to the Host with, which bypasses the
+    /// network and works whether or not the Host has been started
+    pub fn in_process_handle(&self) -> InProcessHandle {
+        InProcessHandle {
+            db: self.store.clone(),
+            auth: self.cfg.auth_cfg.clone().map(Arc::new),
+            stats: self.stats.clone(),
+        }
+    }
+
+    /// Serve a Node over a user-supplied stream, such as an SSH port-forward or the stdin/stdout
+    /// of a child process, which works whether or not the Host has been started
+    ///
+    /// `label` stands in for the Node's address in the Host's connections.
+    pub fn serve_stream<S: AsyncStream + 'static>(&self, label: impl Into<String>, stream: S) {
+        let label = label.into();
+        let mut stream = FramedStream::new(Box::new(stream), STREAM_MAX_FRAME_SIZE);
+        let db = self.store.clone();
+        let auth = self.cfg.auth_cfg.clone().map(Arc::new);
+        let stats = self.stats.clone();
+        let connections = Arc::clone(&self.connections);
+
+        self.runtime.spawn(async move {
+            let (name, identity) =
+                match dispatch::handshake(&mut stream, STREAM_MAX_NAME_SIZE, auth.as_deref()).await
+                {
+                    Ok(handshake) => handshake,
+                    Err(e) => {
+                        warn!("Stream handshake on {} failed: {}", label, e);
+                        stats.handshake_failed(Transport::Stream);
+                        return;
+                    }
+                };
+            debug!("Host received stream connection from {:?}", &name);
+
+            let publisher = label.clone();
+            let handle = tokio::spawn(async move {
+                process_stream(stream, db, auth, identity, stats, publisher).await;
+            });
+            let connection = Connection {
+                handle,
+                stream_addr: label,
+                name,
+            };
+
+            connections.lock().unwrap().push(connection);
+        });
+    }
+
+    /// Addresses the Host's transports are bound to, including ports assigned by the operating
+    /// system, once the Host has started
+    pub fn local_addrs(&self) -> Vec<(Transport, SocketAddr)> {
+        self.local_addrs.clone()
+    }
+
+    /// Address the Host is bound to for the specified transport, once the Host has started
+    pub fn local_addr(&self, transport: Transport) -> Option<SocketAddr> {
+        self.local_addrs
+            .iter()
+            .find(|(t, _addr)| *t == transport)
+            .map(|(_t, addr)| *addr)
+    }
+
+    /// Address of the Host's Foxglove WebSocket server, once the Host has started
+    #[cfg(feature = "foxglove")]
+    pub fn foxglove_addr(&self) -> Option<SocketAddr> {
+        self.foxglove_addr
+    }
+
+    /// Address of the Host's HTTP gateway, once the Host has started
+    #[cfg(feature = "http")]
+    pub fn http_addr(&self) -> Option<SocketAddr> {
+        self.http_addr
+    }
+
+    /// Address of the Host's Prometheus metrics endpoint, once the Host has started
+    #[cfg(feature = "metrics")]
+    pub fn metrics_addr(&self) -> Option<SocketAddr> {
+        self.metrics_addr
+    }
+
+    /// Whether the Host is serving as the primary, or replicating another Host as its standby
+    pub fn role(&self) -> HostRole {
+        match self.role.lock() {
+            Ok(role) => *role,
+            Err(poisoned) => *poisoned.into_inner(),
+        }
+    }
+
+    /// Promote a standby Host to the primary, stopping its replication of the previous primary
+    pub fn promote(&mut self) {
+        if let Some(task) = self.task_replication.take() {
+            task.abort();
+        }
+        if let Ok(mut role) = self.role.lock() {
+            *role = HostRole::Primary;
+        }
+    }
+
+    /// Play a recording back on the Host, blocking until playback reaches its end
+    #[cfg(feature = "mcap")]
+    pub fn play(&self, player_cfg: host::PlayerConfig) -> Result<(), crate::Error> {
+        let db = self.store.clone();
+        self.runtime.block_on(mcap::play(db, Arc::new(player_cfg)))
+    }
+
+    /// Names of all Host connections
+    pub fn connection_names(&self) -> Result<Vec<String>, crate::Error> {
+        match self.connections.lock() {
+            Ok(connections) => Ok(connections.iter().map(|conn| conn.name.clone()).collect()),
+            Err(_) => Err(crate::Error::LockFailure),
+        }
+    }
+
     /// Print information about all Host connections
     pub fn print_connections(&mut self) -> Result<(), crate::Error> {
         match self.connections.lock() {
@@ -0,0 +1,435 @@
+use base64::Engine;
+use hyper::body::{Bytes, HttpBody};
+use hyper::header::{HeaderValue, AUTHORIZATION, CACHE_CONTROL, CONTENT_TYPE, WWW_AUTHENTICATE};
+use hyper::server::conn::Http;
+use hyper::service::service_fn;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::time::{interval, Duration};
+use tracing::*;
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::result::Result;
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::host::auth::authorize_msg;
+use crate::host::host::GenericStore;
+use crate::host::json::{msg_json, JsonCodec};
+use crate::host::stats::StatsTracker;
+use crate::host::{AuthConfig, Credentials, Store};
+use crate::prelude::*;
+use crate::trace::host_span;
+
+/// How often an idle event stream is sent a comment, so that closed clients are noticed
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+/// Number of messages returned by the history endpoint when `n` isn't given
+const DEFAULT_HISTORY: usize = 10;
+
+/// Configuration of the Host's HTTP gateway, which serves its topics as JSON
+///
+/// The gateway serves:
+/// - `GET /topics`: names of all topics
+/// - `GET /topics/{name}`: latest message on a topic
+/// - `GET /topics/{name}/history?n=`: the `n` latest messages on a topic, oldest first
+/// - `GET /topics/{name}/events`: server-sent events carrying each new message on a topic
+/// - `GET /topics/{name}/stats`: statistics on a topic's messages and subscribers
+/// - `POST /topics/{name}`: set a topic from a JSON payload
+///
+/// Payloads of topics with a registered type are converted to and from JSON, while the others
+/// are served as their raw bytes and can't be set over HTTP. If the Host has an `AuthConfig`,
+/// clients present their credentials with HTTP Basic authentication.
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    /// Address the HTTP server listens on
+    pub bind_addr: SocketAddr,
+    /// Largest request body accepted, in bytes
+    pub max_body_size: usize,
+    types: HashMap<String, JsonCodec>,
+}
+
+impl Default for HttpConfig {
+    /// Listen on `127.0.0.1:8080`
+    fn default() -> Self {
+        HttpConfig {
+            bind_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8080),
+            max_body_size: 65_536,
+            types: HashMap::new(),
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Set the address the HTTP server listens on
+    pub fn set_bind_addr(mut self, bind_addr: impl Into<SocketAddr>) -> Self {
+        self.bind_addr = bind_addr.into();
+        self
+    }
+
+    /// Set the largest request body accepted, in bytes
+    pub fn set_max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// Register the type of a topic's messages, so they can be converted to and from JSON
+    pub fn with_type<T: Message + 'static>(mut self, topic: impl Into<String>) -> Self {
+        self.types.insert(topic.into(), JsonCodec::new::<T>());
+        self
+    }
+}
+
+/// Accept HTTP clients on the Host's gateway listener
+pub(crate) async fn serve(
+    listener: TcpListener,
+    db: sled::Db,
+    cfg: Arc<HttpConfig>,
+    auth: Option<Arc<AuthConfig>>,
+    stats: StatsTracker,
+) {
+    let gateway = Gateway {
+        db,
+        cfg,
+        auth,
+        stats,
+    };
+    loop {
+        match listener.accept().await {
+            Ok((stream, addr)) => {
+                let gateway = gateway.clone();
+                tokio::spawn(async move {
+                    let service = service_fn(move |request| {
+                        let gateway = gateway.clone();
+                        async move { Ok::<_, Infallible>(gateway.handle(request).await) }
+                    });
+                    if let Err(e) = Http::new()
+                        .http1_only(true)
+                        .serve_connection(stream, service)
+                        .await
+                    {
+                        debug!("HTTP connection from {} closed: {}", addr, e);
+                    }
+                });
+            }
+            Err(e) => error!("Error accepting HTTP connection: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Gateway {
+    db: sled::Db,
+    cfg: Arc<HttpConfig>,
+    auth: Option<Arc<AuthConfig>>,
+    stats: StatsTracker,
+}
+
+impl Gateway {
+    async fn handle(&self, request: Request<Body>) -> Response<Body> {
+        debug!("{} {}", request.method(), request.uri());
+        let segments: Vec<String> = request
+            .uri()
+            .path()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(percent_decode)
+            .collect();
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+        let result = match (request.method(), segments.as_slice()) {
+            (&Method::GET, ["topics"]) => self.topics(),
+            (&Method::GET, ["topics", topic]) => self.latest(&request, topic),
+            (&Method::GET, ["topics", topic, "history"]) => self.history(&request, topic),
+            (&Method::GET, ["topics", topic, "events"]) => self.events(&request, topic),
+            (&Method::GET, ["topics", topic, "stats"]) => self.stats(&request, topic),
+            (&Method::POST, ["topics", topic]) => {
+                let topic = topic.to_string();
+                self.publish(request, &topic).await
+            }
+            (_, ["topics"])
+            | (_, ["topics", _])
+            | (_, ["topics", _, "history" | "events" | "stats"]) => Err(HttpError::new(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "Method not allowed",
+            )),
+            _ => Err(HttpError::new(StatusCode::NOT_FOUND, "Not found")),
+        };
+        result.unwrap_or_else(HttpError::into_response)
+    }
+
+    /// Authenticate the request's Basic credentials and check they permit the operation,
+    /// returning the identity they authenticated as
+    fn authorize(
+        &self,
+        request: &Request<Body>,
+        msg_type: MsgType,
+        topic: &str,
+    ) -> Result<Option<String>, HttpError> {
+        let auth = match &self.auth {
+            Some(auth) => auth,
+            None => return Ok(None),
+        };
+        let identity = match basic_credentials(request) {
+            Some(credentials) => Some(auth.authenticate(Some(&credentials))?),
+            None => None,
+        };
+        authorize_msg(Some(auth), identity.as_deref(), &msg_type, topic)?;
+        Ok(identity)
+    }
+
+    fn topics(&self) -> Result<Response<Body>, HttpError> {
+        Ok(json_response(StatusCode::OK, json!(self.db.topics()?)))
+    }
+
+    fn latest(&self, request: &Request<Body>, topic: &str) -> Result<Response<Body>, HttpError> {
+        self.authorize(request, MsgType::Get, topic)?;
+        let generic = self.db.get_generic(topic)?;
+        let msg = msg_json(generic, self.cfg.types.get(topic))?;
+        Ok(json_response(StatusCode::OK, msg))
+    }
+
+    fn history(&self, request: &Request<Body>, topic: &str) -> Result<Response<Body>, HttpError> {
+        let n = match query_param(request, "n") {
+            None => DEFAULT_HISTORY,
+            Some(n) => n
+                .parse()
+                .map_err(|_| HttpError::new(StatusCode::BAD_REQUEST, "`n` must be a number"))?,
+        };
+        self.authorize(request, MsgType::GetNth(n), topic)?;
+
+        let tree = self.db.open_tree(topic.as_bytes())?;
+        let mut msgs = Vec::new();
+        for entry in tree.iter().rev().take(n) {
+            let (_timestamp, bytes) = entry?;
+            let generic: GenericMsg = postcard::from_bytes(&bytes)?;
+            msgs.push(msg_json(generic, self.cfg.types.get(topic))?);
+        }
+        if msgs.is_empty() && n > 0 {
+            return Err(Error::NonExistentTopic(topic.to_string()).into());
+        }
+        msgs.reverse();
+        Ok(json_response(StatusCode::OK, Value::Array(msgs)))
+    }
+
+    /// Stream each message set on the topic as a server-sent event
+    fn events(&self, request: &Request<Body>, topic: &str) -> Result<Response<Body>, HttpError> {
+        self.authorize(request, MsgType::Subscribe, topic)?;
+        let mut subscriber = self.db.open_tree(topic.as_bytes())?.watch_prefix(vec![]);
+        let guard = self.stats.subscribe(&self.db, topic);
+        let codec = self.cfg.types.get(topic).cloned();
+
+        // Events are forwarded from a separate task, so a slow client loses events rather than
+        // holding up the Host's writes
+        let (tx, mut rx) = mpsc::channel::<Bytes>(1024);
+        let watch = tokio::spawn(async move {
+            while let Some(event) = (&mut subscriber).await {
+                if let sled::Event::Insert { value, .. } = event {
+                    let generic: GenericMsg = match postcard::from_bytes(&value) {
+                        Ok(generic) => generic,
+                        Err(_) => continue,
+                    };
+                    let event = match msg_json(generic, codec.as_ref()) {
+                        Ok(msg) => format!("data: {}\n\n", msg),
+                        Err(e) => {
+                            warn!("Unable to convert message to JSON: {}", e);
+                            continue;
+                        }
+                    };
+                    if let Err(mpsc::error::TrySendError::Closed(_)) = tx.try_send(event.into()) {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let (mut sender, body) = Body::channel();
+        tokio::spawn(async move {
+            let _subscriber = guard;
+            let mut keep_alive = interval(KEEP_ALIVE_INTERVAL);
+            loop {
+                let chunk = tokio::select! {
+                    Some(event) = rx.recv() => event,
+                    _ = keep_alive.tick() => Bytes::from_static(b": keep-alive\n\n"),
+                };
+                if sender.send_data(chunk).await.is_err() {
+                    break;
+                }
+            }
+            watch.abort();
+        });
+
+        let mut response = Response::new(body);
+        let headers = response.headers_mut();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/event-stream"));
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        Ok(response)
+    }
+
+    fn stats(&self, request: &Request<Body>, topic: &str) -> Result<Response<Body>, HttpError> {
+        self.authorize(request, MsgType::Stats, topic)?;
+        let stats = self.stats.topic_stats(&self.db, topic)?;
+        let stats = serde_json::to_value(stats)
+            .map_err(|e| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        Ok(json_response(StatusCode::OK, stats))
+    }
+
+    async fn publish(
+        &self,
+        mut request: Request<Body>,
+        topic: &str,
+    ) -> Result<Response<Body>, HttpError> {
+        let identity = self.authorize(&request, MsgType::Set, topic)?;
+        let codec = self.cfg.types.get(topic).ok_or_else(|| {
+            HttpError::new(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                format!("No type is registered for topic `{}`", topic),
+            )
+        })?;
+
+        let mut body = Vec::new();
+        while let Some(chunk) = request.body_mut().data().await {
+            let chunk =
+                chunk.map_err(|e| HttpError::new(StatusCode::BAD_REQUEST, e.to_string()))?;
+            if body.len() + chunk.len() > self.cfg.max_body_size {
+                return Err(HttpError::new(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    "Request body is too large",
+                ));
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        let value: Value = serde_json::from_slice(&body)
+            .map_err(|e| HttpError::new(StatusCode::BAD_REQUEST, e.to_string()))?;
+        let mut generic = codec.encode(topic, value).map_err(|_| {
+            HttpError::new(
+                StatusCode::BAD_REQUEST,
+                format!("Payload does not match the type registered for `{}`", topic),
+            )
+        })?;
+        // Clients continue their traces with a W3C `traceparent` header, which is ignored if
+        // it's invalid
+        if let Some(context) = request
+            .headers()
+            .get("traceparent")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok())
+        {
+            generic.trace = Some(context);
+        }
+        let _span = host_span(&mut generic).entered();
+        self.db.clone().insert_generic(generic.clone())?;
+        self.stats
+            .record(&generic, identity.as_deref().unwrap_or("http"));
+        Ok(Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .body(Body::empty())
+            .unwrap_or_default())
+    }
+}
+
+/// Error response, carrying its message as JSON
+#[derive(Debug)]
+struct HttpError {
+    status: StatusCode,
+    message: String,
+}
+
+impl HttpError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        HttpError {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn into_response(self) -> Response<Body> {
+        let mut response = json_response(self.status, json!({ "error": self.message }));
+        if self.status == StatusCode::UNAUTHORIZED {
+            response.headers_mut().insert(
+                WWW_AUTHENTICATE,
+                HeaderValue::from_static("Basic realm=\"meadow\""),
+            );
+        }
+        response
+    }
+}
+
+impl From<Error> for HttpError {
+    fn from(e: Error) -> Self {
+        let status = match &e {
+            Error::Unauthenticated => StatusCode::UNAUTHORIZED,
+            Error::PermissionDenied { .. } => StatusCode::FORBIDDEN,
+            Error::NonExistentTopic(_) | Error::NoNthValue => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        HttpError::new(status, e.to_string())
+    }
+}
+
+impl From<sled::Error> for HttpError {
+    fn from(e: sled::Error) -> Self {
+        Error::from(e).into()
+    }
+}
+
+impl From<postcard::Error> for HttpError {
+    fn from(e: postcard::Error) -> Self {
+        Error::from(e).into()
+    }
+}
+
+fn json_response(status: StatusCode, value: Value) -> Response<Body> {
+    let mut response = Response::new(Body::from(value.to_string()));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+/// Credentials from a request's `Authorization: Basic` header
+fn basic_credentials(request: &Request<Body>) -> Option<Credentials> {
+    let header = request.headers().get(AUTHORIZATION)?.to_str().ok()?;
+    let encoded = header.strip_prefix("Basic ")?;
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .ok()?;
+    let (identity, token) = std::str::from_utf8(&decoded).ok()?.split_once(':')?;
+    Some(Credentials::new(identity, token))
+}
+
+fn query_param<'a>(request: &'a Request<Body>, name: &str) -> Option<&'a str> {
+    request.uri().query()?.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key == name).then_some(value)
+    })
+}
+
+/// Decode a percent-encoded path segment, so that topic names can contain reserved characters
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
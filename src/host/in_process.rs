@@ -0,0 +1,51 @@
+use tracing::*;
+
+use std::result::Result;
+use std::sync::Arc;
+
+use crate::discovery::Transport;
+use crate::error::Error;
+use crate::host::dispatch::Dispatcher;
+use crate::host::stats::StatsTracker;
+use crate::host::{AuthConfig, Credentials};
+
+/// Handle to a Host running in the same process as its Nodes
+///
+/// In-process Nodes operate on the Host's store directly instead of over a socket, while still
+/// being authenticated and authorized against the Host's `AuthConfig`.
+#[derive(Debug, Clone)]
+pub struct InProcessHandle {
+    pub(crate) db: sled::Db,
+    pub(crate) auth: Option<Arc<AuthConfig>>,
+    pub(crate) stats: StatsTracker,
+}
+
+impl InProcessHandle {
+    /// Authenticate a Node, returning the Host's side of its connection, which handles its
+    /// requests just as they would be over any other transport
+    pub(crate) fn connect(
+        &self,
+        name: &str,
+        credentials: Option<&Credentials>,
+    ) -> Result<Dispatcher, Error> {
+        debug!("Host received in-process connection from {:?}", name);
+        let identity = match &self.auth {
+            Some(auth) => auth.authenticate(credentials).map(Some),
+            None => Ok(None),
+        };
+        match identity {
+            Ok(identity) => Ok(Dispatcher::new(
+                self.db.clone(),
+                self.auth.clone(),
+                self.stats.clone(),
+                Transport::InProcess,
+                identity,
+                "in-process",
+            )),
+            Err(e) => {
+                self.stats.handshake_failed(Transport::InProcess);
+                Err(e)
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use serde_json::{json, Value};
+
+use std::convert::TryInto;
+use std::fmt::{Debug, Formatter};
+use std::result::Result;
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::prelude::*;
+
+type Decoder = Arc<dyn Fn(GenericMsg) -> Result<Value, Error> + Send + Sync>;
+type Encoder = Arc<dyn Fn(&str, Value) -> Result<GenericMsg, Error> + Send + Sync>;
+
+/// Converts a topic's messages to and from JSON, for the type registered to the topic
+#[derive(Clone)]
+pub(crate) struct JsonCodec {
+    decode: Decoder,
+    encode: Encoder,
+}
+
+impl Debug for JsonCodec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsonCodec").finish_non_exhaustive()
+    }
+}
+
+impl JsonCodec {
+    pub(crate) fn new<T: Message + 'static>() -> Self {
+        JsonCodec {
+            decode: Arc::new(|generic: GenericMsg| {
+                let msg: Msg<T> = generic.try_into()?;
+                serde_json::to_value(&msg.data).map_err(json_error)
+            }),
+            encode: Arc::new(|topic: &str, value: Value| {
+                let data: T = serde_json::from_value(value).map_err(json_error)?;
+                Msg::new(MsgType::Set, topic, data).to_generic()
+            }),
+        }
+    }
+
+    /// Decode a stored message's payload to JSON
+    pub(crate) fn decode(&self, generic: GenericMsg) -> Result<Value, Error> {
+        (self.decode)(generic)
+    }
+
+    /// Encode a JSON payload as a message to set on the topic
+    pub(crate) fn encode(&self, topic: &str, value: Value) -> Result<GenericMsg, Error> {
+        (self.encode)(topic, value)
+    }
+}
+
+pub(crate) fn json_error(e: serde_json::Error) -> Error {
+    std::io::Error::from(e).into()
+}
+
+/// JSON of a stored message, whose payload is decoded if its topic has a registered type and
+/// is otherwise left as its raw bytes
+#[cfg(any(feature = "foxglove", feature = "http", feature = "mqtt"))]
+pub(crate) fn msg_json(generic: GenericMsg, codec: Option<&JsonCodec>) -> Result<Value, Error> {
+    let (topic, timestamp) = (generic.topic.clone(), generic.timestamp.to_rfc3339());
+    let data_type = generic.data_type.clone();
+    let data = match codec {
+        Some(codec) => codec.decode(generic)?,
+        None => json!(generic.data),
+    };
+    Ok(json!({
+        "topic": topic,
+        "timestamp": timestamp,
+        "data_type": data_type,
+        "data": data,
+    }))
+}
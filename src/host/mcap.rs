@@ -0,0 +1,717 @@
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use tokio::time::{interval, sleep_until, Duration, Instant};
+use tracing::*;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::result::Result;
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::host::host::GenericStore;
+use crate::host::json::{json_error, JsonCodec};
+use crate::prelude::*;
+
+/// Magic bytes at the start and end of every MCAP file
+const MAGIC: &[u8; 8] = b"\x89MCAP0\r\n";
+const OP_HEADER: u8 = 0x01;
+const OP_FOOTER: u8 = 0x02;
+const OP_SCHEMA: u8 = 0x03;
+const OP_CHANNEL: u8 = 0x04;
+const OP_MESSAGE: u8 = 0x05;
+const OP_CHUNK: u8 = 0x06;
+const OP_STATISTICS: u8 = 0x0B;
+const OP_DATA_END: u8 = 0x0F;
+/// Encoding of messages recorded as their raw payload, without a registered schema
+const POSTCARD_ENCODING: &str = "postcard";
+const JSON_ENCODING: &str = "json";
+/// Channel metadata key carrying the messages' meadow `data_type`
+const DATA_TYPE_KEY: &str = "data_type";
+/// How often the recorder flushes to disk, and looks for new topics
+const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// JSON schema of a topic, and conversions between its messages and JSON
+#[derive(Debug, Clone)]
+struct Schema {
+    name: String,
+    schema: String,
+    codec: JsonCodec,
+}
+
+/// Configuration of the Host's recorder, which writes messages to an MCAP file as they're set
+///
+/// Messages on topics with a registered schema are recorded as JSON, which tools like Foxglove
+/// Studio can decode, while others are recorded as their raw postcard payload. Every channel
+/// carries the meadow `data_type` of its messages in its metadata.
+#[derive(Debug, Clone)]
+pub struct RecorderConfig {
+    /// Path of the MCAP file, which is overwritten if it exists
+    pub path: PathBuf,
+    topics: Vec<String>,
+    schemas: HashMap<String, Schema>,
+}
+
+impl RecorderConfig {
+    /// Record every topic on the Host to the MCAP file at the given path
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        RecorderConfig {
+            path: path.into(),
+            topics: Vec::new(),
+            schemas: HashMap::new(),
+        }
+    }
+
+    /// Only record the selected topics, rather than every topic on the Host
+    pub fn with_topic(mut self, topic: impl Into<String>) -> Self {
+        self.topics.push(topic.into());
+        self
+    }
+
+    /// Register the type of a topic's messages, with its JSON schema, so they're recorded as
+    /// JSON
+    pub fn with_schema<T: Message + 'static>(
+        mut self,
+        topic: impl Into<String>,
+        schema_name: impl Into<String>,
+        json_schema: impl Into<String>,
+    ) -> Self {
+        let schema = Schema {
+            name: schema_name.into(),
+            schema: json_schema.into(),
+            codec: JsonCodec::new::<T>(),
+        };
+        self.schemas.insert(topic.into(), schema);
+        self
+    }
+}
+
+/// Configuration of a player, which sets the messages of a recording on the Host at their
+/// original timing
+///
+/// Recordings are either MCAP files or the sled directories Hosts store their messages in.
+#[derive(Debug, Clone)]
+pub struct PlayerConfig {
+    /// Path of the MCAP file or sled directory
+    pub path: PathBuf,
+    /// Playback speed relative to the original timing, where `f64::INFINITY` sets messages as
+    /// fast as possible
+    pub speed: f64,
+    /// Whether playback restarts from the beginning once it reaches the end
+    pub looping: bool,
+    /// Whether messages keep their recorded timestamps, rather than being stamped as they're set
+    pub original_timestamps: bool,
+    topics: Vec<String>,
+    types: HashMap<String, JsonCodec>,
+}
+
+impl PlayerConfig {
+    /// Play every topic of the recording at the given path once, at its original speed
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        PlayerConfig {
+            path: path.into(),
+            speed: 1.0,
+            looping: false,
+            original_timestamps: false,
+            topics: Vec::new(),
+            types: HashMap::new(),
+        }
+    }
+
+    /// Set the playback speed relative to the original timing
+    pub fn set_speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Restart playback from the beginning once it reaches the end
+    pub fn set_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Keep the recorded timestamps of messages, rather than stamping them as they're set
+    ///
+    /// Subscribed Nodes ignore messages older than the latest they have, so looped playback
+    /// should be stamped as it's set.
+    pub fn set_original_timestamps(mut self, original_timestamps: bool) -> Self {
+        self.original_timestamps = original_timestamps;
+        self
+    }
+
+    /// Only play the selected topics, rather than every topic of the recording
+    pub fn with_topic(mut self, topic: impl Into<String>) -> Self {
+        self.topics.push(topic.into());
+        self
+    }
+
+    /// Register the type of a topic's messages, so those recorded as JSON can be played
+    pub fn with_type<T: Message + 'static>(mut self, topic: impl Into<String>) -> Self {
+        self.types.insert(topic.into(), JsonCodec::new::<T>());
+        self
+    }
+
+    fn selected(&self, topic: &str) -> bool {
+        self.topics.is_empty() || self.topics.iter().any(|selected| selected == topic)
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> Error {
+    Error::InvalidRecording(reason.into())
+}
+
+fn nanos(timestamp: &DateTime<Utc>) -> u64 {
+    timestamp.timestamp_nanos_opt().unwrap_or_default().max(0) as u64
+}
+
+fn put_str(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+/// Writes an unchunked MCAP file, with a summary of its schemas, channels and statistics
+struct McapWriter {
+    out: BufWriter<File>,
+    position: u64,
+    schema_ids: HashMap<String, u16>,
+    channel_ids: HashMap<(String, String, bool), u16>,
+    /// Schema and channel records, repeated in the summary section
+    summary: Vec<(u8, Vec<u8>)>,
+    channel_counts: BTreeMap<u16, u64>,
+    message_count: u64,
+    start_time: u64,
+    end_time: u64,
+    sequence: u32,
+    finished: bool,
+}
+
+impl McapWriter {
+    fn create(path: &Path) -> Result<Self, Error> {
+        let mut writer = McapWriter {
+            out: BufWriter::new(File::create(path)?),
+            position: 0,
+            schema_ids: HashMap::new(),
+            channel_ids: HashMap::new(),
+            summary: Vec::new(),
+            channel_counts: BTreeMap::new(),
+            message_count: 0,
+            start_time: u64::MAX,
+            end_time: 0,
+            sequence: 0,
+            finished: false,
+        };
+        writer.write_bytes(MAGIC)?;
+        let mut header = Vec::new();
+        put_str(&mut header, "");
+        put_str(&mut header, concat!("meadow ", env!("CARGO_PKG_VERSION")));
+        writer.write_record(OP_HEADER, &header)?;
+        Ok(writer)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.out.write_all(bytes)?;
+        self.position += bytes.len() as u64;
+        Ok(())
+    }
+
+    fn write_record(&mut self, opcode: u8, content: &[u8]) -> Result<(), Error> {
+        self.write_bytes(&[opcode])?;
+        self.write_bytes(&(content.len() as u64).to_le_bytes())?;
+        self.write_bytes(content)
+    }
+
+    /// ID of the topic's JSON schema, which is written the first time it's used
+    fn schema_id(&mut self, topic: &str, schema: &Schema) -> Result<u16, Error> {
+        if let Some(id) = self.schema_ids.get(topic) {
+            return Ok(*id);
+        }
+        let id = self.schema_ids.len() as u16 + 1;
+        let mut record = id.to_le_bytes().to_vec();
+        put_str(&mut record, &schema.name);
+        put_str(&mut record, "jsonschema");
+        put_str(&mut record, &schema.schema);
+        self.write_record(OP_SCHEMA, &record)?;
+        self.summary.push((OP_SCHEMA, record));
+        self.schema_ids.insert(topic.to_string(), id);
+        Ok(id)
+    }
+
+    /// ID of the channel for a topic's messages of one type, which is written the first time
+    /// it's used
+    fn channel_id(&mut self, generic: &GenericMsg, schema: Option<&Schema>) -> Result<u16, Error> {
+        let key = (
+            generic.topic.clone(),
+            generic.data_type.clone(),
+            schema.is_some(),
+        );
+        if let Some(id) = self.channel_ids.get(&key) {
+            return Ok(*id);
+        }
+        let (schema_id, encoding) = match schema {
+            Some(schema) => (self.schema_id(&generic.topic, schema)?, JSON_ENCODING),
+            None => (0, POSTCARD_ENCODING),
+        };
+        let id = self.channel_ids.len() as u16;
+        let mut record = id.to_le_bytes().to_vec();
+        record.extend_from_slice(&schema_id.to_le_bytes());
+        put_str(&mut record, &generic.topic);
+        put_str(&mut record, encoding);
+        let mut metadata = Vec::new();
+        put_str(&mut metadata, DATA_TYPE_KEY);
+        put_str(&mut metadata, &generic.data_type);
+        record.extend_from_slice(&(metadata.len() as u32).to_le_bytes());
+        record.extend_from_slice(&metadata);
+        self.write_record(OP_CHANNEL, &record)?;
+        self.summary.push((OP_CHANNEL, record));
+        self.channel_ids.insert(key, id);
+        Ok(id)
+    }
+
+    /// Write a message, as JSON if its topic has a schema that its data can be decoded with
+    fn write_msg(&mut self, generic: GenericMsg, schema: Option<&Schema>) -> Result<(), Error> {
+        let json = match schema {
+            Some(schema) => match schema.codec.decode(generic.clone()) {
+                Ok(value) => Some(serde_json::to_vec(&value).map_err(json_error)?),
+                Err(e) => {
+                    warn!("Recording {} as postcard: {}", generic.topic, e);
+                    None
+                }
+            },
+            None => None,
+        };
+        let schema = schema.filter(|_| json.is_some());
+        let channel_id = self.channel_id(&generic, schema)?;
+        let time = nanos(&generic.timestamp);
+
+        let mut record = channel_id.to_le_bytes().to_vec();
+        record.extend_from_slice(&self.sequence.to_le_bytes());
+        record.extend_from_slice(&time.to_le_bytes());
+        record.extend_from_slice(&time.to_le_bytes());
+        record.extend_from_slice(json.as_deref().unwrap_or(&generic.data));
+        self.write_record(OP_MESSAGE, &record)?;
+
+        self.sequence = self.sequence.wrapping_add(1);
+        self.message_count += 1;
+        *self.channel_counts.entry(channel_id).or_default() += 1;
+        self.start_time = self.start_time.min(time);
+        self.end_time = self.end_time.max(time);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(self.out.flush()?)
+    }
+
+    /// End the data section, and write the summary and footer
+    fn finish(&mut self) -> Result<(), Error> {
+        self.finished = true;
+        self.write_record(OP_DATA_END, &0u32.to_le_bytes())?;
+
+        let summary_start = self.position;
+        // Schemas are summarized ahead of the channels that refer to them
+        let mut summary = std::mem::take(&mut self.summary);
+        summary.sort_by_key(|(opcode, _record)| *opcode);
+        for (opcode, record) in summary {
+            self.write_record(opcode, &record)?;
+        }
+        let mut statistics = self.message_count.to_le_bytes().to_vec();
+        statistics.extend_from_slice(&(self.schema_ids.len() as u16).to_le_bytes());
+        statistics.extend_from_slice(&(self.channel_ids.len() as u32).to_le_bytes());
+        // Attachment, metadata and chunk counts
+        statistics.extend_from_slice(&[0u8; 12]);
+        statistics.extend_from_slice(&self.start_time.min(self.end_time).to_le_bytes());
+        statistics.extend_from_slice(&self.end_time.to_le_bytes());
+        let mut counts = Vec::new();
+        for (channel_id, count) in &self.channel_counts {
+            counts.extend_from_slice(&channel_id.to_le_bytes());
+            counts.extend_from_slice(&count.to_le_bytes());
+        }
+        statistics.extend_from_slice(&(counts.len() as u32).to_le_bytes());
+        statistics.extend_from_slice(&counts);
+        self.write_record(OP_STATISTICS, &statistics)?;
+
+        let mut footer = summary_start.to_le_bytes().to_vec();
+        footer.extend_from_slice(&[0u8; 12]);
+        self.write_record(OP_FOOTER, &footer)?;
+        self.write_bytes(MAGIC)?;
+        self.flush()
+    }
+}
+
+impl Drop for McapWriter {
+    fn drop(&mut self) {
+        if !self.finished {
+            if let Err(e) = self.finish() {
+                error!("Unable to finish recording: {}", e);
+            }
+        }
+    }
+}
+
+/// Entry set on a topic's tree, as (topic, key, value)
+type Entry = (String, sled::IVec, sled::IVec);
+
+/// Writes the messages set on the Host's topics to an MCAP file
+///
+/// Selected topics are watched from the start, while every other topic is watched once it's
+/// first seen, after which the messages it already holds are recorded.
+struct Recorder {
+    db: sled::Db,
+    cfg: Arc<RecorderConfig>,
+    writer: McapWriter,
+    tx: mpsc::UnboundedSender<Entry>,
+    rx: mpsc::UnboundedReceiver<Entry>,
+    watched: HashSet<String>,
+    /// Messages recorded as their topic was first seen, which its watcher may also report
+    recorded: HashSet<(String, sled::IVec)>,
+    /// Dropped along with the recorder, which stops the watchers
+    watchers: JoinSet<()>,
+}
+
+impl Recorder {
+    fn watch(&mut self, topic: String, tree: &sled::Tree) {
+        let mut subscriber = tree.watch_prefix(vec![]);
+        let tx = self.tx.clone();
+        self.watched.insert(topic.clone());
+        self.watchers.spawn(async move {
+            while let Some(event) = (&mut subscriber).await {
+                if let sled::Event::Insert { key, value } = event {
+                    if tx.send((topic.clone(), key, value)).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Watch the topics created since the last check, and record the messages they hold
+    fn watch_new_topics(&mut self, watch: bool) {
+        if !self.cfg.topics.is_empty() {
+            return;
+        }
+        for topic in self.db.topics().unwrap_or_default() {
+            if self.watched.contains(&topic) {
+                continue;
+            }
+            let tree = match self.db.open_tree(topic.as_bytes()) {
+                Ok(tree) => tree,
+                Err(e) => {
+                    error!("Unable to record {}: {}", topic, e);
+                    continue;
+                }
+            };
+            match watch {
+                true => self.watch(topic.clone(), &tree),
+                false => {
+                    self.watched.insert(topic.clone());
+                }
+            }
+            for (key, value) in tree.iter().flatten() {
+                self.recorded.insert((topic.clone(), key));
+                self.write(&value);
+            }
+        }
+    }
+
+    fn receive(&mut self, (topic, key, value): Entry) {
+        if !self.recorded.remove(&(topic, key)) {
+            self.write(&value);
+        }
+    }
+
+    fn write(&mut self, value: &[u8]) {
+        let result = postcard::from_bytes::<GenericMsg>(value)
+            .map_err(Error::from)
+            .and_then(|generic| {
+                let schema = self.cfg.schemas.get(&generic.topic);
+                self.writer.write_msg(generic, schema)
+            });
+        if let Err(e) = result {
+            error!("Unable to record message: {}", e);
+        }
+    }
+}
+
+impl Drop for Recorder {
+    /// Record the messages still waiting to be written, before the recording is finished
+    fn drop(&mut self) {
+        while let Ok(entry) = self.rx.try_recv() {
+            self.receive(entry);
+        }
+        // Nothing is left to watch the topics for
+        self.watch_new_topics(false);
+    }
+}
+
+/// Record messages set on the Host's topics to an MCAP file, until the recorder is stopped
+pub(crate) async fn record(db: sled::Db, cfg: Arc<RecorderConfig>) {
+    let writer = match McapWriter::create(&cfg.path) {
+        Ok(writer) => writer,
+        Err(e) => return error!("Unable to record to {:?}: {}", cfg.path, e),
+    };
+    info!("Recording to {:?}", cfg.path);
+
+    // Recording never holds up the Host's writes, as the file is written to from this task
+    let (tx, rx) = mpsc::unbounded_channel();
+    let mut recorder = Recorder {
+        db: db.clone(),
+        cfg: cfg.clone(),
+        writer,
+        tx,
+        rx,
+        watched: HashSet::new(),
+        recorded: HashSet::new(),
+        watchers: JoinSet::new(),
+    };
+    // Messages already on the Host's topics aren't recorded
+    let topics = match cfg.topics.is_empty() {
+        true => db.topics().unwrap_or_default(),
+        false => cfg.topics.clone(),
+    };
+    for topic in topics {
+        match db.open_tree(topic.as_bytes()) {
+            Ok(tree) => recorder.watch(topic, &tree),
+            Err(e) => error!("Unable to record {}: {}", topic, e),
+        }
+    }
+
+    let mut tick = interval(FLUSH_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = tick.tick() => {
+                if let Err(e) = recorder.writer.flush() {
+                    error!("Unable to flush recording: {}", e);
+                }
+                recorder.watch_new_topics(true);
+            }
+            Some(entry) = recorder.rx.recv() => recorder.receive(entry),
+        }
+    }
+}
+
+/// Cursor over the bytes of an MCAP record
+struct Cursor<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if self.buf.len() < n {
+            return Err(invalid("truncated MCAP record"));
+        }
+        let (taken, rest) = self.buf.split_at(n);
+        self.buf = rest;
+        Ok(taken)
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        Ok(u16::from_le_bytes(
+            self.take(2)?.try_into().unwrap_or_default(),
+        ))
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_le_bytes(
+            self.take(4)?.try_into().unwrap_or_default(),
+        ))
+    }
+
+    fn u64(&mut self) -> Result<u64, Error> {
+        Ok(u64::from_le_bytes(
+            self.take(8)?.try_into().unwrap_or_default(),
+        ))
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|_| invalid("non-UTF-8 string"))
+    }
+}
+
+/// Channel of an MCAP file, as needed to play its messages
+struct Channel {
+    topic: String,
+    encoding: String,
+    data_type: String,
+}
+
+/// Reads the messages of an MCAP file, from its data section
+#[derive(Default)]
+struct McapReader {
+    schemas: HashMap<u16, String>,
+    channels: HashMap<u16, Channel>,
+    /// Channels whose messages can't be played, which are only warned about once
+    skipped: HashSet<u16>,
+    msgs: Vec<GenericMsg>,
+}
+
+impl McapReader {
+    fn read(&mut self, records: &[u8], cfg: &PlayerConfig) -> Result<(), Error> {
+        let mut cursor = Cursor { buf: records };
+        while !cursor.buf.is_empty() {
+            let opcode = cursor.take(1)?[0];
+            let len = cursor.u64()? as usize;
+            let mut record = Cursor {
+                buf: cursor.take(len)?,
+            };
+            match opcode {
+                OP_SCHEMA => {
+                    let id = record.u16()?;
+                    self.schemas.insert(id, record.string()?);
+                }
+                OP_CHANNEL => {
+                    let id = record.u16()?;
+                    let schema_id = record.u16()?;
+                    let topic = record.string()?;
+                    let encoding = record.string()?;
+                    let metadata_len = record.u32()? as usize;
+                    let mut metadata = Cursor {
+                        buf: record.take(metadata_len)?,
+                    };
+                    // Channels recorded by other tools are played with the name of their schema
+                    let mut data_type = self.schemas.get(&schema_id).cloned().unwrap_or_default();
+                    while !metadata.buf.is_empty() {
+                        let (key, value) = (metadata.string()?, metadata.string()?);
+                        if key == DATA_TYPE_KEY {
+                            data_type = value;
+                        }
+                    }
+                    let channel = Channel {
+                        topic,
+                        encoding,
+                        data_type,
+                    };
+                    self.channels.insert(id, channel);
+                }
+                OP_MESSAGE => {
+                    let channel_id = record.u16()?;
+                    let _sequence = record.u32()?;
+                    let log_time = record.u64()?;
+                    let _publish_time = record.u64()?;
+                    self.read_msg(channel_id, log_time, record.buf, cfg)?;
+                }
+                OP_CHUNK => {
+                    let _start_time = record.u64()?;
+                    let _end_time = record.u64()?;
+                    let _uncompressed_size = record.u64()?;
+                    let _uncompressed_crc = record.u32()?;
+                    let compression = record.string()?;
+                    if !compression.is_empty() {
+                        return Err(invalid(format!(
+                            "{} compressed chunks aren't supported",
+                            compression
+                        )));
+                    }
+                    let records_len = record.u64()? as usize;
+                    let records = record.take(records_len)?;
+                    self.read(records, cfg)?;
+                }
+                OP_DATA_END | OP_FOOTER => return Ok(()),
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn read_msg(
+        &mut self,
+        channel_id: u16,
+        log_time: u64,
+        data: &[u8],
+        cfg: &PlayerConfig,
+    ) -> Result<(), Error> {
+        let channel = match self.channels.get(&channel_id) {
+            Some(channel) => channel,
+            None => return Err(invalid(format!("unknown channel {}", channel_id))),
+        };
+        if !cfg.selected(&channel.topic) {
+            return Ok(());
+        }
+        let mut generic = if channel.encoding == JSON_ENCODING {
+            let codec = match cfg.types.get(&channel.topic) {
+                Some(codec) => codec,
+                None => {
+                    if self.skipped.insert(channel_id) {
+                        warn!("No type is registered for {}, skipping it", channel.topic);
+                    }
+                    return Ok(());
+                }
+            };
+            let value: Value = serde_json::from_slice(data).map_err(json_error)?;
+            codec.encode(&channel.topic, value)?
+        } else {
+            GenericMsg {
+                msg_type: MsgType::Set,
+                timestamp: Utc::now(),
+                topic: channel.topic.clone(),
+                data_type: channel.data_type.clone(),
+                data: data.to_vec(),
+                trace: None,
+                received: None,
+                latency: None,
+            }
+        };
+        generic.timestamp = Utc.timestamp_nanos(log_time as i64);
+        self.msgs.push(generic);
+        Ok(())
+    }
+}
+
+/// Read the selected messages of a recording, in the order they were set
+fn load(cfg: &PlayerConfig) -> Result<Vec<GenericMsg>, Error> {
+    let mut msgs = if cfg.path.is_dir() {
+        // Hosts store each message on a topic's tree, keyed by its timestamp
+        let db = sled::Config::new().path(&cfg.path).open()?;
+        let mut msgs = Vec::new();
+        for topic in db.topics()?.into_iter().filter(|topic| cfg.selected(topic)) {
+            for (_key, value) in db.open_tree(topic.as_bytes())?.iter().flatten() {
+                let mut generic: GenericMsg = postcard::from_bytes(&value)?;
+                generic.msg_type = MsgType::Set;
+                msgs.push(generic);
+            }
+        }
+        msgs
+    } else {
+        let bytes = std::fs::read(&cfg.path)?;
+        if !bytes.starts_with(MAGIC) {
+            return Err(invalid("not an MCAP file or sled directory"));
+        }
+        let mut reader = McapReader::default();
+        reader.read(&bytes[MAGIC.len()..], cfg)?;
+        reader.msgs
+    };
+    msgs.sort_by_key(|msg| msg.timestamp);
+    Ok(msgs)
+}
+
+/// Set the messages of a recording on the Host at their original timing, scaled by the
+/// player's speed
+pub(crate) async fn play(mut db: sled::Db, cfg: Arc<PlayerConfig>) -> Result<(), Error> {
+    let msgs = load(&cfg)?;
+    let first = match msgs.first() {
+        Some(msg) => msg.timestamp,
+        None => return Ok(()),
+    };
+    info!("Playing {} messages from {:?}", msgs.len(), cfg.path);
+    loop {
+        let start = Instant::now();
+        for msg in &msgs {
+            let offset = (msg.timestamp - first).to_std().unwrap_or_default();
+            let offset =
+                Duration::try_from_secs_f64(offset.as_secs_f64() / cfg.speed).unwrap_or_default();
+            sleep_until(start + offset).await;
+            let mut msg = msg.clone();
+            if !cfg.original_timestamps {
+                msg.timestamp = Utc::now();
+            }
+            db.insert_generic(msg)?;
+        }
+        if !cfg.looping {
+            return Ok(());
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::server::conn::Http;
+use hyper::service::service_fn;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use tokio::net::TcpListener;
+use tracing::*;
+
+use std::convert::Infallible;
+use std::fmt::Write;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::atomic::Ordering;
+
+use crate::discovery::Transport;
+use crate::host::stats::{StatsTracker, LATENCY_BUCKETS, REQUEST_TYPES, TRANSPORTS};
+
+/// Configuration of the Host's Prometheus metrics endpoint
+///
+/// The endpoint serves `GET /metrics` in the Prometheus text format. Metrics are kept in
+/// counters that are cheap to update, and are only collected into text when scraped.
+#[derive(Debug, Clone)]
+pub struct MetricsConfig {
+    /// Address the metrics endpoint listens on
+    pub bind_addr: SocketAddr,
+}
+
+impl Default for MetricsConfig {
+    /// Listen on `127.0.0.1:9464`
+    fn default() -> Self {
+        MetricsConfig {
+            bind_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 9464),
+        }
+    }
+}
+
+impl MetricsConfig {
+    /// Set the address the metrics endpoint listens on
+    pub fn set_bind_addr(mut self, bind_addr: impl Into<SocketAddr>) -> Self {
+        self.bind_addr = bind_addr.into();
+        self
+    }
+}
+
+pub(crate) async fn serve(listener: TcpListener, db: sled::Db, stats: StatsTracker) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, addr)) => {
+                let (db, stats) = (db.clone(), stats.clone());
+                tokio::spawn(async move {
+                    let service = service_fn(move |request| {
+                        let response = respond(&request, &db, &stats);
+                        async move { Ok::<_, Infallible>(response) }
+                    });
+                    if let Err(e) = Http::new()
+                        .http1_only(true)
+                        .serve_connection(stream, service)
+                        .await
+                    {
+                        debug!("Metrics connection from {} closed: {}", addr, e);
+                    }
+                });
+            }
+            Err(e) => error!("Error accepting metrics connection: {}", e),
+        }
+    }
+}
+
+fn respond(request: &Request<Body>, db: &sled::Db, stats: &StatsTracker) -> Response<Body> {
+    let status = match (request.method(), request.uri().path()) {
+        (&Method::GET, "/metrics") => StatusCode::OK,
+        (_, "/metrics") => StatusCode::METHOD_NOT_ALLOWED,
+        _ => StatusCode::NOT_FOUND,
+    };
+    let mut response = match status {
+        StatusCode::OK => Response::new(Body::from(render(db, stats))),
+        _ => Response::new(Body::empty()),
+    };
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; version=0.0.4"),
+    );
+    response
+}
+
+/// Collect the Host's metrics in the Prometheus text format
+pub(crate) fn render(db: &sled::Db, stats: &StatsTracker) -> String {
+    let counters = &stats.counters;
+    let mut out = String::new();
+
+    // UDP is connectionless, so it has no connections to count
+    header(
+        &mut out,
+        "meadow_connections",
+        "gauge",
+        "Open Node connections, by transport",
+    );
+    for (i, transport) in TRANSPORTS.iter().enumerate() {
+        if *transport != Transport::Udp {
+            let value = counters.connections[i].load(Ordering::Relaxed);
+            sample(
+                &mut out,
+                "meadow_connections",
+                &[("transport", transport_label(*transport))],
+                value,
+            );
+        }
+    }
+    for (name, help, values) in [
+        (
+            "meadow_handshake_failures_total",
+            "Nodes that failed to connect or authenticate, by transport",
+            &counters.handshake_failures,
+        ),
+        (
+            "meadow_decode_errors_total",
+            "Messages that couldn't be deserialized, by transport",
+            &counters.decode_errors,
+        ),
+    ] {
+        header(&mut out, name, "counter", help);
+        for (i, transport) in TRANSPORTS.iter().enumerate() {
+            let value = values[i].load(Ordering::Relaxed);
+            sample(
+                &mut out,
+                name,
+                &[("transport", transport_label(*transport))],
+                value,
+            );
+        }
+    }
+
+    header(
+        &mut out,
+        "meadow_requests_total",
+        "counter",
+        "Authorized requests received, by topic and type",
+    );
+    for (topic, counts) in stats.requests() {
+        for (request_type, count) in REQUEST_TYPES.iter().zip(counts) {
+            if count > 0 {
+                sample(
+                    &mut out,
+                    "meadow_requests_total",
+                    &[("topic", &topic), ("type", request_type)],
+                    count,
+                );
+            }
+        }
+    }
+
+    let totals = stats.totals();
+    header(
+        &mut out,
+        "meadow_messages_total",
+        "counter",
+        "Messages set, by topic",
+    );
+    for (topic, messages, _bytes, _subscribers) in &totals {
+        sample(
+            &mut out,
+            "meadow_messages_total",
+            &[("topic", topic)],
+            messages,
+        );
+    }
+    header(
+        &mut out,
+        "meadow_message_bytes_total",
+        "counter",
+        "Payload bytes set, by topic",
+    );
+    for (topic, _messages, bytes, _subscribers) in &totals {
+        sample(
+            &mut out,
+            "meadow_message_bytes_total",
+            &[("topic", topic)],
+            bytes,
+        );
+    }
+    header(
+        &mut out,
+        "meadow_subscriptions",
+        "gauge",
+        "Open subscriptions, by topic",
+    );
+    for (topic, _messages, _bytes, subscribers) in &totals {
+        sample(
+            &mut out,
+            "meadow_subscriptions",
+            &[("topic", topic)],
+            subscribers,
+        );
+    }
+
+    header(
+        &mut out,
+        "meadow_store_size_bytes",
+        "gauge",
+        "Size of the Host's sled database on disk",
+    );
+    match db.size_on_disk() {
+        Ok(size) => sample(&mut out, "meadow_store_size_bytes", &[], size),
+        Err(e) => error!("Unable to measure the size of the Host's store: {}", e),
+    }
+
+    let name = "meadow_request_duration_seconds";
+    header(
+        &mut out,
+        name,
+        "histogram",
+        "Time taken to reply to requests, by type",
+    );
+    for (request_type, histogram) in REQUEST_TYPES.iter().zip(&counters.latencies) {
+        // Subscriptions last as long as their Node, so aren't timed
+        if *request_type == "subscribe" {
+            continue;
+        }
+        let mut cumulative = 0;
+        for (bound, bucket) in LATENCY_BUCKETS.iter().zip(&histogram.buckets) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let bound = bound.to_string();
+            let labels = [("type", *request_type), ("le", bound.as_str())];
+            sample(&mut out, &format!("{}_bucket", name), &labels, cumulative);
+        }
+        let count = histogram.count.load(Ordering::Relaxed);
+        let labels = [("type", *request_type), ("le", "+Inf")];
+        sample(&mut out, &format!("{}_bucket", name), &labels, count);
+        let sum = histogram.sum_nanos.load(Ordering::Relaxed) as f64 / 1e9;
+        sample(
+            &mut out,
+            &format!("{}_sum", name),
+            &[("type", request_type)],
+            sum,
+        );
+        sample(
+            &mut out,
+            &format!("{}_count", name),
+            &[("type", request_type)],
+            count,
+        );
+    }
+
+    out
+}
+
+fn transport_label(transport: Transport) -> &'static str {
+    match transport {
+        Transport::Tcp => "tcp",
+        Transport::Udp => "udp",
+        Transport::Quic => "quic",
+        Transport::Tls => "tls",
+        Transport::Unix => "unix",
+        Transport::InProcess => "in_process",
+        Transport::Serial => "serial",
+        Transport::Stream => "stream",
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: impl std::fmt::Display) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(key, value)| format!("{}=\"{}\"", key, escape(value)))
+            .collect();
+        let _ = write!(out, "{{{}}}", labels.join(","));
+    }
+    let _ = writeln!(out, " {}", value);
+}
+
+/// Escape a label value, which may be any topic name
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -1,16 +1,67 @@
+mod auth;
+mod clock;
 mod config;
+mod dispatch;
+mod federation;
+#[cfg(feature = "foxglove")]
+mod foxglove;
 #[allow(clippy::module_inception)]
 pub mod host;
+#[cfg(feature = "http")]
+mod http;
+mod in_process;
+#[cfg(any(
+    feature = "foxglove",
+    feature = "http",
+    feature = "mqtt",
+    feature = "mcap"
+))]
+mod json;
+#[cfg(feature = "mcap")]
+mod mcap;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "mqtt")]
+mod mqtt;
 pub mod network_config;
 #[cfg(feature = "quic")]
+pub mod pki;
+#[cfg(feature = "quic")]
 pub mod quic;
+mod replication;
 
+#[cfg(feature = "serial")]
+mod serial;
+mod stats;
+mod stream;
 mod tcp;
+#[cfg(feature = "tls")]
+mod tls;
 mod udp;
+#[cfg(unix)]
+mod unix;
 
+pub use crate::host::auth::{AclRule, AuthConfig, Credentials, Identity, Permission};
 pub use crate::host::config::*;
+pub use crate::host::federation::{BridgeDirection, PeerConfig, TopicBridge};
+#[cfg(feature = "foxglove")]
+pub use crate::host::foxglove::FoxgloveConfig;
 pub use crate::host::host::*;
-pub use crate::host::network_config::{QuicConfig, TcpConfig, UdpConfig};
+#[cfg(feature = "http")]
+pub use crate::host::http::HttpConfig;
+pub use crate::host::in_process::InProcessHandle;
+#[cfg(feature = "mcap")]
+pub use crate::host::mcap::{PlayerConfig, RecorderConfig};
+#[cfg(feature = "metrics")]
+pub use crate::host::metrics::MetricsConfig;
+#[cfg(feature = "mqtt")]
+pub use crate::host::mqtt::{MqttConfig, MqttEncoding, MqttQoS, TopicMapping};
+#[cfg(unix)]
+pub use crate::host::network_config::UnixConfig;
+pub use crate::host::network_config::{AddressFamily, QuicConfig, TcpConfig, TlsConfig, UdpConfig};
 
+pub(crate) use crate::host::dispatch::{Dispatcher, Subscription};
 #[cfg(feature = "quic")]
 pub use crate::host::quic::generate_certs;
+pub use crate::host::replication::{HostRole, ReplicationConfig};
+pub use crate::host::stats::{SizePercentiles, TopicStats};
@@ -0,0 +1,329 @@
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, Publish};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use tokio::time::{sleep, Duration};
+use tracing::*;
+
+use std::collections::{HashMap, HashSet};
+use std::result::Result;
+use std::sync::{Arc, Mutex};
+
+use crate::error::Error;
+use crate::host::host::GenericStore;
+use crate::host::json::{json_error, msg_json, JsonCodec};
+use crate::prelude::*;
+
+pub use rumqttc::QoS as MqttQoS;
+
+/// How long the bridge waits before reconnecting to an unreachable broker
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Encoding of the payloads the bridge exchanges with the broker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MqttEncoding {
+    /// The whole serialized `GenericMsg`, for exchanging messages with other meadow Hosts
+    Postcard,
+    /// The message's data as JSON, for topics with a registered type; messages on other
+    /// topics are exported as JSON carrying their raw bytes
+    Json,
+}
+
+/// Pairing of a meadow topic with an MQTT topic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicMapping {
+    pub meadow_topic: String,
+    /// MQTT topic, which may be a filter with `+` and `#` wildcards for imports
+    pub mqtt_topic: String,
+    pub qos: MqttQoS,
+    pub encoding: MqttEncoding,
+}
+
+impl TopicMapping {
+    /// Map a meadow topic to an MQTT topic, with at-least-once delivery of postcard payloads
+    pub fn new(meadow_topic: impl Into<String>, mqtt_topic: impl Into<String>) -> Self {
+        TopicMapping {
+            meadow_topic: meadow_topic.into(),
+            mqtt_topic: mqtt_topic.into(),
+            qos: MqttQoS::AtLeastOnce,
+            encoding: MqttEncoding::Postcard,
+        }
+    }
+
+    /// Set the quality of service messages are published or subscribed with
+    pub fn set_qos(mut self, qos: MqttQoS) -> Self {
+        self.qos = qos;
+        self
+    }
+
+    /// Set the encoding of the mapping's MQTT payloads
+    pub fn set_encoding(mut self, encoding: MqttEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+}
+
+/// Configuration of the Host's bridge to an MQTT broker
+///
+/// Messages set on exported topics are published to the broker, and messages the broker
+/// delivers on imported topics are set on the Host.
+#[derive(Debug, Clone)]
+pub struct MqttConfig {
+    pub broker_host: String,
+    pub broker_port: u16,
+    /// Client ID the bridge connects to the broker with
+    pub client_id: String,
+    pub keep_alive: Duration,
+    /// Largest MQTT packet sent or received, in bytes
+    pub max_packet_size: usize,
+    credentials: Option<(String, String)>,
+    exports: Vec<TopicMapping>,
+    imports: Vec<TopicMapping>,
+    types: HashMap<String, JsonCodec>,
+}
+
+impl MqttConfig {
+    /// Bridge to the broker at the given host and port
+    pub fn new(broker_host: impl Into<String>, broker_port: u16) -> Self {
+        MqttConfig {
+            broker_host: broker_host.into(),
+            broker_port,
+            client_id: "meadow".into(),
+            keep_alive: Duration::from_secs(5),
+            max_packet_size: 65_536,
+            credentials: None,
+            exports: Vec::new(),
+            imports: Vec::new(),
+            types: HashMap::new(),
+        }
+    }
+
+    /// Set the client ID the bridge connects to the broker with
+    pub fn set_client_id(mut self, client_id: impl Into<String>) -> Self {
+        self.client_id = client_id.into();
+        self
+    }
+
+    /// Set the interval at which the broker and bridge check the connection is alive
+    pub fn set_keep_alive(mut self, keep_alive: Duration) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    /// Set the largest MQTT packet sent or received, in bytes
+    pub fn set_max_packet_size(mut self, max_packet_size: usize) -> Self {
+        self.max_packet_size = max_packet_size;
+        self
+    }
+
+    /// Authenticate with the broker using a username and password
+    pub fn set_credentials(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.credentials = Some((username.into(), password.into()));
+        self
+    }
+
+    /// Publish messages set on a meadow topic to the broker
+    pub fn with_export(mut self, mapping: TopicMapping) -> Self {
+        self.exports.push(mapping);
+        self
+    }
+
+    /// Set messages the broker delivers on an MQTT topic on a meadow topic
+    pub fn with_import(mut self, mapping: TopicMapping) -> Self {
+        self.imports.push(mapping);
+        self
+    }
+
+    /// Register the type of a meadow topic's messages, so they can be converted to and from JSON
+    pub fn with_type<T: Message + 'static>(mut self, meadow_topic: impl Into<String>) -> Self {
+        self.types
+            .insert(meadow_topic.into(), JsonCodec::new::<T>());
+        self
+    }
+}
+
+/// Messages imported on topics the bridge also exports, which aren't exported again so that they
+/// don't echo back to the broker
+type Imported = Arc<Mutex<HashSet<(String, Vec<u8>)>>>;
+
+/// Run the bridge, reconnecting to the broker whenever the connection is lost
+pub(crate) async fn bridge(db: sled::Db, cfg: Arc<MqttConfig>) {
+    let mut options = MqttOptions::new(&cfg.client_id, &cfg.broker_host, cfg.broker_port);
+    options
+        .set_keep_alive(cfg.keep_alive)
+        .set_max_packet_size(cfg.max_packet_size, cfg.max_packet_size);
+    if let Some((username, password)) = &cfg.credentials {
+        options.set_credentials(username, password);
+    }
+    let (client, mut eventloop) = AsyncClient::new(options, 1024);
+    let imported: Imported = Arc::new(Mutex::new(HashSet::new()));
+
+    // Dropped along with the bridge, which stops the export tasks
+    let mut tasks = JoinSet::new();
+    for mapping in &cfg.exports {
+        let subscriber = match db.open_tree(mapping.meadow_topic.as_bytes()) {
+            Ok(tree) => tree.watch_prefix(vec![]),
+            Err(e) => {
+                error!("Unable to export {}: {}", mapping.meadow_topic, e);
+                continue;
+            }
+        };
+        let export = Export {
+            mapping: mapping.clone(),
+            codec: cfg.types.get(&mapping.meadow_topic).cloned(),
+            client: client.clone(),
+            imported: imported.clone(),
+        };
+        tasks.spawn(export.run(subscriber));
+    }
+
+    loop {
+        match eventloop.poll().await {
+            // Subscriptions are renewed on every connection, as the session isn't persisted
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                info!("Connected to MQTT broker {}", cfg.broker_host);
+                for mapping in &cfg.imports {
+                    if let Err(e) = client.try_subscribe(&mapping.mqtt_topic, mapping.qos) {
+                        error!("Unable to subscribe to {}: {}", mapping.mqtt_topic, e);
+                    }
+                }
+            }
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                for mapping in cfg
+                    .imports
+                    .iter()
+                    .filter(|mapping| mqtt_match(&mapping.mqtt_topic, &publish.topic))
+                {
+                    let codec = cfg.types.get(&mapping.meadow_topic);
+                    let exported = cfg
+                        .exports
+                        .iter()
+                        .any(|export| export.meadow_topic == mapping.meadow_topic);
+                    let imported = Some(&imported).filter(|_| exported);
+                    if let Err(e) = import(&db, mapping, codec, &publish, imported) {
+                        warn!("Unable to import {}: {}", publish.topic, e);
+                    }
+                }
+            }
+            Ok(_) => (),
+            Err(e) => {
+                warn!("MQTT connection error: {}", e);
+                sleep(RECONNECT_DELAY).await;
+            }
+        }
+    }
+}
+
+/// Set a message delivered by the broker on the mapping's meadow topic
+fn import(
+    db: &sled::Db,
+    mapping: &TopicMapping,
+    codec: Option<&JsonCodec>,
+    publish: &Publish,
+    imported: Option<&Imported>,
+) -> Result<(), Error> {
+    let mut generic = match mapping.encoding {
+        MqttEncoding::Postcard => postcard::from_bytes::<GenericMsg>(&publish.payload)?,
+        MqttEncoding::Json => {
+            let codec = match codec {
+                Some(codec) => codec,
+                None => {
+                    warn!("No type is registered for {}", mapping.meadow_topic);
+                    return Ok(());
+                }
+            };
+            let value = serde_json::from_slice(&publish.payload).map_err(json_error)?;
+            codec.encode(&mapping.meadow_topic, value)?
+        }
+    };
+    generic.topic = mapping.meadow_topic.clone();
+
+    let key = generic.timestamp.to_string().into_bytes();
+    if let Some(Ok(mut imported)) = imported.map(|imported| imported.lock()) {
+        imported.insert((generic.topic.clone(), key));
+    }
+    db.clone().insert_generic(generic)
+}
+
+/// Publishes the messages set on a meadow topic to the broker
+struct Export {
+    mapping: TopicMapping,
+    codec: Option<JsonCodec>,
+    client: AsyncClient,
+    imported: Imported,
+}
+
+impl Export {
+    async fn run(self, mut subscriber: sled::Subscriber) {
+        // Messages are published from a separate task, so that a slow broker drops messages
+        // rather than holding up the Host's writes
+        let (tx, mut rx) = mpsc::channel::<Vec<u8>>(1024);
+        let publisher = {
+            let (client, mapping) = (self.client.clone(), self.mapping.clone());
+            tokio::spawn(async move {
+                while let Some(payload) = rx.recv().await {
+                    if let Err(e) = client
+                        .publish(&mapping.mqtt_topic, mapping.qos, false, payload)
+                        .await
+                    {
+                        error!("Unable to publish to {}: {}", mapping.mqtt_topic, e);
+                    }
+                }
+            })
+        };
+
+        while let Some(event) = (&mut subscriber).await {
+            if let sled::Event::Insert { key, value } = event {
+                let imported = match self.imported.lock() {
+                    Ok(mut imported) => {
+                        imported.remove(&(self.mapping.meadow_topic.clone(), key.to_vec()))
+                    }
+                    Err(_) => false,
+                };
+                if imported {
+                    continue;
+                }
+                match self.payload(&value) {
+                    Ok(payload) => {
+                        if tx.try_send(payload).is_err() {
+                            debug!("Dropped MQTT export on {}", self.mapping.mqtt_topic);
+                        }
+                    }
+                    Err(e) => warn!("Unable to export {}: {}", self.mapping.meadow_topic, e),
+                }
+            }
+        }
+        publisher.abort();
+    }
+
+    fn payload(&self, value: &[u8]) -> Result<Vec<u8>, Error> {
+        match self.mapping.encoding {
+            MqttEncoding::Postcard => Ok(value.to_vec()),
+            MqttEncoding::Json => {
+                let generic: GenericMsg = postcard::from_bytes(value)?;
+                let json = match &self.codec {
+                    Some(codec) => codec.decode(generic)?,
+                    None => msg_json(generic, None)?,
+                };
+                serde_json::to_vec(&json).map_err(json_error)
+            }
+        }
+    }
+}
+
+/// Match an MQTT topic against a filter, which may contain `+` and `#` wildcards
+fn mqtt_match(filter: &str, topic: &str) -> bool {
+    let mut topic_levels = topic.split('/');
+    for level in filter.split('/') {
+        match (level, topic_levels.next()) {
+            ("#", _) => return true,
+            ("+", Some(_)) => (),
+            (level, Some(topic_level)) if level == topic_level => (),
+            _ => return false,
+        }
+    }
+    topic_levels.next().is_none()
+}
@@ -1,5 +1,21 @@
+use crate::networks::get_socket_addr;
+use crate::Error;
+
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 
+/// IP address family the Host listens on
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum AddressFamily {
+    /// The interface's IPv4 address
+    #[default]
+    Ipv4,
+    /// The interface's IPv6 address, or its scoped link-local address if it has no other
+    Ipv6,
+    /// Both IPv4 and IPv6 on every interface, using the unspecified IPv6 address
+    DualStack,
+}
+
 /// Configuration for network interfaces
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct NetworkConfig {
@@ -7,6 +23,9 @@ pub struct NetworkConfig {
     pub socket_num: u16,
     pub max_buffer_size: usize,
     pub max_name_size: usize,
+    pub address_family: AddressFamily,
+    /// Explicit address to listen on, overriding the interface, address family and socket number
+    pub bind_addr: Option<SocketAddr>,
 }
 
 impl NetworkConfig {
@@ -17,6 +36,8 @@ impl NetworkConfig {
             socket_num: 25_000,
             max_buffer_size: 10_000,
             max_name_size: 100,
+            address_family: AddressFamily::Ipv4,
+            bind_addr: None,
         }
     }
 
@@ -37,6 +58,27 @@ impl NetworkConfig {
         self.max_name_size = max_name_size;
         self
     }
+
+    /// Set the IP address family to listen on
+    pub fn set_address_family(mut self, address_family: AddressFamily) -> NetworkConfig {
+        self.address_family = address_family;
+        self
+    }
+
+    /// Listen on an explicit socket address, where port `0` is assigned by the operating system
+    pub fn set_bind_addr(mut self, bind_addr: impl Into<SocketAddr>) -> NetworkConfig {
+        self.bind_addr = Some(bind_addr.into());
+        self
+    }
+
+    /// Socket address the Host listens on, resolved from the interface and address family unless
+    /// an explicit `bind_addr` is set
+    pub fn socket_addr(&self) -> Result<SocketAddr, Error> {
+        match self.bind_addr {
+            Some(bind_addr) => Ok(bind_addr),
+            None => get_socket_addr(&self.interface, self.address_family, self.socket_num),
+        }
+    }
 }
 
 /// Strongly-typed alias of `NetworkConfig` for TCP configuration
@@ -50,6 +92,8 @@ pub struct QuicConfig {
     pub network_cfg: NetworkConfig,
     pub cert_path: PathBuf,
     pub key_path: PathBuf,
+    /// Certificate authority used to verify Node certificates, if mutual TLS is required
+    pub client_ca_path: Option<PathBuf>,
 }
 
 impl Default for QuicConfig {
@@ -60,9 +104,12 @@ impl Default for QuicConfig {
                 socket_num: 25_000,
                 max_buffer_size: 10_000,
                 max_name_size: 100,
+                address_family: AddressFamily::Ipv4,
+                bind_addr: None,
             },
             cert_path: Path::new("target").join("cert.pem"),
             key_path: Path::new("target").join("priv_key.pem"),
+            client_ca_path: None,
         }
     }
 }
@@ -76,9 +123,87 @@ impl QuicConfig {
                 socket_num: 25_000,
                 max_buffer_size: 10_000,
                 max_name_size: 100,
+                address_family: AddressFamily::Ipv4,
+                bind_addr: None,
             },
             cert_path: Path::new("target").join("cert.pem"),
             key_path: Path::new("target").join("priv_key.pem"),
+            client_ca_path: None,
         }
     }
+
+    /// Require Nodes to present a certificate signed by the certificate authority at `client_ca_path`
+    pub fn with_client_ca(mut self, client_ca_path: impl Into<PathBuf>) -> Self {
+        self.client_ca_path = Some(client_ca_path.into());
+        self
+    }
+}
+
+/// Configuration type for the Host's TLS-encrypted `TcpListener`, reusing the QUIC certificate format
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TlsConfig {
+    pub network_cfg: NetworkConfig,
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        TlsConfig::new("lo")
+    }
+}
+
+impl TlsConfig {
+    /// Create a new `TlsConfig` on a defined network interface on port `25_001`
+    pub fn new(interface: impl Into<String>) -> Self {
+        TlsConfig {
+            network_cfg: NetworkConfig::default(interface).set_socket_num(25_001u16),
+            cert_path: Path::new("target").join("cert.pem"),
+            key_path: Path::new("target").join("priv_key.pem"),
+        }
+    }
+}
+
+/// Configuration type for the Host's `UnixListener`, which only accepts Nodes on the same machine
+#[cfg(unix)]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UnixConfig {
+    /// Path of the socket file, which is replaced if it is left over from a previous Host
+    pub path: PathBuf,
+    pub max_buffer_size: usize,
+    pub max_name_size: usize,
+    /// Permission bits of the socket file, e.g. `0o660` to only allow the owner and group to connect
+    pub mode: Option<u32>,
+}
+
+#[cfg(unix)]
+impl Default for UnixConfig {
+    fn default() -> Self {
+        UnixConfig::new(std::env::temp_dir().join("meadow.sock"))
+    }
+}
+
+#[cfg(unix)]
+impl UnixConfig {
+    /// Create a new `UnixConfig` listening on the socket file at `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        UnixConfig {
+            path: path.into(),
+            max_buffer_size: 10_000,
+            max_name_size: 100,
+            mode: None,
+        }
+    }
+
+    /// Set the permission bits of the socket file
+    pub fn set_mode(mut self, mode: u32) -> UnixConfig {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Set the maximum buffer size for packets intended to be received
+    pub fn set_max_buffer_size(mut self, max_buffer_size: usize) -> UnixConfig {
+        self.max_buffer_size = max_buffer_size;
+        self
+    }
 }
@@ -0,0 +1,296 @@
+use crate::error::{Error, Quic::*};
+use crate::host::quic::{certificate_name, set_validity_days, QuicCertGenConfig};
+
+use chrono::{DateTime, TimeZone, Utc};
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DnType, IsCa, KeyPair, KeyUsagePurpose,
+};
+use rustls::client::{ServerCertVerifier, WebPkiVerifier};
+use rustls::server::{AllowAnyAuthenticatedClient, ClientCertVerifier};
+use rustls::ServerName;
+use sha2::{Digest, Sha256};
+use x509_parser::extensions::GeneralName;
+
+use std::convert::TryFrom;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Local certificate authority used to issue Host and Node certificates
+///
+/// Hosts configured with `QuicConfig::with_client_ca` using this authority's certificate accept
+/// any Node presenting a certificate it issued.
+pub struct CertificateAuthority {
+    cert: Certificate,
+    cert_pem: String,
+}
+
+impl CertificateAuthority {
+    /// Create a new self-signed certificate authority
+    pub fn generate(
+        common_name: impl Into<String>,
+        validity_days: Option<u32>,
+    ) -> Result<Self, Error> {
+        let mut params = CertificateParams::new(Vec::new());
+        params
+            .distinguished_name
+            .push(DnType::CommonName, common_name.into());
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.key_usages = vec![
+            KeyUsagePurpose::KeyCertSign,
+            KeyUsagePurpose::CrlSign,
+            KeyUsagePurpose::DigitalSignature,
+        ];
+        if let Some(days) = validity_days {
+            set_validity_days(&mut params, days);
+        }
+
+        let cert = Certificate::from_params(params)?;
+        let cert_pem = cert.serialize_pem()?;
+        Ok(CertificateAuthority { cert, cert_pem })
+    }
+
+    /// Load a certificate authority from its PEM-encoded certificate and private key
+    ///
+    /// Only the subject name and key are needed to sign new certificates, so the existing
+    /// certificate is left unchanged and is appended to every chain this authority issues.
+    pub fn load(cert_path: impl AsRef<Path>, key_path: impl AsRef<Path>) -> Result<Self, Error> {
+        let cert_pem = fs::read_to_string(cert_path)?;
+        let key_pair = KeyPair::from_pem(&fs::read_to_string(key_path)?)?;
+
+        let der = first_certificate(&cert_pem)?;
+        let (_, parsed) = x509_parser::parse_x509_certificate(&der)
+            .map_err(|e| Error::Quic(InvalidCertificate(e.to_string())))?;
+        if !parsed.is_ca() {
+            return Err(Error::Quic(InvalidCertificate(
+                "certificate is not a certificate authority".into(),
+            )));
+        }
+
+        let mut params = CertificateParams::new(Vec::new());
+        params.distinguished_name = rcgen::DistinguishedName::new();
+        for attr in parsed.subject().iter_attributes() {
+            let oid: Vec<u64> = match attr.attr_type().iter() {
+                Some(arcs) => arcs.collect(),
+                None => continue,
+            };
+            if let Ok(value) = attr.as_str() {
+                params
+                    .distinguished_name
+                    .push(DnType::from_oid(&oid), value);
+            }
+        }
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.alg = key_pair
+            .compatible_algs()
+            .next()
+            .ok_or_else(|| Error::Quic(CertificateGeneration("unsupported key".into())))?;
+        params.key_pair = Some(key_pair);
+
+        let cert = Certificate::from_params(params)?;
+        Ok(CertificateAuthority { cert, cert_pem })
+    }
+
+    /// PEM-encoded certificate of this authority
+    pub fn cert_pem(&self) -> &str {
+        &self.cert_pem
+    }
+
+    /// Write the authority's certificate and private key to the specified paths
+    pub fn write(
+        &self,
+        cert_path: impl AsRef<Path>,
+        key_path: impl AsRef<Path>,
+    ) -> Result<(), Error> {
+        fs::write(cert_path, &self.cert_pem)?;
+        fs::write(key_path, self.cert.serialize_private_key_pem())?;
+        Ok(())
+    }
+
+    /// Issue a certificate signed by this authority, writing the certificate chain (followed by
+    /// the authority's certificate) and private key to the paths in `config`
+    pub fn issue(&self, config: &QuicCertGenConfig) -> Result<(), Error> {
+        let cert = Certificate::from_params(config.params())?;
+        let chain = format!(
+            "{}{}",
+            cert.serialize_pem_with_signer(&self.cert)?,
+            self.cert_pem
+        );
+        fs::write(&config.cert_pem_path, chain)?;
+        fs::write(&config.priv_key_pem_path, cert.serialize_private_key_pem())?;
+        Ok(())
+    }
+}
+
+/// Summary of a certificate's identity and validity period
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    /// Common name, or first DNS subject alternative name, used to identify mutual TLS peers
+    pub name: Option<String>,
+    pub dns_names: Vec<String>,
+    pub ip_addresses: Vec<IpAddr>,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    pub is_ca: bool,
+    /// Colon-separated SHA-256 digest of the DER-encoded certificate
+    pub fingerprint: String,
+}
+
+impl CertificateInfo {
+    /// Time remaining until the certificate expires, which is negative once it has expired
+    pub fn expires_in(&self) -> chrono::Duration {
+        self.not_after - Utc::now()
+    }
+
+    /// Whether the certificate is currently within its validity period
+    pub fn is_current(&self) -> bool {
+        let now = Utc::now();
+        self.not_before <= now && now <= self.not_after
+    }
+}
+
+/// Read every certificate in a PEM file, in the order they appear
+pub fn inspect(cert_path: impl AsRef<Path>) -> Result<Vec<CertificateInfo>, Error> {
+    read_certificates(cert_path.as_ref())?
+        .iter()
+        .map(|der| certificate_info(der))
+        .collect()
+}
+
+/// SHA-256 fingerprint of the first certificate in a PEM file, which Hosts announce in their
+/// discovery beacons
+pub fn fingerprint(cert_path: impl AsRef<Path>) -> Result<String, Error> {
+    let certs = read_certificates(cert_path.as_ref())?;
+    Ok(sha256_fingerprint(&certs[0]))
+}
+
+/// Validate the first certificate in a PEM file, returning its details
+///
+/// The certificate must be within its validity period. If `ca_path` is provided, the chain must
+/// be signed by one of the certificates in it, and if `server_name` is provided the certificate
+/// must also be valid for that DNS name, as Nodes require of the Host.
+pub fn validate(
+    cert_path: impl AsRef<Path>,
+    ca_path: Option<PathBuf>,
+    server_name: Option<&str>,
+) -> Result<CertificateInfo, Error> {
+    let chain: Vec<rustls::Certificate> = read_certificates(cert_path.as_ref())?
+        .into_iter()
+        .map(rustls::Certificate)
+        .collect();
+    let (end_entity, intermediates) = chain
+        .split_first()
+        .ok_or_else(|| Error::Quic(InvalidCertificate("no certificates found".into())))?;
+
+    let info = certificate_info(&end_entity.0)?;
+    if !info.is_current() {
+        return Err(Error::Quic(InvalidCertificate(format!(
+            "certificate is only valid from {} until {}",
+            info.not_before, info.not_after
+        ))));
+    }
+
+    let now = SystemTime::now();
+    let invalid = |e: rustls::Error| Error::Quic(InvalidCertificate(e.to_string()));
+    if let Some(ca_path) = ca_path {
+        let roots = crate::node::quic::read_root_certs(Some(ca_path))?;
+        if let Some(server_name) = server_name {
+            let name =
+                ServerName::try_from(server_name).map_err(|_e| Error::Quic(InvalidDnsName))?;
+            WebPkiVerifier::new(roots, None)
+                .verify_server_cert(
+                    end_entity,
+                    intermediates,
+                    &name,
+                    &mut std::iter::empty(),
+                    &[],
+                    now,
+                )
+                .map_err(invalid)?;
+        } else {
+            AllowAnyAuthenticatedClient::new(roots)
+                .verify_client_cert(end_entity, intermediates, now)
+                .map_err(invalid)?;
+        }
+    } else if let Some(server_name) = server_name {
+        if !info.dns_names.iter().any(|name| name == server_name) {
+            return Err(Error::Quic(InvalidCertificate(format!(
+                "certificate is not valid for \"{}\"",
+                server_name
+            ))));
+        }
+    }
+
+    Ok(info)
+}
+
+fn read_certificates(cert_path: &Path) -> Result<Vec<Vec<u8>>, Error> {
+    let mut reader = BufReader::new(File::open(cert_path)?);
+    let certs = rustls_pemfile::certs(&mut reader)?;
+    if certs.is_empty() {
+        return Err(Error::Quic(InvalidCertificate(format!(
+            "no certificates found in {}",
+            cert_path.display()
+        ))));
+    }
+    Ok(certs)
+}
+
+fn first_certificate(pem: &str) -> Result<Vec<u8>, Error> {
+    rustls_pemfile::certs(&mut pem.as_bytes())?
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::Quic(InvalidCertificate("no certificates found".into())))
+}
+
+fn certificate_info(der: &[u8]) -> Result<CertificateInfo, Error> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der)
+        .map_err(|e| Error::Quic(InvalidCertificate(e.to_string())))?;
+
+    let mut dns_names = Vec::new();
+    let mut ip_addresses = Vec::new();
+    if let Ok(Some(san)) = cert.subject_alternative_name() {
+        for name in &san.value.general_names {
+            match name {
+                GeneralName::DNSName(name) => dns_names.push(name.to_string()),
+                GeneralName::IPAddress(bytes) => {
+                    if let Ok(octets) = <[u8; 4]>::try_from(*bytes) {
+                        ip_addresses.push(IpAddr::V4(Ipv4Addr::from(octets)));
+                    } else if let Ok(octets) = <[u8; 16]>::try_from(*bytes) {
+                        ip_addresses.push(IpAddr::V6(Ipv6Addr::from(octets)));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let time = |timestamp: i64| {
+        Utc.timestamp_opt(timestamp, 0)
+            .single()
+            .ok_or_else(|| Error::Quic(InvalidCertificate("invalid validity period".into())))
+    };
+    Ok(CertificateInfo {
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        name: certificate_name(&rustls::Certificate(der.to_vec())),
+        dns_names,
+        ip_addresses,
+        not_before: time(cert.validity().not_before.timestamp())?,
+        not_after: time(cert.validity().not_after.timestamp())?,
+        is_ca: cert.is_ca(),
+        fingerprint: sha256_fingerprint(der),
+    })
+}
+
+fn sha256_fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der)
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<String>>()
+        .join(":")
+}
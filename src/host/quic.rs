@@ -2,7 +2,12 @@ use crate::error::{
     Error, HostOperation,
     Quic::{self, *},
 };
+use crate::host::auth::authorize_msg;
+use crate::host::clock;
+use crate::host::stats::StatsTracker;
+use crate::host::{AuthConfig, Credentials};
 use crate::prelude::*;
+use crate::trace::host_span;
 use futures_util::lock::Mutex;
 use futures_util::StreamExt;
 use quinn::Connection as QuicConnection;
@@ -11,40 +16,130 @@ use std::sync::{Arc, Mutex as StdMutex};
 use tokio::sync::Mutex as TokioMutex;
 use tokio::time::{sleep, Duration};
 
-use chrono::Utc;
+use chrono::{Datelike, Utc};
 use postcard::from_bytes;
 use postcard::to_allocvec;
 use quinn::{Endpoint, RecvStream, SendStream, ServerConfig};
+use std::net::IpAddr;
 use std::path::PathBuf;
 use std::{fs, fs::File, io::BufReader};
 use tracing::*;
 
 /// Configuration struct for generating QUIC private key and certificates
-#[derive(Debug, Clone)]
+///
+/// Nodes verify the Host by DNS name, so the certificate should include the name set by
+/// `NetworkConfig::set_server_name`. IP address subject alternative names are included for
+/// other clients, but can't be used as a Node's server name.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct QuicCertGenConfig {
-    subject_alt_names: Vec<String>,
-    cert_pem_path: PathBuf,
-    priv_key_pem_path: PathBuf,
+    /// Subject common name of the certificate, which names Nodes verified over mutual TLS
+    pub common_name: Option<String>,
+    /// DNS subject alternative names of the certificate
+    pub dns_names: Vec<String>,
+    /// IP address subject alternative names of the certificate
+    pub ip_addresses: Vec<IpAddr>,
+    /// Output path of the PEM-encoded certificate
+    pub cert_pem_path: PathBuf,
+    /// Output path of the PEM-encoded private key
+    pub priv_key_pem_path: PathBuf,
+    /// Number of days from today for which the certificate is valid, or effectively
+    /// indefinitely if `None`
+    pub validity_days: Option<u32>,
 }
 
 impl Default for QuicCertGenConfig {
     fn default() -> QuicCertGenConfig {
         QuicCertGenConfig {
-            subject_alt_names: vec!["localhost".into()],
+            common_name: None,
+            dns_names: vec!["localhost".into()],
+            ip_addresses: Vec::new(),
             cert_pem_path: "target/cert.pem".into(),
             priv_key_pem_path: "target/priv_key.pem".into(),
+            validity_days: None,
         }
     }
 }
 
-pub fn generate_certs(config: QuicCertGenConfig) {
-    let cert = rcgen::generate_simple_self_signed(config.subject_alt_names)
-        .expect("Error generating self-signed certificate");
-    let cert_pem = cert.serialize_pem().expect("Error serialzing ");
-    fs::write(config.cert_pem_path, cert_pem).expect("Error writing certificate to file");
+impl QuicCertGenConfig {
+    /// Set the subject common name of the certificate
+    pub fn with_common_name(mut self, common_name: impl Into<String>) -> Self {
+        self.common_name = Some(common_name.into());
+        self
+    }
+
+    /// Replace the certificate's DNS subject alternative names
+    pub fn with_dns_names(mut self, dns_names: Vec<String>) -> Self {
+        self.dns_names = dns_names;
+        self
+    }
+
+    /// Add a DNS subject alternative name to the certificate
+    pub fn with_dns_name(mut self, dns_name: impl Into<String>) -> Self {
+        self.dns_names.push(dns_name.into());
+        self
+    }
+
+    /// Add an IP address subject alternative name to the certificate
+    pub fn with_ip_address(mut self, ip_address: impl Into<IpAddr>) -> Self {
+        self.ip_addresses.push(ip_address.into());
+        self
+    }
+
+    /// Set the output path of the certificate
+    pub fn with_cert_path(mut self, cert_pem_path: impl Into<PathBuf>) -> Self {
+        self.cert_pem_path = cert_pem_path.into();
+        self
+    }
+
+    /// Set the output path of the private key
+    pub fn with_key_path(mut self, priv_key_pem_path: impl Into<PathBuf>) -> Self {
+        self.priv_key_pem_path = priv_key_pem_path.into();
+        self
+    }
+
+    /// Limit the certificate to being valid for `days` days from today
+    pub fn with_validity_days(mut self, days: u32) -> Self {
+        self.validity_days = Some(days);
+        self
+    }
+
+    /// Certificate parameters described by this configuration
+    pub(crate) fn params(&self) -> rcgen::CertificateParams {
+        let mut params = rcgen::CertificateParams::new(self.dns_names.clone());
+        params.subject_alt_names.extend(
+            self.ip_addresses
+                .iter()
+                .map(|ip| rcgen::SanType::IpAddress(*ip)),
+        );
+        // rcgen's placeholder common name would otherwise name every Node verified over mutual
+        // TLS the same, rather than by its DNS name
+        params.distinguished_name = rcgen::DistinguishedName::new();
+        if let Some(name) = &self.common_name {
+            params
+                .distinguished_name
+                .push(rcgen::DnType::CommonName, name.as_str());
+        }
+        if let Some(days) = self.validity_days {
+            set_validity_days(&mut params, days);
+        }
+        params
+    }
+}
 
-    let priv_key_pem = cert.serialize_private_key_pem();
-    fs::write(config.priv_key_pem_path, priv_key_pem).expect("Error writing private key to file");
+/// Limit certificate parameters to being valid from today until `days` days from now
+pub(crate) fn set_validity_days(params: &mut rcgen::CertificateParams, days: u32) {
+    let today = Utc::now().date_naive();
+    let expiry = today + chrono::Duration::days(days.into());
+    params.not_before = rcgen::date_time_ymd(today.year(), today.month() as u8, today.day() as u8);
+    params.not_after =
+        rcgen::date_time_ymd(expiry.year(), expiry.month() as u8, expiry.day() as u8);
+}
+/// Generate a self-signed certificate and private key, writing them to the configured paths
+pub fn generate_certs(config: QuicCertGenConfig) -> Result<(), Error> {
+    let cert = rcgen::Certificate::from_params(config.params())?;
+    fs::write(&config.cert_pem_path, cert.serialize_pem()?)?;
+    fs::write(&config.priv_key_pem_path, cert.serialize_private_key_pem())?;
+    Ok(())
 }
 
 pub fn read_certs_from_file(
@@ -72,113 +167,219 @@ pub fn read_certs_from_file(
     }
 }
 
-pub async fn process_quic(stream: (SendStream, RecvStream), db: sled::Db, buf: &mut [u8]) {
+/// Build the Host's QUIC `ServerConfig`, requiring Node certificates if a client CA is configured
+pub fn generate_server_config(config: &QuicConfig) -> Result<ServerConfig, Error> {
+    let (certs, key) = read_certs_from_file(&config.cert_path, &config.key_path)?;
+    let client_ca_path = match &config.client_ca_path {
+        Some(path) => path.clone(),
+        None => return Ok(ServerConfig::with_single_cert(certs, key)?),
+    };
+
+    let roots = crate::node::quic::read_root_certs(Some(client_ca_path))?;
+    // QUIC requires TLS 1.3, and that the maximum early data size is either 0 or `u32::MAX`
+    let mut crypto = rustls::ServerConfig::builder()
+        .with_safe_default_cipher_suites()
+        .with_safe_default_kx_groups()
+        .with_protocol_versions(&[&rustls::version::TLS13])?
+        .with_client_cert_verifier(rustls::server::AllowAnyAuthenticatedClient::new(roots))
+        .with_single_cert(certs, key)?;
+    crypto.max_early_data_size = u32::MAX;
+    Ok(ServerConfig::with_crypto(Arc::new(crypto)))
+}
+
+/// Name of the verified certificate a Node presented over mutual TLS, if any
+///
+/// This is the certificate's subject Common Name, or its first DNS subject
+/// alternative name if it has no Common Name.
+pub fn peer_certificate_name(connection: &QuicConnection) -> Option<String> {
+    let certs = connection
+        .peer_identity()?
+        .downcast::<Vec<rustls::Certificate>>()
+        .ok()?;
+    certificate_name(certs.first()?)
+}
+
+pub(crate) fn certificate_name(cert: &rustls::Certificate) -> Option<String> {
+    use x509_parser::extensions::GeneralName;
+
+    let (_, cert) = x509_parser::parse_x509_certificate(&cert.0).ok()?;
+    if let Some(name) = cert
+        .subject()
+        .iter_common_name()
+        .find_map(|cn| cn.as_str().ok())
+    {
+        return Some(name.to_string());
+    }
+    let san = cert.subject_alternative_name().ok()??;
+    san.value.general_names.iter().find_map(|name| match name {
+        GeneralName::DNSName(name) => Some(name.to_string()),
+        _ => None,
+    })
+}
+
+/// Authenticate a Node using the first bi-directional stream of a new QUIC connection,
+/// returning the Node's name and authenticated identity
+pub async fn authenticate(
+    connection: &QuicConnection,
+    auth: &AuthConfig,
+    max_buffer_size: usize,
+) -> Result<(String, String), Error> {
+    let (mut tx, mut rx) = connection.accept_bi().await?;
+    let mut buf = vec![0u8; max_buffer_size];
+    let n = rx.read(&mut buf).await?.ok_or(Error::Quic(Connection))?;
+    let msg: GenericMsg = from_bytes(&buf[..n])?;
+
+    let result = match msg.msg_type {
+        MsgType::Auth => {
+            let credentials: Option<Credentials> = from_bytes(&msg.data)?;
+            auth.authenticate(credentials.as_ref())
+        }
+        _ => Err(Error::Unauthenticated),
+    };
+    let reply = match &result {
+        Ok(_identity) => GenericMsg::auth(&msg.topic, None)?,
+        Err(e) => GenericMsg::error(e.clone()),
+    };
+    tx.write_all(&reply.as_bytes()?).await?;
+    tx.finish().await?;
+
+    Ok((msg.topic, result?))
+}
+
+/// Complete an incoming QUIC connection, authenticating the Node if the Host has an `AuthConfig`
+///
+/// Returns the connection along with the Node's name and, if authenticated, its identity. Nodes
+/// verified over mutual TLS are named by their certificate.
+pub(crate) async fn accept(
+    connecting: quinn::Connecting,
+    auth: Option<&AuthConfig>,
+    max_buffer_size: usize,
+) -> Result<(QuicConnection, String, Option<String>), Error> {
+    let connection = connecting.await?;
+    debug!(
+        "Received QUIC connection from {:?}",
+        connection.remote_address()
+    );
+
+    // Hosts with an `AuthConfig` require credentials on the first stream
+    let (name, identity) = match auth {
+        Some(auth) => match authenticate(&connection, auth, max_buffer_size).await {
+            Ok((name, identity)) => (name, Some(identity)),
+            Err(e) => {
+                connection.close(0u32.into(), b"unauthenticated");
+                return Err(e);
+            }
+        },
+        None => ("TO_DO: temp".to_string(), None),
+    };
+    let name = peer_certificate_name(&connection).unwrap_or(name);
+    Ok((connection, name, identity))
+}
+
+pub(crate) async fn process_quic(
+    stream: (SendStream, RecvStream),
+    db: sled::Db,
+    buf: &mut [u8],
+    auth: Option<Arc<AuthConfig>>,
+    identity: Option<String>,
+    stats: StatsTracker,
+    publisher: String,
+) {
     let (mut tx, mut rx) = stream;
 
     if let Ok(Some(n)) = rx.read(buf).await {
         let bytes = &buf[..n];
-        let msg: GenericMsg = match from_bytes(bytes) {
+        let mut msg: GenericMsg = match from_bytes(bytes) {
             Ok(msg) => msg,
             Err(e) => {
                 error!("Had received Msg of {} bytes: {:?}, Error: {}", n, bytes, e);
-                panic!("{}", e);
+                stats.decode_failed(Transport::Quic);
+                // The stream is dropped, as its request can't be answered
+                if let Ok(bytes) = GenericMsg::error(e.into()).as_bytes() {
+                    if let Err(e) = tx.write_all(&bytes).await {
+                        error!("{}", e);
+                    }
+                }
+                return;
             }
         };
         info!("{:?}", &msg);
-        match msg.msg_type {
-            MsgType::Error(e) => {
-                error!("Received {}", e);
-            }
-            MsgType::Set => {
-                let tree = db
-                    .open_tree(msg.topic.as_bytes())
-                    .expect("Error opening tree");
-
-                let db_result = match tree.insert(msg.timestamp.to_string(), bytes) {
-                    Ok(_prev_msg) => crate::error::HostOperation::SUCCESS, //"SUCCESS".to_string(),
-                    Err(_e) => {
-                        error!("{:?}", _e);
-                        crate::error::HostOperation::FAILURE
-                    }
-                };
+        msg.received = Some(Utc::now());
+        let _request = stats.time_request(&msg.msg_type);
+        let span = host_span(&mut msg);
 
-                if let Ok(bytes) = postcard::to_allocvec(&db_result) {
-                    for _ in 0..10 {
-                        match tx.write(&bytes).await {
-                            Ok(_n) => {
-                                break;
-                            }
-                            Err(e) => {
-                                error!("{}", e);
-                                continue;
-                            }
-                        }
+        // The rest of the message is handled within the Host's span
+        async {
+            if let Err(e) = authorize_msg(
+                auth.as_deref(),
+                identity.as_deref(),
+                &msg.msg_type,
+                &msg.topic,
+            ) {
+                warn!("{}", e);
+                if let Ok(bytes) = GenericMsg::error(e).as_bytes() {
+                    if let Err(e) = tx.write_all(&bytes).await {
+                        error!("{}", e);
                     }
                 }
+                return;
             }
-            MsgType::Get => {
-                let tree = db
-                    .open_tree(msg.topic.as_bytes())
-                    .expect("Error opening tree");
-
-                let return_bytes = match tree.last() {
-                    Ok(Some(msg)) => msg.1,
-                    _ => {
-                        let e: String = format!("Error: no topic \"{}\" exists", &msg.topic);
-                        error!("{}", &e);
-                        e.as_bytes().into()
-                    }
-                };
+            stats.count_request(&db, &msg.msg_type, &msg.topic);
 
-                match tx.write(&return_bytes).await {
-                    Ok(_n) => {}
-                    Err(e) => {
-                        error!("{}", e);
+            match msg.msg_type {
+                MsgType::Error(e) => {
+                    error!("Received {}", e);
+                }
+                // Standbys only replicate Hosts over TCP
+                MsgType::Replicate => {
+                    if let Ok(bytes) = GenericMsg::error(Error::UnsupportedOperation).as_bytes() {
+                        if let Err(e) = tx.write_all(&bytes).await {
+                            error!("{}", e);
+                        }
                     }
                 }
-            }
-            MsgType::GetNth(n) => {
-                let tree = db
-                    .open_tree(msg.topic.as_bytes())
-                    .expect("Error opening tree");
-
-                match tree.iter().nth_back(n) {
-                    Some(topic) => {
-                        let return_bytes = match topic {
-                            Ok((_timestamp, bytes)) => bytes,
-                            Err(e) => {
-                                let e: String =
-                                    format!("Error: no topic \"{}\" exists", &msg.topic);
-                                error!("{}", &e);
-                                e.as_bytes().into()
-                            }
-                        };
-
-                        match tx.write(&return_bytes).await {
-                            Ok(_n) => {}
-                            Err(e) => {
-                                error!("{}", e);
-                            }
+                MsgType::Auth => {
+                    // Only Hosts with an `AuthConfig` check credentials, so acknowledge the message
+                    if let Ok(Ok(bytes)) = GenericMsg::auth(&msg.topic, None).map(|m| m.as_bytes())
+                    {
+                        if let Err(e) = tx.write_all(&bytes).await {
+                            error!("{}", e);
                         }
                     }
-                    None => {
-                        let e: String = format!("Error: no topic \"{}\" exists", &msg.topic);
-                        error!("{}", &e);
-
-                        match tx.write(&e.as_bytes()).await {
-                            Ok(_n) => {}
-                            Err(e) => {
-                                error!("{}", e);
+                }
+                MsgType::Set => {
+                    let tree = db
+                        .open_tree(msg.topic.as_bytes())
+                        .expect("Error opening tree");
+
+                    let db_result = match tree
+                        .insert(msg.timestamp.to_string(), &*clock::stored(&msg, bytes))
+                    {
+                        Ok(_prev_msg) => {
+                            stats.record(&msg, &publisher);
+                            crate::error::HostOperation::SUCCESS
+                        }
+                        Err(_e) => {
+                            error!("{:?}", _e);
+                            crate::error::HostOperation::FAILURE
+                        }
+                    };
+
+                    if let Ok(bytes) = postcard::to_allocvec(&db_result) {
+                        for _ in 0..10 {
+                            match tx.write(&bytes).await {
+                                Ok(_n) => {
+                                    break;
+                                }
+                                Err(e) => {
+                                    error!("{}", e);
+                                    continue;
+                                }
                             }
                         }
                     }
                 }
-            }
-            MsgType::Subscribe => {
-                let specialized: Msg<Duration> = msg.clone().try_into().unwrap();
-                let rate = specialized.data;
-
-                loop {
+                MsgType::Get => {
                     let tree = db
                         .open_tree(msg.topic.as_bytes())
                         .expect("Error opening tree");
@@ -192,40 +393,138 @@ pub async fn process_quic(stream: (SendStream, RecvStream), db: sled::Db, buf: &
                         }
                     };
 
-                    match tx.write(&return_bytes).await {
+                    match tx
+                        .write(&clock::forwarded(
+                            &return_bytes,
+                            stats.tracks_latency(&msg.topic),
+                        ))
+                        .await
+                    {
                         Ok(_n) => {}
                         Err(e) => {
                             error!("{}", e);
                         }
                     }
-                    sleep(rate).await;
                 }
-            }
-            MsgType::Topics => {
-                let names = db.tree_names();
-                let mut strings = Vec::new();
-                for name in names {
-                    if let Ok(name) = std::str::from_utf8(&name[..]) {
-                        strings.push(name.to_string());
+                MsgType::GetNth(n) => {
+                    let tree = db
+                        .open_tree(msg.topic.as_bytes())
+                        .expect("Error opening tree");
+
+                    match tree.iter().nth_back(n) {
+                        Some(topic) => {
+                            let return_bytes = match topic {
+                                Ok((_timestamp, bytes)) => bytes,
+                                Err(e) => {
+                                    error!("Error reading topic \"{}\": {}", &msg.topic, e);
+                                    format!("Error: no topic \"{}\" exists", &msg.topic)
+                                        .as_bytes()
+                                        .into()
+                                }
+                            };
+
+                            match tx
+                                .write(&clock::forwarded(
+                                    &return_bytes,
+                                    stats.tracks_latency(&msg.topic),
+                                ))
+                                .await
+                            {
+                                Ok(_n) => {}
+                                Err(e) => {
+                                    error!("{}", e);
+                                }
+                            }
+                        }
+                        None => {
+                            let e: String = format!("Error: no topic \"{}\" exists", &msg.topic);
+                            error!("{}", &e);
+
+                            match tx.write(e.as_bytes()).await {
+                                Ok(_n) => {}
+                                Err(e) => {
+                                    error!("{}", e);
+                                }
+                            }
+                        }
                     }
                 }
-                // Remove default sled tree name
-                let index = strings
-                    .iter()
-                    .position(|x| *x == "__sled__default")
-                    .unwrap();
-                strings.remove(index);
-                if let Ok(data) = to_allocvec(&strings) {
-                    let mut packet = GenericMsg::topics();
-                    packet.set_data(data);
-
-                    if let Ok(bytes) = to_allocvec(&packet) {
-                        if let Err(e) = tx.write(&bytes).await {
-                            error!("Error sending data back on QUIC/TOPICS: {:?}", e);
+                MsgType::Subscribe => {
+                    let specialized: Msg<Duration> = msg.clone().try_into().unwrap();
+                    let rate = specialized.data;
+                    let _subscriber = stats.subscribe(&db, &msg.topic);
+
+                    loop {
+                        let tree = db
+                            .open_tree(msg.topic.as_bytes())
+                            .expect("Error opening tree");
+
+                        let return_bytes = match tree.last() {
+                            Ok(Some(msg)) => msg.1,
+                            _ => {
+                                let e: String =
+                                    format!("Error: no topic \"{}\" exists", &msg.topic);
+                                error!("{}", &e);
+                                e.as_bytes().into()
+                            }
+                        };
+
+                        // The subscription ends once the Node closes its stream
+                        if let Err(e) = tx
+                            .write(&clock::forwarded(
+                                &return_bytes,
+                                stats.tracks_latency(&msg.topic),
+                            ))
+                            .await
+                        {
+                            error!("{}", e);
+                            break;
+                        }
+                        sleep(rate).await;
+                    }
+                }
+                MsgType::Stats => {
+                    if let Ok(bytes) = stats.reply(&db, &msg.topic).as_bytes() {
+                        if let Err(e) = tx.write_all(&bytes).await {
+                            error!("Error sending data back on QUIC/STATS: {:?}", e);
+                        }
+                    }
+                }
+                MsgType::Clock => {
+                    if let Ok(bytes) = clock::reply(&msg).as_bytes() {
+                        if let Err(e) = tx.write_all(&bytes).await {
+                            error!("Error sending data back on QUIC/CLOCK: {:?}", e);
+                        }
+                    }
+                }
+                MsgType::Topics => {
+                    let names = db.tree_names();
+                    let mut strings = Vec::new();
+                    for name in names {
+                        if let Ok(name) = std::str::from_utf8(&name[..]) {
+                            strings.push(name.to_string());
+                        }
+                    }
+                    // Remove default sled tree name
+                    let index = strings
+                        .iter()
+                        .position(|x| *x == "__sled__default")
+                        .unwrap();
+                    strings.remove(index);
+                    if let Ok(data) = to_allocvec(&strings) {
+                        let mut packet = GenericMsg::topics();
+                        packet.set_data(data);
+
+                        if let Ok(bytes) = to_allocvec(&packet) {
+                            if let Err(e) = tx.write(&bytes).await {
+                                error!("Error sending data back on QUIC/TOPICS: {:?}", e);
+                            }
                         }
                     }
                 }
             }
         }
+        .instrument(span)
+        .await;
     }
 }
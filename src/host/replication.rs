@@ -0,0 +1,256 @@
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use tokio::time::{interval, sleep, timeout, Duration, Instant};
+use tracing::*;
+
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::result::Result;
+use std::sync::{Arc, Mutex};
+
+use crate::error::Error;
+use crate::node::tcp::{handshake, send_msg};
+use crate::prelude::*;
+
+/// Interval at which the primary sends heartbeats to its standbys, and looks for new topics
+const HEARTBEAT_INTERVAL: Duration = Duration::from_millis(250);
+/// Time without hearing from the primary after which its connection is considered lost
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(1);
+/// Number of messages queued for a standby before it's considered to have fallen behind
+const BACKLOG: usize = 1024;
+/// Largest frame a standby accepts from the primary, so that a corrupt length can't exhaust its
+/// memory
+const MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+
+/// Whether a Host is serving Nodes as the primary, or replicating another Host as its standby
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostRole {
+    Primary,
+    Standby,
+}
+
+/// Configuration of a standby Host, which replicates every topic of a primary Host
+///
+/// The standby takes over as the primary once it hasn't heard from the primary for the failover
+/// timeout, or when promoted with `Host::promote`.
+#[derive(Debug, Clone)]
+pub struct ReplicationConfig {
+    /// Address of the primary Host's TCP listener
+    pub primary_addr: SocketAddr,
+    /// Name the standby presents to the primary Host
+    pub name: String,
+    /// Time without hearing from the primary after which the standby promotes itself, if any
+    pub failover_timeout: Option<Duration>,
+    /// How long the standby waits before reconnecting to an unreachable primary
+    pub retry_interval: Duration,
+    credentials: Option<Credentials>,
+}
+
+impl ReplicationConfig {
+    /// Replicate the Host listening for TCP connections at the given address
+    pub fn new(primary_addr: impl Into<SocketAddr>) -> Self {
+        ReplicationConfig {
+            primary_addr: primary_addr.into(),
+            name: "standby".into(),
+            failover_timeout: Some(Duration::from_secs(3)),
+            retry_interval: Duration::from_millis(250),
+            credentials: None,
+        }
+    }
+
+    /// Set the name the standby presents to the primary Host
+    pub fn set_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Set the time without hearing from the primary after which the standby promotes itself,
+    /// or `None` to only promote it with `Host::promote`
+    pub fn set_failover_timeout(mut self, failover_timeout: Option<Duration>) -> Self {
+        self.failover_timeout = failover_timeout;
+        self
+    }
+
+    /// Set how long the standby waits before reconnecting to an unreachable primary
+    pub fn set_retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = retry_interval;
+        self
+    }
+
+    /// Present credentials to a primary Host that requires authentication
+    pub fn set_credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+}
+
+/// Entry of a topic's tree, as (topic, key, value)
+type Entry = (Vec<u8>, Vec<u8>, Vec<u8>);
+
+/// Write a length-prefixed frame, where an empty frame is a heartbeat
+async fn write_frame(stream: &mut TcpStream, frame: &[u8]) -> Result<(), Error> {
+    stream
+        .write_all(&(frame.len() as u32).to_le_bytes())
+        .await?;
+    stream.write_all(frame).await?;
+    Ok(())
+}
+
+/// Read a length-prefixed frame, rejecting any larger than `MAX_FRAME_SIZE`
+async fn read_frame(stream: &mut TcpStream) -> Result<Vec<u8>, Error> {
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len).await?;
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_FRAME_SIZE {
+        return Err(std::io::Error::from(std::io::ErrorKind::InvalidData).into());
+    }
+    let mut frame = vec![0u8; len];
+    stream.read_exact(&mut frame).await?;
+    Ok(frame)
+}
+
+/// Stream every topic of the primary's store to a standby, followed by each message as it's set
+///
+/// Topics are snapshotted as they're first seen, after they're watched so that no message set in
+/// between is missed. A standby that falls behind is disconnected, and resyncs once it
+/// reconnects.
+pub(crate) async fn serve(mut stream: TcpStream, db: sled::Db, authorized: Result<(), Error>) {
+    // The first frame tells the standby whether it may replicate the Host
+    let reply = match postcard::to_allocvec(&authorized) {
+        Ok(reply) => reply,
+        Err(e) => return error!("Unable to reply to standby: {}", e),
+    };
+    if write_frame(&mut stream, &reply).await.is_err() || authorized.is_err() {
+        return;
+    }
+
+    let (tx, mut rx) = mpsc::channel::<Entry>(BACKLOG);
+    let mut watched = HashSet::new();
+    // Dropped along with the connection, which stops the watchers
+    let mut watchers = JoinSet::new();
+    let mut heartbeat = interval(HEARTBEAT_INTERVAL);
+
+    loop {
+        let result = tokio::select! {
+            _ = heartbeat.tick() => {
+                let mut result = write_frame(&mut stream, &[]).await;
+                for name in db.tree_names() {
+                    if &name[..] == b"__sled__default" || !watched.insert(name.clone()) {
+                        continue;
+                    }
+                    let tree = match db.open_tree(&name) {
+                        Ok(tree) => tree,
+                        Err(e) => {
+                            error!("Unable to replicate {:?}: {}", name, e);
+                            continue;
+                        }
+                    };
+                    let mut subscriber = tree.watch_prefix(vec![]);
+                    let (tx, topic) = (tx.clone(), name.to_vec());
+                    watchers.spawn(async move {
+                        while let Some(event) = (&mut subscriber).await {
+                            if let sled::Event::Insert { key, value } = event {
+                                let entry = (topic.clone(), key.to_vec(), value.to_vec());
+                                if tx.try_send(entry).is_err() {
+                                    return;
+                                }
+                            }
+                        }
+                    });
+                    for (key, value) in tree.iter().flatten() {
+                        let entry = (name.to_vec(), key.to_vec(), value.to_vec());
+                        result = match postcard::to_allocvec(&entry) {
+                            Ok(frame) => write_frame(&mut stream, &frame).await,
+                            Err(e) => Err(e.into()),
+                        };
+                        if result.is_err() {
+                            break;
+                        }
+                    }
+                }
+                result
+            }
+            Some(entry) = rx.recv() => match postcard::to_allocvec(&entry) {
+                Ok(frame) => write_frame(&mut stream, &frame).await,
+                Err(e) => Err(e.into()),
+            },
+            Some(_) = watchers.join_next() => {
+                warn!("Standby fell behind, disconnecting it");
+                return;
+            }
+        };
+        if let Err(e) = result {
+            debug!("Standby disconnected: {}", e);
+            return;
+        }
+    }
+}
+
+/// Replicate the primary's store as a standby, until the primary is lost for the failover
+/// timeout and the standby promotes itself
+pub(crate) async fn replicate(
+    db: sled::Db,
+    cfg: Arc<ReplicationConfig>,
+    role: Arc<Mutex<HostRole>>,
+) {
+    let mut last_contact = Instant::now();
+    loop {
+        match connect(&cfg).await {
+            Ok(mut stream) => {
+                info!("Replicating primary Host at {}", cfg.primary_addr);
+                last_contact = Instant::now();
+                while let Ok(Ok(frame)) = timeout(CONNECTION_TIMEOUT, read_frame(&mut stream)).await
+                {
+                    last_contact = Instant::now();
+                    if frame.is_empty() {
+                        continue;
+                    }
+                    if let Err(e) = apply(&db, &frame) {
+                        error!("Unable to replicate message: {}", e);
+                    }
+                }
+                warn!("Lost connection to primary Host at {}", cfg.primary_addr);
+            }
+            Err(e) if e.is_access_denied() => {
+                error!("Primary Host refused replication: {}", e);
+                last_contact = Instant::now();
+            }
+            Err(e) => debug!("Unable to reach primary {}: {}", cfg.primary_addr, e),
+        }
+
+        if let Some(failover_timeout) = cfg.failover_timeout {
+            if last_contact.elapsed() >= failover_timeout {
+                warn!("Primary Host is down, promoting standby");
+                if let Ok(mut role) = role.lock() {
+                    *role = HostRole::Primary;
+                }
+                return;
+            }
+        }
+        sleep(cfg.retry_interval).await;
+    }
+}
+
+/// Connect to the primary as a Node, and request its replication stream
+async fn connect(cfg: &ReplicationConfig) -> Result<TcpStream, Error> {
+    let connection = async {
+        let stream = TcpStream::connect(cfg.primary_addr).await?;
+        let stream = handshake(stream, cfg.name.clone(), cfg.credentials.as_ref()).await?;
+        let mut stream = stream;
+        send_msg(&stream, GenericMsg::replicate().as_bytes()?).await?;
+        let authorized: Result<(), Error> = postcard::from_bytes(&read_frame(&mut stream).await?)?;
+        authorized.map(|()| stream)
+    };
+    match timeout(CONNECTION_TIMEOUT, connection).await {
+        Ok(result) => result,
+        Err(_elapsed) => Err(Error::StreamConnection),
+    }
+}
+
+fn apply(db: &sled::Db, frame: &[u8]) -> Result<(), Error> {
+    let (topic, key, value): Entry = postcard::from_bytes(frame)?;
+    db.open_tree(topic)?.insert(key, value)?;
+    Ok(())
+}
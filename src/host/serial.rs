@@ -0,0 +1,44 @@
+use tokio::time::{timeout, Duration};
+
+use std::result::Result;
+use std::sync::Arc;
+
+use crate::discovery::Transport;
+use crate::error::Error;
+use crate::host::dispatch::{serve, Dispatcher, Frames};
+use crate::host::stats::StatsTracker;
+use crate::host::AuthConfig;
+use crate::serial::SerialPort;
+
+impl Frames for SerialPort {
+    async fn read_frame(&mut self) -> Result<Vec<u8>, Error> {
+        SerialPort::read_frame(self).await
+    }
+
+    async fn write_frame(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        SerialPort::write_frame(self, bytes).await
+    }
+
+    /// A serial link can't be closed, so the Node ends a subscription by sending another frame
+    async fn wait(&mut self, rate: Duration) -> Option<Result<Vec<u8>, Error>> {
+        // Reading a frame is cancel-safe, so a partially received frame is kept for the next read
+        timeout(rate, SerialPort::read_frame(self)).await.ok()
+    }
+}
+
+/// Host process for handling the Node at the other end of a serial link
+///
+/// A serial link carries a single Node at a time. Each `MsgType::Auth` frame (re-)authenticates
+/// the link, so a Node that restarts on the same link just repeats its handshake.
+pub(crate) async fn process_serial(
+    port: SerialPort,
+    db: sled::Db,
+    max_name_size: usize,
+    auth: Option<Arc<AuthConfig>>,
+    stats: StatsTracker,
+    device: String,
+) {
+    let dispatcher = Dispatcher::new(db, auth, stats, Transport::Serial, None, device)
+        .reauthenticating(max_name_size);
+    serve(port, dispatcher).await;
+}
@@ -0,0 +1,423 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::discovery::Transport;
+use crate::error::Error;
+use crate::host::Store;
+use crate::msg::{GenericMsg, MsgType};
+
+/// Window over which publish rates are measured
+const RATE_WINDOW: Duration = Duration::from_secs(10);
+/// Number of recent messages kept per topic for measuring rates and payload sizes
+const MAX_SAMPLES: usize = 1000;
+/// Topic that requests on topics the Host doesn't store are counted under
+pub(crate) const OTHER_TOPIC: &str = "other";
+
+/// Transports with their own connection and error counters, in the order they're stored
+pub(crate) const TRANSPORTS: [Transport; 8] = [
+    Transport::Tcp,
+    Transport::Udp,
+    Transport::Quic,
+    Transport::Tls,
+    Transport::Unix,
+    Transport::InProcess,
+    Transport::Serial,
+    Transport::Stream,
+];
+
+/// Request types with their own counters, in the order they're stored
+pub(crate) const REQUEST_TYPES: [&str; 8] = [
+    "set",
+    "get",
+    "get_nth",
+    "subscribe",
+    "topics",
+    "stats",
+    "auth",
+    "clock",
+];
+
+/// Upper bounds of the request latency histogram's buckets, in seconds
+pub(crate) const LATENCY_BUCKETS: [f64; 12] = [
+    0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 1.0,
+];
+
+/// Statistics are only ever updated whole, so they remain valid if a holder panicked
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn transport_index(transport: Transport) -> usize {
+    TRANSPORTS
+        .iter()
+        .position(|t| *t == transport)
+        .unwrap_or_default()
+}
+
+/// Whether the Host stores any messages on a topic
+fn stored(db: &sled::Db, topic: &str) -> bool {
+    db.tree_names()
+        .iter()
+        .any(|name| &name[..] == topic.as_bytes())
+        && db.open_tree(topic).is_ok_and(|tree| !tree.is_empty())
+}
+
+fn request_index(msg_type: &MsgType) -> Option<usize> {
+    match msg_type {
+        MsgType::Set => Some(0),
+        MsgType::Get => Some(1),
+        MsgType::GetNth(_) => Some(2),
+        MsgType::Subscribe => Some(3),
+        MsgType::Topics => Some(4),
+        MsgType::Stats => Some(5),
+        MsgType::Auth => Some(6),
+        MsgType::Clock => Some(7),
+        MsgType::Replicate | MsgType::Error(_) => None,
+    }
+}
+
+/// Statistics on the messages set on a topic since the Host started
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TopicStats {
+    /// Topic name
+    pub topic: String,
+    /// Number of messages set on the topic
+    pub count: u64,
+    /// Messages set per second, over the last ten seconds
+    pub rate: f64,
+    /// Payload bytes set per second, over the last ten seconds
+    pub bytes_per_sec: f64,
+    /// Identity of the last Node to publish to the topic, or its address if it wasn't
+    /// authenticated
+    pub last_publisher: Option<String>,
+    /// Time the last message was set, according to the Host
+    pub last_update: Option<DateTime<Utc>>,
+    /// Payload sizes of the topic's recent messages
+    pub payload_sizes: SizePercentiles,
+    /// Number of subscriptions currently open on the topic
+    pub subscribers: usize,
+}
+
+/// Percentiles of payload sizes, in bytes
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SizePercentiles {
+    pub p50: usize,
+    pub p90: usize,
+    pub p99: usize,
+    pub max: usize,
+}
+
+#[derive(Debug, Default)]
+struct TopicState {
+    count: u64,
+    /// Total payload bytes set on the topic
+    bytes: u64,
+    /// Time each recent message was set, along with its payload size
+    samples: VecDeque<(Instant, usize)>,
+    last_publisher: Option<String>,
+    last_update: Option<DateTime<Utc>>,
+    subscribers: usize,
+    /// Whether any message set on the topic has carried latency metadata
+    latency: bool,
+}
+
+impl TopicState {
+    fn stats(&self, topic: &str) -> TopicStats {
+        let now = Instant::now();
+        let recent: Vec<_> = self
+            .samples
+            .iter()
+            .filter(|(at, _size)| now.duration_since(*at) <= RATE_WINDOW)
+            .collect();
+        // Rates count the intervals since the oldest recent message, so they fall off once
+        // messages stop being set
+        let (rate, bytes_per_sec) = match recent.split_first() {
+            Some((oldest, newer)) if !newer.is_empty() => {
+                let elapsed = now.duration_since(oldest.0).as_secs_f64();
+                let bytes: usize = newer.iter().map(|(_at, size)| size).sum();
+                (newer.len() as f64 / elapsed, bytes as f64 / elapsed)
+            }
+            _ => (0.0, 0.0),
+        };
+
+        let mut sizes: Vec<usize> = self.samples.iter().map(|(_at, size)| *size).collect();
+        sizes.sort_unstable();
+        // Nearest-rank percentiles
+        let percentile = |p: usize| match sizes.len() {
+            0 => 0,
+            n => sizes[(n * p).div_ceil(100).max(1) - 1],
+        };
+
+        TopicStats {
+            topic: topic.to_string(),
+            count: self.count,
+            rate,
+            bytes_per_sec,
+            last_publisher: self.last_publisher.clone(),
+            last_update: self.last_update,
+            payload_sizes: SizePercentiles {
+                p50: percentile(50),
+                p90: percentile(90),
+                p99: percentile(99),
+                max: sizes.last().copied().unwrap_or_default(),
+            },
+            subscribers: self.subscribers,
+        }
+    }
+}
+
+/// Distribution of request latencies, with each bucket counting the requests that fell in it
+#[derive(Debug, Default)]
+pub(crate) struct Histogram {
+    pub(crate) buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+    pub(crate) count: AtomicU64,
+    pub(crate) sum_nanos: AtomicU64,
+}
+
+impl Histogram {
+    fn observe(&self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        if let Some(i) = LATENCY_BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.buckets[i].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_nanos
+            .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+/// Host-wide counters, which are updated without locking so they're cheap to keep
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    /// Open connections, by transport
+    pub(crate) connections: [AtomicU64; TRANSPORTS.len()],
+    /// Nodes that failed to connect or authenticate, by transport
+    pub(crate) handshake_failures: [AtomicU64; TRANSPORTS.len()],
+    /// Messages that couldn't be deserialized, by transport
+    pub(crate) decode_errors: [AtomicU64; TRANSPORTS.len()],
+    /// Time taken to handle each request until its reply was sent, by request type
+    pub(crate) latencies: [Histogram; REQUEST_TYPES.len()],
+}
+
+/// Per-topic statistics and Host-wide counters shared by each of the Host's connections
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StatsTracker {
+    topics: Arc<Mutex<HashMap<String, TopicState>>>,
+    /// Requests received on each topic, by request type
+    requests: Arc<Mutex<HashMap<String, [u64; REQUEST_TYPES.len()]>>>,
+    pub(crate) counters: Arc<Counters>,
+}
+
+impl StatsTracker {
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, TopicState>> {
+        lock(&self.topics)
+    }
+
+    /// Record a message set on the Host by `publisher`
+    pub(crate) fn record(&self, msg: &GenericMsg, publisher: &str) {
+        let mut topics = self.lock();
+        let state = topics.entry(msg.topic.clone()).or_default();
+        state.count += 1;
+        state.bytes += msg.data.len() as u64;
+        if state.samples.len() == MAX_SAMPLES {
+            state.samples.pop_front();
+        }
+        state.samples.push_back((Instant::now(), msg.data.len()));
+        state.last_publisher = Some(publisher.to_string());
+        state.last_update = Some(Utc::now());
+        state.latency |= msg.latency.is_some();
+    }
+
+    /// Whether the latency of messages on a topic is tracked by their publishers, in which case
+    /// they're stamped as they're forwarded to Nodes
+    pub(crate) fn tracks_latency(&self, topic: &str) -> bool {
+        self.lock().get(topic).is_some_and(|state| state.latency)
+    }
+
+    /// Messages, payload bytes and open subscriptions on each topic tracked since the Host
+    /// started, which are cheaper to collect than their full statistics
+    #[cfg(feature = "metrics")]
+    pub(crate) fn totals(&self) -> Vec<(String, u64, u64, usize)> {
+        let mut totals: Vec<_> = self
+            .lock()
+            .iter()
+            .map(|(topic, state)| (topic.clone(), state.count, state.bytes, state.subscribers))
+            .collect();
+        totals.sort();
+        totals
+    }
+
+    /// Count a request on a topic once the Node is authorized to make it
+    ///
+    /// Requests on topics that the Host doesn't store are counted under `OTHER_TOPIC`, so Nodes
+    /// can't grow the Host's statistics without bound.
+    pub(crate) fn count_request(&self, db: &sled::Db, msg_type: &MsgType, topic: &str) {
+        if let Some(i) = request_index(msg_type) {
+            let topic = match msg_type == &MsgType::Set || stored(db, topic) {
+                true => topic,
+                false => OTHER_TOPIC,
+            };
+            let mut requests = lock(&self.requests);
+            match requests.get_mut(topic) {
+                Some(counts) => counts[i] += 1,
+                None => {
+                    let mut counts = [0; REQUEST_TYPES.len()];
+                    counts[i] = 1;
+                    requests.insert(topic.to_string(), counts);
+                }
+            }
+        }
+    }
+
+    /// Time a request until the returned guard is dropped once it has been replied to
+    ///
+    /// Subscriptions aren't timed.
+    pub(crate) fn time_request(&self, msg_type: &MsgType) -> RequestGuard {
+        RequestGuard {
+            counters: Arc::clone(&self.counters),
+            index: request_index(msg_type).filter(|i| REQUEST_TYPES[*i] != "subscribe"),
+            start: Instant::now(),
+        }
+    }
+
+    /// Requests received on each topic, by request type
+    #[cfg(feature = "metrics")]
+    pub(crate) fn requests(&self) -> Vec<(String, [u64; REQUEST_TYPES.len()])> {
+        let mut requests: Vec<_> = lock(&self.requests)
+            .iter()
+            .map(|(topic, counts)| (topic.clone(), *counts))
+            .collect();
+        requests.sort();
+        requests
+    }
+
+    /// Count a connection on the transport for as long as the returned guard is held
+    pub(crate) fn connect(&self, transport: Transport) -> ConnectionGuard {
+        let index = transport_index(transport);
+        self.counters.connections[index].fetch_add(1, Ordering::Relaxed);
+        ConnectionGuard {
+            counters: Arc::clone(&self.counters),
+            index,
+        }
+    }
+
+    /// Count a Node that failed to connect or authenticate
+    pub(crate) fn handshake_failed(&self, transport: Transport) {
+        self.counters.handshake_failures[transport_index(transport)]
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a message that couldn't be deserialized
+    pub(crate) fn decode_failed(&self, transport: Transport) {
+        self.counters.decode_errors[transport_index(transport)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a subscription on the topic for as long as the returned guard is held, if the Host
+    /// stores the topic
+    pub(crate) fn subscribe(&self, db: &sled::Db, topic: &str) -> SubscriberGuard {
+        let mut topics = self.lock();
+        let tracked = topics.contains_key(topic) || stored(db, topic);
+        if tracked {
+            topics.entry(topic.to_string()).or_default().subscribers += 1;
+        }
+        SubscriberGuard {
+            tracker: self.clone(),
+            topic: tracked.then(|| topic.to_string()),
+        }
+    }
+
+    /// Statistics on a topic, which are empty for topics stored on the Host that haven't been
+    /// set since it started
+    pub(crate) fn topic_stats(&self, db: &sled::Db, topic: &str) -> Result<TopicStats, Error> {
+        if let Some(state) = self.lock().get(topic) {
+            return Ok(state.stats(topic));
+        }
+        match db.topics()?.iter().any(|t| t == topic) {
+            true => Ok(TopicState::default().stats(topic)),
+            false => Err(Error::NonExistentTopic(topic.to_string())),
+        }
+    }
+
+    /// Reply to a `MsgType::Stats` request, carrying the topic's statistics or the reason they
+    /// couldn't be produced
+    pub(crate) fn reply(&self, db: &sled::Db, topic: &str) -> GenericMsg {
+        self.topic_stats(db, topic)
+            .and_then(|stats| {
+                let mut reply = GenericMsg::stats(topic);
+                reply.set_data(postcard::to_allocvec(&stats)?);
+                Ok(reply)
+            })
+            .unwrap_or_else(GenericMsg::error)
+    }
+
+    /// Statistics on each of the stored topics and every topic tracked since the Host started,
+    /// sorted by topic
+    pub(crate) fn all_topic_stats(&self, mut stored: Vec<String>) -> Vec<TopicStats> {
+        let topics = self.lock();
+        stored.extend(topics.keys().cloned());
+        stored.sort();
+        stored.dedup();
+        stored
+            .iter()
+            .map(|topic| match topics.get(topic) {
+                Some(state) => state.stats(topic),
+                None => TopicState::default().stats(topic),
+            })
+            .collect()
+    }
+}
+
+/// Counts a subscription on a topic until it's dropped along with the subscription's task
+#[derive(Debug)]
+pub(crate) struct SubscriberGuard {
+    tracker: StatsTracker,
+    /// Topic the subscription is counted on, unless the Host didn't store it
+    topic: Option<String>,
+}
+
+impl Drop for SubscriberGuard {
+    fn drop(&mut self) {
+        if let Some(topic) = &self.topic {
+            if let Some(state) = self.tracker.lock().get_mut(topic) {
+                state.subscribers = state.subscribers.saturating_sub(1);
+            }
+        }
+    }
+}
+
+/// Counts a connection on a transport until it's dropped along with the connection's task
+#[derive(Debug)]
+pub(crate) struct ConnectionGuard {
+    counters: Arc<Counters>,
+    index: usize,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.counters.connections[self.index].fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Times a request until it's dropped, once the request has been replied to
+#[derive(Debug)]
+pub(crate) struct RequestGuard {
+    counters: Arc<Counters>,
+    index: Option<usize>,
+    start: Instant,
+}
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        if let Some(i) = self.index {
+            self.counters.latencies[i].observe(self.start.elapsed());
+        }
+    }
+}
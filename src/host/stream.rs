@@ -0,0 +1,37 @@
+use std::result::Result;
+use std::sync::Arc;
+
+use crate::discovery::Transport;
+use crate::error::Error;
+use crate::host::dispatch::{serve, Dispatcher, Frames};
+use crate::host::stats::StatsTracker;
+use crate::host::AuthConfig;
+use crate::stream::FramedStream;
+
+/// Largest frame accepted from a Node connected over a user-supplied stream
+pub(crate) const STREAM_MAX_FRAME_SIZE: usize = 10_000;
+/// Longest Node name kept from the handshake of a user-supplied stream
+pub(crate) const STREAM_MAX_NAME_SIZE: usize = 100;
+
+impl Frames for FramedStream {
+    async fn read_frame(&mut self) -> Result<Vec<u8>, Error> {
+        FramedStream::read_frame(self).await
+    }
+
+    async fn write_frame(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        FramedStream::write_frame(self, bytes).await
+    }
+}
+
+/// Host process for handling a Node connected over a user-supplied stream
+pub(crate) async fn process_stream(
+    stream: FramedStream,
+    db: sled::Db,
+    auth: Option<Arc<AuthConfig>>,
+    identity: Option<String>,
+    stats: StatsTracker,
+    label: String,
+) {
+    let dispatcher = Dispatcher::new(db, auth, stats, Transport::Stream, identity, label);
+    serve(stream, dispatcher).await;
+}
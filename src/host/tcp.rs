@@ -12,38 +12,46 @@ use std::sync::Arc;
 // Misc other imports
 use chrono::Utc;
 
-use crate::error::Error;
-use crate::host::GenericStore;
+use crate::error::{Error, HostError};
+use crate::host::auth::authorize_msg;
+use crate::host::clock;
+use crate::host::replication;
+use crate::host::stats::StatsTracker;
+use crate::host::{AuthConfig, Credentials, GenericStore};
 use crate::prelude::*;
+use crate::trace::host_span;
 use std::convert::TryInto;
 use std::result::Result;
 
-/// Initiate a TCP connection with a Node
-#[inline]
-#[tracing::instrument]
-pub async fn handshake(
-    stream: TcpStream,
-    max_buffer_size: usize,
-    max_name_size: usize,
-) -> Result<(TcpStream, String), Error> {
-    // Handshake
-    let mut buf = vec![0u8; max_buffer_size];
-    // debug!("Starting handshake");
-    let mut _name: String = String::with_capacity(max_name_size);
-
-    stream.readable().await?;
-
-    let n = stream.try_read_buf(&mut buf)?;
-    let name = std::str::from_utf8(&buf[..n])?.to_string();
-
-    // debug!("Returning from handshake: ({:?}, {})", &stream, &_name);
-    Ok((stream, name))
+/// Send a `MsgType::Error` reply to the Node
+async fn reply_error(stream: &TcpStream, e: Error) {
+    if let Ok(bytes) = GenericMsg::error(e).as_bytes() {
+        if let Ok(()) = stream.writable().await {
+            if let Err(e) = stream.try_write(&bytes) {
+                error!("Error sending error reply on TCP: {:?}", e);
+            }
+        }
+    }
 }
 
 /// Host process for handling incoming connections from Nodes
 #[tracing::instrument(skip_all)]
 #[inline]
-pub async fn process_tcp(stream: TcpStream, db: sled::Db, max_buffer_size: usize) {
+pub async fn process_tcp(
+    stream: TcpStream,
+    db: sled::Db,
+    max_buffer_size: usize,
+    auth: Option<Arc<AuthConfig>>,
+    identity: Option<String>,
+    stats: StatsTracker,
+) {
+    // Nodes are identified in statistics by their identity, or their address if unauthenticated
+    let publisher = match (&identity, stream.peer_addr()) {
+        (Some(identity), _) => identity.clone(),
+        (None, Ok(addr)) => addr.to_string(),
+        (None, Err(_e)) => "tcp".into(),
+    };
+    let _connection = stats.connect(Transport::Tcp);
     let mut buf = vec![0u8; max_buffer_size];
     loop {
         if let Err(e) = stream.readable().await {
@@ -58,95 +66,112 @@ pub async fn process_tcp(stream: TcpStream, db: sled::Db, max_buffer_size: usize
                 }
 
                 let bytes = &buf[..n];
-                let msg: GenericMsg = match from_bytes(bytes) {
+                let mut msg: GenericMsg = match from_bytes(bytes) {
                     Ok(msg) => {
                         info!("{:?}", msg);
                         msg
                     }
                     Err(e) => {
                         error!("Had received Msg of {} bytes: {:?}, Error: {}", n, bytes, e);
-                        panic!("Had received Msg of {} bytes: {:?}, Error: {}", n, bytes, e);
+                        stats.decode_failed(Transport::Tcp);
+                        // The connection is dropped, as it can't be told where the next
+                        // message starts
+                        reply_error(&stream, e.into()).await;
+                        return;
                     }
                 };
 
                 info!("{:?}", msg.msg_type);
+                msg.received = Some(Utc::now());
+                let _request = stats.time_request(&msg.msg_type);
+                let span = host_span(&mut msg);
 
-                match &msg.msg_type {
-                    MsgType::Subscribe => {
-                        start_subscription(msg.clone(), db.clone(), &stream).await;
-                    }
-                    _ => {
-                        let msg = process_msg(msg.clone(), db.clone()).unwrap();
-                    }
+                // The connection carries a standby's replication stream from here on
+                if msg.msg_type == MsgType::Replicate {
+                    let authorized =
+                        authorize_msg(auth.as_deref(), identity.as_deref(), &msg.msg_type, "*");
+                    replication::serve(stream, db, authorized).await;
+                    return;
                 }
 
-                match &msg.msg_type {
-                    MsgType::Error(e) => {
-                        todo!()
+                // The rest of the message is handled within the Host's span
+                async {
+                    if let Err(e) = authorize_msg(
+                        auth.as_deref(),
+                        identity.as_deref(),
+                        &msg.msg_type,
+                        &msg.topic,
+                    ) {
+                        warn!("{}", e);
+                        reply_error(&stream, e).await;
+                        return;
                     }
-                    MsgType::Set => {
-                        // println!("received {} bytes, to be assigned to: {}", n, &msg.name);
-                        let tree = db
-                            .open_tree(msg.topic.as_bytes())
-                            .expect("Error opening tree");
-
-                        let db_result = {
-                            match tree.insert(msg.timestamp.to_string().as_bytes(), bytes) {
-                                Ok(_prev_msg) => {
-                                    info!("{:?}", msg.data);
-                                    Ok(())
-                                }
-                                Err(e) => Err(crate::error::HostOperation::FAILURE),
-                            }
-                        };
+                    stats.count_request(&db, &msg.msg_type, &msg.topic);
 
-                        if let Ok(bytes) = postcard::to_allocvec(&db_result) {
-                            loop {
-                                match stream.try_write(&bytes) {
-                                    Ok(_n) => {
-                                        break;
-                                    }
-                                    Err(_e) => {
-                                        // if e.kind() == std::io::ErrorKind::WouldBlock {}
-                                        continue;
+                    match &msg.msg_type {
+                        MsgType::Error(e) => {
+                            error!("Received {}", e);
+                        }
+                        // Served above, as the connection becomes a replication stream
+                        MsgType::Replicate => (),
+                        MsgType::Auth => {
+                            // Nodes authenticate during the handshake, so only acknowledge the message
+                            if let Ok(Ok(bytes)) =
+                                GenericMsg::auth(&msg.topic, None).map(|m| m.as_bytes())
+                            {
+                                if let Ok(()) = stream.writable().await {
+                                    if let Err(e) = stream.try_write(&bytes) {
+                                        error!("Error sending data back on TCP/AUTH: {:?}", e);
                                     }
                                 }
                             }
                         }
-                    }
-                    MsgType::Get => {
-                        let tree = db
-                            .open_tree(msg.topic.as_bytes())
-                            .expect("Error opening tree");
+                        MsgType::Set => {
+                            // println!("received {} bytes, to be assigned to: {}", n, &msg.name);
+                            let tree = db
+                                .open_tree(msg.topic.as_bytes())
+                                .expect("Error opening tree");
 
-                        if let Ok(topic) = tree.last() {
-                            let return_bytes = match topic {
-                                Some(msg) => msg.1,
-                                None => {
-                                    let e: String =
-                                        format!("Error: no topic \"{}\" exists", &msg.topic);
-                                    error!("{}", &e);
-                                    e.as_bytes().into()
+                            let db_result = {
+                                match tree.insert(
+                                    msg.timestamp.to_string().as_bytes(),
+                                    &*clock::stored(&msg, bytes),
+                                ) {
+                                    Ok(_prev_msg) => {
+                                        info!("{:?}", msg.data);
+                                        stats.record(&msg, &publisher);
+                                        Ok(())
+                                    }
+                                    Err(e) => {
+                                        error!("{:?}", e);
+                                        Err(crate::error::HostOperation::FAILURE)
+                                    }
                                 }
                             };
 
-                            if let Ok(()) = stream.writable().await {
-                                if let Err(e) = stream.try_write(&return_bytes) {
-                                    error!("Error sending data back on TCP/TOPICS: {:?}", e);
+                            if let Ok(bytes) = postcard::to_allocvec(&db_result) {
+                                loop {
+                                    match stream.try_write(&bytes) {
+                                        Ok(_n) => {
+                                            break;
+                                        }
+                                        Err(_e) => {
+                                            // if e.kind() == std::io::ErrorKind::WouldBlock {}
+                                            continue;
+                                        }
+                                    }
                                 }
                             }
                         }
-                    }
-                    MsgType::GetNth(n) => {
-                        let tree = db
-                            .open_tree(msg.topic.as_bytes())
-                            .expect("Error opening tree");
+                        MsgType::Get => {
+                            let tree = db
+                                .open_tree(msg.topic.as_bytes())
+                                .expect("Error opening tree");
 
-                        match tree.iter().nth_back(*n) {
-                            Some(topic) => {
+                            if let Ok(topic) = tree.last() {
                                 let return_bytes = match topic {
-                                    Ok((_timestamp, bytes)) => bytes,
-                                    Err(e) => {
+                                    Some(msg) => msg.1,
+                                    None => {
                                         let e: String =
                                             format!("Error: no topic \"{}\" exists", &msg.topic);
                                         error!("{}", &e);
@@ -155,99 +180,136 @@ pub async fn process_tcp(stream: TcpStream, db: sled::Db, max_buffer_size: usize
                                 };
 
                                 if let Ok(()) = stream.writable().await {
-                                    if let Err(e) = stream.try_write(&return_bytes) {
-                                        error!("Error sending data back on TCP/TOPICS: {:?}", e);
-                                    }
-                                }
-                            }
-                            None => {
-                                let e: String =
-                                    format!("Error: no topic \"{}\" exists", &msg.topic);
-                                error!("{}", &e);
-
-                                if let Ok(()) = stream.writable().await {
-                                    if let Err(e) = stream.try_write(e.as_bytes()) {
+                                    if let Err(e) = stream.try_write(&clock::forwarded(
+                                        &return_bytes,
+                                        stats.tracks_latency(&msg.topic),
+                                    )) {
                                         error!("Error sending data back on TCP/TOPICS: {:?}", e);
                                     }
                                 }
                             }
                         }
-                    }
-                    MsgType::Subscribe => {
-                        let specialized: Msg<Duration> = msg.clone().try_into().unwrap();
-                        let rate = specialized.data;
+                        MsgType::GetNth(n) => {
+                            let tree = db
+                                .open_tree(msg.topic.as_bytes())
+                                .expect("Error opening tree");
 
-                        if let Ok(tree) = db.open_tree(msg.topic.as_bytes()) {
-                            loop {
-                                if let Ok(topic) = tree.last() {
+                            match tree.iter().nth_back(*n) {
+                                Some(topic) => {
                                     let return_bytes = match topic {
-                                        Some(msg) => msg.1,
-                                        None => {
-                                            let e: String = format!(
-                                                "Error: no topic \"{}\" exists",
-                                                &msg.topic
-                                            );
-                                            error!("{}", &e);
-                                            e.clone().as_bytes().into()
+                                        Ok((_timestamp, bytes)) => bytes,
+                                        Err(e) => {
+                                            error!("Error reading topic \"{}\": {}", &msg.topic, e);
+                                            format!("Error: no topic \"{}\" exists", &msg.topic)
+                                                .as_bytes()
+                                                .into()
                                         }
                                     };
 
                                     if let Ok(()) = stream.writable().await {
-                                        if let Err(e) = stream.try_write(&return_bytes) {
+                                        if let Err(e) = stream.try_write(&clock::forwarded(
+                                            &return_bytes,
+                                            stats.tracks_latency(&msg.topic),
+                                        )) {
+                                            error!(
+                                                "Error sending data back on TCP/TOPICS: {:?}",
+                                                e
+                                            );
+                                        }
+                                    }
+                                }
+                                None => {
+                                    let e: String =
+                                        format!("Error: no topic \"{}\" exists", &msg.topic);
+                                    error!("{}", &e);
+
+                                    if let Ok(()) = stream.writable().await {
+                                        if let Err(e) = stream.try_write(e.as_bytes()) {
                                             error!(
                                                 "Error sending data back on TCP/TOPICS: {:?}",
                                                 e
                                             );
                                         }
                                     }
-                                    sleep(rate).await;
                                 }
                             }
                         }
-                    }
-                    MsgType::Topics => {
-                        let names = db.tree_names();
-
-                        let mut strings = Vec::new();
-                        for name in names {
-                            match std::str::from_utf8(&name[..]) {
-                                Ok(name) => {
-                                    strings.push(name.to_string());
+                        MsgType::Subscribe => {
+                            let _subscriber = stats.subscribe(&db, &msg.topic);
+                            start_subscription(msg.clone(), db.clone(), &stats, &stream).await;
+                        }
+                        MsgType::Stats => {
+                            if let Ok(bytes) = stats.reply(&db, &msg.topic).as_bytes() {
+                                if let Ok(()) = stream.writable().await {
+                                    if let Err(e) = stream.try_write(&bytes) {
+                                        error!("Error sending data back on TCP/STATS: {:?}", e);
+                                    }
                                 }
-                                Err(_e) => {
-                                    error!("Error converting topic name {:?} to UTF-8 bytes", name);
+                            }
+                        }
+                        MsgType::Clock => {
+                            if let Ok(bytes) = clock::reply(&msg).as_bytes() {
+                                if let Ok(()) = stream.writable().await {
+                                    if let Err(e) = stream.try_write(&bytes) {
+                                        error!("Error sending data back on TCP/CLOCK: {:?}", e);
+                                    }
                                 }
                             }
                         }
-                        // Remove default sled tree name
-                        let index = strings
-                            .iter()
-                            .position(|x| *x == "__sled__default")
-                            .unwrap();
-                        strings.remove(index);
+                        MsgType::Topics => {
+                            let names = db.tree_names();
 
-                        match to_allocvec(&strings) {
-                            Ok(data) => {
-                                let mut packet = GenericMsg::topics();
-                                packet.set_data(data);
+                            let mut strings = Vec::new();
+                            for name in names {
+                                match std::str::from_utf8(&name[..]) {
+                                    Ok(name) => {
+                                        strings.push(name.to_string());
+                                    }
+                                    Err(_e) => {
+                                        error!(
+                                            "Error converting topic name {:?} to UTF-8 bytes",
+                                            name
+                                        );
+                                    }
+                                }
+                            }
+                            // Remove default sled tree name
+                            let index = strings
+                                .iter()
+                                .position(|x| *x == "__sled__default")
+                                .unwrap();
+                            strings.remove(index);
 
-                                if let Ok(bytes) = to_allocvec(&packet) {
-                                    if let Ok(()) = stream.writable().await {
-                                        if let Err(e) = stream.try_write(&bytes) {
-                                            error!(
-                                                "Error sending data back on TCP/TOPICS: {:?}",
-                                                e
-                                            );
+                            match to_allocvec(&strings) {
+                                Ok(data) => {
+                                    let mut packet = GenericMsg::topics();
+                                    packet.set_data(data);
+
+                                    if let Ok(bytes) = to_allocvec(&packet) {
+                                        if let Ok(()) = stream.writable().await {
+                                            if let Err(e) = stream.try_write(&bytes) {
+                                                error!(
+                                                    "Error sending data back on TCP/TOPICS: {:?}",
+                                                    e
+                                                );
+                                            }
                                         }
                                     }
                                 }
-                            }
-                            Err(e) => {
-                                error!("{:?}", e);
+                                Err(e) => {
+                                    error!("{:?}", e);
+                                }
                             }
                         }
                     }
                 }
+                .instrument(span)
+                .await;
+
+                // The connection only carried the subscription, which has ended
+                if msg.msg_type == MsgType::Subscribe {
+                    return;
+                }
             }
             Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
                 // println!("Error::WouldBlock: {:?}", e);
@@ -255,12 +317,18 @@ pub async fn process_tcp(stream: TcpStream, db: sled::Db, max_buffer_size: usize
             }
             Err(e) => {
                 error!("Error: {:?}", e);
+                break;
             }
         }
     }
 }
 
-async fn start_subscription(msg: GenericMsg, db: sled::Db, stream: &TcpStream) {
+async fn start_subscription(
+    msg: GenericMsg,
+    db: sled::Db,
+    stats: &StatsTracker,
+    stream: &TcpStream,
+) {
     let specialized: Msg<Duration> = msg.clone().try_into().unwrap();
     let rate = specialized.data;
 
@@ -277,8 +345,17 @@ async fn start_subscription(msg: GenericMsg, db: sled::Db, stream: &TcpStream) {
                 };
 
                 if let Ok(()) = stream.writable().await {
-                    if let Err(e) = stream.try_write(&return_bytes) {
-                        error!("Error sending data back on TCP/TOPICS: {:?}", e);
+                    match stream.try_write(&clock::forwarded(
+                        &return_bytes,
+                        stats.tracks_latency(&msg.topic),
+                    )) {
+                        Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => (),
+                        // The subscription ends once the Node disconnects
+                        Err(e) => {
+                            debug!("Ending TCP subscription: {:?}", e);
+                            break;
+                        }
+                        Ok(_n) => (),
                     }
                 }
                 sleep(rate).await;
@@ -286,7 +363,3 @@ async fn start_subscription(msg: GenericMsg, db: sled::Db, stream: &TcpStream) {
         }
     }
 }
-
-fn process_msg(msg: GenericMsg, db: sled::Db) -> Result<Option<GenericMsg>, Error> {
-    Ok(None)
-}
@@ -0,0 +1,48 @@
+use tokio::net::TcpStream;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+use std::net::SocketAddr;
+use std::path::Path;
+use std::result::Result;
+use std::sync::Arc;
+
+use crate::discovery::Transport;
+use crate::error::Error;
+use crate::host::dispatch::{serve, Dispatcher, Unframed};
+use crate::host::quic::read_certs_from_file;
+use crate::host::stats::StatsTracker;
+use crate::host::AuthConfig;
+
+/// Create a `TlsAcceptor` from the Host's certificate chain and private key files
+pub fn generate_acceptor(cert_path: &Path, key_path: &Path) -> Result<TlsAcceptor, Error> {
+    let (certs, key) = read_certs_from_file(cert_path, key_path)?;
+    let config = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)?;
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Host process for handling incoming TLS connections from Nodes
+///
+/// Identical to the TCP process, but run over the encrypted stream once the TLS session and the
+/// Node's handshake have been established.
+pub(crate) async fn process_tls(
+    stream: Unframed<TlsStream<TcpStream>>,
+    db: sled::Db,
+    auth: Option<Arc<AuthConfig>>,
+    identity: Option<String>,
+    stats: StatsTracker,
+    stream_addr: SocketAddr,
+) {
+    let dispatcher = Dispatcher::new(
+        db,
+        auth,
+        stats,
+        Transport::Tls,
+        identity,
+        stream_addr.to_string(),
+    );
+    serve(stream, dispatcher).await;
+}
@@ -12,20 +12,77 @@ use std::sync::Arc;
 // Misc other imports
 use chrono::Utc;
 
+use crate::host::auth::authorize_msg;
+use crate::host::clock;
+use crate::host::stats::StatsTracker;
+use crate::host::{AuthConfig, Credentials};
 use crate::prelude::*;
+use crate::trace::host_span;
+use std::collections::HashMap;
 use std::convert::TryInto;
+use std::net::SocketAddr;
+use std::time::Instant;
+
+/// Most Node addresses the Host keeps an identity for at once
+const MAX_IDENTITIES: usize = 1024;
+
+/// Identities of the Nodes that have authenticated over UDP, by address
+///
+/// Senders can authenticate from any number of ports, so once full the least recently active
+/// address is forgotten, and its Node has to authenticate again.
+#[derive(Debug, Default)]
+struct Identities {
+    peers: HashMap<SocketAddr, (String, Instant)>,
+}
+
+impl Identities {
+    /// Identity of the Node at an address, marking it as active
+    fn get(&mut self, addr: &SocketAddr) -> Option<&str> {
+        self.peers.get_mut(addr).map(|(identity, last_active)| {
+            *last_active = Instant::now();
+            identity.as_str()
+        })
+    }
+
+    fn insert(&mut self, addr: SocketAddr, identity: String) {
+        if !self.peers.contains_key(&addr) && self.peers.len() >= MAX_IDENTITIES {
+            let idlest = self
+                .peers
+                .iter()
+                .min_by_key(|(_addr, (_identity, last_active))| *last_active)
+                .map(|(addr, _peer)| *addr);
+            if let Some(idlest) = idlest {
+                self.peers.remove(&idlest);
+            }
+        }
+        self.peers.insert(addr, (identity, Instant::now()));
+    }
+
+    fn remove(&mut self, addr: &SocketAddr) {
+        self.peers.remove(addr);
+    }
+}
 
 /// Host process for handling incoming connections from Nodes
-#[tracing::instrument(skip(db))]
+///
+/// UDP is connectionless, so Nodes authenticate by sending a `MsgType::Auth`
+/// message, after which the Host associates the sender's address with the
+/// verified identity. As anyone able to spoof that address could then publish
+/// as the Node without seeing any reply, Hosts with an `AuthConfig` refuse
+/// publishes over UDP.
+#[tracing::instrument(skip(db, auth, stats))]
 #[inline]
 pub async fn process_udp(
     rt_handle: Handle,
     socket: UdpSocket,
     db: sled::Db,
     max_buffer_size: usize,
+    auth: Option<Arc<AuthConfig>>,
+    stats: StatsTracker,
 ) {
     let mut buf = vec![0u8; max_buffer_size];
     let s = Arc::new(socket);
+    let mut identities = Identities::default();
 
     // TO_DO_PART_B: Tried to with try_read_buf(), but seems to panic?
     // let mut buf = Vec::with_capacity(max_buffer_size);
@@ -36,67 +93,121 @@ pub async fn process_udp(
             Ok((0, _)) => break, // TO_DO: break or continue?
             Ok((n, return_addr)) => {
                 let bytes = &buf[..n];
-                let msg: GenericMsg = match from_bytes(bytes) {
+                let mut msg: GenericMsg = match from_bytes(bytes) {
                     Ok(msg) => msg,
                     Err(e) => {
                         error!("Had received Msg of {} bytes: {:?}, Error: {}", n, bytes, e);
+                        stats.decode_failed(Transport::Udp);
                         continue;
                     }
                 };
+                msg.received = Some(Utc::now());
+                let _request = stats.time_request(&msg.msg_type);
+                let span = host_span(&mut msg);
 
-                match msg.msg_type {
-                    MsgType::Error(e) => {
-                        todo!();
+                // The rest of the message is handled within the Host's span
+                async {
+                    if auth.is_some() && msg.msg_type == MsgType::Set {
+                        warn!(
+                            "Refusing UDP publish to {:?} from {}, as the Host requires authentication",
+                            msg.topic, return_addr
+                        );
+                        return;
                     }
-                    MsgType::Set => {
-                        info!("Received SET message: {:?}", &msg);
-                        let tree = db
-                            .open_tree(msg.topic.as_bytes())
-                            .expect("Error opening tree");
-
-                        let _db_result = {
-                            match tree.insert(msg.timestamp.to_string().as_bytes(), bytes) {
-                                Ok(_prev_msg) => {
-                                    info!("{:?}", msg.data);
-                                    crate::error::HostOperation::SUCCESS
+                    if let Err(e) = authorize_msg(
+                        auth.as_deref(),
+                        identities.get(&return_addr),
+                        &msg.msg_type,
+                        &msg.topic,
+                    ) {
+                        warn!("{}", e);
+                        // Publishes aren't acknowledged over UDP, so only reply to requests
+                        if msg.msg_type != MsgType::Set {
+                            if let Ok(bytes) = GenericMsg::error(e).as_bytes() {
+                                if let Err(e) = s.send_to(&bytes, return_addr).await {
+                                    error!("Error sending data back on UDP: {}", e)
                                 }
-                                Err(_e) => crate::error::HostOperation::FAILURE,
                             }
-                        };
+                        }
+                        return;
                     }
-                    MsgType::Get => {
-                        let tree = db
-                            .open_tree(msg.topic.as_bytes())
-                            .expect("Error opening tree");
-
-                        if let Ok(topic) = tree.last() {
-                            let return_bytes = match topic {
-                                Some(msg) => msg.1,
-                                None => {
-                                    let e: String =
-                                        format!("Error: no topic \"{}\" exists", &msg.topic);
-                                    error!("{}", &e);
-                                    e.as_bytes().into()
-                                }
+                    stats.count_request(&db, &msg.msg_type, &msg.topic);
+
+                    match msg.msg_type {
+                        MsgType::Error(e) => {
+                            error!("Received {}", e);
+                        }
+                        MsgType::Auth => {
+                            let reply = match &auth {
+                                Some(auth) => match from_bytes::<Option<Credentials>>(&msg.data)
+                                    .map_err(crate::Error::from)
+                                    .and_then(|credentials| auth.authenticate(credentials.as_ref()))
+                                {
+                                    Ok(identity) => {
+                                        identities.insert(return_addr, identity);
+                                        GenericMsg::auth(&msg.topic, None)
+                                    }
+                                    Err(e) => {
+                                        warn!(
+                                            "UDP authentication from {} failed: {}",
+                                            return_addr, e
+                                        );
+                                        stats.handshake_failed(Transport::Udp);
+                                        identities.remove(&return_addr);
+                                        Ok(GenericMsg::error(e))
+                                    }
+                                },
+                                None => GenericMsg::auth(&msg.topic, None),
                             };
+                            if let Ok(Ok(bytes)) = reply.map(|m| m.as_bytes()) {
+                                if let Err(e) = s.send_to(&bytes, return_addr).await {
+                                    error!("Error sending data back on UDP/AUTH: {}", e)
+                                }
+                            }
+                        }
+                        // Standbys only replicate Hosts over TCP
+                        MsgType::Replicate => {
+                            if let Ok(bytes) =
+                                GenericMsg::error(crate::Error::UnsupportedOperation).as_bytes()
+                            {
+                                if let Err(e) = s.send_to(&bytes, return_addr).await {
+                                    error!("Error sending data back on UDP: {}", e)
+                                }
+                            }
+                        }
+                        MsgType::Set => {
+                            info!("Received SET message: {:?}", &msg);
+                            let tree = db
+                                .open_tree(msg.topic.as_bytes())
+                                .expect("Error opening tree");
 
-                            if let Ok(()) = s.writable().await {
-                                if let Err(e) = s.try_send_to(&return_bytes, return_addr) {
-                                    error!("Error sending data back on UDP/GET: {}", e)
-                                };
+                            let _db_result = {
+                                match tree.insert(
+                                    msg.timestamp.to_string().as_bytes(),
+                                    &*clock::stored(&msg, bytes),
+                                ) {
+                                    Ok(_prev_msg) => {
+                                        info!("{:?}", msg.data);
+                                        let publisher = match identities.get(&return_addr) {
+                                            Some(identity) => identity.to_string(),
+                                            None => return_addr.to_string(),
+                                        };
+                                        stats.record(&msg, &publisher);
+                                        crate::error::HostOperation::SUCCESS
+                                    }
+                                    Err(_e) => crate::error::HostOperation::FAILURE,
+                                }
                             };
                         }
-                    }
-                    MsgType::GetNth(n) => {
-                        let tree = db
-                            .open_tree(msg.topic.as_bytes())
-                            .expect("Error opening tree");
+                        MsgType::Get => {
+                            let tree = db
+                                .open_tree(msg.topic.as_bytes())
+                                .expect("Error opening tree");
 
-                        match tree.iter().nth_back(n) {
-                            Some(topic) => {
+                            if let Ok(topic) = tree.last() {
                                 let return_bytes = match topic {
-                                    Ok((_timestamp, bytes)) => bytes,
-                                    Err(e) => {
+                                    Some(msg) => msg.1,
+                                    None => {
                                         let e: String =
                                             format!("Error: no topic \"{}\" exists", &msg.topic);
                                         error!("{}", &e);
@@ -105,105 +216,182 @@ pub async fn process_udp(
                                 };
 
                                 if let Ok(()) = s.writable().await {
-                                    if let Err(e) = s.try_send_to(&return_bytes, return_addr) {
-                                        error!("Error sending data back on UDP/GET: {}", e)
-                                    };
-                                };
-                            }
-                            None => {
-                                let e: String =
-                                    format!("Error: no topic \"{}\" exists", &msg.topic);
-                                error!("{}", &e);
-
-                                if let Ok(()) = s.writable().await {
-                                    if let Err(e) = s.try_send_to(e.as_bytes(), return_addr) {
+                                    if let Err(e) = s.try_send_to(
+                                        &clock::forwarded(
+                                            &return_bytes,
+                                            stats.tracks_latency(&msg.topic),
+                                        ),
+                                        return_addr,
+                                    ) {
                                         error!("Error sending data back on UDP/GET: {}", e)
                                     };
                                 };
                             }
                         }
-                    }
-                    MsgType::Subscribe => {
-                        let specialized: Msg<Duration> = msg.clone().try_into().unwrap();
-                        let rate = specialized.data;
-                        info!("Received SUBSCRIBE message: {:?}", &msg);
-                        info!("Received subscription @ rate {:?}", rate);
-
-                        let db = db.clone();
-
-                        rt_handle.spawn(async move {
-                            loop {
-                                let tree = db
-                                    .open_tree(msg.topic.as_bytes())
-                                    .expect("Error opening tree");
-                                if let Ok(topic) = tree.last() {
+                        MsgType::GetNth(n) => {
+                            let tree = db
+                                .open_tree(msg.topic.as_bytes())
+                                .expect("Error opening tree");
+
+                            match tree.iter().nth_back(n) {
+                                Some(topic) => {
                                     let return_bytes = match topic {
-                                        Some(msg) => msg.1,
-                                        None => {
-                                            let e: String = format!(
-                                                "Error: no topic \"{}\" exists",
-                                                &msg.topic
+                                        Ok((_timestamp, bytes)) => bytes,
+                                        Err(e) => {
+                                            error!(
+                                                "Error reading topic \"{}\": {}",
+                                                &msg.topic, e
                                             );
-                                            error!("{}", &e);
-                                            e.as_bytes().into()
+                                            format!("Error: no topic \"{}\" exists", &msg.topic)
+                                                .as_bytes()
+                                                .into()
                                         }
                                     };
-                                    let return_msg = from_bytes::<GenericMsg>(&return_bytes);
-                                    info!("Host sending return to subscriber: {:?}", return_msg);
 
                                     if let Ok(()) = s.writable().await {
-                                        if let Err(e) = s.try_send_to(&return_bytes, return_addr) {
+                                        if let Err(e) = s.try_send_to(
+                                            &clock::forwarded(
+                                                &return_bytes,
+                                                stats.tracks_latency(&msg.topic),
+                                            ),
+                                            return_addr,
+                                        ) {
+                                            error!("Error sending data back on UDP/GET: {}", e)
+                                        };
+                                    };
+                                }
+                                None => {
+                                    let e: String =
+                                        format!("Error: no topic \"{}\" exists", &msg.topic);
+                                    error!("{}", &e);
+
+                                    if let Ok(()) = s.writable().await {
+                                        if let Err(e) = s.try_send_to(e.as_bytes(), return_addr) {
                                             error!("Error sending data back on UDP/GET: {}", e)
                                         };
                                     };
                                 }
-                                sleep(rate).await;
                             }
-                        });
-                    }
-                    MsgType::Topics => {
-                        let names = db.tree_names();
-
-                        let mut strings = Vec::new();
-                        for name in names {
-                            match std::str::from_utf8(&name[..]) {
-                                Ok(name) => {
-                                    strings.push(name.to_string());
+                        }
+                        MsgType::Subscribe => {
+                            let specialized: Msg<Duration> = msg.clone().try_into().unwrap();
+                            let rate = specialized.data;
+                            info!("Received SUBSCRIBE message: {:?}", &msg);
+                            info!("Received subscription @ rate {:?}", rate);
+
+                            let db = db.clone();
+                            let stats = stats.clone();
+                            // UDP subscriptions are counted until the Host stops, as they're never
+                            // closed by their Nodes
+                            let subscriber = stats.subscribe(&db, &msg.topic);
+
+                            rt_handle.spawn(async move {
+                                let _subscriber = subscriber;
+                                loop {
+                                    let tree = db
+                                        .open_tree(msg.topic.as_bytes())
+                                        .expect("Error opening tree");
+                                    if let Ok(topic) = tree.last() {
+                                        let return_bytes = match topic {
+                                            Some(msg) => msg.1,
+                                            None => {
+                                                let e: String = format!(
+                                                    "Error: no topic \"{}\" exists",
+                                                    &msg.topic
+                                                );
+                                                error!("{}", &e);
+                                                e.as_bytes().into()
+                                            }
+                                        };
+                                        let return_msg = from_bytes::<GenericMsg>(&return_bytes);
+                                        info!(
+                                            "Host sending return to subscriber: {:?}",
+                                            return_msg
+                                        );
+
+                                        if let Ok(()) = s.writable().await {
+                                            if let Err(e) = s.try_send_to(
+                                                &clock::forwarded(
+                                                    &return_bytes,
+                                                    stats.tracks_latency(&msg.topic),
+                                                ),
+                                                return_addr,
+                                            ) {
+                                                error!("Error sending data back on UDP/GET: {}", e)
+                                            };
+                                        };
+                                    }
+                                    sleep(rate).await;
                                 }
-                                Err(_e) => {
-                                    error!("Error converting topic name {:?} to UTF-8 bytes", name);
+                            });
+                        }
+                        MsgType::Stats => {
+                            if let Ok(bytes) = stats.reply(&db, &msg.topic).as_bytes() {
+                                if let Ok(()) = s.writable().await {
+                                    if let Err(e) = s.try_send_to(&bytes, return_addr) {
+                                        error!("Error sending data back on UDP/STATS: {:?}", e);
+                                    }
                                 }
                             }
                         }
-                        // Remove default sled tree name
-                        let index = strings
-                            .iter()
-                            .position(|x| *x == "__sled__default")
-                            .unwrap();
-                        strings.remove(index);
-
-                        match to_allocvec(&strings) {
-                            Ok(data) => {
-                                let mut packet = GenericMsg::topics();
-                                packet.set_data(data);
-
-                                if let Ok(bytes) = to_allocvec(&packet) {
-                                    if let Ok(()) = s.writable().await {
-                                        if let Err(e) = s.try_send_to(&bytes, return_addr) {
-                                            error!(
-                                                "Error sending data back on UDP/TOPICS: {:?}",
-                                                e
-                                            );
-                                        }
+                        MsgType::Clock => {
+                            if let Ok(bytes) = clock::reply(&msg).as_bytes() {
+                                if let Ok(()) = s.writable().await {
+                                    if let Err(e) = s.try_send_to(&bytes, return_addr) {
+                                        error!("Error sending data back on UDP/CLOCK: {:?}", e);
+                                    }
+                                }
+                            }
+                        }
+                        MsgType::Topics => {
+                            let names = db.tree_names();
+
+                            let mut strings = Vec::new();
+                            for name in names {
+                                match std::str::from_utf8(&name[..]) {
+                                    Ok(name) => {
+                                        strings.push(name.to_string());
+                                    }
+                                    Err(_e) => {
+                                        error!(
+                                            "Error converting topic name {:?} to UTF-8 bytes",
+                                            name
+                                        );
                                     }
                                 }
                             }
-                            Err(e) => {
-                                error!("{:?}", e);
+                            // Remove default sled tree name
+                            let index = strings
+                                .iter()
+                                .position(|x| *x == "__sled__default")
+                                .unwrap();
+                            strings.remove(index);
+
+                            match to_allocvec(&strings) {
+                                Ok(data) => {
+                                    let mut packet = GenericMsg::topics();
+                                    packet.set_data(data);
+
+                                    if let Ok(bytes) = to_allocvec(&packet) {
+                                        if let Ok(()) = s.writable().await {
+                                            if let Err(e) = s.try_send_to(&bytes, return_addr) {
+                                                error!(
+                                                    "Error sending data back on UDP/TOPICS: {:?}",
+                                                    e
+                                                );
+                                            }
+                                        }
+                                    }
+                                }
+                                Err(e) => {
+                                    error!("{:?}", e);
+                                }
                             }
                         }
                     }
                 }
+                .instrument(span)
+                .await;
             }
             Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
                 // println!("Error::WouldBlock: {:?}", e);
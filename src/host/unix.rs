@@ -0,0 +1,71 @@
+use tokio::net::UnixStream;
+
+use std::fs::{DirBuilder, Permissions};
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+use std::os::unix::net::UnixListener;
+use std::path::Path;
+use std::result::Result;
+use std::sync::Arc;
+
+use crate::discovery::Transport;
+use crate::error::Error;
+use crate::host::dispatch::{serve, Dispatcher, Unframed};
+use crate::host::stats::StatsTracker;
+use crate::host::{AuthConfig, UnixConfig};
+
+/// Bind the Host's `UnixListener`, replacing a socket file left over from a previous Host
+///
+/// Returns an `AddrInUse` error if another Host is still accepting connections on the socket.
+pub(crate) fn bind(cfg: &UnixConfig) -> Result<UnixListener, Error> {
+    if let Ok(metadata) = std::fs::symlink_metadata(&cfg.path) {
+        if !metadata.file_type().is_socket() {
+            return Err(std::io::Error::from(std::io::ErrorKind::AlreadyExists).into());
+        }
+        if std::os::unix::net::UnixStream::connect(&cfg.path).is_ok() {
+            return Err(std::io::Error::from(std::io::ErrorKind::AddrInUse).into());
+        }
+        std::fs::remove_file(&cfg.path)?;
+    }
+
+    let listener = match cfg.mode {
+        None => UnixListener::bind(&cfg.path)?,
+        Some(mode) => bind_with_mode(&cfg.path, mode)?,
+    };
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+/// Bind a socket file with the permission bits `mode`
+///
+/// The socket is bound within a directory only the Host can enter and moved into place once its
+/// permissions are set, so that it's never reachable with the umask's more permissive default.
+fn bind_with_mode(path: &Path, mode: u32) -> Result<UnixListener, Error> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let dir = path.with_file_name(format!(".{}.{}", file_name, std::process::id()));
+    DirBuilder::new().mode(0o700).create(&dir)?;
+
+    let staged = dir.join("socket");
+    let listener = UnixListener::bind(&staged).and_then(|listener| {
+        std::fs::set_permissions(&staged, Permissions::from_mode(mode))?;
+        std::fs::rename(&staged, path)?;
+        Ok(listener)
+    });
+    // The socket is only left behind if it couldn't be moved into place
+    let _ = std::fs::remove_file(&staged);
+    let _ = std::fs::remove_dir(&dir);
+    Ok(listener?)
+}
+
+/// Host process for handling incoming Unix domain socket connections from Nodes
+pub(crate) async fn process_unix(
+    stream: Unframed<UnixStream>,
+    db: sled::Db,
+    auth: Option<Arc<AuthConfig>>,
+    identity: Option<String>,
+    stats: StatsTracker,
+    path: String,
+) {
+    // Unix domain socket peers are unnamed, so unauthenticated Nodes publish as the socket path
+    let dispatcher = Dispatcher::new(db, auth, stats, Transport::Unix, identity, path);
+    serve(stream, dispatcher).await;
+}
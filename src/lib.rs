@@ -21,8 +21,15 @@
 //!| TCP      | **X**     | **X**      | **X**     |            |
 //!| UDP      | **X**     | **X**      | **X**     |            |
 //!| QUIC     | **X**     | **X**      | **X**     | **X**      |
+//!| TLS      | **X**     | **X**      | **X**     | **X**      |
+//!| Unix     | **X**     | **X**      | **X**     |            |
+//!| In-process | **X**   | **X**      | **X**     |            |
+//!| Serial   | **X**     | **X**      | **X**     |            |
+//!| Stream   | **X**     | **X**      | **X**     |            |
 //!
 
+/// Automatic discovery of Hosts on the local network using multicast beacons
+pub mod discovery;
 /// Error types used by Meadow
 pub mod error;
 /// Central coordination process, which stores published data and responds to requests
@@ -33,6 +40,13 @@ pub mod msg;
 pub mod networks;
 /// Objects that publish and request strongly-typed data to named topics on the Host
 pub mod node;
+/// Framing and device access for serial links, such as UARTs and telemetry radios
+#[cfg(feature = "serial")]
+pub mod serial;
+/// Transport over user-supplied `AsyncRead + AsyncWrite` streams
+pub mod stream;
+/// Distributed tracing context carried by messages, and its export to OpenTelemetry
+pub mod trace;
 
 /// Re-export of Serde's `Serialize` and `Deserialize` traits
 pub use serde::{Deserialize, Serialize};
@@ -50,18 +64,52 @@ pub mod prelude {
     pub use crate::{Deserialize, Serialize};
     pub use chrono::*;
 
+    pub use crate::discovery::Transport;
     pub use crate::error::Error;
-    pub use crate::msg::{GenericMsg, Message, Msg, MsgType};
+    pub use crate::msg::{GenericMsg, Latency, Message, Msg, MsgType, RawData};
     pub use crate::networks::get_ip;
 
-    pub use crate::host::{Host, HostConfig, SledConfig, Store, UdpConfig};
+    pub use crate::host::{
+        AuthConfig, BridgeDirection, Credentials, Host, HostConfig, HostRole, Identity, PeerConfig,
+        Permission, ReplicationConfig, SledConfig, Store, TopicBridge, TopicStats, UdpConfig,
+    };
+    pub use crate::node::clock::ClockSync;
     pub use crate::node::config::NodeConfig;
     pub use crate::node::config::RuntimeConfig;
-    pub use crate::node::network_config::{Blocking, NetworkConfig, Nonblocking, Tcp, Udp};
+    pub use crate::node::latency::{LatencyReport, LatencyTracker};
+    pub use crate::node::network_config::{
+        Blocking, InProcess, NetworkConfig, Nonblocking, Stream, Tcp, Udp,
+    };
     pub use crate::node::{Active, Idle, Node, Subscription};
+    pub use crate::stream::StreamConnector;
+    pub use crate::trace::{TraceContext, TraceLayer};
 
+    #[cfg(feature = "foxglove")]
+    pub use crate::host::FoxgloveConfig;
+    #[cfg(feature = "http")]
+    pub use crate::host::HttpConfig;
+    #[cfg(feature = "tls")]
+    pub use crate::host::TlsConfig;
+    #[cfg(unix)]
+    pub use crate::host::UnixConfig;
     #[cfg(feature = "quic")]
     pub use crate::host::{generate_certs, QuicConfig};
+    #[cfg(feature = "mqtt")]
+    pub use crate::host::{MqttConfig, MqttEncoding, MqttQoS, TopicMapping};
+    #[cfg(feature = "mcap")]
+    pub use crate::host::{PlayerConfig, RecorderConfig};
     #[cfg(feature = "quic")]
     pub use crate::node::network_config::Quic;
+    #[cfg(feature = "serial")]
+    pub use crate::node::network_config::Serial;
+    #[cfg(feature = "tls")]
+    pub use crate::node::network_config::Tls;
+    #[cfg(unix)]
+    pub use crate::node::network_config::Unix;
+    #[cfg(feature = "crypto")]
+    pub use crate::node::security::SecurityConfig;
+    #[cfg(feature = "serial")]
+    pub use crate::serial::SerialConfig;
+    #[cfg(feature = "otel")]
+    pub use crate::trace::OtlpConfig;
 }
@@ -1,7 +1,10 @@
+use crate::trace::TraceContext;
 use crate::Error;
 use chrono::{DateTime, Utc};
 use postcard::to_allocvec;
-use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde::de::{DeserializeOwned, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::convert::{Into, TryInto};
 use std::time::Duration;
 
@@ -26,6 +29,28 @@ pub enum MsgType {
     Subscribe,
     /// Communicate success or failure of certain Host-side operations
     Error(crate::Error),
+    /// Present a Node's name and optional credentials to the Host
+    Auth,
+    /// Request a stream of every message set on the Host, for replicating it on a standby Host
+    Replicate,
+    /// Request statistics on a topic's messages and subscribers from the Host
+    Stats,
+    /// Request the Host's clock, for estimating the offset of a Node's clock from it
+    Clock,
+}
+
+/// Times a message passed each hop from its publisher to a Node receiving it, for measuring its
+/// end-to-end latency
+///
+/// The time the Host received the message is its `received` timestamp.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Latency {
+    /// Time the publishing Node sent the message
+    pub sent: Option<DateTime<Utc>>,
+    /// Time the Host sent the message on to the Node receiving it, by the Host's clock
+    pub forwarded: Option<DateTime<Utc>>,
+    /// Time the Node receiving the message received it
+    pub delivered: Option<DateTime<Utc>>,
 }
 
 /// Message format containing a strongly-typed data payload and associated metadata
@@ -42,10 +67,18 @@ pub struct Msg<T> {
     pub data_type: String,
     /// Strongly-typed data payload
     pub data: T,
+    /// Trace context of the span the message was sent in, if it was traced
+    pub trace: Option<TraceContext>,
+    /// Time the Host received the message, by the Host's clock
+    pub received: Option<DateTime<Utc>>,
+    /// Times the message passed each hop to the Node receiving it, if its publisher tracks latency
+    pub latency: Option<Latency>,
 }
 
 impl<T: Message> Msg<T> {
     /// Create a new strongly-typed message (default timestamp is from `SystemTime` in UTC)
+    ///
+    /// The message carries the trace context of the current span, if it is traced.
     pub fn new(msg_type: MsgType, topic: impl Into<String>, data: T) -> Self {
         Msg {
             msg_type,
@@ -53,6 +86,9 @@ impl<T: Message> Msg<T> {
             topic: topic.into(),
             data_type: std::any::type_name::<T>().to_string(),
             data,
+            trace: TraceContext::current(),
+            received: None,
+            latency: None,
         }
     }
 
@@ -71,6 +107,31 @@ impl<T: Message> Msg<T> {
         self.data = data;
     }
 
+    /// Span continuing the trace the message was sent in, for handling it within
+    ///
+    /// Messages that weren't traced give a disabled span.
+    pub fn span(&self) -> tracing::Span {
+        match &self.trace {
+            Some(context) => tracing::info_span!(
+                "meadow.receive",
+                topic = %self.topic,
+                traceparent = %context
+            ),
+            None => tracing::Span::none(),
+        }
+    }
+
+    /// Whether the message was set after `other`
+    ///
+    /// Messages the Host received are ordered by its clock, so that Nodes' clocks drifting
+    /// apart doesn't reorder them.
+    pub(crate) fn is_newer_than(&self, other: &Msg<T>) -> bool {
+        match (self.received, other.received) {
+            (Some(received), Some(other)) => received > other,
+            _ => self.timestamp > other.timestamp,
+        }
+    }
+
     /// Attempt conversion to `GenericMsg`
     pub fn to_generic(self) -> Result<GenericMsg, crate::Error> {
         self.try_into()
@@ -90,6 +151,12 @@ pub struct GenericMsg {
     pub data_type: String,
     /// Generic byte-represented data payload
     pub data: Vec<u8>,
+    /// Trace context of the span the message was sent in, if it was traced
+    pub trace: Option<TraceContext>,
+    /// Time the Host received the message, by the Host's clock
+    pub received: Option<DateTime<Utc>>,
+    /// Times the message passed each hop to the Node receiving it, if its publisher tracks latency
+    pub latency: Option<Latency>,
 }
 
 impl GenericMsg {
@@ -102,6 +169,9 @@ impl GenericMsg {
             topic: topic.into(),
             data_type: std::any::type_name::<T>().to_string(),
             data,
+            trace: TraceContext::current(),
+            received: None,
+            latency: None,
         }
     }
 
@@ -114,6 +184,9 @@ impl GenericMsg {
             topic: topic.into(),
             data_type: std::any::type_name::<T>().to_string(),
             data: Vec::new(),
+            trace: TraceContext::current(),
+            received: None,
+            latency: None,
         }
     }
 
@@ -133,6 +206,9 @@ impl GenericMsg {
             topic: topic.into(),
             data_type: std::any::type_name::<T>().to_string(),
             data: Vec::new(),
+            trace: TraceContext::current(),
+            received: None,
+            latency: None,
         }
     }
 
@@ -145,6 +221,75 @@ impl GenericMsg {
             topic: String::new(),
             data_type: std::any::type_name::<()>().to_string(),
             data: Vec::new(),
+            trace: TraceContext::current(),
+            received: None,
+            latency: None,
+        }
+    }
+
+    /// Create a `MsgType::Auth` message carrying a Node's name and optional credentials
+    #[inline]
+    pub fn auth(
+        name: impl Into<String>,
+        credentials: Option<&crate::host::Credentials>,
+    ) -> Result<Self, Error> {
+        Ok(GenericMsg {
+            msg_type: MsgType::Auth,
+            timestamp: Utc::now(),
+            topic: name.into(),
+            data_type: std::any::type_name::<Option<crate::host::Credentials>>().to_string(),
+            data: postcard::to_allocvec(&credentials)?,
+            trace: TraceContext::current(),
+            received: None,
+            latency: None,
+        })
+    }
+
+    /// Create a `MsgType::Replicate` message, requesting a stream of every topic on the Host
+    #[inline]
+    pub fn replicate() -> Self {
+        GenericMsg {
+            msg_type: MsgType::Replicate,
+            timestamp: Utc::now(),
+            topic: "*".into(),
+            data_type: std::any::type_name::<()>().to_string(),
+            data: Vec::new(),
+            trace: TraceContext::current(),
+            received: None,
+            latency: None,
+        }
+    }
+
+    /// Create a `MsgType::Stats` message, requesting statistics on a topic
+    #[inline]
+    pub fn stats(topic: impl Into<String>) -> Self {
+        GenericMsg {
+            msg_type: MsgType::Stats,
+            timestamp: Utc::now(),
+            topic: topic.into(),
+            data_type: std::any::type_name::<crate::host::TopicStats>().to_string(),
+            data: Vec::new(),
+            trace: TraceContext::current(),
+            received: None,
+            latency: None,
+        }
+    }
+
+    /// Create a `MsgType::Clock` message, requesting the Host's clock
+    ///
+    /// The Host replies with the time it received the request as the data of a `Msg<DateTime<Utc>>`,
+    /// timestamped with the time it sent the reply.
+    #[inline]
+    pub fn clock() -> Self {
+        GenericMsg {
+            msg_type: MsgType::Clock,
+            timestamp: Utc::now(),
+            topic: String::new(),
+            data_type: std::any::type_name::<DateTime<Utc>>().to_string(),
+            data: Vec::new(),
+            trace: TraceContext::current(),
+            received: None,
+            latency: None,
         }
     }
 
@@ -156,6 +301,9 @@ impl GenericMsg {
             topic: String::new(),
             data_type: std::any::type_name::<()>().to_string(),
             data: Vec::new(),
+            trace: TraceContext::current(),
+            received: None,
+            latency: None,
         }
     }
 
@@ -170,10 +318,71 @@ impl GenericMsg {
     }
 }
 
+/// Data payload kept as its encoded bytes, for handling topics without their data types
+///
+/// Publishing `RawData` sets the bytes on the Host as they are, and requesting it returns a
+/// message's payload without decoding it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RawData(pub Vec<u8>);
+
+impl Serialize for RawData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Postcard doesn't prefix tuples with their length, so the bytes are written unchanged
+        let mut tuple = serializer.serialize_tuple(self.0.len())?;
+        for byte in &self.0 {
+            tuple.serialize_element(byte)?;
+        }
+        tuple.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for RawData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RawDataVisitor;
+
+        impl<'de> Visitor<'de> for RawDataVisitor {
+            type Value = RawData;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a sequence of bytes")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<RawData, A::Error> {
+                let mut bytes = Vec::new();
+                while let Some(byte) = next_byte(&mut seq)? {
+                    bytes.push(byte);
+                }
+                Ok(RawData(bytes))
+            }
+        }
+
+        /// Next byte of the payload, or `None` once there are none left
+        ///
+        /// The payload's length isn't encoded, so postcard reports the end of the payload as the
+        /// end of its input rather than the end of the sequence.
+        fn next_byte<'de, A: SeqAccess<'de>>(seq: &mut A) -> Result<Option<u8>, A::Error> {
+            match seq.next_element() {
+                Err(e)
+                    if e.to_string() == postcard::Error::DeserializeUnexpectedEnd.to_string() =>
+                {
+                    Ok(None)
+                }
+                next => next,
+            }
+        }
+
+        deserializer.deserialize_tuple(usize::MAX, RawDataVisitor)
+    }
+}
+
 impl<T: Message> TryInto<Msg<T>> for GenericMsg {
     type Error = crate::Error;
 
     fn try_into(self) -> Result<Msg<T>, Error> {
+        // Host-side failures are propagated to the Node as errors
+        if let MsgType::Error(e) = self.msg_type {
+            return Err(e);
+        }
         let data = postcard::from_bytes::<T>(&self.data[..])?;
         Ok(Msg {
             msg_type: self.msg_type,
@@ -181,6 +390,9 @@ impl<T: Message> TryInto<Msg<T>> for GenericMsg {
             topic: self.topic.clone(),
             data_type: self.data_type.clone(),
             data,
+            trace: self.trace,
+            received: self.received,
+            latency: self.latency,
         })
     }
 }
@@ -196,6 +408,9 @@ impl<T: Message> TryInto<GenericMsg> for Msg<T> {
             topic: self.topic.clone(),
             data_type: self.data_type.clone(),
             data,
+            trace: self.trace,
+            received: self.received,
+            latency: self.latency,
         })
     }
 }
@@ -211,3 +426,25 @@ fn msg_conversions() {
     let msg_rc: Msg<i32> = generic_rc.try_into().unwrap();
     assert_eq!(msg, msg_rc);
 }
+
+#[test]
+fn raw_data_is_unchanged() {
+    let generic = Msg::new(MsgType::Set, "pose", (1.0f32, -2i64, "x".to_string()))
+        .to_generic()
+        .unwrap();
+    let raw: Msg<RawData> = generic.clone().try_into().unwrap();
+    assert_eq!(raw.data.0, generic.data);
+    assert_eq!(raw.to_generic().unwrap(), generic);
+}
+
+#[test]
+fn raw_data_propagates_errors() {
+    use serde::de::value::{Error as ValueError, SeqDeserializer};
+
+    let bytes = SeqDeserializer::<_, ValueError>::new(vec![1u32, 2].into_iter());
+    assert_eq!(RawData::deserialize(bytes).unwrap(), RawData(vec![1, 2]));
+
+    // A sequence that isn't made of bytes is an error, rather than a truncated payload
+    let not_bytes = SeqDeserializer::<_, ValueError>::new(vec![1u32, 300, 2].into_iter());
+    assert!(RawData::deserialize(not_bytes).is_err());
+}
@@ -1,5 +1,7 @@
+use crate::host::AddressFamily;
 use crate::Error;
-use std::net::{IpAddr, Ipv4Addr};
+use socket2::{Domain, Protocol, Socket, Type};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6};
 
 /// Get the IP address on a network interface for this computer
 pub fn get_ip(interface_name: &str) -> Result<Ipv4Addr, Error> {
@@ -25,3 +27,80 @@ pub fn get_ip(interface_name: &str) -> Result<Ipv4Addr, Error> {
 
     Ok(source_ip)
 }
+
+/// Get the IPv6 socket address on a network interface for this computer
+///
+/// Link-local addresses are only used if the interface has no other IPv6 address, and are scoped
+/// to the interface so that they can be bound and connected to.
+pub fn get_ipv6(interface_name: &str, port: u16) -> Result<SocketAddrV6, Error> {
+    let interface = match pnet_datalink::interfaces()
+        .into_iter()
+        .find(|iface| iface.name == interface_name)
+    {
+        Some(interface) => interface,
+        None => return Err(Error::InvalidInterface),
+    };
+
+    let ips: Vec<Ipv6Addr> = interface
+        .ips
+        .iter()
+        .filter_map(|ip| match ip.ip() {
+            IpAddr::V6(ip) => Some(ip),
+            _ => None,
+        })
+        .collect();
+    match ips.iter().find(|ip| !is_link_local(ip)) {
+        Some(ip) => Ok(SocketAddrV6::new(*ip, port, 0, 0)),
+        None => match ips.first() {
+            Some(ip) => Ok(SocketAddrV6::new(*ip, port, 0, interface.index)),
+            None => Err(Error::InvalidInterface),
+        },
+    }
+}
+
+/// Get the socket address on a network interface for this computer in the requested address
+/// family, where dual-stack addresses listen on every interface
+pub fn get_socket_addr(
+    interface_name: &str,
+    address_family: AddressFamily,
+    port: u16,
+) -> Result<SocketAddr, Error> {
+    match address_family {
+        AddressFamily::Ipv4 => Ok(SocketAddr::new(IpAddr::V4(get_ip(interface_name)?), port)),
+        AddressFamily::Ipv6 => Ok(SocketAddr::V6(get_ipv6(interface_name, port)?)),
+        AddressFamily::DualStack => Ok(SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), port)),
+    }
+}
+
+/// Whether an IPv6 address is in the unicast link-local range `fe80::/10`
+fn is_link_local(ip: &Ipv6Addr) -> bool {
+    (ip.segments()[0] & 0xffc0) == 0xfe80
+}
+
+/// Create a socket for the address, which accepts IPv4 traffic if it is the unspecified IPv6
+/// address, independent of the platform's dual-stack default
+fn socket(addr: SocketAddr, ty: Type, protocol: Protocol) -> std::io::Result<Socket> {
+    let socket = Socket::new(Domain::for_address(addr), ty, Some(protocol))?;
+    if let SocketAddr::V6(addr) = addr {
+        socket.set_only_v6(!addr.ip().is_unspecified())?;
+    }
+    socket.set_nonblocking(true)?;
+    Ok(socket)
+}
+
+/// Bind a UDP socket to the address
+pub(crate) fn bind_udp(addr: SocketAddr) -> std::io::Result<std::net::UdpSocket> {
+    let socket = socket(addr, Type::DGRAM, Protocol::UDP)?;
+    socket.bind(&addr.into())?;
+    Ok(socket.into())
+}
+
+/// Bind a TCP listener to the address
+pub(crate) fn bind_tcp(addr: SocketAddr) -> std::io::Result<std::net::TcpListener> {
+    let socket = socket(addr, Type::STREAM, Protocol::TCP)?;
+    #[cfg(unix)]
+    socket.set_reuse_address(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(1024)?;
+    Ok(socket.into())
+}
@@ -0,0 +1,101 @@
+use chrono::{DateTime, Duration, Utc};
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::Arc;
+
+use crate::msg::{GenericMsg, Msg};
+use crate::Error;
+
+/// Number of timestamp exchanges in each synchronization, of which the fastest is kept
+const SAMPLES: usize = 8;
+
+/// Estimate of the offset of a Node's clock from its Host's
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSync {
+    /// Time to add to the Node's clock to match the Host's
+    pub offset: Duration,
+    /// Round-trip time of the exchange the offset was estimated from, which bounds its error
+    pub round_trip: Duration,
+}
+
+impl ClockSync {
+    /// Estimate the offset from an exchange sent at `sent` and answered at `returned` by the
+    /// Node's clock, which the Host `received` and `replied` to by its own
+    fn new(
+        sent: DateTime<Utc>,
+        received: DateTime<Utc>,
+        replied: DateTime<Utc>,
+        returned: DateTime<Utc>,
+    ) -> Self {
+        ClockSync {
+            offset: ((received - sent) + (replied - returned)) / 2,
+            round_trip: (returned - sent) - (replied - received),
+        }
+    }
+}
+
+/// Clock offset last measured by a Node, shared by the clones of its configuration
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ClockCorrection {
+    enabled: bool,
+    /// Offset in nanoseconds, kept atomically so publishing doesn't wait on a lock
+    offset: Arc<AtomicI64>,
+    measured: Arc<AtomicBool>,
+}
+
+impl ClockCorrection {
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Shift a timestamp from the Node's clock onto the Host's, if correction is enabled and an
+    /// offset has been measured
+    pub(crate) fn correct(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        if !self.enabled || !self.measured.load(Ordering::Acquire) {
+            return timestamp;
+        }
+        timestamp + Duration::nanoseconds(self.offset.load(Ordering::Relaxed))
+    }
+
+    /// Exchange timestamps with the Host, keeping the estimate from the fastest round trip
+    ///
+    /// `exchange` sends a `MsgType::Clock` request and returns the Host's reply.
+    pub(crate) async fn sync<F, Fut>(&self, mut exchange: F) -> Result<ClockSync, Error>
+    where
+        F: FnMut(GenericMsg) -> Fut,
+        Fut: Future<Output = Result<Msg<DateTime<Utc>>, Error>>,
+    {
+        let mut best = sample(&mut exchange).await?;
+        for _ in 1..SAMPLES {
+            let sample = sample(&mut exchange).await?;
+            if sample.round_trip < best.round_trip {
+                best = sample;
+            }
+        }
+
+        // Offsets beyond the range of an `i64` of nanoseconds (about 292 years) aren't applied
+        if let Some(nanos) = best.offset.num_nanoseconds() {
+            self.offset.store(nanos, Ordering::Relaxed);
+            self.measured.store(true, Ordering::Release);
+        }
+        Ok(best)
+    }
+}
+
+/// Time a single exchange of timestamps with the Host
+async fn sample<F, Fut>(exchange: &mut F) -> Result<ClockSync, Error>
+where
+    F: FnMut(GenericMsg) -> Fut,
+    Fut: Future<Output = Result<Msg<DateTime<Utc>>, Error>>,
+{
+    let request = GenericMsg::clock();
+    let sent = request.timestamp;
+    let reply = exchange(request).await?;
+    Ok(ClockSync::new(
+        sent,
+        reply.data,
+        reply.timestamp,
+        Utc::now(),
+    ))
+}
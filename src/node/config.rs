@@ -4,6 +4,9 @@ use std::sync::Arc;
 use tokio::runtime::Handle;
 use tokio::sync::Mutex as TokioMutex;
 
+use crate::host::Credentials;
+use crate::node::clock::ClockCorrection;
+use crate::node::latency::LatencyTracker;
 use crate::node::network_config::*;
 use crate::node::Node;
 use crate::node::{Active, Idle};
@@ -48,6 +51,14 @@ pub struct NodeConfig<B: Block, I: Interface + Default, T: Message> {
     pub topic: Option<String>,
     pub network_cfg: NetworkConfig<B, I>,
     pub runtime_cfg: RuntimeConfig,
+    pub credentials: Option<Credentials>,
+    /// Keys used to seal published payloads and open received ones
+    #[cfg(feature = "crypto")]
+    pub security: Option<crate::node::security::SecurityConfig>,
+    /// Offset from the Host's clock applied to published timestamps
+    pub(crate) clock: ClockCorrection,
+    /// Histograms the latencies of received messages are recorded in
+    pub latency: Option<LatencyTracker>,
 }
 
 impl<B: Block, I: Interface + Default + Clone, T: Message> NodeConfig<B, I, T>
@@ -61,6 +72,11 @@ where
             topic: Some(topic.into()),
             network_cfg: NetworkConfig::<B, I>::default(),
             runtime_cfg: RuntimeConfig::default(),
+            credentials: None,
+            #[cfg(feature = "crypto")]
+            security: None,
+            clock: ClockCorrection::default(),
+            latency: None,
         }
     }
 
@@ -74,9 +90,46 @@ where
         self.runtime_cfg = runtime_cfg;
         self
     }
+
+    /// Present an identity and pre-shared token to Hosts that require authentication
+    pub fn with_credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Encrypt and/or sign published payloads, and open and verify received ones
+    #[cfg(feature = "crypto")]
+    pub fn with_security(mut self, security: crate::node::security::SecurityConfig) -> Self {
+        self.security = Some(security);
+        self
+    }
+
+    /// Shift the timestamps of published messages onto the Host's clock, by the offset last
+    /// measured with `sync_clock`
+    pub fn with_clock_correction(mut self, enabled: bool) -> Self {
+        self.clock.set_enabled(enabled);
+        self
+    }
+
+    /// Stamp published messages with the time they were sent, and record the latencies of
+    /// stamped messages the Node receives in `tracker`
+    pub fn with_latency_tracker(mut self, tracker: LatencyTracker) -> Self {
+        self.latency = Some(tracker);
+        self
+    }
 }
 
 impl<B: Block, I: Interface + Default + Clone, T: Message> NodeConfig<B, I, T> {
+    /// Codec used to encode and decode the Node's messages
+    pub(crate) fn codec(&self) -> crate::node::PayloadCodec {
+        crate::node::PayloadCodec {
+            #[cfg(feature = "crypto")]
+            security: self.security.clone(),
+            clock: self.clock.clone(),
+            latency: self.latency.clone(),
+        }
+    }
+
     /// Construct a Node from the specified configuration
     pub fn build(self) -> Result<Node<B, I, Idle, T>, Error> {
         let (runtime, rt_handle) = {
@@ -118,6 +171,14 @@ impl<B: Block, I: Interface + Default + Clone, T: Message> NodeConfig<B, I, T> {
             endpoint: None,
             #[cfg(feature = "quic")]
             connection: None,
+            #[cfg(feature = "tls")]
+            tls_stream: None,
+            #[cfg(unix)]
+            unix_stream: None,
+            in_process: None,
+            #[cfg(feature = "serial")]
+            serial_port: None,
+            framed_stream: None,
             topic,
             subscription_data: Arc::new(TokioMutex::new(None)),
             task_subscribe: None,
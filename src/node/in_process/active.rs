@@ -0,0 +1,138 @@
+use crate::node::in_process::InProcessConnection;
+use crate::node::network_config::{Blocking, InProcess, Nonblocking};
+use crate::node::{Active, Node};
+use crate::prelude::*;
+
+use std::result::Result;
+use tracing::*;
+
+impl<T: Message + 'static, B: crate::node::network_config::Block> Node<B, InProcess, Active, T> {
+    /// Get the Node's connection to the Host
+    fn connection(&self) -> Result<&InProcessConnection, Error> {
+        match self.in_process.as_ref() {
+            Some(connection) => Ok(connection),
+            None => Err(Error::AccessStream),
+        }
+    }
+}
+
+impl<T: Message + 'static> Node<Nonblocking, InProcess, Active, T> {
+    /// Send data to host on Node's assigned topic using `Msg<T>` packet
+    #[tracing::instrument]
+    #[inline]
+    pub async fn publish(&self, val: T) -> Result<(), Error> {
+        let msg = Msg::new(MsgType::Set, self.topic.clone(), val);
+        self.connection()?.publish(msg, &self.cfg.codec())
+    }
+
+    #[tracing::instrument]
+    #[inline]
+    pub async fn publish_msg(&self, msg: Msg<T>) -> Result<(), Error> {
+        self.connection()?.publish(msg, &self.cfg.codec())
+    }
+
+    /// Request data from host on Node's assigned topic
+    #[tracing::instrument]
+    #[inline]
+    pub async fn request(&self) -> Result<Msg<T>, Error> {
+        self.connection()?.request(&self.topic, &self.cfg.codec())
+    }
+
+    /// Request data from host on Node's assigned topic
+    #[tracing::instrument]
+    #[inline]
+    pub async fn request_nth_back(&self, n: usize) -> Result<Msg<T>, Error> {
+        self.connection()?
+            .request_nth_back(&self.topic, n, &self.cfg.codec())
+    }
+
+    #[tracing::instrument]
+    #[inline]
+    pub async fn topics(&self) -> Result<Msg<Vec<String>>, Error> {
+        self.connection()?.topics()
+    }
+
+    /// Request statistics on the Node's assigned topic from the Host
+    #[tracing::instrument]
+    #[inline]
+    pub async fn stats(&self) -> Result<Msg<TopicStats>, Error> {
+        self.connection()?.stats(&self.topic)
+    }
+
+    /// Estimate the offset of the Node's clock from the Host's
+    ///
+    /// If the Node was configured `with_clock_correction`, the timestamps it publishes are
+    /// corrected by the offset from here on.
+    #[tracing::instrument]
+    #[inline]
+    pub async fn sync_clock(&self) -> Result<ClockSync, Error> {
+        let connection = self.connection()?;
+        self.cfg
+            .clock
+            .sync(|request| async move { connection.clock(request) })
+            .await
+    }
+}
+
+impl<T: Message + 'static> Node<Blocking, InProcess, Active, T> {
+    /// Send data to host on Node's assigned topic using `Msg<T>` packet
+    #[tracing::instrument]
+    #[inline]
+    pub fn publish(&self, val: T) -> Result<(), Error> {
+        let msg = Msg::new(MsgType::Set, self.topic.clone(), val);
+        self.connection()?.publish(msg, &self.cfg.codec())
+    }
+
+    #[tracing::instrument]
+    #[inline]
+    pub fn publish_msg(&self, msg: Msg<T>) -> Result<(), Error> {
+        self.connection()?.publish(msg, &self.cfg.codec())
+    }
+
+    /// Request data from host on Node's assigned topic
+    #[tracing::instrument]
+    #[inline]
+    pub fn request(&self) -> Result<Msg<T>, Error> {
+        self.connection()?.request(&self.topic, &self.cfg.codec())
+    }
+
+    /// Request data from host on Node's assigned topic
+    #[tracing::instrument]
+    #[inline]
+    pub fn request_nth_back(&self, n: usize) -> Result<Msg<T>, Error> {
+        self.connection()?
+            .request_nth_back(&self.topic, n, &self.cfg.codec())
+    }
+
+    #[tracing::instrument]
+    #[inline]
+    pub fn topics(&self) -> Result<Msg<Vec<String>>, Error> {
+        self.connection()?.topics()
+    }
+
+    /// Request statistics on the Node's assigned topic from the Host
+    #[tracing::instrument]
+    #[inline]
+    pub fn stats(&self) -> Result<Msg<TopicStats>, Error> {
+        self.connection()?.stats(&self.topic)
+    }
+
+    /// Estimate the offset of the Node's clock from the Host's
+    ///
+    /// If the Node was configured `with_clock_correction`, the timestamps it publishes are
+    /// corrected by the offset from here on.
+    #[tracing::instrument]
+    #[inline]
+    pub fn sync_clock(&self) -> Result<ClockSync, Error> {
+        let connection = self.connection()?;
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+        handle.block_on(
+            self.cfg
+                .clock
+                .sync(|request| async move { connection.clock(request) }),
+        )
+    }
+}
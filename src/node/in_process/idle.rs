@@ -0,0 +1,256 @@
+use crate::prelude::*;
+
+use crate::host::Subscription as HostSubscription;
+use crate::node::in_process::InProcessConnection;
+use crate::node::network_config::{Blocking, InProcess, Nonblocking};
+use crate::node::*;
+
+use tokio::sync::Mutex as TokioMutex;
+use tokio::time::{sleep, Duration};
+
+use tracing::*;
+
+use std::marker::PhantomData;
+use std::result::Result;
+use std::sync::Arc;
+
+impl<T: Message> From<Node<Nonblocking, InProcess, Idle, T>>
+    for Node<Nonblocking, InProcess, Active, T>
+{
+    fn from(node: Node<Nonblocking, InProcess, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            #[cfg(feature = "quic")]
+            endpoint: node.endpoint,
+            #[cfg(feature = "quic")]
+            connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message> From<Node<Nonblocking, InProcess, Idle, T>>
+    for Node<Nonblocking, InProcess, Subscription, T>
+{
+    fn from(node: Node<Nonblocking, InProcess, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            #[cfg(feature = "quic")]
+            endpoint: node.endpoint,
+            #[cfg(feature = "quic")]
+            connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message, B: Block> Node<B, InProcess, Idle, T> {
+    /// Connect to the Host through the configured `InProcessHandle`
+    fn connect(&self) -> Result<InProcessConnection, Error> {
+        let handle = match &self.cfg.network_cfg.interface_cfg {
+            Some(handle) => handle.clone(),
+            None => return Err(Error::StreamConnection),
+        };
+        InProcessConnection::connect(handle, &self.topic, self.cfg.credentials.as_ref())
+    }
+}
+
+impl<T: Message + 'static> Node<Nonblocking, InProcess, Idle, T> {
+    /// Connect the Node to a Host in the same process
+    #[tracing::instrument(skip_all)]
+    pub async fn activate(mut self) -> Result<Node<Nonblocking, InProcess, Active, T>, Error> {
+        self.in_process = Some(self.connect()?);
+        debug!("{}: Connected to in-process Host", self.topic);
+
+        Ok(Node::<Nonblocking, InProcess, Active, T>::from(self))
+    }
+
+    #[tracing::instrument]
+    pub async fn subscribe(
+        mut self,
+        rate: Duration,
+    ) -> Result<Node<Nonblocking, InProcess, Subscription, T>, Error> {
+        let connection = self.connect()?;
+        let subscription = connection.subscribe(&self.topic, rate)?;
+
+        let subscription_data: Arc<TokioMutex<Option<Msg<T>>>> = Arc::new(TokioMutex::new(None));
+        let data = Arc::clone(&subscription_data);
+
+        let codec = self.cfg.codec();
+        let task_subscribe = tokio::spawn(async move {
+            run_subscription::<T>(connection, subscription, rate, data, codec).await;
+        });
+        self.task_subscribe = Some(task_subscribe);
+
+        let mut subscription_node = Node::<Nonblocking, InProcess, Subscription, T>::from(self);
+        subscription_node.subscription_data = subscription_data;
+
+        Ok(subscription_node)
+    }
+}
+
+/// Poll the Host's store for the latest value on the subscribed topic at the requested rate
+async fn run_subscription<T: Message>(
+    connection: InProcessConnection,
+    subscription: HostSubscription,
+    rate: Duration,
+    data: Arc<TokioMutex<Option<Msg<T>>>>,
+    codec: PayloadCodec,
+) {
+    loop {
+        match connection
+            .next(&subscription)
+            .and_then(|generic| codec.decode::<T>(generic))
+        {
+            Ok(msg) => {
+                let mut data = data.lock().await;
+                let newer = match data.as_ref() {
+                    Some(existing) => msg.is_newer_than(existing),
+                    None => true,
+                };
+                if newer {
+                    *data = Some(msg);
+                }
+            }
+            Err(e) => error!("Subscription Error: {:?}", e),
+        }
+        sleep(rate).await;
+    }
+}
+
+//------
+
+impl<T: Message> From<Node<Blocking, InProcess, Idle, T>> for Node<Blocking, InProcess, Active, T> {
+    fn from(node: Node<Blocking, InProcess, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            #[cfg(feature = "quic")]
+            endpoint: node.endpoint,
+            #[cfg(feature = "quic")]
+            connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message> From<Node<Blocking, InProcess, Idle, T>>
+    for Node<Blocking, InProcess, Subscription, T>
+{
+    fn from(node: Node<Blocking, InProcess, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            #[cfg(feature = "quic")]
+            endpoint: node.endpoint,
+            #[cfg(feature = "quic")]
+            connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message + 'static> Node<Blocking, InProcess, Idle, T> {
+    /// Connect the Node to a Host in the same process
+    #[tracing::instrument(skip_all)]
+    pub fn activate(mut self) -> Result<Node<Blocking, InProcess, Active, T>, Error> {
+        self.in_process = Some(self.connect()?);
+        debug!("{}: Connected to in-process Host", self.topic);
+
+        Ok(Node::<Blocking, InProcess, Active, T>::from(self))
+    }
+
+    #[tracing::instrument]
+    pub fn subscribe(
+        mut self,
+        rate: Duration,
+    ) -> Result<Node<Blocking, InProcess, Subscription, T>, Error> {
+        let connection = self.connect()?;
+        let subscription = connection.subscribe(&self.topic, rate)?;
+
+        let subscription_data: Arc<TokioMutex<Option<Msg<T>>>> = Arc::new(TokioMutex::new(None));
+        let data = Arc::clone(&subscription_data);
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+
+        let codec = self.cfg.codec();
+        let task_subscribe = handle.spawn(async move {
+            run_subscription::<T>(connection, subscription, rate, data, codec).await;
+        });
+        self.task_subscribe = Some(task_subscribe);
+
+        let mut subscription_node = Node::<Blocking, InProcess, Subscription, T>::from(self);
+        subscription_node.subscription_data = subscription_data;
+
+        Ok(subscription_node)
+    }
+}
@@ -0,0 +1,99 @@
+mod active;
+mod idle;
+mod subscription;
+
+use chrono::{DateTime, Utc};
+
+use std::convert::TryInto;
+use std::result::Result;
+use tokio::time::Duration;
+
+use crate::host::{Dispatcher, InProcessHandle, Subscription, TopicStats};
+use crate::msg::{GenericMsg, Message, Msg};
+use crate::node::PayloadCodec;
+use crate::Error;
+
+/// Connection of an in-process Node to its Host, whose requests the Host handles directly
+#[derive(Debug)]
+pub(crate) struct InProcessConnection {
+    dispatcher: Dispatcher,
+}
+
+impl InProcessConnection {
+    /// Connect to the Host, presenting any credentials
+    pub(crate) fn connect(
+        handle: InProcessHandle,
+        topic: &str,
+        credentials: Option<&crate::host::Credentials>,
+    ) -> Result<Self, Error> {
+        let dispatcher = handle.connect(topic, credentials)?;
+        Ok(InProcessConnection { dispatcher })
+    }
+
+    /// Store a message on the Host
+    #[inline]
+    pub(crate) fn publish<T: Message>(
+        &self,
+        msg: Msg<T>,
+        codec: &PayloadCodec,
+    ) -> Result<(), Error> {
+        let generic = codec.to_generic(msg)?;
+        self.dispatcher.publish(generic)
+    }
+
+    /// Request the last message on a topic from the Host
+    #[inline]
+    pub(crate) fn request<T: Message>(
+        &self,
+        topic: &str,
+        codec: &PayloadCodec,
+    ) -> Result<Msg<T>, Error> {
+        let generic = self.dispatcher.request(GenericMsg::get::<T>(topic))?;
+        codec.decode(generic)
+    }
+
+    /// Request the message `n` back from the last on a topic from the Host
+    #[inline]
+    pub(crate) fn request_nth_back<T: Message>(
+        &self,
+        topic: &str,
+        n: usize,
+        codec: &PayloadCodec,
+    ) -> Result<Msg<T>, Error> {
+        let generic = self
+            .dispatcher
+            .request(GenericMsg::get_nth::<T>(topic, n))?;
+        codec.decode(generic)
+    }
+
+    /// Request the list of topics from the Host
+    #[inline]
+    pub(crate) fn topics(&self) -> Result<Msg<Vec<String>>, Error> {
+        self.dispatcher.request(GenericMsg::topics())?.try_into()
+    }
+
+    /// Request statistics on a topic from the Host
+    #[inline]
+    pub(crate) fn stats(&self, topic: &str) -> Result<Msg<TopicStats>, Error> {
+        self.dispatcher
+            .request(GenericMsg::stats(topic))?
+            .try_into()
+    }
+
+    /// Reply to a `MsgType::Clock` request
+    pub(crate) fn clock(&self, request: GenericMsg) -> Result<Msg<DateTime<Utc>>, Error> {
+        self.dispatcher.request(request)?.try_into()
+    }
+
+    /// Subscribe to a topic, which is counted as subscribed for as long as the returned
+    /// `Subscription` is held
+    pub(crate) fn subscribe(&self, topic: &str, rate: Duration) -> Result<Subscription, Error> {
+        self.dispatcher
+            .subscribe(GenericMsg::subscribe(topic, rate)?)
+    }
+
+    /// Latest message for a subscription
+    pub(crate) fn next(&self, subscription: &Subscription) -> Result<GenericMsg, Error> {
+        self.dispatcher.next(subscription)
+    }
+}
@@ -0,0 +1,176 @@
+use chrono::{DateTime, Utc};
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::msg::Latency;
+
+/// Buckets per doubling of latency, which keeps percentiles within about 9% of the exact value
+const BUCKETS_PER_DOUBLING: f64 = 8.0;
+
+/// Distribution of the latencies of messages over one hop, in logarithmically-sized buckets
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    /// Number of latencies in each bucket, by bucket index
+    buckets: BTreeMap<u32, u64>,
+    count: u64,
+    max: Duration,
+}
+
+impl LatencyHistogram {
+    fn bucket(latency: Duration) -> u32 {
+        match latency.as_nanos() {
+            0 | 1 => 0,
+            nanos => ((nanos as f64).log2() * BUCKETS_PER_DOUBLING).ceil() as u32,
+        }
+    }
+
+    /// Upper bound of a bucket
+    fn bound(bucket: u32) -> Duration {
+        Duration::from_nanos(2f64.powf(bucket as f64 / BUCKETS_PER_DOUBLING).round() as u64)
+    }
+
+    fn record(&mut self, latency: Duration) {
+        *self.buckets.entry(Self::bucket(latency)).or_default() += 1;
+        self.count += 1;
+        self.max = self.max.max(latency);
+    }
+
+    /// Number of latencies recorded
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Longest latency recorded
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// Latency that `percentile` percent of messages arrived within, to the upper bound of its
+    /// bucket
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        // Nearest-rank percentiles
+        let rank = ((self.count as f64 * percentile / 100.0).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, count) in &self.buckets {
+            seen += count;
+            if seen >= rank {
+                return Self::bound(*bucket).min(self.max);
+            }
+        }
+        self.max
+    }
+
+    /// Median latency
+    pub fn p50(&self) -> Duration {
+        self.percentile(50.0)
+    }
+
+    /// 99th percentile latency
+    pub fn p99(&self) -> Duration {
+        self.percentile(99.0)
+    }
+
+    /// Upper bound of each bucket that latencies were recorded in, with the number recorded
+    pub fn buckets(&self) -> Vec<(Duration, u64)> {
+        self.buckets
+            .iter()
+            .map(|(bucket, count)| (Self::bound(*bucket), *count))
+            .collect()
+    }
+}
+
+/// Latencies of the messages received on a topic, over each hop from their publishers
+///
+/// Hops between a Node and the Host compare timestamps from both their clocks, so they're only
+/// as accurate as the clocks are synchronized, as with `sync_clock` and `with_clock_correction`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyReport {
+    /// From the publishing Node sending each message to a Node receiving it
+    pub end_to_end: LatencyHistogram,
+    /// From the publishing Node sending each message to the Host receiving it
+    pub to_host: LatencyHistogram,
+    /// From the Host receiving each message to it sending the message on
+    pub on_host: LatencyHistogram,
+    /// From the Host sending each message on to a Node receiving it
+    pub from_host: LatencyHistogram,
+    /// Time the publisher sent the last message recorded
+    last_sent: Option<DateTime<Utc>>,
+}
+
+impl LatencyReport {
+    fn record(&mut self, latency: &Latency, received: Option<DateTime<Utc>>) {
+        let Latency {
+            sent,
+            forwarded,
+            delivered,
+        } = *latency;
+        // Polling a topic receives its last message again until a new one is set, so it's only
+        // counted the first time
+        if sent.is_none() || sent == self.last_sent {
+            return;
+        }
+        self.last_sent = sent;
+
+        let hops = [
+            (&mut self.end_to_end, sent, delivered),
+            (&mut self.to_host, sent, received),
+            (&mut self.on_host, received, forwarded),
+            (&mut self.from_host, forwarded, delivered),
+        ];
+        for (histogram, from, to) in hops {
+            if let (Some(from), Some(to)) = (from, to) {
+                // Skew between clocks can make a hop appear to take negative time
+                histogram.record((to - from).to_std().unwrap_or_default());
+            }
+        }
+    }
+}
+
+/// Latencies of the messages received by Nodes, by topic
+///
+/// Nodes configured with a tracker stamp the messages they publish with the time they were sent,
+/// and record the latencies of stamped messages they receive. Clones of a tracker share its
+/// histograms, so one tracker can be shared by several Nodes.
+#[derive(Debug, Clone, Default)]
+pub struct LatencyTracker {
+    topics: Arc<Mutex<HashMap<String, LatencyReport>>>,
+}
+
+impl LatencyTracker {
+    /// Create a tracker without any latencies recorded
+    pub fn new() -> Self {
+        LatencyTracker::default()
+    }
+
+    /// Histograms are only ever updated whole, so they remain valid if a holder panicked
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, LatencyReport>> {
+        match self.topics.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Latencies of the messages received on a topic, if any have been recorded
+    pub fn report(&self, topic: &str) -> Option<LatencyReport> {
+        self.lock().get(topic).cloned()
+    }
+
+    /// Latencies of the messages received on each topic
+    pub fn reports(&self) -> HashMap<String, LatencyReport> {
+        self.lock().clone()
+    }
+
+    pub(crate) fn record(&self, topic: &str, latency: &Latency, received: Option<DateTime<Utc>>) {
+        let mut topics = self.lock();
+        match topics.get_mut(topic) {
+            Some(report) => report.record(latency, received),
+            None => {
+                let mut report = LatencyReport::default();
+                report.record(latency, received);
+                topics.insert(topic.to_string(), report);
+            }
+        }
+    }
+}
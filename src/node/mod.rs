@@ -1,10 +1,22 @@
+pub mod clock;
 pub mod config;
+pub mod in_process;
+pub mod latency;
 pub mod network_config;
 pub mod tcp;
 pub mod udp;
 
 #[cfg(feature = "quic")]
 pub mod quic;
+#[cfg(feature = "crypto")]
+pub mod security;
+#[cfg(feature = "serial")]
+pub mod serial;
+pub mod stream;
+#[cfg(feature = "tls")]
+pub mod tls;
+#[cfg(unix)]
+pub mod unix;
 
 /// State marker for a Node that has not been connected to a Host
 #[derive(Debug)]
@@ -19,11 +31,19 @@ pub struct Subscription;
 mod private {
     pub trait Sealed {}
 
-    use crate::node::network_config::{Tcp, Udp};
+    use crate::node::network_config::{InProcess, Stream, Tcp, Udp};
     impl Sealed for Udp {}
     impl Sealed for Tcp {}
+    impl Sealed for InProcess {}
+    impl Sealed for Stream {}
     #[cfg(feature = "quic")]
     impl Sealed for crate::node::network_config::Quic {}
+    #[cfg(feature = "tls")]
+    impl Sealed for crate::node::network_config::Tls {}
+    #[cfg(unix)]
+    impl Sealed for crate::node::network_config::Unix {}
+    #[cfg(feature = "serial")]
+    impl Sealed for crate::node::network_config::Serial {}
 
     use crate::node::{Active, Idle};
     impl Sealed for Idle {}
@@ -45,6 +65,7 @@ use tracing::*;
 
 use std::net::SocketAddr;
 
+use std::convert::TryInto;
 use std::marker::{PhantomData, Sync};
 use std::result::Result;
 use std::sync::Arc;
@@ -69,10 +90,95 @@ use rustls::Certificate;
 use std::fs::File;
 #[cfg(feature = "quic")]
 use std::io::BufReader;
-
+// TLS stuff
+#[cfg(feature = "tls")]
+use tokio_rustls::client::TlsStream;
+// Unix domain socket stuff
+#[cfg(unix)]
+use tokio::net::UnixStream;
+
+use crate::error::HostOperation;
 use crate::node::config::NodeConfig;
 use std::sync::Mutex;
 
+/// Interpret the Host's acknowledgement of a published message
+#[inline]
+pub(crate) fn check_publish_ack(bytes: &[u8]) -> Result<(), Error> {
+    if let Ok(GenericMsg {
+        msg_type: MsgType::Error(e),
+        ..
+    }) = from_bytes::<GenericMsg>(bytes)
+    {
+        return Err(e);
+    }
+    if let Ok(HostOperation::FAILURE) = from_bytes::<HostOperation>(bytes) {
+        error!("Host-side error on publish");
+    }
+    Ok(())
+}
+
+/// Encodes the messages a Node publishes and decodes the messages it receives, sealing and
+/// opening their payloads if the Node has a `SecurityConfig`
+///
+/// Published timestamps are corrected onto the Host's clock before sealing, as they're signed.
+/// Nodes with a `LatencyTracker` also stamp the messages they publish with the time they were
+/// sent, and record the latencies of the stamped messages they receive.
+#[derive(Debug, Clone, Default)]
+pub struct PayloadCodec {
+    #[cfg(feature = "crypto")]
+    pub(crate) security: Option<security::SecurityConfig>,
+    pub(crate) clock: clock::ClockCorrection,
+    pub(crate) latency: Option<latency::LatencyTracker>,
+}
+
+impl PayloadCodec {
+    /// Serialize a message to be sent to the Host
+    #[inline]
+    pub(crate) fn encode<T: Message>(&self, msg: Msg<T>) -> Result<Vec<u8>, Error> {
+        Ok(self.to_generic(msg)?.as_bytes()?)
+    }
+
+    /// Convert a message to be sent to the Host into a `GenericMsg`
+    #[inline]
+    pub(crate) fn to_generic<T: Message>(&self, msg: Msg<T>) -> Result<GenericMsg, Error> {
+        let mut generic = msg.to_generic()?;
+        if generic.msg_type == MsgType::Set {
+            generic.timestamp = self.clock.correct(generic.timestamp);
+            if self.latency.is_some() {
+                generic.latency = Some(Latency {
+                    sent: Some(self.clock.correct(Utc::now())),
+                    ..Default::default()
+                });
+            }
+        }
+        #[cfg(feature = "crypto")]
+        if let (Some(security), MsgType::Set) = (&self.security, &generic.msg_type) {
+            security.seal(&mut generic)?;
+        }
+        Ok(generic)
+    }
+
+    /// Convert a message from the Host into a strongly-typed message
+    ///
+    /// Only published (`MsgType::Set`) messages carry sealed payloads.
+    #[inline]
+    pub(crate) fn decode<T: Message>(&self, generic: GenericMsg) -> Result<Msg<T>, Error> {
+        let mut generic = generic;
+        if let (Some(_), Some(latency)) = (&self.latency, &mut generic.latency) {
+            latency.delivered = Some(self.clock.correct(Utc::now()));
+        }
+        #[cfg(feature = "crypto")]
+        if let (Some(security), MsgType::Set) = (&self.security, &generic.msg_type) {
+            security.open(&mut generic)?;
+        }
+        // Only messages that could be opened have their latency recorded
+        if let (Some(tracker), Some(latency)) = (&self.latency, &generic.latency) {
+            tracker.record(&generic.topic, latency, generic.received);
+        }
+        generic.try_into()
+    }
+}
+
 /// Strongly-typed Node capable of publish/request on Host
 #[derive(Debug)]
 pub struct Node<B: Block, I: Interface + Default, State, T: Message> {
@@ -82,13 +188,21 @@ pub struct Node<B: Block, I: Interface + Default, State, T: Message> {
     pub(crate) runtime: Option<Runtime>,
     pub(crate) rt_handle: Option<Handle>,
     pub(crate) topic: String,
-    pub(crate) stream: Option<TcpStream>,
+    pub(crate) stream: Option<TokioMutex<TcpStream>>,
     pub(crate) socket: Option<UdpSocket>,
     pub(crate) buffer: Arc<TokioMutex<Vec<u8>>>,
     #[cfg(feature = "quic")]
     pub(crate) endpoint: Option<Endpoint>,
     #[cfg(feature = "quic")]
     pub(crate) connection: Option<QuicConnection>,
+    #[cfg(feature = "tls")]
+    pub(crate) tls_stream: Option<TokioMutex<TlsStream<TcpStream>>>,
+    #[cfg(unix)]
+    pub(crate) unix_stream: Option<TokioMutex<UnixStream>>,
+    pub(crate) in_process: Option<in_process::InProcessConnection>,
+    #[cfg(feature = "serial")]
+    pub(crate) serial_port: Option<TokioMutex<crate::serial::SerialPort>>,
+    pub(crate) framed_stream: Option<TokioMutex<crate::stream::FramedStream>>,
     pub(crate) subscription_data: Arc<TokioMutex<Option<Msg<T>>>>,
     pub(crate) task_subscribe: Option<JoinHandle<()>>,
 }
@@ -113,4 +227,10 @@ impl<B: Block, I: Interface + Default, State, T: Message> Node<B, I, State, T> {
     pub fn topic(&self) -> String {
         self.topic.clone()
     }
+
+    /// Latencies of the messages the Node has received on its topic, if it was configured with a
+    /// `LatencyTracker`
+    pub fn latency(&self) -> Option<latency::LatencyReport> {
+        self.cfg.latency.as_ref()?.report(&self.topic)
+    }
 }
@@ -3,21 +3,84 @@ use std::marker::PhantomData;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::{Path, PathBuf};
 
+use crate::discovery::{discover_trusted, DiscoveryConfig, Transport};
+use crate::host::InProcessHandle;
 use crate::node::private;
-pub trait Interface: private::Sealed + Default {}
+#[cfg(feature = "serial")]
+use crate::serial::SerialConfig;
+use crate::stream::StreamConnector;
+use crate::Error;
+
+pub trait Interface: private::Sealed + Default {
+    /// Transport the Node connects to the Host over
+    const TRANSPORT: Transport;
+    /// Settings specific to the transport, kept in `NetworkConfig::interface_cfg`
+    type Config: Clone + Debug;
+}
 pub trait Block: private::Sealed + Default + Sized {}
 
 #[derive(Debug, Clone, Default)]
 pub struct Tcp {}
-impl Interface for Tcp {}
+impl Interface for Tcp {
+    const TRANSPORT: Transport = Transport::Tcp;
+    type Config = ();
+}
 #[derive(Debug, Clone, Default)]
 pub struct Udp {}
-impl Interface for Udp {}
+impl Interface for Udp {
+    const TRANSPORT: Transport = Transport::Udp;
+    type Config = ();
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct Quic {}
 #[cfg(feature = "quic")]
-impl Interface for Quic {}
+impl Interface for Quic {
+    const TRANSPORT: Transport = Transport::Quic;
+    type Config = ();
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Tls {}
+#[cfg(feature = "tls")]
+impl Interface for Tls {
+    const TRANSPORT: Transport = Transport::Tls;
+    type Config = ();
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Unix {}
+#[cfg(unix)]
+impl Interface for Unix {
+    const TRANSPORT: Transport = Transport::Unix;
+    /// Path of the Host's Unix domain socket, used in place of `host_addr`
+    type Config = Option<PathBuf>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Serial {}
+#[cfg(feature = "serial")]
+impl Interface for Serial {
+    const TRANSPORT: Transport = Transport::Serial;
+    /// Serial device linked to the Host, used in place of `host_addr`
+    type Config = Option<SerialConfig>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InProcess {}
+impl Interface for InProcess {
+    const TRANSPORT: Transport = Transport::InProcess;
+    /// Handle to a Host in the same process, used in place of `host_addr`
+    type Config = Option<InProcessHandle>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Stream {}
+impl Interface for Stream {
+    const TRANSPORT: Transport = Transport::Stream;
+    /// Opens user-supplied streams to the Host, used in place of `host_addr`
+    type Config = Option<StreamConnector>;
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct Blocking;
@@ -29,32 +92,115 @@ impl Block for Nonblocking {}
 
 /// Configuration for network interfaces
 #[derive(Clone, Debug)]
-pub struct NetworkConfig<Block, Interface>
+pub struct NetworkConfig<B, I>
 where
-    Interface: Default,
+    I: Interface,
 {
-    __interface: PhantomData<Interface>,
-    __block: PhantomData<Block>,
+    __interface: PhantomData<I>,
+    __block: PhantomData<B>,
     /// Socket address for the connected Host
     pub host_addr: SocketAddr,
+    /// Addresses of standby Hosts, tried in order when the Host at `host_addr` is unreachable
+    ///
+    /// Only TCP Nodes fail over to them; Nodes on other transports only connect to `host_addr`.
+    pub fallback_addrs: Vec<SocketAddr>,
     /// Max buffer size that the Node will allocate for Host responses
     pub max_buffer_size: usize,
+    /// Certificate chain used to verify the Host; QUIC Nodes also present it to the Host when
+    /// `key_path` is set, for mutual TLS
     pub cert_path: Option<PathBuf>,
+    /// Private key matching the Node's certificate, used for mutual TLS over QUIC only if set
     pub key_path: Option<PathBuf>,
+    /// Name used to verify the Host's certificate, which must match one of its DNS subject
+    /// alternative names
+    pub server_name: String,
     pub send_tries: usize,
+    /// Name of a Host to discover, which replaces `host_addr` with the address it announces
+    pub host_name: Option<String>,
+    /// Multicast group used to discover the Host named by `host_name`
+    pub discovery_cfg: DiscoveryConfig,
+    /// Settings specific to the Node's transport
+    pub interface_cfg: I::Config,
 }
 
-impl<B: Block> Default for NetworkConfig<B, Tcp> {
-    fn default() -> NetworkConfig<B, Tcp> {
-        Self {
-            __interface: PhantomData::<Tcp>,
+impl<B: Block, I: Interface> NetworkConfig<B, I> {
+    /// Connect to a Host on `localhost` at `port`, with the transport's own settings
+    fn localhost(port: u16, max_buffer_size: usize, interface_cfg: I::Config) -> Self {
+        NetworkConfig {
+            __interface: PhantomData::<I>,
             __block: PhantomData::<B>,
-            host_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 25_000),
-            max_buffer_size: 1024,
+            host_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), port),
+            fallback_addrs: Vec::new(),
+            max_buffer_size,
             cert_path: None,
             key_path: None,
+            server_name: "localhost".into(),
             send_tries: 10,
+            host_name: None,
+            discovery_cfg: DiscoveryConfig::default(),
+            interface_cfg,
+        }
+    }
+
+    /// Discover the Host by name on each connection instead of connecting to `host_addr`
+    pub fn set_host_name(mut self, host_name: impl Into<String>) -> Self {
+        self.host_name = Some(host_name.into());
+        self
+    }
+
+    /// Set the multicast group used to discover the Host
+    pub fn set_discovery_config(mut self, discovery_cfg: DiscoveryConfig) -> Self {
+        self.discovery_cfg = discovery_cfg;
+        self
+    }
+
+    /// Update `host_addr` from the named Host's beacon, if discovery is enabled, and return it
+    ///
+    /// Nodes that verify the Host's certificate only trust beacons announcing the fingerprint of
+    /// one of the certificates in their `cert_path`, so a spoofed beacon can't redirect them.
+    pub(crate) async fn discover_host(&mut self) -> Result<SocketAddr, Error> {
+        if let Some(host_name) = &self.host_name {
+            let fingerprints = self.trusted_fingerprints()?;
+            let beacon = discover_trusted(host_name, &self.discovery_cfg, |beacon| {
+                match (&fingerprints, &beacon.fingerprint) {
+                    (None, _) => true,
+                    (Some(fingerprints), Some(fingerprint)) => fingerprints.contains(fingerprint),
+                    (Some(_), None) => false,
+                }
+            })
+            .await?;
+            self.host_addr = beacon
+                .addr(I::TRANSPORT)
+                .ok_or_else(|| Error::HostNotFound(host_name.clone()))?;
         }
+        Ok(self.host_addr)
+    }
+
+    /// Fingerprints of the certificates the Node verifies the Host against, if its transport
+    /// verifies the Host at all
+    fn trusted_fingerprints(&self) -> Result<Option<Vec<String>>, Error> {
+        #[cfg(feature = "quic")]
+        if let (Transport::Quic | Transport::Tls, Some(cert_path)) = (I::TRANSPORT, &self.cert_path)
+        {
+            let certs = crate::host::pki::inspect(cert_path)?;
+            return Ok(Some(
+                certs.into_iter().map(|cert| cert.fingerprint).collect(),
+            ));
+        }
+        Ok(None)
+    }
+
+    /// Addresses of the Host and its standbys, in the order they're tried
+    pub(crate) fn host_addrs(&self) -> Vec<SocketAddr> {
+        let mut addrs = vec![self.host_addr];
+        addrs.extend(&self.fallback_addrs);
+        addrs
+    }
+}
+
+impl<B: Block> Default for NetworkConfig<B, Tcp> {
+    fn default() -> NetworkConfig<B, Tcp> {
+        NetworkConfig::localhost(25_000, 1024, ())
     }
 }
 
@@ -65,6 +211,19 @@ impl<B: Block> NetworkConfig<B, Tcp> {
         self
     }
 
+    /// Define an ordered list of Host addresses, where the Node connects to the first reachable
+    /// Host and fails over to the next whenever its connection is lost
+    ///
+    /// Failover is only available to TCP Nodes.
+    pub fn set_host_addrs(mut self, host_addrs: impl IntoIterator<Item = SocketAddr>) -> Self {
+        let mut host_addrs = host_addrs.into_iter();
+        if let Some(host_addr) = host_addrs.next() {
+            self.host_addr = host_addr;
+        }
+        self.fallback_addrs = host_addrs.collect();
+        self
+    }
+
     /// Set a max buffer size for Host responses
     pub fn set_max_buffer_size(mut self, max_buffer_size: impl Into<usize>) -> Self {
         self.max_buffer_size = max_buffer_size.into();
@@ -74,15 +233,7 @@ impl<B: Block> NetworkConfig<B, Tcp> {
 
 impl<B: Block> Default for NetworkConfig<B, Udp> {
     fn default() -> NetworkConfig<B, Udp> {
-        Self {
-            __interface: PhantomData::<Udp>,
-            __block: PhantomData::<B>,
-            host_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 25_000),
-            max_buffer_size: 2048,
-            cert_path: None,
-            key_path: None,
-            send_tries: 10,
-        }
+        NetworkConfig::localhost(25_000, 2048, ())
     }
 }
 
@@ -100,20 +251,17 @@ impl<B: Block> NetworkConfig<B, Udp> {
     }
 }
 
+#[cfg(feature = "quic")]
 impl<B: Block> Default for NetworkConfig<B, Quic> {
     fn default() -> NetworkConfig<B, Quic> {
-        Self {
-            __interface: PhantomData::<Quic>,
-            __block: PhantomData::<B>,
-            host_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 25_000),
-            max_buffer_size: 4096,
-            send_tries: 10,
+        NetworkConfig {
             cert_path: Some(Path::new("target").join("cert.pem")),
-            key_path: Some(Path::new("target").join("priv_key.pem")),
+            ..NetworkConfig::localhost(25_000, 4096, ())
         }
     }
 }
 
+#[cfg(feature = "quic")]
 impl<B: Block> NetworkConfig<B, Quic> {
     /// Define a custom address for the Host to which the Node will connect
     pub fn set_host_addr(mut self, host_addr: impl Into<SocketAddr>) -> Self {
@@ -121,9 +269,136 @@ impl<B: Block> NetworkConfig<B, Quic> {
         self
     }
 
+    /// Set the path of the certificate chain used to verify the Host
+    pub fn set_cert_path(mut self, cert_path: Option<PathBuf>) -> Self {
+        self.cert_path = cert_path;
+        self
+    }
+
+    /// Set the path of the Node's private key to enable mutual TLS, or `None` to disable it
+    pub fn set_key_path(mut self, key_path: Option<PathBuf>) -> Self {
+        self.key_path = key_path;
+        self
+    }
+
+    /// Set the name used to verify the Host's certificate, e.g. when the Host is not on `localhost`
+    pub fn set_server_name(mut self, server_name: impl Into<String>) -> Self {
+        self.server_name = server_name.into();
+        self
+    }
+
+    /// Set a max buffer size for Host responses
+    pub fn set_max_buffer_size(mut self, max_buffer_size: impl Into<usize>) -> Self {
+        self.max_buffer_size = max_buffer_size.into();
+        self
+    }
+}
+
+#[cfg(feature = "tls")]
+impl<B: Block> Default for NetworkConfig<B, Tls> {
+    fn default() -> NetworkConfig<B, Tls> {
+        NetworkConfig {
+            cert_path: Some(Path::new("target").join("cert.pem")),
+            ..NetworkConfig::localhost(25_001, 4096, ())
+        }
+    }
+}
+
+#[cfg(feature = "tls")]
+impl<B: Block> NetworkConfig<B, Tls> {
+    /// Define a custom address for the Host to which the Node will connect
+    pub fn set_host_addr(mut self, host_addr: impl Into<SocketAddr>) -> Self {
+        self.host_addr = host_addr.into();
+        self
+    }
+
+    /// Set the path of the certificate chain used to verify the Host
+    pub fn set_cert_path(mut self, cert_path: Option<PathBuf>) -> Self {
+        self.cert_path = cert_path;
+        self
+    }
+
+    /// Set the name used to verify the Host's certificate, e.g. when the Host is not on `localhost`
+    pub fn set_server_name(mut self, server_name: impl Into<String>) -> Self {
+        self.server_name = server_name.into();
+        self
+    }
+
     /// Set a max buffer size for Host responses
     pub fn set_max_buffer_size(mut self, max_buffer_size: impl Into<usize>) -> Self {
         self.max_buffer_size = max_buffer_size.into();
         self
     }
 }
+
+#[cfg(unix)]
+impl<B: Block> Default for NetworkConfig<B, Unix> {
+    fn default() -> NetworkConfig<B, Unix> {
+        NetworkConfig::localhost(25_000, 1024, Some(std::env::temp_dir().join("meadow.sock")))
+    }
+}
+
+#[cfg(unix)]
+impl<B: Block> NetworkConfig<B, Unix> {
+    /// Define a custom path of the Host's Unix domain socket to which the Node will connect
+    pub fn set_socket_path(mut self, socket_path: impl Into<PathBuf>) -> Self {
+        self.interface_cfg = Some(socket_path.into());
+        self
+    }
+
+    /// Set a max buffer size for Host responses
+    pub fn set_max_buffer_size(mut self, max_buffer_size: impl Into<usize>) -> Self {
+        self.max_buffer_size = max_buffer_size.into();
+        self
+    }
+}
+
+impl<B: Block> Default for NetworkConfig<B, InProcess> {
+    fn default() -> NetworkConfig<B, InProcess> {
+        NetworkConfig::localhost(25_000, 1024, None)
+    }
+}
+
+impl<B: Block> NetworkConfig<B, InProcess> {
+    /// Connect the Node to a Host in the same process, from `Host::in_process_handle`
+    pub fn set_host_handle(mut self, host_handle: InProcessHandle) -> Self {
+        self.interface_cfg = Some(host_handle);
+        self
+    }
+}
+
+impl<B: Block> Default for NetworkConfig<B, Stream> {
+    fn default() -> NetworkConfig<B, Stream> {
+        NetworkConfig::localhost(25_000, 10_000, None)
+    }
+}
+
+impl<B: Block> NetworkConfig<B, Stream> {
+    /// Set how the Node opens streams to the Host
+    pub fn set_connector(mut self, connector: StreamConnector) -> Self {
+        self.interface_cfg = Some(connector);
+        self
+    }
+
+    /// Set the largest frame the Node accepts from the Host
+    pub fn set_max_buffer_size(mut self, max_buffer_size: impl Into<usize>) -> Self {
+        self.max_buffer_size = max_buffer_size.into();
+        self
+    }
+}
+
+#[cfg(feature = "serial")]
+impl<B: Block> Default for NetworkConfig<B, Serial> {
+    fn default() -> NetworkConfig<B, Serial> {
+        NetworkConfig::localhost(25_000, 1024, None)
+    }
+}
+
+#[cfg(feature = "serial")]
+impl<B: Block> NetworkConfig<B, Serial> {
+    /// Set the serial device linked to the Host
+    pub fn set_serial_config(mut self, serial_cfg: SerialConfig) -> Self {
+        self.interface_cfg = Some(serial_cfg);
+        self
+    }
+}
@@ -1,4 +1,5 @@
 use crate::error::{Error, Quic::*};
+use crate::node::check_publish_ack;
 use crate::node::network_config::{Nonblocking, Quic};
 use crate::node::Active;
 use crate::node::Node;
@@ -14,24 +15,29 @@ use quinn::Connection as QuicConnection;
 use std::result::Result;
 use tracing::*;
 
-/// Quic implements the Interface trait
+// Quic implements the Interface trait
 // impl Interface for Quic {}
 
 impl<T: Message + 'static> Node<Nonblocking, Quic, Active, T> {
     #[tracing::instrument(skip(self))]
     pub async fn publish(&self, val: T) -> Result<(), Error> {
-        let packet = Msg::new(MsgType::Set, self.topic.clone(), val)
-            .to_generic()?
-            .as_bytes()?;
+        let packet = self
+            .cfg
+            .codec()
+            .encode(Msg::new(MsgType::Set, self.topic.clone(), val))?;
 
         if let Some(connection) = &self.connection {
             match connection.open_bi().await {
-                Ok((mut send, _recv)) => {
+                Ok((mut send, mut recv)) => {
                     debug!("Node succesfully opened stream from connection");
 
                     if let Ok(()) = send.write_all(&packet).await {
                         if let Ok(()) = send.finish().await {
                             debug!("Node successfully wrote packet to stream");
+                            let mut buf = self.buffer.lock().await;
+                            if let Ok(Some(n)) = recv.read(&mut buf).await {
+                                check_publish_ack(&buf[..n])?;
+                            }
                         }
                     } else {
                         error!("Error writing packet to stream");
@@ -50,16 +56,20 @@ impl<T: Message + 'static> Node<Nonblocking, Quic, Active, T> {
 
     #[tracing::instrument(skip(self))]
     pub async fn publish_msg(&self, msg: Msg<T>) -> Result<(), Error> {
-        let packet = msg.to_generic()?.as_bytes()?;
+        let packet = self.cfg.codec().encode(msg)?;
 
         if let Some(connection) = &self.connection {
             match connection.open_bi().await {
-                Ok((mut send, _recv)) => {
+                Ok((mut send, mut recv)) => {
                     debug!("Node succesfully opened stream from connection");
 
                     if let Ok(()) = send.write_all(&packet).await {
                         if let Ok(()) = send.finish().await {
                             debug!("Node successfully wrote packet to stream");
+                            let mut buf = self.buffer.lock().await;
+                            if let Ok(Some(n)) = recv.read(&mut buf).await {
+                                check_publish_ack(&buf[..n])?;
+                            }
                         }
                     } else {
                         error!("Error writing packet to stream");
@@ -93,7 +103,7 @@ impl<T: Message + 'static> Node<Nonblocking, Quic, Active, T> {
                     Some(n) => {
                         let bytes = &buf[..n];
                         let generic = from_bytes::<GenericMsg>(bytes)?;
-                        let msg = generic.try_into()?;
+                        let msg = self.cfg.codec().decode(generic)?;
 
                         return Ok(msg);
                     }
@@ -123,6 +133,46 @@ impl<T: Message + 'static> Node<Nonblocking, Quic, Active, T> {
         let topics: Msg<Vec<String>> = reply.try_into()?;
         Ok(topics)
     }
+
+    /// Request statistics on the Node's assigned topic from the Host
+    pub async fn stats(&self) -> Result<Msg<TopicStats>, Error> {
+        let packet = GenericMsg::stats(&self.topic).as_bytes()?;
+
+        let mut buf = self.buffer.lock().await;
+
+        let connection = self.connection.clone().ok_or(Connection)?;
+
+        let (mut send, mut recv) = connection.open_bi().await?;
+        debug!("Node succesfully opened stream from connection");
+        send.write_all(&packet).await?;
+        send.finish().await?;
+
+        let n = recv.read(&mut buf).await?.ok_or(Connection)?;
+        let bytes = &buf[..n];
+        let reply = from_bytes::<GenericMsg>(bytes)?;
+        let stats: Msg<TopicStats> = reply.try_into()?;
+        Ok(stats)
+    }
+
+    /// Estimate the offset of the Node's clock from the Host's
+    ///
+    /// If the Node was configured `with_clock_correction`, the timestamps it publishes are
+    /// corrected by the offset from here on.
+    pub async fn sync_clock(&self) -> Result<ClockSync, Error> {
+        self.cfg
+            .clock
+            .sync(|request| async move {
+                let mut buf = self.buffer.lock().await;
+                let connection = self.connection.clone().ok_or(Connection)?;
+                let (mut send, mut recv) = connection.open_bi().await?;
+                send.write_all(&request.as_bytes()?).await?;
+                send.finish().await?;
+
+                let n = recv.read(&mut buf).await?.ok_or(Connection)?;
+                from_bytes::<GenericMsg>(&buf[..n])?.try_into()
+            })
+            .await
+    }
 }
 
 //-----
@@ -132,9 +182,10 @@ use crate::node::network_config::Blocking;
 impl<T: Message + 'static> Node<Blocking, Quic, Active, T> {
     #[tracing::instrument(skip(self))]
     pub fn publish(&self, val: T) -> Result<(), Error> {
-        let packet = Msg::new(MsgType::Set, &self.topic, val)
-            .to_generic()?
-            .as_bytes()?;
+        let packet = self
+            .cfg
+            .codec()
+            .encode(Msg::new(MsgType::Set, &self.topic, val))?;
 
         let handle = match &self.rt_handle {
             Some(handle) => handle,
@@ -144,12 +195,16 @@ impl<T: Message + 'static> Node<Blocking, Quic, Active, T> {
         if let Some(connection) = &self.connection {
             handle.block_on(async {
                 match connection.open_bi().await {
-                    Ok((mut send, _recv)) => {
+                    Ok((mut send, mut recv)) => {
                         debug!("Node succesfully opened stream from connection");
 
                         if let Ok(()) = send.write_all(&packet).await {
                             if let Ok(()) = send.finish().await {
                                 debug!("Node successfully wrote packet to stream");
+                                let mut buf = self.buffer.lock().await;
+                                if let Ok(Some(n)) = recv.read(&mut buf).await {
+                                    check_publish_ack(&buf[..n])?;
+                                }
                             }
                         } else {
                             error!("Error writing packet to stream");
@@ -169,7 +224,7 @@ impl<T: Message + 'static> Node<Blocking, Quic, Active, T> {
 
     #[tracing::instrument(skip(self))]
     pub fn publish_msg(&self, msg: Msg<T>) -> Result<(), Error> {
-        let packet = msg.to_generic()?.as_bytes()?;
+        let packet = self.cfg.codec().encode(msg)?;
 
         let handle = match &self.rt_handle {
             Some(handle) => handle,
@@ -179,12 +234,16 @@ impl<T: Message + 'static> Node<Blocking, Quic, Active, T> {
         if let Some(connection) = &self.connection {
             handle.block_on(async {
                 match connection.open_bi().await {
-                    Ok((mut send, _recv)) => {
+                    Ok((mut send, mut recv)) => {
                         debug!("Node succesfully opened stream from connection");
 
                         if let Ok(()) = send.write_all(&packet).await {
                             if let Ok(()) = send.finish().await {
                                 debug!("Node successfully wrote packet to stream");
+                                let mut buf = self.buffer.lock().await;
+                                if let Ok(Some(n)) = recv.read(&mut buf).await {
+                                    check_publish_ack(&buf[..n])?;
+                                }
                             }
                         } else {
                             error!("Error writing packet to stream");
@@ -225,7 +284,7 @@ impl<T: Message + 'static> Node<Blocking, Quic, Active, T> {
                         Some(n) => {
                             let bytes = &buf[..n];
                             let generic = from_bytes::<GenericMsg>(bytes)?;
-                            let msg = generic.try_into()?;
+                            let msg = self.cfg.codec().decode(generic)?;
 
                             return Ok(msg);
                         }
@@ -261,7 +320,7 @@ impl<T: Message + 'static> Node<Blocking, Quic, Active, T> {
                         Some(n) => {
                             let bytes = &buf[..n];
                             let generic = from_bytes::<GenericMsg>(bytes)?;
-                            let msg = generic.try_into()?;
+                            let msg = self.cfg.codec().decode(generic)?;
 
                             return Ok(msg);
                         }
@@ -299,4 +358,52 @@ impl<T: Message + 'static> Node<Blocking, Quic, Active, T> {
             Ok(topics)
         })
     }
+
+    /// Request statistics on the Node's assigned topic from the Host
+    pub fn stats(&self) -> Result<Msg<TopicStats>, Error> {
+        let packet = GenericMsg::stats(&self.topic).as_bytes()?;
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+
+        handle.block_on(async {
+            let mut buf = self.buffer.lock().await;
+
+            let connection = self.connection.clone().ok_or(Connection)?;
+
+            let (mut send, mut recv) = connection.open_bi().await?;
+            debug!("Node succesfully opened stream from connection");
+            send.write_all(&packet).await?;
+            send.finish().await?;
+
+            let n = recv.read(&mut buf).await?.ok_or(Connection)?;
+            let bytes = &buf[..n];
+            let reply = from_bytes::<GenericMsg>(bytes)?;
+            let stats: Msg<TopicStats> = reply.try_into()?;
+            Ok(stats)
+        })
+    }
+
+    /// Estimate the offset of the Node's clock from the Host's
+    ///
+    /// If the Node was configured `with_clock_correction`, the timestamps it publishes are
+    /// corrected by the offset from here on.
+    pub fn sync_clock(&self) -> Result<ClockSync, Error> {
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+        handle.block_on(self.cfg.clock.sync(|request| async move {
+            let mut buf = self.buffer.lock().await;
+            let connection = self.connection.clone().ok_or(Connection)?;
+            let (mut send, mut recv) = connection.open_bi().await?;
+            send.write_all(&request.as_bytes()?).await?;
+            send.finish().await?;
+
+            let n = recv.read(&mut buf).await?.ok_or(Connection)?;
+            from_bytes::<GenericMsg>(&buf[..n])?.try_into()
+        }))
+    }
 }
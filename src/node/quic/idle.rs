@@ -13,7 +13,6 @@ use tokio::time::{sleep, Duration};
 
 use tracing::*;
 
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::result::Result;
 use std::sync::Arc;
 
@@ -26,7 +25,7 @@ use std::marker::PhantomData;
 use quinn::Endpoint;
 
 use crate::msg::*;
-use crate::node::quic::generate_client_config_from_certs;
+use crate::node::quic::{authenticate, client_addr, generate_client_config};
 use chrono::Utc;
 
 impl<T: Message> From<Node<Nonblocking, Quic, Idle, T>> for Node<Nonblocking, Quic, Active, T> {
@@ -43,6 +42,14 @@ impl<T: Message> From<Node<Nonblocking, Quic, Idle, T>> for Node<Nonblocking, Qu
             buffer: node.buffer,
             endpoint: node.endpoint,
             connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
             subscription_data: node.subscription_data,
             task_subscribe: None,
         }
@@ -65,6 +72,14 @@ impl<T: Message> From<Node<Nonblocking, Quic, Idle, T>>
             buffer: node.buffer,
             endpoint: node.endpoint,
             connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
             subscription_data: node.subscription_data,
             task_subscribe: node.task_subscribe,
         }
@@ -83,19 +98,24 @@ impl<T: Message + 'static> Node<Nonblocking, Quic, Idle, T> {
     }
 
     async fn create_connection(&mut self) -> Result<(), Error> {
-        let host_addr = self.cfg.network_cfg.host_addr;
+        let host_addr = self.cfg.network_cfg.discover_host().await?;
         let cert_path = self.cfg.network_cfg.cert_path.clone();
+        let key_path = self.cfg.network_cfg.key_path.clone();
+        let server_name = self.cfg.network_cfg.server_name.clone();
+        let credentials = self.cfg.credentials.clone();
+        let topic = self.topic.clone();
 
         let (endpoint, connection) = {
             // QUIC, needs to be done inside of a tokio context
-            let client_cfg = generate_client_config_from_certs(cert_path)?;
-            let client_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 0);
+            let client_cfg = generate_client_config(cert_path, key_path)?;
 
-            let mut endpoint = Endpoint::client(client_addr)?;
+            let mut endpoint = Endpoint::client(client_addr(host_addr))?;
             endpoint.set_default_client_config(client_cfg);
 
-            // TO_DO: This shouldn't just be "localhost"
-            let connection = endpoint.connect(host_addr, "localhost")?.await?;
+            let connection = endpoint.connect(host_addr, &server_name)?.await?;
+            if let Some(credentials) = &credentials {
+                authenticate(&connection, &topic, credentials).await?;
+            }
 
             debug!("{:?}", &endpoint.local_addr());
 
@@ -122,6 +142,7 @@ impl<T: Message + 'static> Node<Nonblocking, Quic, Idle, T> {
 
         let packet = GenericMsg::subscribe(topic, rate)?;
 
+        let codec = self.cfg.codec();
         let task_subscribe = tokio::spawn(async move {
             if let Some(connection) = connection {
                 loop {
@@ -130,10 +151,14 @@ impl<T: Message + 'static> Node<Nonblocking, Quic, Idle, T> {
                         buffer.clone(),
                         connection.clone(),
                         data.clone(),
+                        &codec,
                     )
                     .await
                     {
                         error!("{:?}", e);
+                        if e.is_access_denied() {
+                            break;
+                        }
                     }
                 }
             }
@@ -154,6 +179,7 @@ async fn run_subscription<T: Message>(
     buffer: Arc<TokioMutex<Vec<u8>>>,
     connection: quinn::Connection,
     data: Arc<TokioMutex<Option<Msg<T>>>>,
+    codec: &PayloadCodec,
 ) -> Result<(), Error> {
     let (mut send, mut recv) = connection.open_bi().await?;
 
@@ -168,16 +194,11 @@ async fn run_subscription<T: Message>(
 
             let generic = from_bytes::<GenericMsg>(bytes)?;
             info!("QUIC received generic: {:?}", &generic);
-            let msg: Msg<T> = generic.try_into()?;
+            let msg: Msg<T> = codec.decode(generic)?;
 
             if let Some(data) = data.lock().await.as_ref() {
                 debug!("Timestamp: {}", data.timestamp);
-                let delta = msg.timestamp - data.timestamp;
-                info!(
-                    "The time difference between QUIC subscription msg tx/rx is: {} us",
-                    delta
-                );
-                if delta <= chrono::Duration::zero() {
+                if !msg.is_newer_than(data) {
                     warn!("Data is not newer, skipping to next subscription iteration");
                     continue;
                 }
@@ -207,6 +228,14 @@ impl<T: Message> From<Node<Blocking, Quic, Idle, T>> for Node<Blocking, Quic, Ac
             buffer: node.buffer,
             endpoint: node.endpoint,
             connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
             subscription_data: node.subscription_data,
             task_subscribe: None,
         }
@@ -227,6 +256,14 @@ impl<T: Message> From<Node<Blocking, Quic, Idle, T>> for Node<Blocking, Quic, Su
             buffer: node.buffer,
             endpoint: node.endpoint,
             connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
             subscription_data: node.subscription_data,
             task_subscribe: node.task_subscribe,
         }
@@ -245,24 +282,29 @@ impl<T: Message + 'static> Node<Blocking, Quic, Idle, T> {
     }
 
     fn create_connection(&mut self) -> Result<(), Error> {
-        let host_addr = self.cfg.network_cfg.host_addr;
         let cert_path = self.cfg.network_cfg.cert_path.clone();
+        let key_path = self.cfg.network_cfg.key_path.clone();
+        let server_name = self.cfg.network_cfg.server_name.clone();
+        let credentials = self.cfg.credentials.clone();
+        let topic = self.topic.clone();
 
         let handle = match &self.rt_handle {
             Some(handle) => handle,
             None => return Err(Error::HandleAccess),
         };
+        let host_addr = handle.block_on(self.cfg.network_cfg.discover_host())?;
 
         let (endpoint, connection) = handle.block_on(async move {
             // QUIC, needs to be done inside of a tokio context
-            let client_cfg = generate_client_config_from_certs(cert_path)?;
-            let client_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 0);
+            let client_cfg = generate_client_config(cert_path, key_path)?;
 
-            let mut endpoint = Endpoint::client(client_addr)?;
+            let mut endpoint = Endpoint::client(client_addr(host_addr))?;
             endpoint.set_default_client_config(client_cfg);
 
-            // TO_DO: This shouldn't just be "localhost"
-            let connection = endpoint.connect(host_addr, "localhost")?.await?;
+            let connection = endpoint.connect(host_addr, &server_name)?.await?;
+            if let Some(credentials) = &credentials {
+                authenticate(&connection, &topic, credentials).await?;
+            }
 
             debug!("{:?}", &endpoint.local_addr());
 
@@ -293,6 +335,7 @@ impl<T: Message + 'static> Node<Blocking, Quic, Idle, T> {
             Some(handle) => handle,
             None => return Err(Error::HandleAccess),
         };
+        let codec = self.cfg.codec();
         let task_subscribe = handle.spawn(async move {
             if let Some(connection) = connection {
                 loop {
@@ -301,10 +344,14 @@ impl<T: Message + 'static> Node<Blocking, Quic, Idle, T> {
                         buffer.clone(),
                         connection.clone(),
                         data.clone(),
+                        &codec,
                     )
                     .await
                     {
                         error!("{:?}", e);
+                        if e.is_access_denied() {
+                            break;
+                        }
                     }
                 }
             }
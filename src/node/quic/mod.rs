@@ -4,20 +4,61 @@ mod subscription;
 
 use std::fs::File;
 use std::io::BufReader;
-use std::net::SocketAddr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use crate::error::Quic::*;
 use crate::prelude::*;
 
+use crate::host::Credentials;
 use quinn::ClientConfig;
 use rustls::Certificate;
 
 use tracing::*;
 
+/// Unspecified address in the Host's address family, which the Node's QUIC endpoint binds to
+pub(crate) fn client_addr(host_addr: SocketAddr) -> SocketAddr {
+    match host_addr {
+        SocketAddr::V4(_) => SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+        SocketAddr::V6(_) => SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0),
+    }
+}
+
 pub fn generate_client_config_from_certs(
     cert_path: Option<PathBuf>,
 ) -> Result<ClientConfig, Error> {
+    let certs = read_root_certs(cert_path)?;
+    Ok(ClientConfig::with_root_certificates(certs))
+}
+
+/// Create a client configuration which trusts the certificate chain at `cert_path` and, if a
+/// `key_path` is provided, presents that chain to the Host for mutual TLS authentication
+pub fn generate_client_config(
+    cert_path: Option<PathBuf>,
+    key_path: Option<PathBuf>,
+) -> Result<ClientConfig, Error> {
+    let key_path = match key_path {
+        Some(key_path) => key_path,
+        None => return generate_client_config_from_certs(cert_path),
+    };
+    let roots = read_root_certs(cert_path.clone())?;
+    let cert_path = cert_path.ok_or(Error::Quic(NoProvidedCertPath))?;
+    let (certs, key) = crate::host::quic::read_certs_from_file(cert_path, key_path)?;
+
+    // QUIC requires TLS 1.3
+    let mut crypto = rustls::ClientConfig::builder()
+        .with_safe_default_cipher_suites()
+        .with_safe_default_kx_groups()
+        .with_protocol_versions(&[&rustls::version::TLS13])?
+        .with_root_certificates(roots)
+        .with_single_cert(certs, key)?;
+    crypto.enable_early_data = true;
+    Ok(ClientConfig::new(Arc::new(crypto)))
+}
+
+/// Read the Host's certificate chain into a store of trusted root certificates
+pub fn read_root_certs(cert_path: Option<PathBuf>) -> Result<rustls::RootCertStore, Error> {
     let mut certs = rustls::RootCertStore::empty();
 
     let path = match cert_path {
@@ -39,5 +80,25 @@ pub fn generate_client_config_from_certs(
         }
     }
 
-    Ok(ClientConfig::with_root_certificates(certs))
+    Ok(certs)
+}
+
+/// Present the Node's credentials to the Host on the first stream of a new connection
+pub async fn authenticate(
+    connection: &quinn::Connection,
+    topic: &str,
+    credentials: &Credentials,
+) -> Result<(), Error> {
+    let (mut send, mut recv) = connection.open_bi().await?;
+    send.write_all(&GenericMsg::auth(topic, Some(credentials))?.as_bytes()?)
+        .await?;
+    send.finish().await?;
+
+    let mut buf = vec![0u8; 1024];
+    let n = recv.read(&mut buf).await?.ok_or(Error::Quic(Connection))?;
+    let reply = postcard::from_bytes::<GenericMsg>(&buf[..n])?;
+    match reply.msg_type {
+        MsgType::Error(e) => Err(e),
+        _ => Ok(()),
+    }
 }
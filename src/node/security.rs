@@ -0,0 +1,188 @@
+use crate::msg::GenericMsg;
+use crate::Error;
+
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use postcard::{from_bytes, to_allocvec};
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
+
+/// Keys used to encrypt and sign the payloads a Node publishes, and to open and verify the
+/// payloads it receives
+///
+/// Payloads are sealed before they leave the Node, so the Host stores them sealed and neither
+/// the Host nor the network can read or forge them. Every Node on a topic must share the same
+/// encryption key, and Nodes with trusted publishers reject any payload that isn't signed by one
+/// of them.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SecurityConfig {
+    key: Option<[u8; 32]>,
+    signing_key: Option<[u8; 32]>,
+    trusted_publishers: Vec<[u8; 32]>,
+}
+
+impl fmt::Debug for SecurityConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecurityConfig")
+            .field("encrypted", &self.key.is_some())
+            .field("public_key", &self.public_key())
+            .field("trusted_publishers", &self.trusted_publishers)
+            .finish()
+    }
+}
+
+/// Sealed form of a `GenericMsg` data payload
+#[derive(Serialize, Deserialize)]
+struct SealedPayload {
+    /// Nonce used to encrypt the payload, if it is encrypted
+    nonce: Option<[u8; 12]>,
+    payload: Vec<u8>,
+    /// Publisher's public key and signature
+    signature: Option<([u8; 32], Vec<u8>)>,
+}
+
+impl SecurityConfig {
+    /// Create a configuration that neither encrypts nor signs payloads
+    pub fn new() -> Self {
+        SecurityConfig::default()
+    }
+
+    /// Generate a random 32-byte key, for use as either an encryption or signing key
+    pub fn generate_key() -> [u8; 32] {
+        let mut key = [0u8; 32];
+        OsRng.fill_bytes(&mut key);
+        key
+    }
+
+    /// Encrypt published payloads with a key shared by every Node on the topic or fleet
+    pub fn with_key(mut self, key: [u8; 32]) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// Sign published payloads with the Node's Ed25519 secret key
+    pub fn with_signing_key(mut self, signing_key: [u8; 32]) -> Self {
+        self.signing_key = Some(signing_key);
+        self
+    }
+
+    /// Accept payloads signed by the publisher with this Ed25519 public key, requiring that every
+    /// received payload is signed by a trusted publisher
+    pub fn with_trusted_publisher(mut self, public_key: [u8; 32]) -> Self {
+        self.trusted_publishers.push(public_key);
+        self
+    }
+
+    /// Public key matching the Node's signing key, which subscribers add as a trusted publisher
+    pub fn public_key(&self) -> Option<[u8; 32]> {
+        self.signing_key
+            .map(|key| SigningKey::from_bytes(&key).verifying_key().to_bytes())
+    }
+
+    /// Encrypt and/or sign a message's data payload in place
+    pub(crate) fn seal(&self, msg: &mut GenericMsg) -> Result<(), Error> {
+        let (nonce, payload) = match &self.key {
+            Some(key) => {
+                let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+                let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+                let aad = associated_data(msg)?;
+                let ciphertext = cipher
+                    .encrypt(
+                        &nonce,
+                        Payload {
+                            msg: &msg.data,
+                            aad: &aad,
+                        },
+                    )
+                    .map_err(|_e| Error::Crypto)?;
+                (Some(nonce.into()), ciphertext)
+            }
+            None => (None, msg.data.clone()),
+        };
+
+        let signature = match &self.signing_key {
+            Some(key) => {
+                let signing_key = SigningKey::from_bytes(key);
+                let signature = signing_key.sign(&signed_data(msg, &nonce, &payload)?);
+                Some((
+                    signing_key.verifying_key().to_bytes(),
+                    signature.to_bytes().to_vec(),
+                ))
+            }
+            None => None,
+        };
+
+        msg.data = to_allocvec(&SealedPayload {
+            nonce,
+            payload,
+            signature,
+        })?;
+        Ok(())
+    }
+
+    /// Verify and/or decrypt a message's sealed data payload in place
+    pub(crate) fn open(&self, msg: &mut GenericMsg) -> Result<(), Error> {
+        let sealed: SealedPayload = from_bytes(&msg.data).map_err(|_e| Error::Crypto)?;
+
+        if !self.trusted_publishers.is_empty() {
+            let (public_key, signature) =
+                sealed.signature.as_ref().ok_or(Error::UntrustedPublisher)?;
+            if !self.trusted_publishers.contains(public_key) {
+                return Err(Error::UntrustedPublisher);
+            }
+            let verifying_key =
+                VerifyingKey::from_bytes(public_key).map_err(|_e| Error::UntrustedPublisher)?;
+            let signature =
+                Signature::from_slice(signature).map_err(|_e| Error::UntrustedPublisher)?;
+            verifying_key
+                .verify(
+                    &signed_data(msg, &sealed.nonce, &sealed.payload)?,
+                    &signature,
+                )
+                .map_err(|_e| Error::UntrustedPublisher)?;
+        }
+
+        msg.data = match (&self.key, sealed.nonce) {
+            (Some(key), Some(nonce)) => {
+                let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+                let aad = associated_data(msg)?;
+                cipher
+                    .decrypt(
+                        Nonce::from_slice(&nonce),
+                        Payload {
+                            msg: &sealed.payload,
+                            aad: &aad,
+                        },
+                    )
+                    .map_err(|_e| Error::Crypto)?
+            }
+            (None, None) => sealed.payload,
+            // Encrypted payloads require a key, and Nodes with a key only accept encrypted payloads
+            _ => return Err(Error::Crypto),
+        };
+        Ok(())
+    }
+}
+
+/// Message metadata authenticated alongside the payload, so a sealed payload can't be replayed
+/// onto another topic or type
+fn associated_data(msg: &GenericMsg) -> Result<Vec<u8>, Error> {
+    Ok(to_allocvec(&(&msg.topic, &msg.data_type, &msg.timestamp))?)
+}
+
+fn signed_data(
+    msg: &GenericMsg,
+    nonce: &Option<[u8; 12]>,
+    payload: &[u8],
+) -> Result<Vec<u8>, Error> {
+    Ok(to_allocvec(&(
+        &msg.topic,
+        &msg.data_type,
+        &msg.timestamp,
+        nonce,
+        payload,
+    ))?)
+}
@@ -0,0 +1,235 @@
+use crate::node::network_config::{Blocking, Nonblocking, Serial};
+use crate::node::serial::*;
+use crate::node::{Active, Node};
+use crate::prelude::*;
+use crate::serial::SerialPort;
+
+use std::result::Result;
+use tracing::*;
+
+use tokio::sync::MutexGuard;
+
+impl<T: Message + 'static, B: crate::node::network_config::Block> Node<B, Serial, Active, T> {
+    /// Lock the Node's serial port for a single request/response exchange
+    async fn lock_port(&self) -> Result<MutexGuard<'_, SerialPort>, Error> {
+        match self.serial_port.as_ref() {
+            Some(port) => Ok(port.lock().await),
+            None => Err(Error::AccessStream),
+        }
+    }
+}
+
+impl<T: Message + 'static> Node<Nonblocking, Serial, Active, T> {
+    /// Send data to host on Node's assigned topic using `Msg<T>` packet
+    #[tracing::instrument]
+    #[inline]
+    pub async fn publish(&self, val: T) -> Result<(), Error> {
+        let packet = self
+            .cfg
+            .codec()
+            .encode(Msg::new(MsgType::Set, self.topic.clone(), val))?;
+
+        let mut port = self.lock_port().await?;
+        send_msg(&mut port, packet).await?;
+        await_publish_ack(&mut port).await
+    }
+
+    #[tracing::instrument]
+    #[inline]
+    pub async fn publish_msg(&self, msg: Msg<T>) -> Result<(), Error> {
+        let packet = self.cfg.codec().encode(msg)?;
+
+        let mut port = self.lock_port().await?;
+        send_msg(&mut port, packet).await?;
+        await_publish_ack(&mut port).await
+    }
+
+    /// Request data from host on Node's assigned topic
+    #[tracing::instrument]
+    #[inline]
+    pub async fn request(&self) -> Result<Msg<T>, Error> {
+        let packet = GenericMsg::get::<T>(self.topic.clone()).as_bytes()?;
+
+        let mut port = self.lock_port().await?;
+        send_msg(&mut port, packet).await?;
+        await_response::<T>(&mut port, &self.cfg.codec()).await
+    }
+
+    /// Request data from host on Node's assigned topic
+    #[tracing::instrument]
+    #[inline]
+    pub async fn request_nth_back(&self, n: usize) -> Result<Msg<T>, Error> {
+        let packet = GenericMsg::get_nth::<T>(&self.topic, n).as_bytes()?;
+
+        let mut port = self.lock_port().await?;
+        send_msg(&mut port, packet).await?;
+        await_response::<T>(&mut port, &self.cfg.codec()).await
+    }
+
+    #[tracing::instrument]
+    #[inline]
+    pub async fn topics(&self) -> Result<Msg<Vec<String>>, Error> {
+        let packet = GenericMsg::topics().as_bytes()?;
+
+        let mut port = self.lock_port().await?;
+        send_msg(&mut port, packet).await?;
+        await_response::<Vec<String>>(&mut port, &self.cfg.codec()).await
+    }
+
+    /// Request statistics on the Node's assigned topic from the Host
+    #[tracing::instrument]
+    #[inline]
+    pub async fn stats(&self) -> Result<Msg<TopicStats>, Error> {
+        let packet = GenericMsg::stats(&self.topic).as_bytes()?;
+
+        let mut port = self.lock_port().await?;
+        send_msg(&mut port, packet).await?;
+        await_response::<TopicStats>(&mut port, &self.cfg.codec()).await
+    }
+
+    /// Estimate the offset of the Node's clock from the Host's
+    ///
+    /// If the Node was configured `with_clock_correction`, the timestamps it publishes are
+    /// corrected by the offset from here on.
+    #[tracing::instrument]
+    #[inline]
+    pub async fn sync_clock(&self) -> Result<ClockSync, Error> {
+        self.cfg
+            .clock
+            .sync(|request| async move {
+                let mut port = self.lock_port().await?;
+                send_msg(&mut port, request.as_bytes()?).await?;
+                await_response(&mut port, &self.cfg.codec()).await
+            })
+            .await
+    }
+}
+
+impl<T: Message + 'static> Node<Blocking, Serial, Active, T> {
+    /// Send data to host on Node's assigned topic using `Msg<T>` packet
+    #[tracing::instrument]
+    #[inline]
+    pub fn publish(&self, val: T) -> Result<(), Error> {
+        let packet = self
+            .cfg
+            .codec()
+            .encode(Msg::new(MsgType::Set, self.topic.clone(), val))?;
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+
+        handle.block_on(async {
+            let mut port = self.lock_port().await?;
+            send_msg(&mut port, packet).await?;
+            await_publish_ack(&mut port).await
+        })
+    }
+
+    #[tracing::instrument]
+    #[inline]
+    pub fn publish_msg(&self, msg: Msg<T>) -> Result<(), Error> {
+        let packet = self.cfg.codec().encode(msg)?;
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+
+        handle.block_on(async {
+            let mut port = self.lock_port().await?;
+            send_msg(&mut port, packet).await?;
+            await_publish_ack(&mut port).await
+        })
+    }
+
+    /// Request data from host on Node's assigned topic
+    #[tracing::instrument]
+    #[inline]
+    pub fn request(&self) -> Result<Msg<T>, Error> {
+        let packet = GenericMsg::get::<T>(&self.topic).as_bytes()?;
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+
+        handle.block_on(async {
+            let mut port = self.lock_port().await?;
+            send_msg(&mut port, packet).await?;
+            await_response::<T>(&mut port, &self.cfg.codec()).await
+        })
+    }
+
+    /// Request data from host on Node's assigned topic
+    #[tracing::instrument]
+    #[inline]
+    pub fn request_nth_back(&self, n: usize) -> Result<Msg<T>, Error> {
+        let packet = GenericMsg::get_nth::<T>(&self.topic, n).as_bytes()?;
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+
+        handle.block_on(async {
+            let mut port = self.lock_port().await?;
+            send_msg(&mut port, packet).await?;
+            await_response::<T>(&mut port, &self.cfg.codec()).await
+        })
+    }
+
+    #[tracing::instrument]
+    #[inline]
+    pub fn topics(&self) -> Result<Msg<Vec<String>>, Error> {
+        let packet = GenericMsg::topics().as_bytes()?;
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+
+        handle.block_on(async {
+            let mut port = self.lock_port().await?;
+            send_msg(&mut port, packet).await?;
+            await_response::<Vec<String>>(&mut port, &self.cfg.codec()).await
+        })
+    }
+
+    /// Request statistics on the Node's assigned topic from the Host
+    #[tracing::instrument]
+    #[inline]
+    pub fn stats(&self) -> Result<Msg<TopicStats>, Error> {
+        let packet = GenericMsg::stats(&self.topic).as_bytes()?;
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+
+        handle.block_on(async {
+            let mut port = self.lock_port().await?;
+            send_msg(&mut port, packet).await?;
+            await_response::<TopicStats>(&mut port, &self.cfg.codec()).await
+        })
+    }
+
+    /// Estimate the offset of the Node's clock from the Host's
+    ///
+    /// If the Node was configured `with_clock_correction`, the timestamps it publishes are
+    /// corrected by the offset from here on.
+    #[tracing::instrument]
+    #[inline]
+    pub fn sync_clock(&self) -> Result<ClockSync, Error> {
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+        handle.block_on(self.cfg.clock.sync(|request| async move {
+            let mut port = self.lock_port().await?;
+            send_msg(&mut port, request.as_bytes()?).await?;
+            await_response(&mut port, &self.cfg.codec()).await
+        }))
+    }
+}
@@ -0,0 +1,287 @@
+extern crate alloc;
+use crate::prelude::*;
+
+use crate::node::network_config::{Blocking, Nonblocking, Serial};
+use crate::node::serial::{await_response, handshake, send_msg};
+use crate::node::*;
+use crate::serial::{SerialConfig, SerialPort};
+
+use tokio::sync::Mutex as TokioMutex;
+use tokio::time::Duration;
+
+use tracing::*;
+
+use std::marker::PhantomData;
+use std::result::Result;
+use std::sync::Arc;
+
+impl<T: Message> From<Node<Nonblocking, Serial, Idle, T>> for Node<Nonblocking, Serial, Active, T> {
+    fn from(node: Node<Nonblocking, Serial, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            #[cfg(feature = "quic")]
+            endpoint: node.endpoint,
+            #[cfg(feature = "quic")]
+            connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message> From<Node<Nonblocking, Serial, Idle, T>>
+    for Node<Nonblocking, Serial, Subscription, T>
+{
+    fn from(node: Node<Nonblocking, Serial, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            #[cfg(feature = "quic")]
+            endpoint: node.endpoint,
+            #[cfg(feature = "quic")]
+            connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message, B: Block> Node<B, Serial, Idle, T> {
+    /// Configuration of the serial device linked to the Host
+    fn serial_config(&self) -> Result<SerialConfig, Error> {
+        match &self.cfg.network_cfg.interface_cfg {
+            Some(serial_cfg) => Ok(serial_cfg.clone()),
+            None => Err(Error::StreamConnection),
+        }
+    }
+}
+
+impl<T: Message + 'static> Node<Nonblocking, Serial, Idle, T> {
+    /// Open the Node's serial device and connect to the Host at the other end of the link
+    #[tracing::instrument(skip_all)]
+    pub async fn activate(mut self) -> Result<Node<Nonblocking, Serial, Active, T>, Error> {
+        let serial_cfg = self.serial_config()?;
+        let topic = self.topic.clone();
+
+        let mut port = SerialPort::open(&serial_cfg)?;
+        handshake(&mut port, topic, self.cfg.credentials.as_ref()).await?;
+        debug!("Established Node<=>Host serial link: {:?}", serial_cfg.path);
+        self.serial_port = Some(TokioMutex::new(port));
+
+        Ok(Node::<Nonblocking, Serial, Active, T>::from(self))
+    }
+
+    #[tracing::instrument]
+    pub async fn subscribe(
+        mut self,
+        rate: Duration,
+    ) -> Result<Node<Nonblocking, Serial, Subscription, T>, Error> {
+        let serial_cfg = self.serial_config()?;
+        let topic = self.topic.clone();
+
+        let subscription_data: Arc<TokioMutex<Option<Msg<T>>>> = Arc::new(TokioMutex::new(None));
+        let data = Arc::clone(&subscription_data);
+
+        let packet = GenericMsg::subscribe(&topic, rate)?;
+
+        let mut port = SerialPort::open(&serial_cfg)?;
+        handshake(&mut port, topic, self.cfg.credentials.as_ref()).await?;
+
+        let codec = self.cfg.codec();
+        let task_subscribe = tokio::spawn(async move {
+            if let Err(e) = run_subscription::<T>(packet, &mut port, data, &codec).await {
+                error!("{:?}", e);
+            }
+        });
+        self.task_subscribe = Some(task_subscribe);
+
+        let mut subscription_node = Node::<Nonblocking, Serial, Subscription, T>::from(self);
+        subscription_node.subscription_data = subscription_data;
+
+        Ok(subscription_node)
+    }
+}
+
+/// Request a subscription from the Host and store each newer value it pushes over the link
+async fn run_subscription<T: Message>(
+    packet: GenericMsg,
+    port: &mut SerialPort,
+    data: Arc<TokioMutex<Option<Msg<T>>>>,
+    codec: &PayloadCodec,
+) -> Result<(), Error> {
+    send_msg(port, packet.as_bytes()?).await?;
+
+    loop {
+        match await_response::<T>(port, codec).await {
+            Ok(msg) => {
+                let mut data = data.lock().await;
+                if let Some(existing) = data.as_ref() {
+                    if !msg.is_newer_than(existing) {
+                        continue;
+                    }
+                }
+                *data = Some(msg);
+            }
+            Err(e) if e.is_access_denied() || e == Error::StreamConnection => return Err(e),
+            // The serial link is only read from, so I/O errors won't clear up by retrying
+            Err(e @ Error::Io { .. }) => return Err(e),
+            Err(e) => {
+                error!("Subscription Error: {:?}", e);
+                continue;
+            }
+        };
+    }
+}
+
+//------
+
+impl<T: Message> From<Node<Blocking, Serial, Idle, T>> for Node<Blocking, Serial, Active, T> {
+    fn from(node: Node<Blocking, Serial, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            #[cfg(feature = "quic")]
+            endpoint: node.endpoint,
+            #[cfg(feature = "quic")]
+            connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message> From<Node<Blocking, Serial, Idle, T>> for Node<Blocking, Serial, Subscription, T> {
+    fn from(node: Node<Blocking, Serial, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            #[cfg(feature = "quic")]
+            endpoint: node.endpoint,
+            #[cfg(feature = "quic")]
+            connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message + 'static> Node<Blocking, Serial, Idle, T> {
+    /// Open the Node's serial device and connect to the Host at the other end of the link
+    #[tracing::instrument(skip_all)]
+    pub fn activate(mut self) -> Result<Node<Blocking, Serial, Active, T>, Error> {
+        let serial_cfg = self.serial_config()?;
+        let topic = self.topic.clone();
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+
+        let credentials = self.cfg.credentials.clone();
+        let port: SerialPort = handle.block_on(async {
+            let mut port = SerialPort::open(&serial_cfg)?;
+            handshake(&mut port, topic, credentials.as_ref()).await?;
+            Ok::<SerialPort, Error>(port)
+        })?;
+        debug!("Established Node<=>Host serial link: {:?}", serial_cfg.path);
+        self.serial_port = Some(TokioMutex::new(port));
+
+        Ok(Node::<Blocking, Serial, Active, T>::from(self))
+    }
+
+    #[tracing::instrument]
+    pub fn subscribe(
+        mut self,
+        rate: Duration,
+    ) -> Result<Node<Blocking, Serial, Subscription, T>, Error> {
+        let serial_cfg = self.serial_config()?;
+        let topic = self.topic.clone();
+
+        let subscription_data: Arc<TokioMutex<Option<Msg<T>>>> = Arc::new(TokioMutex::new(None));
+        let data = Arc::clone(&subscription_data);
+
+        let packet = GenericMsg::subscribe(&topic, rate)?;
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+
+        let credentials = self.cfg.credentials.clone();
+        let mut port: SerialPort = handle.block_on(async {
+            let mut port = SerialPort::open(&serial_cfg)?;
+            handshake(&mut port, topic, credentials.as_ref()).await?;
+            Ok::<SerialPort, Error>(port)
+        })?;
+
+        let codec = self.cfg.codec();
+        let task_subscribe = handle.spawn(async move {
+            if let Err(e) = run_subscription::<T>(packet, &mut port, data, &codec).await {
+                error!("{:?}", e);
+            }
+        });
+        self.task_subscribe = Some(task_subscribe);
+
+        let mut subscription_node = Node::<Blocking, Serial, Subscription, T>::from(self);
+        subscription_node.subscription_data = subscription_data;
+
+        Ok(subscription_node)
+    }
+}
@@ -0,0 +1,76 @@
+mod active;
+mod idle;
+mod subscription;
+
+extern crate alloc;
+
+use tracing::*;
+
+use std::result::Result;
+
+use alloc::vec::Vec;
+use postcard::from_bytes;
+
+use crate::host::Credentials;
+use crate::msg::{GenericMsg, Message, Msg, MsgType};
+use crate::node::PayloadCodec;
+use crate::serial::SerialPort;
+use crate::Error;
+
+/// Run the initial Node <=> Host connection handshake, presenting any credentials to the Host
+///
+/// Frames still in flight from an earlier Node on the same link are skipped.
+pub async fn handshake(
+    port: &mut SerialPort,
+    topic: String,
+    credentials: Option<&Credentials>,
+) -> Result<(), Error> {
+    let packet = GenericMsg::auth(&topic, credentials)?.as_bytes()?;
+    send_msg(port, packet).await?;
+    debug!("{}: Wrote serial handshake to host", topic);
+
+    loop {
+        let reply = await_generic(port).await?;
+        match reply.msg_type {
+            MsgType::Auth => break,
+            MsgType::Error(e) => {
+                error!("{}: Host rejected handshake: {}", topic, e);
+                return Err(e);
+            }
+            _ => continue,
+        }
+    }
+    debug!("{}: Successfully connected to host", topic);
+
+    Ok(())
+}
+
+/// Send a `GenericMsg` of `MsgType` from the Node to the Host
+#[inline]
+pub async fn send_msg(port: &mut SerialPort, packet: Vec<u8>) -> Result<(), Error> {
+    port.write_frame(&packet).await
+}
+
+/// Set Node to wait for response from Host, with data to be deserialized into `Msg<T>`-type
+#[inline]
+pub async fn await_response<T: Message>(
+    port: &mut SerialPort,
+    codec: &PayloadCodec,
+) -> Result<Msg<T>, Error> {
+    let generic = await_generic(port).await?;
+    codec.decode(generic)
+}
+
+/// Set Node to wait for a `GenericMsg` response from Host
+#[inline]
+pub async fn await_generic(port: &mut SerialPort) -> Result<GenericMsg, Error> {
+    let bytes = port.read_frame().await?;
+    Ok(from_bytes::<GenericMsg>(&bytes)?)
+}
+
+/// Set Node to wait for the Host's acknowledgement of a published message
+#[inline]
+pub async fn await_publish_ack(port: &mut SerialPort) -> Result<(), Error> {
+    let bytes = port.read_frame().await?;
+    crate::node::check_publish_ack(&bytes)
+}
@@ -0,0 +1,295 @@
+extern crate alloc;
+use crate::prelude::*;
+
+use crate::node::network_config::{Blocking, Nonblocking, Stream};
+use crate::node::stream::{await_response, handshake, send_msg};
+use crate::node::*;
+use crate::stream::{FramedStream, StreamConnector};
+
+use tokio::sync::Mutex as TokioMutex;
+use tokio::time::Duration;
+
+use tracing::*;
+
+use std::marker::PhantomData;
+use std::result::Result;
+use std::sync::Arc;
+
+impl<T: Message> From<Node<Nonblocking, Stream, Idle, T>> for Node<Nonblocking, Stream, Active, T> {
+    fn from(node: Node<Nonblocking, Stream, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            #[cfg(feature = "quic")]
+            endpoint: node.endpoint,
+            #[cfg(feature = "quic")]
+            connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message> From<Node<Nonblocking, Stream, Idle, T>>
+    for Node<Nonblocking, Stream, Subscription, T>
+{
+    fn from(node: Node<Nonblocking, Stream, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            #[cfg(feature = "quic")]
+            endpoint: node.endpoint,
+            #[cfg(feature = "quic")]
+            connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message, B: Block> Node<B, Stream, Idle, T> {
+    /// Connector that opens the Node's streams to the Host
+    fn connector(&self) -> Result<StreamConnector, Error> {
+        match &self.cfg.network_cfg.interface_cfg {
+            Some(connector) => Ok(connector.clone()),
+            None => Err(Error::StreamConnection),
+        }
+    }
+}
+
+impl<T: Message + 'static> Node<Nonblocking, Stream, Idle, T> {
+    /// Attempt a connection from the Node to the Host over a user-supplied stream
+    #[tracing::instrument(skip_all)]
+    pub async fn activate(mut self) -> Result<Node<Nonblocking, Stream, Active, T>, Error> {
+        let connector = self.connector()?;
+        let max_frame_size = self.cfg.network_cfg.max_buffer_size;
+        let topic = self.topic.clone();
+
+        let mut stream = connector.connect(max_frame_size).await?;
+        handshake(&mut stream, topic, self.cfg.credentials.as_ref()).await?;
+        debug!("Established Node<=>Host stream");
+        self.framed_stream = Some(TokioMutex::new(stream));
+
+        Ok(Node::<Nonblocking, Stream, Active, T>::from(self))
+    }
+
+    #[tracing::instrument]
+    pub async fn subscribe(
+        mut self,
+        rate: Duration,
+    ) -> Result<Node<Nonblocking, Stream, Subscription, T>, Error> {
+        let connector = self.connector()?;
+        let max_frame_size = self.cfg.network_cfg.max_buffer_size;
+        let topic = self.topic.clone();
+
+        let subscription_data: Arc<TokioMutex<Option<Msg<T>>>> = Arc::new(TokioMutex::new(None));
+        let data = Arc::clone(&subscription_data);
+
+        let packet = GenericMsg::subscribe(&topic, rate)?;
+
+        let mut stream = connector.connect(max_frame_size).await?;
+        handshake(&mut stream, topic, self.cfg.credentials.as_ref()).await?;
+
+        let codec = self.cfg.codec();
+        let task_subscribe = tokio::spawn(async move {
+            if let Err(e) = run_subscription::<T>(packet, &mut stream, data, &codec).await {
+                error!("{:?}", e);
+            }
+        });
+        self.task_subscribe = Some(task_subscribe);
+
+        let mut subscription_node = Node::<Nonblocking, Stream, Subscription, T>::from(self);
+        subscription_node.subscription_data = subscription_data;
+
+        Ok(subscription_node)
+    }
+}
+
+/// Request a subscription from the Host and store each newer value it pushes over the stream
+async fn run_subscription<T: Message>(
+    packet: GenericMsg,
+    stream: &mut FramedStream,
+    data: Arc<TokioMutex<Option<Msg<T>>>>,
+    codec: &PayloadCodec,
+) -> Result<(), Error> {
+    send_msg(stream, packet.as_bytes()?).await?;
+
+    loop {
+        match await_response::<T>(stream, codec).await {
+            Ok(msg) => {
+                let mut data = data.lock().await;
+                if let Some(existing) = data.as_ref() {
+                    if !msg.is_newer_than(existing) {
+                        continue;
+                    }
+                }
+                *data = Some(msg);
+            }
+            Err(e) if e.is_access_denied() || e == Error::StreamConnection => return Err(e),
+            // A stream that fails to read or overruns its frame size can't be resynchronized
+            Err(e @ Error::Io { .. }) => return Err(e),
+            Err(e) => {
+                error!("Subscription Error: {:?}", e);
+                continue;
+            }
+        };
+    }
+}
+
+//------
+
+impl<T: Message> From<Node<Blocking, Stream, Idle, T>> for Node<Blocking, Stream, Active, T> {
+    fn from(node: Node<Blocking, Stream, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            #[cfg(feature = "quic")]
+            endpoint: node.endpoint,
+            #[cfg(feature = "quic")]
+            connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message> From<Node<Blocking, Stream, Idle, T>> for Node<Blocking, Stream, Subscription, T> {
+    fn from(node: Node<Blocking, Stream, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            #[cfg(feature = "quic")]
+            endpoint: node.endpoint,
+            #[cfg(feature = "quic")]
+            connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message + 'static> Node<Blocking, Stream, Idle, T> {
+    /// Attempt a connection from the Node to the Host over a user-supplied stream
+    #[tracing::instrument(skip_all)]
+    pub fn activate(mut self) -> Result<Node<Blocking, Stream, Active, T>, Error> {
+        let connector = self.connector()?;
+        let max_frame_size = self.cfg.network_cfg.max_buffer_size;
+        let topic = self.topic.clone();
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+
+        let credentials = self.cfg.credentials.clone();
+        let stream: FramedStream = handle.block_on(async {
+            let mut stream = connector.connect(max_frame_size).await?;
+            handshake(&mut stream, topic, credentials.as_ref()).await?;
+            Ok::<FramedStream, Error>(stream)
+        })?;
+        debug!("Established Node<=>Host stream");
+        self.framed_stream = Some(TokioMutex::new(stream));
+
+        Ok(Node::<Blocking, Stream, Active, T>::from(self))
+    }
+
+    #[tracing::instrument]
+    pub fn subscribe(
+        mut self,
+        rate: Duration,
+    ) -> Result<Node<Blocking, Stream, Subscription, T>, Error> {
+        let connector = self.connector()?;
+        let max_frame_size = self.cfg.network_cfg.max_buffer_size;
+        let topic = self.topic.clone();
+
+        let subscription_data: Arc<TokioMutex<Option<Msg<T>>>> = Arc::new(TokioMutex::new(None));
+        let data = Arc::clone(&subscription_data);
+
+        let packet = GenericMsg::subscribe(&topic, rate)?;
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+
+        let credentials = self.cfg.credentials.clone();
+        let mut stream: FramedStream = handle.block_on(async {
+            let mut stream = connector.connect(max_frame_size).await?;
+            handshake(&mut stream, topic, credentials.as_ref()).await?;
+            Ok::<FramedStream, Error>(stream)
+        })?;
+
+        let codec = self.cfg.codec();
+        let task_subscribe = handle.spawn(async move {
+            if let Err(e) = run_subscription::<T>(packet, &mut stream, data, &codec).await {
+                error!("{:?}", e);
+            }
+        });
+        self.task_subscribe = Some(task_subscribe);
+
+        let mut subscription_node = Node::<Blocking, Stream, Subscription, T>::from(self);
+        subscription_node.subscription_data = subscription_data;
+
+        Ok(subscription_node)
+    }
+}
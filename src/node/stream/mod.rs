@@ -0,0 +1,68 @@
+mod active;
+mod idle;
+mod subscription;
+
+extern crate alloc;
+
+use tracing::*;
+
+use std::result::Result;
+
+use alloc::vec::Vec;
+use postcard::from_bytes;
+
+use crate::host::Credentials;
+use crate::msg::{GenericMsg, Message, Msg, MsgType};
+use crate::node::PayloadCodec;
+use crate::stream::FramedStream;
+use crate::Error;
+
+/// Run the initial Node <=> Host connection handshake, presenting any credentials to the Host
+pub async fn handshake(
+    stream: &mut FramedStream,
+    topic: String,
+    credentials: Option<&Credentials>,
+) -> Result<(), Error> {
+    let packet = GenericMsg::auth(&topic, credentials)?.as_bytes()?;
+    send_msg(stream, packet).await?;
+    debug!("{}: Wrote stream handshake to host", topic);
+
+    let reply = await_generic(stream).await?;
+    if let MsgType::Error(e) = reply.msg_type {
+        error!("{}: Host rejected handshake: {}", topic, e);
+        return Err(e);
+    }
+    debug!("{}: Successfully connected to host", topic);
+
+    Ok(())
+}
+
+/// Send a `GenericMsg` of `MsgType` from the Node to the Host
+#[inline]
+pub async fn send_msg(stream: &mut FramedStream, packet: Vec<u8>) -> Result<(), Error> {
+    stream.write_frame(&packet).await
+}
+
+/// Set Node to wait for response from Host, with data to be deserialized into `Msg<T>`-type
+#[inline]
+pub async fn await_response<T: Message>(
+    stream: &mut FramedStream,
+    codec: &PayloadCodec,
+) -> Result<Msg<T>, Error> {
+    let generic = await_generic(stream).await?;
+    codec.decode(generic)
+}
+
+/// Set Node to wait for a `GenericMsg` response from Host
+#[inline]
+pub async fn await_generic(stream: &mut FramedStream) -> Result<GenericMsg, Error> {
+    let bytes = stream.read_frame().await?;
+    Ok(from_bytes::<GenericMsg>(&bytes)?)
+}
+
+/// Set Node to wait for the Host's acknowledgement of a published message
+#[inline]
+pub async fn await_publish_ack(stream: &mut FramedStream) -> Result<(), Error> {
+    let bytes = stream.read_frame().await?;
+    crate::node::check_publish_ack(&bytes)
+}
@@ -1,5 +1,5 @@
-use crate::error::HostOperation;
-use crate::node::network_config::Nonblocking;
+use crate::node::check_publish_ack;
+use crate::node::network_config::{Block, Nonblocking};
 use crate::node::tcp::*;
 use crate::node::{Active, Node};
 use crate::prelude::*;
@@ -18,280 +18,204 @@ use tracing::*;
 
 use crate::node::network_config::{Interface, Tcp};
 
-impl<T: Message + 'static> Node<Nonblocking, Tcp, Active, T> {
-    // TO_DO: The error handling in the async blocks need to be improved
-    /// Send data to host on Node's assigned topic using `Msg<T>` packet
-    #[tracing::instrument]
-    #[inline]
-    pub async fn publish(&self, val: T) -> Result<(), Error> {
-        let packet = Msg::new(MsgType::Set, self.topic.clone(), val)
-            .to_generic()?
-            .as_bytes()?;
-
+impl<B: Block, T: Message + 'static> Node<B, Tcp, Active, T> {
+    /// Send a message to the Host and read its reply into `buf`
+    ///
+    /// If the connection to the Host was lost, the Node reconnects to the first reachable Host in
+    /// its list of Host addresses and resends the message.
+    async fn exchange(&self, packet: &[u8], buf: &mut [u8]) -> Result<usize, Error> {
         let stream = match self.stream.as_ref() {
             Some(stream) => stream,
             None => return Err(Error::AccessStream),
         };
+        let mut stream = stream.lock().await;
+
+        match exchange(&stream, packet, buf).await {
+            Err(e) if e.is_connection_lost() => {
+                warn!("{}: Lost connection to Host, reconnecting", self.topic);
+                let addrs = self.cfg.network_cfg.host_addrs();
+                let new_stream = try_connections(&addrs).await?;
+                *stream = handshake(
+                    new_stream,
+                    self.topic.clone(),
+                    self.cfg.credentials.as_ref(),
+                )
+                .await?;
+                exchange(&stream, packet, buf).await
+            }
+            result => result,
+        }
+    }
 
-        // Send the publish message
-        send_msg(stream, packet).await?;
+    /// Send a message to the Host and wait for it to be acknowledged
+    async fn publish_packet(&self, packet: Vec<u8>) -> Result<(), Error> {
+        let mut buf = self.buffer.lock().await;
+        let n = self.exchange(&packet, &mut buf).await?;
+        check_publish_ack(&buf[..n])
+    }
 
-        // Wait for the publish acknowledgement
+    /// Send a request to the Host and decode its reply
+    async fn request_packet<U: Message>(&self, packet: Vec<u8>) -> Result<Msg<U>, Error> {
         let mut buf = self.buffer.lock().await;
-        loop {
-            if let Ok(()) = stream.readable().await {
-                match stream.try_read(&mut buf) {
-                    Ok(0) => continue,
-                    Ok(n) => {
-                        let bytes = &buf[..n];
-                        if let Ok(HostOperation::FAILURE) = from_bytes::<HostOperation>(bytes) {
-                            error!("Host-side error on publish");
-                        }
+        let n = self.exchange(&packet, &mut buf).await?;
+        let generic = from_bytes::<GenericMsg>(&buf[..n])?;
+        self.cfg.codec().decode(generic)
+    }
+}
 
-                        break;
-                    }
-                    Err(_e) => {
-                        // if e.kind() == std::io::ErrorKind::WouldBlock {}
-                        continue;
-                    }
-                }
-            }
-        }
-        Ok(())
+impl<T: Message + 'static> Node<Nonblocking, Tcp, Active, T> {
+    /// Send data to host on Node's assigned topic using `Msg<T>` packet
+    #[tracing::instrument]
+    #[inline]
+    pub async fn publish(&self, val: T) -> Result<(), Error> {
+        let packet = self
+            .cfg
+            .codec()
+            .encode(Msg::new(MsgType::Set, self.topic.clone(), val))?;
+        self.publish_packet(packet).await
     }
 
     #[tracing::instrument]
     #[inline]
     pub async fn publish_msg(&self, msg: Msg<T>) -> Result<(), Error> {
-        let packet = msg.to_generic()?.as_bytes()?;
-        let stream = match self.stream.as_ref() {
-            Some(stream) => stream,
-            None => return Err(Error::AccessStream),
-        };
-
-        // Send the publish message
-        send_msg(stream, packet).await?;
-
-        // Wait for the publish acknowledgement
-        let mut buf = self.buffer.lock().await;
-        loop {
-            if let Ok(()) = stream.readable().await {
-                match stream.try_read(&mut buf) {
-                    Ok(0) => continue,
-                    Ok(n) => {
-                        let bytes = &buf[..n];
-                        if let Ok(HostOperation::FAILURE) = from_bytes::<HostOperation>(bytes) {
-                            error!("Host-side error on publish");
-                        }
-
-                        break;
-                    }
-                    Err(_e) => {
-                        // if e.kind() == std::io::ErrorKind::WouldBlock {}
-                        continue;
-                    }
-                }
-            }
-        }
-        Ok(())
+        let packet = self.cfg.codec().encode(msg)?;
+        self.publish_packet(packet).await
     }
 
     /// Request data from host on Node's assigned topic
     #[tracing::instrument]
     #[inline]
     pub async fn request(&self) -> Result<Msg<T>, Error> {
-        let stream = match self.stream.as_ref() {
-            Some(stream) => stream,
-            None => return Err(Error::AccessStream),
-        };
-
         let packet = GenericMsg::get::<T>(self.topic.clone()).as_bytes()?;
-
-        let mut buffer = self.buffer.lock().await;
-        send_msg(stream, packet).await?;
-        let msg = await_response::<T>(stream, &mut buffer).await?;
-        Ok(msg)
+        self.request_packet(packet).await
     }
 
     #[tracing::instrument]
     #[inline]
     pub async fn topics(&self) -> Result<Msg<Vec<String>>, Error> {
-        let stream = match self.stream.as_ref() {
-            Some(stream) => stream,
-            None => return Err(Error::AccessStream),
-        };
-
         let packet = GenericMsg::topics().as_bytes()?;
+        self.request_packet(packet).await
+    }
 
-        let mut buffer = self.buffer.lock().await;
-        send_msg(stream, packet).await?;
-        let msg = await_response::<Vec<String>>(stream, &mut buffer).await?;
-        Ok(msg)
+    /// Request statistics on the Node's assigned topic from the Host
+    #[tracing::instrument]
+    #[inline]
+    pub async fn stats(&self) -> Result<Msg<TopicStats>, Error> {
+        let packet = GenericMsg::stats(&self.topic).as_bytes()?;
+        self.request_packet(packet).await
+    }
+
+    /// Estimate the offset of the Node's clock from the Host's
+    ///
+    /// If the Node was configured `with_clock_correction`, the timestamps it publishes are
+    /// corrected by the offset from here on.
+    #[tracing::instrument]
+    #[inline]
+    pub async fn sync_clock(&self) -> Result<ClockSync, Error> {
+        self.cfg
+            .clock
+            .sync(|request| async move { self.request_packet(request.as_bytes()?).await })
+            .await
     }
 }
 
 use crate::node::network_config::Blocking;
 
 impl<T: Message + 'static> Node<Blocking, Tcp, Active, T> {
-    // TO_DO: The error handling in the async blocks need to be improved
     /// Send data to host on Node's assigned topic using `Msg<T>` packet
     #[tracing::instrument]
     #[inline]
     pub fn publish(&self, val: T) -> Result<(), Error> {
-        let packet = Msg::new(MsgType::Set, self.topic.clone(), val)
-            .to_generic()?
-            .as_bytes()?;
-
-        let stream = match self.stream.as_ref() {
-            Some(stream) => stream,
-            None => return Err(Error::AccessStream),
-        };
+        let packet = self
+            .cfg
+            .codec()
+            .encode(Msg::new(MsgType::Set, self.topic.clone(), val))?;
 
         let handle = match &self.rt_handle {
             Some(handle) => handle,
             None => return Err(Error::HandleAccess),
         };
-
-        handle.block_on(async {
-            // Send the publish message
-            send_msg(stream, packet).await?;
-
-            // Wait for the publish acknowledgement
-            let mut buf = self.buffer.lock().await;
-            loop {
-                if let Ok(()) = stream.readable().await {
-                    match stream.try_read(&mut buf) {
-                        Ok(0) => continue,
-                        Ok(n) => {
-                            let bytes = &buf[..n];
-                            if let Ok(HostOperation::FAILURE) = from_bytes::<HostOperation>(bytes) {
-                                error!("Host-side error on publish");
-                            }
-
-                            break;
-                        }
-                        Err(_e) => {
-                            // if e.kind() == std::io::ErrorKind::WouldBlock {}
-                            continue;
-                        }
-                    }
-                }
-            }
-            Ok(())
-        })
+        handle.block_on(self.publish_packet(packet))
     }
 
     #[tracing::instrument]
     #[inline]
     pub fn publish_msg(&self, msg: Msg<T>) -> Result<(), Error> {
-        let packet = msg.to_generic()?.as_bytes()?;
-
-        let stream = match self.stream.as_ref() {
-            Some(stream) => stream,
-            None => return Err(Error::AccessStream),
-        };
+        let packet = self.cfg.codec().encode(msg)?;
 
         let handle = match &self.rt_handle {
             Some(handle) => handle,
             None => return Err(Error::HandleAccess),
         };
-
-        handle.block_on(async {
-            // Send the publish message
-            send_msg(stream, packet).await?;
-
-            // Wait for the publish acknowledgement
-            let mut buf = self.buffer.lock().await;
-            loop {
-                if let Ok(()) = stream.readable().await {
-                    match stream.try_read(&mut buf) {
-                        Ok(0) => continue,
-                        Ok(n) => {
-                            let bytes = &buf[..n];
-                            if let Ok(HostOperation::FAILURE) = from_bytes::<HostOperation>(bytes) {
-                                error!("Host-side error on publish");
-                            }
-
-                            break;
-                        }
-                        Err(_e) => {
-                            // if e.kind() == std::io::ErrorKind::WouldBlock {}
-                            continue;
-                        }
-                    }
-                }
-            }
-            Ok(())
-        })
+        handle.block_on(self.publish_packet(packet))
     }
 
     /// Request data from host on Node's assigned topic
     #[tracing::instrument]
     #[inline]
     pub fn request(&self) -> Result<Msg<T>, Error> {
-        let stream = match self.stream.as_ref() {
-            Some(stream) => stream,
-            None => return Err(Error::AccessStream),
-        };
-
         let packet = GenericMsg::get::<T>(&self.topic).as_bytes()?;
 
         let handle = match &self.rt_handle {
             Some(handle) => handle,
             None => return Err(Error::HandleAccess),
         };
-
-        handle.block_on(async {
-            let mut buffer = self.buffer.lock().await;
-            send_msg(stream, packet).await?;
-            let msg = await_response::<T>(stream, &mut buffer).await?;
-            Ok(msg)
-        })
+        handle.block_on(self.request_packet(packet))
     }
 
     /// Request data from host on Node's assigned topic
     #[tracing::instrument]
     #[inline]
     pub fn request_nth_back(&self, n: usize) -> Result<Msg<T>, Error> {
-        let stream = match self.stream.as_ref() {
-            Some(stream) => stream,
-            None => return Err(Error::AccessStream),
-        };
-
         let packet = GenericMsg::get_nth::<T>(&self.topic, n).as_bytes()?;
 
         let handle = match &self.rt_handle {
             Some(handle) => handle,
             None => return Err(Error::HandleAccess),
         };
-
-        handle.block_on(async {
-            let mut buffer = self.buffer.lock().await;
-            send_msg(stream, packet).await?;
-            let msg = await_response::<T>(stream, &mut buffer).await?;
-            Ok(msg)
-        })
+        handle.block_on(self.request_packet(packet))
     }
 
     #[tracing::instrument]
     #[inline]
     pub fn topics(&self) -> Result<Msg<Vec<String>>, Error> {
-        let stream = match self.stream.as_ref() {
-            Some(stream) => stream,
-            None => return Err(Error::AccessStream),
+        let packet = GenericMsg::topics().as_bytes()?;
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
         };
+        handle.block_on(self.request_packet(packet))
+    }
 
-        let packet = GenericMsg::topics().as_bytes()?;
+    /// Request statistics on the Node's assigned topic from the Host
+    #[tracing::instrument]
+    #[inline]
+    pub fn stats(&self) -> Result<Msg<TopicStats>, Error> {
+        let packet = GenericMsg::stats(&self.topic).as_bytes()?;
 
-        if let Some(handle) = &self.rt_handle {
-            handle.block_on(async {
-                let mut buffer = self.buffer.lock().await;
-                send_msg(stream, packet).await?;
-                let msg = await_response::<Vec<String>>(stream, &mut buffer).await?;
-                Ok(msg)
-            })
-        } else {
-            Err(Error::HandleAccess)
-        }
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+        handle.block_on(self.request_packet(packet))
+    }
+
+    /// Estimate the offset of the Node's clock from the Host's
+    ///
+    /// If the Node was configured `with_clock_correction`, the timestamps it publishes are
+    /// corrected by the offset from here on.
+    #[tracing::instrument]
+    #[inline]
+    pub fn sync_clock(&self) -> Result<ClockSync, Error> {
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+        handle.block_on(
+            self.cfg
+                .clock
+                .sync(|request| async move { self.request_packet(request.as_bytes()?).await }),
+        )
     }
 }
@@ -4,7 +4,6 @@ use crate::prelude::*;
 use crate::node::network_config::{Nonblocking, Tcp};
 use crate::node::*;
 
-use tcp::try_connection;
 use tokio::net::UdpSocket;
 use tokio::sync::Mutex as TokioMutex;
 use tokio::time::{sleep, Duration};
@@ -41,6 +40,14 @@ impl<T: Message> From<Node<Nonblocking, Tcp, Idle, T>> for Node<Nonblocking, Tcp
             endpoint: node.endpoint,
             #[cfg(feature = "quic")]
             connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
             subscription_data: node.subscription_data,
             task_subscribe: None,
         }
@@ -63,6 +70,14 @@ impl<T: Message> From<Node<Nonblocking, Tcp, Idle, T>> for Node<Nonblocking, Tcp
             endpoint: node.endpoint,
             #[cfg(feature = "quic")]
             connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
             subscription_data: node.subscription_data,
             task_subscribe: None,
         }
@@ -76,21 +91,17 @@ impl<T: Message + 'static> Node<Nonblocking, Tcp, Idle, T> {
     /// Attempt connection from the Node to the Host located at the specified address
     #[tracing::instrument(skip_all)]
     pub async fn activate(mut self) -> Result<Node<Nonblocking, Tcp, Active, T>, Error> {
-        let addr = self.cfg.network_cfg.host_addr;
+        self.cfg.network_cfg.discover_host().await?;
+        let addrs = self.cfg.network_cfg.host_addrs();
         let topic = self.topic.clone();
 
-        let stream: Result<TcpStream, Error> = {
-            let stream = try_connection(addr).await?;
-            let stream = handshake(stream, topic).await?;
-            Ok(stream)
-        };
-        if let Ok(stream) = stream {
-            debug!(
-                "Established Node<=>Host TCP stream: {:?}",
-                stream.local_addr()
-            );
-            self.stream = Some(stream);
-        }
+        let stream = try_connections(&addrs).await?;
+        let stream = handshake(stream, topic, self.cfg.credentials.as_ref()).await?;
+        debug!(
+            "Established Node<=>Host TCP stream: {:?}",
+            stream.local_addr()
+        );
+        self.stream = Some(TokioMutex::new(stream));
 
         Ok(Node::<Nonblocking, Tcp, Active, T>::from(self))
     }
@@ -100,7 +111,8 @@ impl<T: Message + 'static> Node<Nonblocking, Tcp, Idle, T> {
         mut self,
         rate: Duration,
     ) -> Result<Node<Nonblocking, Tcp, Subscription, T>, Error> {
-        let addr = self.cfg.network_cfg.host_addr;
+        self.cfg.network_cfg.discover_host().await?;
+        let addrs = self.cfg.network_cfg.host_addrs();
         let topic = self.topic.clone();
 
         let subscription_data: Arc<TokioMutex<Option<Msg<T>>>> = Arc::new(TokioMutex::new(None));
@@ -109,24 +121,20 @@ impl<T: Message + 'static> Node<Nonblocking, Tcp, Idle, T> {
         let buffer = self.buffer.clone();
         let packet = GenericMsg::subscribe(&topic, rate)?;
 
-        let task_subscribe = tokio::spawn(async move {
-            if let Ok(stream) = try_connection(addr).await {
-                if let Ok(stream) = handshake(stream, topic.clone()).await {
-                    loop {
-                        if let Err(e) = run_subscription::<T>(
-                            packet.clone(),
-                            buffer.clone(),
-                            &stream,
-                            data.clone(),
-                        )
-                        .await
-                        {
-                            error!("{:?}", e);
-                        }
-                    }
-                }
-            }
-        });
+        let credentials = self.cfg.credentials.clone();
+        let stream = try_connections(&addrs).await?;
+        let stream = handshake(stream, topic.clone(), credentials.as_ref()).await?;
+
+        let subscriber = Subscriber {
+            addrs,
+            topic,
+            credentials,
+            packet,
+            buffer,
+            data,
+            codec: self.cfg.codec(),
+        };
+        let task_subscribe = tokio::spawn(subscriber.run(stream));
         self.task_subscribe = Some(task_subscribe);
 
         let mut subscription_node = Node::<Nonblocking, Tcp, Subscription, T>::from(self);
@@ -136,26 +144,89 @@ impl<T: Message + 'static> Node<Nonblocking, Tcp, Idle, T> {
     }
 }
 
-use crate::node::tcp::{await_response, send_msg};
+use crate::host::Credentials;
+use crate::node::tcp::{await_response, send_msg, try_connections};
+
+/// Keeps a subscription's data up to date, on a connection of its own
+struct Subscriber<T: Message> {
+    addrs: Vec<SocketAddr>,
+    topic: String,
+    credentials: Option<Credentials>,
+    packet: GenericMsg,
+    buffer: Arc<TokioMutex<Vec<u8>>>,
+    data: Arc<TokioMutex<Option<Msg<T>>>>,
+    codec: PayloadCodec,
+}
+
+impl<T: Message> Subscriber<T> {
+    /// Run the subscription, reconnecting to the first reachable Host in the Node's list of Host
+    /// addresses and resubscribing whenever the connection is lost
+    async fn run(self, mut stream: TcpStream) {
+        loop {
+            let result = run_subscription::<T>(
+                self.packet.clone(),
+                self.buffer.clone(),
+                &stream,
+                self.data.clone(),
+                &self.codec,
+            )
+            .await;
+            match result {
+                Err(e) if e.is_access_denied() => {
+                    error!("{:?}", e);
+                    break;
+                }
+                Err(e) if e.is_connection_lost() => {
+                    warn!("{}: Lost subscription to Host, reconnecting", self.topic);
+                    match self.reconnect().await {
+                        Ok(new_stream) => stream = new_stream,
+                        Err(e) => {
+                            error!("{:?}", e);
+                            break;
+                        }
+                    }
+                }
+                Err(e) => error!("{:?}", e),
+                Ok(()) => (),
+            }
+        }
+    }
+
+    /// Connect to the first reachable Host, retrying until one is found
+    async fn reconnect(&self) -> Result<TcpStream, Error> {
+        loop {
+            match try_connections(&self.addrs).await {
+                Ok(stream) => {
+                    match handshake(stream, self.topic.clone(), self.credentials.as_ref()).await {
+                        Ok(stream) => return Ok(stream),
+                        Err(e) if e.is_access_denied() => return Err(e),
+                        Err(e) => error!("{:?}", e),
+                    }
+                }
+                Err(e) => error!("{:?}", e),
+            }
+        }
+    }
+}
+
 async fn run_subscription<T: Message>(
     packet: GenericMsg,
     buffer: Arc<TokioMutex<Vec<u8>>>,
     stream: &TcpStream,
     data: Arc<TokioMutex<Option<Msg<T>>>>,
+    codec: &PayloadCodec,
 ) -> Result<(), Error> {
     send_msg(stream, packet.as_bytes()?).await?;
 
     let mut buffer = buffer.lock().await;
     loop {
-        match await_response::<T>(stream, &mut buffer).await {
+        match await_response::<T>(stream, &mut buffer, codec).await {
             Ok(msg) => {
                 let mut data = data.lock().await;
                 use std::ops::DerefMut;
                 match data.deref_mut() {
                     Some(existing) => {
-                        let delta = msg.timestamp - existing.timestamp;
-                        // println!("The time difference between msg tx/rx is: {} us",delta);
-                        if delta <= chrono::Duration::zero() {
+                        if !msg.is_newer_than(existing) {
                             // println!("Data is not newer, skipping to next subscription iteration");
                             continue;
                         }
@@ -167,6 +238,7 @@ async fn run_subscription<T: Message>(
                     }
                 }
             }
+            Err(e) if e.is_access_denied() || e.is_connection_lost() => return Err(e),
             Err(e) => {
                 error!("Subscription Error: {:?}", e);
                 continue;
@@ -193,6 +265,14 @@ impl<T: Message> From<Node<Blocking, Tcp, Idle, T>> for Node<Blocking, Tcp, Acti
             endpoint: node.endpoint,
             #[cfg(feature = "quic")]
             connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
             subscription_data: node.subscription_data,
             task_subscribe: None,
         }
@@ -215,6 +295,14 @@ impl<T: Message> From<Node<Blocking, Tcp, Idle, T>> for Node<Blocking, Tcp, Subs
             endpoint: node.endpoint,
             #[cfg(feature = "quic")]
             connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
             subscription_data: node.subscription_data,
             task_subscribe: None,
         }
@@ -226,26 +314,25 @@ impl<T: Message + 'static> Node<Blocking, Tcp, Idle, T> {
     /// Attempt connection from the Node to the Host located at the specified address
     #[tracing::instrument(skip_all)]
     pub fn activate(mut self) -> Result<Node<Blocking, Tcp, Active, T>, Error> {
-        let addr = self.cfg.network_cfg.host_addr;
         let topic = self.topic.clone();
 
         let handle = match &self.rt_handle {
             Some(handle) => handle,
             None => return Err(Error::HandleAccess),
         };
-
-        let stream: Result<TcpStream, Error> = handle.block_on(async move {
-            let stream = try_connection(addr).await?;
-            let stream = handshake(stream, topic).await?;
-            Ok(stream)
-        });
-        if let Ok(stream) = stream {
-            debug!(
-                "Established Node<=>Host TCP stream: {:?}",
-                stream.local_addr()
-            );
-            self.stream = Some(stream);
-        }
+        handle.block_on(self.cfg.network_cfg.discover_host())?;
+        let addrs = self.cfg.network_cfg.host_addrs();
+
+        let credentials = self.cfg.credentials.clone();
+        let stream: TcpStream = handle.block_on(async move {
+            let stream = try_connections(&addrs).await?;
+            handshake(stream, topic, credentials.as_ref()).await
+        })?;
+        debug!(
+            "Established Node<=>Host TCP stream: {:?}",
+            stream.local_addr()
+        );
+        self.stream = Some(TokioMutex::new(stream));
 
         Ok(Node::<Blocking, Tcp, Active, T>::from(self))
     }
@@ -255,7 +342,6 @@ impl<T: Message + 'static> Node<Blocking, Tcp, Idle, T> {
         mut self,
         rate: Duration,
     ) -> Result<Node<Blocking, Tcp, Subscription, T>, Error> {
-        let addr = self.cfg.network_cfg.host_addr;
         let topic = self.topic.clone();
 
         let subscription_data: Arc<TokioMutex<Option<Msg<T>>>> = Arc::new(TokioMutex::new(None));
@@ -268,25 +354,25 @@ impl<T: Message + 'static> Node<Blocking, Tcp, Idle, T> {
             Some(handle) => handle,
             None => return Err(Error::HandleAccess),
         };
-
-        let task_subscribe = handle.spawn(async move {
-            if let Ok(stream) = try_connection(addr).await {
-                if let Ok(stream) = handshake(stream, topic.clone()).await {
-                    loop {
-                        if let Err(e) = run_subscription::<T>(
-                            packet.clone(),
-                            buffer.clone(),
-                            &stream,
-                            data.clone(),
-                        )
-                        .await
-                        {
-                            error!("{:?}", e);
-                        }
-                    }
-                }
-            }
-        });
+        handle.block_on(self.cfg.network_cfg.discover_host())?;
+        let addrs = self.cfg.network_cfg.host_addrs();
+
+        let credentials = self.cfg.credentials.clone();
+        let stream: TcpStream = handle.block_on(async {
+            let stream = try_connections(&addrs).await?;
+            handshake(stream, topic.clone(), credentials.as_ref()).await
+        })?;
+
+        let subscriber = Subscriber {
+            addrs,
+            topic,
+            credentials,
+            packet,
+            buffer,
+            data,
+            codec: self.cfg.codec(),
+        };
+        let task_subscribe = handle.spawn(subscriber.run(stream));
         self.task_subscribe = Some(task_subscribe);
 
         let mut subscription_node = Node::<Blocking, Tcp, Subscription, T>::from(self);
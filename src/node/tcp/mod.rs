@@ -23,8 +23,10 @@ use alloc::vec::Vec;
 use postcard::from_bytes;
 use serde::{de::DeserializeOwned, Serialize};
 
-use crate::msg::{GenericMsg, Message, Msg};
+use crate::host::Credentials;
+use crate::msg::{GenericMsg, Message, Msg, MsgType};
 use crate::node::network_config::Interface;
+use crate::node::PayloadCodec;
 use crate::Error;
 use chrono::{DateTime, Utc};
 
@@ -40,50 +42,44 @@ use std::io::BufReader;
 
 /// Attempts to create an async `TcpStream` connection with a Host at the specified socket address
 pub async fn try_connection(host_addr: SocketAddr) -> Result<TcpStream, Error> {
+    try_connections(&[host_addr]).await
+}
+
+/// Attempts to create an async `TcpStream` connection with the first reachable Host, trying each
+/// address in order
+pub async fn try_connections(host_addrs: &[SocketAddr]) -> Result<TcpStream, Error> {
     let mut connection_attempts = 0;
-    let mut stream: Option<TcpStream> = None;
     while connection_attempts < 5 {
-        match TcpStream::connect(host_addr).await {
-            Ok(my_stream) => {
-                stream = Some(my_stream);
-                break;
-            }
-            Err(e) => {
-                connection_attempts += 1;
-                sleep(Duration::from_millis(1_000)).await;
-                warn!("{:?}", e);
+        for host_addr in host_addrs {
+            match TcpStream::connect(host_addr).await {
+                Ok(stream) => return Ok(stream),
+                Err(e) => warn!("{}: {:?}", host_addr, e),
             }
         }
+        connection_attempts += 1;
+        sleep(Duration::from_millis(1_000)).await;
     }
-
-    match stream {
-        Some(stream) => Ok(stream),
-        None => Err(Error::StreamConnection),
-    }
+    Err(Error::StreamConnection)
 }
 
-/// Run the initial Node <=> Host connection handshake
-pub async fn handshake(stream: TcpStream, topic: String) -> Result<TcpStream, Error> {
-    loop {
-        if let Ok(()) = stream.writable().await {
-            match stream.try_write(topic.as_bytes()) {
-                Ok(_n) => {
-                    debug!("{}: Wrote {} bytes to host", topic, _n);
-                    debug!("{}: Successfully connected to host", topic);
-                    break;
-                }
-                Err(e) => {
-                    if e.kind() == std::io::ErrorKind::WouldBlock {
-                    } else {
-                        error!("NODE handshake error: {:?}", e);
-                    }
-                }
-            }
-        };
+/// Run the initial Node <=> Host connection handshake, presenting any credentials to the Host
+pub async fn handshake(
+    stream: TcpStream,
+    topic: String,
+    credentials: Option<&Credentials>,
+) -> Result<TcpStream, Error> {
+    let packet = GenericMsg::auth(&topic, credentials)?.as_bytes()?;
+    send_msg(&stream, packet).await?;
+    debug!("{}: Wrote handshake to host", topic);
+
+    // Waiting on the Host's reply also keeps published data out of the initial handshake
+    let mut buf = vec![0u8; 1024];
+    let reply = await_generic(&stream, &mut buf).await?;
+    if let MsgType::Error(e) = reply.msg_type {
+        error!("{}: Host rejected handshake: {}", topic, e);
+        return Err(e);
     }
-    // TO_DO: Is there a better way to do this?
-    // Pause after connection to avoid accidentally including published data in initial handshake
-    sleep(Duration::from_millis(20)).await;
+    debug!("{}: Successfully connected to host", topic);
 
     Ok(stream)
 }
@@ -101,34 +97,55 @@ pub async fn send_msg(stream: &TcpStream, packet: Vec<u8>) -> Result<(), Error>
                 // debug!("Node successfully wrote {}-byte request to host",n);
                 break;
             }
-            Err(_e) => {
-                // if e.kind() == std::io::ErrorKind::WouldBlock {}
-                continue;
-            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => continue,
+            Err(e) => return Err(e.into()),
         }
     }
     Ok(())
 }
 
+/// Send a message to the Host and read its raw reply
+pub async fn exchange(stream: &TcpStream, packet: &[u8], buf: &mut [u8]) -> Result<usize, Error> {
+    send_msg(stream, packet.to_vec()).await?;
+    loop {
+        stream.readable().await?;
+        match stream.try_read(buf) {
+            // The Host closed the connection
+            Ok(0) => return Err(Error::StreamConnection),
+            Ok(n) => return Ok(n),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
 /// Set Node to wait for response from Host, with data to be deserialized into `Msg<T>`-type
 // #[tracing::instrument]
 #[inline]
 pub async fn await_response<T: Message>(
     stream: &TcpStream,
     buf: &mut [u8], //max_buffer_size: usize,
+    codec: &PayloadCodec,
 ) -> Result<Msg<T>, Error> {
+    let generic = await_generic(stream, buf).await?;
+    codec.decode(generic)
+}
+
+/// Set Node to wait for a `GenericMsg` response from Host
+#[inline]
+pub async fn await_generic(stream: &TcpStream, buf: &mut [u8]) -> Result<GenericMsg, Error> {
     // TO_DO: This can be made cleaner
     loop {
         if let Err(e) = stream.readable().await {
             error!("{}", e);
         }
         match stream.try_read(buf) {
-            Ok(0) => continue,
+            // The Host closed the connection
+            Ok(0) => return Err(Error::StreamConnection),
             Ok(n) => {
                 let bytes = &buf[..n];
                 let generic = from_bytes::<GenericMsg>(bytes)?;
-                let specialized: Msg<T> = generic.try_into()?;
-                return Ok(specialized);
+                return Ok(generic);
             }
             Err(_e) => {
                 // if e.kind() == std::io::ErrorKind::WouldBlock {}
@@ -0,0 +1,309 @@
+extern crate alloc;
+use crate::prelude::*;
+
+use crate::node::network_config::{Blocking, Nonblocking, Tls};
+use crate::node::tls::{await_response, handshake, send_msg, try_tls_connection};
+use crate::node::*;
+
+use tokio::net::TcpStream;
+use tokio::sync::Mutex as TokioMutex;
+use tokio::time::Duration;
+use tokio_rustls::client::TlsStream;
+
+use tracing::*;
+
+use std::marker::PhantomData;
+use std::result::Result;
+use std::sync::Arc;
+
+use alloc::vec::Vec;
+
+impl<T: Message> From<Node<Nonblocking, Tls, Idle, T>> for Node<Nonblocking, Tls, Active, T> {
+    fn from(node: Node<Nonblocking, Tls, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            endpoint: node.endpoint,
+            connection: node.connection,
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message> From<Node<Nonblocking, Tls, Idle, T>> for Node<Nonblocking, Tls, Subscription, T> {
+    fn from(node: Node<Nonblocking, Tls, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            endpoint: node.endpoint,
+            connection: node.connection,
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message + 'static> Node<Nonblocking, Tls, Idle, T> {
+    /// Attempt an encrypted connection from the Node to the Host located at the specified address
+    #[tracing::instrument(skip_all)]
+    pub async fn activate(mut self) -> Result<Node<Nonblocking, Tls, Active, T>, Error> {
+        let addr = self.cfg.network_cfg.discover_host().await?;
+        let cert_path = self.cfg.network_cfg.cert_path.clone();
+        let server_name = self.cfg.network_cfg.server_name.clone();
+        let topic = self.topic.clone();
+
+        let stream = try_tls_connection(addr, cert_path, &server_name).await?;
+        let stream = handshake(stream, topic, self.cfg.credentials.as_ref()).await?;
+        debug!(
+            "Established Node<=>Host TLS stream: {:?}",
+            stream.get_ref().0.local_addr()
+        );
+        self.tls_stream = Some(TokioMutex::new(stream));
+
+        Ok(Node::<Nonblocking, Tls, Active, T>::from(self))
+    }
+
+    #[tracing::instrument]
+    pub async fn subscribe(
+        mut self,
+        rate: Duration,
+    ) -> Result<Node<Nonblocking, Tls, Subscription, T>, Error> {
+        let addr = self.cfg.network_cfg.discover_host().await?;
+        let cert_path = self.cfg.network_cfg.cert_path.clone();
+        let server_name = self.cfg.network_cfg.server_name.clone();
+        let topic = self.topic.clone();
+
+        let subscription_data: Arc<TokioMutex<Option<Msg<T>>>> = Arc::new(TokioMutex::new(None));
+        let data = Arc::clone(&subscription_data);
+
+        let buffer = self.buffer.clone();
+        let packet = GenericMsg::subscribe(&topic, rate)?;
+
+        let stream = try_tls_connection(addr, cert_path, &server_name).await?;
+        let mut stream = handshake(stream, topic, self.cfg.credentials.as_ref()).await?;
+
+        let codec = self.cfg.codec();
+        let task_subscribe = tokio::spawn(async move {
+            loop {
+                if let Err(e) = run_subscription::<T>(
+                    packet.clone(),
+                    buffer.clone(),
+                    &mut stream,
+                    data.clone(),
+                    &codec,
+                )
+                .await
+                {
+                    error!("{:?}", e);
+                    if e.is_access_denied() || e == Error::StreamConnection {
+                        break;
+                    }
+                }
+            }
+        });
+        self.task_subscribe = Some(task_subscribe);
+
+        let mut subscription_node = Node::<Nonblocking, Tls, Subscription, T>::from(self);
+        subscription_node.subscription_data = subscription_data;
+
+        Ok(subscription_node)
+    }
+}
+
+async fn run_subscription<T: Message>(
+    packet: GenericMsg,
+    buffer: Arc<TokioMutex<Vec<u8>>>,
+    stream: &mut TlsStream<TcpStream>,
+    data: Arc<TokioMutex<Option<Msg<T>>>>,
+    codec: &PayloadCodec,
+) -> Result<(), Error> {
+    send_msg(stream, packet.as_bytes()?).await?;
+
+    let mut buffer = buffer.lock().await;
+    loop {
+        match await_response::<T>(stream, &mut buffer, codec).await {
+            Ok(msg) => {
+                let mut data = data.lock().await;
+                if let Some(existing) = data.as_ref() {
+                    if !msg.is_newer_than(existing) {
+                        continue;
+                    }
+                }
+                *data = Some(msg);
+            }
+            Err(e) if e.is_access_denied() || e == Error::StreamConnection => return Err(e),
+            Err(e) => {
+                error!("Subscription Error: {:?}", e);
+                continue;
+            }
+        };
+    }
+}
+
+//------
+
+impl<T: Message> From<Node<Blocking, Tls, Idle, T>> for Node<Blocking, Tls, Active, T> {
+    fn from(node: Node<Blocking, Tls, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            endpoint: node.endpoint,
+            connection: node.connection,
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message> From<Node<Blocking, Tls, Idle, T>> for Node<Blocking, Tls, Subscription, T> {
+    fn from(node: Node<Blocking, Tls, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            endpoint: node.endpoint,
+            connection: node.connection,
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message + 'static> Node<Blocking, Tls, Idle, T> {
+    /// Attempt an encrypted connection from the Node to the Host located at the specified address
+    #[tracing::instrument(skip_all)]
+    pub fn activate(mut self) -> Result<Node<Blocking, Tls, Active, T>, Error> {
+        let cert_path = self.cfg.network_cfg.cert_path.clone();
+        let server_name = self.cfg.network_cfg.server_name.clone();
+        let topic = self.topic.clone();
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+        let addr = handle.block_on(self.cfg.network_cfg.discover_host())?;
+
+        let credentials = self.cfg.credentials.clone();
+        let stream: TlsStream<TcpStream> = handle.block_on(async move {
+            let stream = try_tls_connection(addr, cert_path, &server_name).await?;
+            handshake(stream, topic, credentials.as_ref()).await
+        })?;
+        debug!(
+            "Established Node<=>Host TLS stream: {:?}",
+            stream.get_ref().0.local_addr()
+        );
+        self.tls_stream = Some(TokioMutex::new(stream));
+
+        Ok(Node::<Blocking, Tls, Active, T>::from(self))
+    }
+
+    #[tracing::instrument]
+    pub fn subscribe(
+        mut self,
+        rate: Duration,
+    ) -> Result<Node<Blocking, Tls, Subscription, T>, Error> {
+        let cert_path = self.cfg.network_cfg.cert_path.clone();
+        let server_name = self.cfg.network_cfg.server_name.clone();
+        let topic = self.topic.clone();
+
+        let subscription_data: Arc<TokioMutex<Option<Msg<T>>>> = Arc::new(TokioMutex::new(None));
+        let data = Arc::clone(&subscription_data);
+
+        let buffer = self.buffer.clone();
+        let packet = GenericMsg::subscribe(&topic, rate)?;
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+        let addr = handle.block_on(self.cfg.network_cfg.discover_host())?;
+
+        let credentials = self.cfg.credentials.clone();
+        let mut stream: TlsStream<TcpStream> = handle.block_on(async move {
+            let stream = try_tls_connection(addr, cert_path, &server_name).await?;
+            handshake(stream, topic, credentials.as_ref()).await
+        })?;
+
+        let codec = self.cfg.codec();
+        let task_subscribe = handle.spawn(async move {
+            loop {
+                if let Err(e) = run_subscription::<T>(
+                    packet.clone(),
+                    buffer.clone(),
+                    &mut stream,
+                    data.clone(),
+                    &codec,
+                )
+                .await
+                {
+                    error!("{:?}", e);
+                    if e.is_access_denied() || e == Error::StreamConnection {
+                        break;
+                    }
+                }
+            }
+        });
+        self.task_subscribe = Some(task_subscribe);
+
+        let mut subscription_node = Node::<Blocking, Tls, Subscription, T>::from(self);
+        subscription_node.subscription_data = subscription_data;
+
+        Ok(subscription_node)
+    }
+}
@@ -0,0 +1,122 @@
+mod active;
+mod idle;
+mod subscription;
+
+extern crate alloc;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use tracing::*;
+
+use std::convert::{TryFrom, TryInto};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::result::Result;
+use std::sync::Arc;
+
+use alloc::vec::Vec;
+use postcard::from_bytes;
+
+use crate::error::Quic::*;
+use crate::host::Credentials;
+use crate::msg::{GenericMsg, Message, Msg, MsgType};
+use crate::node::quic::read_root_certs;
+use crate::node::tcp::try_connection;
+use crate::node::PayloadCodec;
+use crate::Error;
+
+use rustls::{ClientConfig, ServerName};
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::TlsConnector;
+
+/// Create a `TlsConnector` which trusts the Host certificate(s) at the specified path
+pub fn generate_connector_from_certs(cert_path: Option<PathBuf>) -> Result<TlsConnector, Error> {
+    let certs = read_root_certs(cert_path)?;
+    let config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(certs)
+        .with_no_client_auth();
+    Ok(TlsConnector::from(Arc::new(config)))
+}
+
+/// Attempts to create an encrypted TLS stream with a Host at the specified socket address,
+/// verifying its certificate against `server_name`
+pub async fn try_tls_connection(
+    host_addr: SocketAddr,
+    cert_path: Option<PathBuf>,
+    server_name: &str,
+) -> Result<TlsStream<TcpStream>, Error> {
+    let connector = generate_connector_from_certs(cert_path)?;
+    let stream = try_connection(host_addr).await?;
+
+    let server_name =
+        ServerName::try_from(server_name).map_err(|_e| Error::Quic(InvalidDnsName))?;
+    let stream = connector.connect(server_name, stream).await?;
+    Ok(stream)
+}
+
+/// Run the initial Node <=> Host connection handshake, presenting any credentials to the Host
+pub async fn handshake(
+    mut stream: TlsStream<TcpStream>,
+    topic: String,
+    credentials: Option<&Credentials>,
+) -> Result<TlsStream<TcpStream>, Error> {
+    let packet = GenericMsg::auth(&topic, credentials)?.as_bytes()?;
+    send_msg(&mut stream, packet).await?;
+    debug!("{}: Wrote TLS handshake to host", topic);
+
+    let mut buf = vec![0u8; 1024];
+    let reply = await_generic(&mut stream, &mut buf).await?;
+    if let MsgType::Error(e) = reply.msg_type {
+        error!("{}: Host rejected handshake: {}", topic, e);
+        return Err(e);
+    }
+    debug!("{}: Successfully connected to host", topic);
+
+    Ok(stream)
+}
+
+/// Send a `GenericMsg` of `MsgType` from the Node to the Host
+#[inline]
+pub async fn send_msg(stream: &mut TlsStream<TcpStream>, packet: Vec<u8>) -> Result<(), Error> {
+    stream.write_all(&packet).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+/// Set Node to wait for response from Host, with data to be deserialized into `Msg<T>`-type
+#[inline]
+pub async fn await_response<T: Message>(
+    stream: &mut TlsStream<TcpStream>,
+    buf: &mut [u8],
+    codec: &PayloadCodec,
+) -> Result<Msg<T>, Error> {
+    let generic = await_generic(stream, buf).await?;
+    codec.decode(generic)
+}
+
+/// Set Node to wait for a `GenericMsg` response from Host
+#[inline]
+pub async fn await_generic(
+    stream: &mut TlsStream<TcpStream>,
+    buf: &mut [u8],
+) -> Result<GenericMsg, Error> {
+    match stream.read(buf).await? {
+        // The Host has closed the connection
+        0 => Err(Error::StreamConnection),
+        n => Ok(from_bytes::<GenericMsg>(&buf[..n])?),
+    }
+}
+
+/// Set Node to wait for the Host's acknowledgement of a published message
+#[inline]
+pub async fn await_publish_ack(
+    stream: &mut TlsStream<TcpStream>,
+    buf: &mut [u8],
+) -> Result<(), Error> {
+    match stream.read(buf).await? {
+        0 => Err(Error::StreamConnection),
+        n => crate::node::check_publish_ack(&buf[..n]),
+    }
+}
@@ -35,6 +35,14 @@ impl<T: Message> From<Node<Nonblocking, Udp, Idle, T>> for Node<Nonblocking, Udp
             endpoint: node.endpoint,
             #[cfg(feature = "quic")]
             connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
             subscription_data: node.subscription_data,
             task_subscribe: None,
         }
@@ -45,9 +53,10 @@ impl<T: Message + 'static> Node<Nonblocking, Udp, Active, T> {
     #[tracing::instrument]
     #[inline]
     pub async fn publish(&self, val: T) -> Result<(), Error> {
-        let packet = Msg::new(MsgType::Set, self.topic.clone(), val)
-            .to_generic()?
-            .as_bytes()?;
+        let packet = self
+            .cfg
+            .codec()
+            .encode(Msg::new(MsgType::Set, self.topic.clone(), val))?;
 
         let socket = match self.socket.as_ref() {
             Some(socket) => socket,
@@ -61,7 +70,7 @@ impl<T: Message + 'static> Node<Nonblocking, Udp, Active, T> {
     }
 
     pub async fn publish_msg(&self, msg: Msg<T>) -> Result<(), Error> {
-        let packet = msg.to_generic()?.as_bytes()?;
+        let packet = self.cfg.codec().encode(msg)?;
         let socket = match self.socket.as_ref() {
             Some(socket) => socket,
             None => return Err(Error::AccessSocket),
@@ -81,7 +90,7 @@ impl<T: Message + 'static> Node<Nonblocking, Udp, Active, T> {
 
         if let Some(socket) = &self.socket {
             send_msg(socket, packet, self.cfg.network_cfg.host_addr).await?;
-            let msg = await_response(socket, buffer).await?;
+            let msg = await_response(socket, buffer, &self.cfg.codec()).await?;
             Ok(msg)
         } else {
             Err(Error::AccessSocket)
@@ -96,12 +105,48 @@ impl<T: Message + 'static> Node<Nonblocking, Udp, Active, T> {
 
         if let Some(socket) = &self.socket {
             send_msg(socket, packet, self.cfg.network_cfg.host_addr).await?;
-            let msg = await_response(socket, buffer).await?;
+            let msg = await_response(socket, buffer, &self.cfg.codec()).await?;
             Ok(msg)
         } else {
             Err(Error::AccessSocket)
         }
     }
+
+    /// Request statistics on the Node's assigned topic from the Host
+    #[tracing::instrument]
+    #[inline]
+    pub async fn stats(&self) -> Result<Msg<TopicStats>, Error> {
+        let packet = GenericMsg::stats(&self.topic).as_bytes()?;
+        let buffer = self.buffer.clone();
+
+        if let Some(socket) = &self.socket {
+            send_msg(socket, packet, self.cfg.network_cfg.host_addr).await?;
+            let msg = await_response(socket, buffer, &self.cfg.codec()).await?;
+            Ok(msg)
+        } else {
+            Err(Error::AccessSocket)
+        }
+    }
+
+    /// Estimate the offset of the Node's clock from the Host's
+    ///
+    /// If the Node was configured `with_clock_correction`, the timestamps it publishes are
+    /// corrected by the offset from here on.
+    #[tracing::instrument]
+    #[inline]
+    pub async fn sync_clock(&self) -> Result<ClockSync, Error> {
+        let socket = self.socket.as_ref().ok_or(Error::AccessSocket)?;
+        self.cfg
+            .clock
+            .sync(|request| {
+                let buffer = self.buffer.clone();
+                async move {
+                    send_msg(socket, request.as_bytes()?, self.cfg.network_cfg.host_addr).await?;
+                    await_response(socket, buffer, &self.cfg.codec()).await
+                }
+            })
+            .await
+    }
 }
 
 //--------
@@ -124,6 +169,14 @@ impl<T: Message> From<Node<Blocking, Udp, Idle, T>> for Node<Blocking, Udp, Acti
             endpoint: node.endpoint,
             #[cfg(feature = "quic")]
             connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
             subscription_data: node.subscription_data,
             task_subscribe: None,
         }
@@ -134,9 +187,10 @@ impl<T: Message + 'static> Node<Blocking, Udp, Active, T> {
     #[tracing::instrument]
     #[inline]
     pub fn publish(&self, val: T) -> Result<(), Error> {
-        let packet = Msg::new(MsgType::Set, self.topic.clone(), val)
-            .to_generic()?
-            .as_bytes()?;
+        let packet = self
+            .cfg
+            .codec()
+            .encode(Msg::new(MsgType::Set, self.topic.clone(), val))?;
 
         let socket = match self.socket.as_ref() {
             Some(socket) => socket,
@@ -159,7 +213,7 @@ impl<T: Message + 'static> Node<Blocking, Udp, Active, T> {
     #[tracing::instrument]
     #[inline]
     pub fn publish_msg(&self, msg: Msg<T>) -> Result<(), Error> {
-        let packet = msg.to_generic()?.as_bytes()?;
+        let packet = self.cfg.codec().encode(msg)?;
 
         let socket = match self.socket.as_ref() {
             Some(socket) => socket,
@@ -193,7 +247,7 @@ impl<T: Message + 'static> Node<Blocking, Udp, Active, T> {
         handle.block_on(async {
             if let Some(socket) = &self.socket {
                 send_msg(socket, packet, self.cfg.network_cfg.host_addr).await?;
-                let msg = await_response(socket, buffer).await?;
+                let msg = await_response(socket, buffer, &self.cfg.codec()).await?;
                 Ok(msg)
             } else {
                 Err(Error::AccessSocket)
@@ -215,7 +269,7 @@ impl<T: Message + 'static> Node<Blocking, Udp, Active, T> {
         handle.block_on(async {
             if let Some(socket) = &self.socket {
                 send_msg(socket, packet, self.cfg.network_cfg.host_addr).await?;
-                let msg = await_response(socket, buffer).await?;
+                let msg = await_response(socket, buffer, &self.cfg.codec()).await?;
                 Ok(msg)
             } else {
                 Err(Error::AccessSocket)
@@ -237,11 +291,55 @@ impl<T: Message + 'static> Node<Blocking, Udp, Active, T> {
         handle.block_on(async {
             if let Some(socket) = &self.socket {
                 send_msg(socket, packet, self.cfg.network_cfg.host_addr).await?;
-                let msg = await_response(socket, buffer).await?;
+                let msg = await_response(socket, buffer, &self.cfg.codec()).await?;
+                Ok(msg)
+            } else {
+                Err(Error::AccessSocket)
+            }
+        })
+    }
+
+    /// Request statistics on the Node's assigned topic from the Host
+    #[tracing::instrument]
+    #[inline]
+    pub fn stats(&self) -> Result<Msg<TopicStats>, Error> {
+        let packet = GenericMsg::stats(&self.topic).as_bytes()?;
+        let buffer = self.buffer.clone();
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+
+        handle.block_on(async {
+            if let Some(socket) = &self.socket {
+                send_msg(socket, packet, self.cfg.network_cfg.host_addr).await?;
+                let msg = await_response(socket, buffer, &self.cfg.codec()).await?;
                 Ok(msg)
             } else {
                 Err(Error::AccessSocket)
             }
         })
     }
+
+    /// Estimate the offset of the Node's clock from the Host's
+    ///
+    /// If the Node was configured `with_clock_correction`, the timestamps it publishes are
+    /// corrected by the offset from here on.
+    #[tracing::instrument]
+    #[inline]
+    pub fn sync_clock(&self) -> Result<ClockSync, Error> {
+        let socket = self.socket.as_ref().ok_or(Error::AccessSocket)?;
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+        handle.block_on(self.cfg.clock.sync(|request| {
+            let buffer = self.buffer.clone();
+            async move {
+                send_msg(socket, request.as_bytes()?, self.cfg.network_cfg.host_addr).await?;
+                await_response(socket, buffer, &self.cfg.codec()).await
+            }
+        }))
+    }
 }
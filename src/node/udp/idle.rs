@@ -2,7 +2,7 @@ extern crate alloc;
 use crate::node::network_config::{Nonblocking, Udp};
 use crate::Error;
 
-use crate::node::udp::send_msg;
+use crate::node::udp::{authenticate, send_msg};
 use crate::node::*;
 
 use tokio::net::UdpSocket;
@@ -37,6 +37,14 @@ impl<T: Message> From<Node<Nonblocking, Udp, Idle, T>> for Node<Nonblocking, Udp
             endpoint: node.endpoint,
             #[cfg(feature = "quic")]
             connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
             subscription_data: node.subscription_data,
             task_subscribe: None,
         }
@@ -46,18 +54,18 @@ impl<T: Message> From<Node<Nonblocking, Udp, Idle, T>> for Node<Nonblocking, Udp
 impl<T: Message + 'static> Node<Nonblocking, Udp, Idle, T> {
     #[tracing::instrument(skip(self))]
     pub async fn activate(mut self) -> Result<Node<Nonblocking, Udp, Active, T>, Error> {
-        match {
-            match UdpSocket::bind("[::]:0").await {
-                Ok(socket) => {
-                    info!("Bound to socket: {:?}", &socket);
-                    Ok(socket)
-                }
-                Err(_e) => Err(Error::AccessSocket),
-            }
-        } {
-            Ok(socket) => self.socket = Some(socket),
-            Err(e) => return Err(e),
-        };
+        // Published messages are sent to the discovered `host_addr`
+        self.cfg.network_cfg.discover_host().await?;
+        let socket = UdpSocket::bind("[::]:0")
+            .await
+            .map_err(|_e| Error::AccessSocket)?;
+        info!("Bound to socket: {:?}", &socket);
+        self.socket = Some(socket);
+
+        if let (Some(socket), Some(credentials)) = (&self.socket, &self.cfg.credentials) {
+            let addr = self.cfg.network_cfg.host_addr;
+            authenticate(socket, addr, &self.topic, credentials, self.buffer.clone()).await?;
+        }
 
         Ok(Node::<Nonblocking, Udp, Active, T>::from(self))
     }
@@ -70,26 +78,36 @@ impl<T: Message + 'static> Node<Nonblocking, Udp, Idle, T> {
         let topic = self.topic.clone();
         let subscription_data: Arc<TokioMutex<Option<Msg<T>>>> = Arc::new(TokioMutex::new(None));
         let data = Arc::clone(&subscription_data);
-        let addr = self.cfg.network_cfg.host_addr;
+        let addr = self.cfg.network_cfg.discover_host().await?;
         let buffer = self.buffer.clone();
 
-        let packet = GenericMsg::subscribe(topic, rate)?;
+        let packet = GenericMsg::subscribe(&topic, rate)?;
+
+        let socket = UdpSocket::bind("[::]:0")
+            .await
+            .map_err(|_e| Error::AccessSocket)?;
+        info!("Bound to socket: {:?}", &socket);
+        if let Some(credentials) = &self.cfg.credentials {
+            authenticate(&socket, addr, &topic, credentials, buffer.clone()).await?;
+        }
 
+        let codec = self.cfg.codec();
         let task_subscribe = tokio::spawn(async move {
-            if let Ok(socket) = UdpSocket::bind("[::]:0").await {
-                info!("Bound to socket: {:?}", &socket);
-                loop {
-                    if let Err(e) = run_subscription::<T>(
-                        packet.clone(),
-                        buffer.clone(),
-                        &socket,
-                        data.clone(),
-                        addr,
-                    )
-                    .await
-                    {
-                        // dbg!(&e);
-                        error!("{:?}", e);
+            loop {
+                if let Err(e) = run_subscription::<T>(
+                    packet.clone(),
+                    buffer.clone(),
+                    &socket,
+                    data.clone(),
+                    &codec,
+                    addr,
+                )
+                .await
+                {
+                    // dbg!(&e);
+                    error!("{:?}", e);
+                    if e.is_access_denied() {
+                        break;
                     }
                 }
             }
@@ -110,20 +128,21 @@ async fn run_subscription<T: Message>(
     buffer: Arc<TokioMutex<Vec<u8>>>,
     socket: &UdpSocket,
     data: Arc<TokioMutex<Option<Msg<T>>>>,
+    codec: &PayloadCodec,
     addr: SocketAddr,
 ) -> Result<(), Error> {
     udp::send_msg(socket, packet.as_bytes()?, addr).await?;
 
     loop {
-        let msg = udp::await_response::<T>(socket, buffer.clone()).await?;
+        let msg = udp::await_response::<T>(socket, buffer.clone(), codec).await?;
         info!("UDP Msg<T> received: {:?}", &msg);
-        let delta = Utc::now() - msg.timestamp;
-        if delta <= chrono::Duration::zero() {
-            info!("Data is not newer, skipping to next subscription iteration");
-            continue;
-        }
-
         let mut data = data.lock().await;
+        if let Some(existing) = data.as_ref() {
+            if !msg.is_newer_than(existing) {
+                info!("Data is not newer, skipping to next subscription iteration");
+                continue;
+            }
+        }
         *data = Some(msg);
         info!("Inserted new subscription data!");
     }
@@ -149,6 +168,14 @@ impl<T: Message> From<Node<Blocking, Udp, Idle, T>> for Node<Blocking, Udp, Subs
             endpoint: node.endpoint,
             #[cfg(feature = "quic")]
             connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            #[cfg(unix)]
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
             subscription_data: node.subscription_data,
             task_subscribe: None,
         }
@@ -162,6 +189,7 @@ impl<T: Message + 'static> Node<Blocking, Udp, Idle, T> {
             Some(handle) => handle,
             None => return Err(Error::HandleAccess),
         };
+        handle.block_on(self.cfg.network_cfg.discover_host())?;
 
         match handle.block_on(async move {
             match UdpSocket::bind("[::]:0").await {
@@ -176,6 +204,17 @@ impl<T: Message + 'static> Node<Blocking, Udp, Idle, T> {
             Err(e) => return Err(e),
         };
 
+        if let (Some(socket), Some(credentials)) = (&self.socket, &self.cfg.credentials) {
+            let addr = self.cfg.network_cfg.host_addr;
+            handle.block_on(authenticate(
+                socket,
+                addr,
+                &self.topic,
+                credentials,
+                self.buffer.clone(),
+            ))?;
+        }
+
         Ok(Node::<Blocking, Udp, Active, T>::from(self))
     }
 
@@ -187,31 +226,45 @@ impl<T: Message + 'static> Node<Blocking, Udp, Idle, T> {
         let topic = self.topic.clone();
         let subscription_data: Arc<TokioMutex<Option<Msg<T>>>> = Arc::new(TokioMutex::new(None));
         let data = Arc::clone(&subscription_data);
-        let addr = self.cfg.network_cfg.host_addr;
         let buffer = self.buffer.clone();
 
-        let packet = GenericMsg::subscribe(topic, rate)?;
+        let packet = GenericMsg::subscribe(&topic, rate)?;
 
         let handle = match &self.rt_handle {
             Some(handle) => handle,
             None => return Err(Error::HandleAccess),
         };
+        let addr = handle.block_on(self.cfg.network_cfg.discover_host())?;
+
+        let credentials = self.cfg.credentials.clone();
+        let socket = handle.block_on(async {
+            let socket = UdpSocket::bind("[::]:0")
+                .await
+                .map_err(|_e| Error::AccessSocket)?;
+            info!("Bound to socket: {:?}", &socket);
+            if let Some(credentials) = &credentials {
+                authenticate(&socket, addr, &topic, credentials, buffer.clone()).await?;
+            }
+            Ok::<UdpSocket, Error>(socket)
+        })?;
 
+        let codec = self.cfg.codec();
         let task_subscribe = handle.spawn(async move {
-            if let Ok(socket) = UdpSocket::bind("[::]:0").await {
-                info!("Bound to socket: {:?}", &socket);
-                loop {
-                    if let Err(e) = run_subscription::<T>(
-                        packet.clone(),
-                        buffer.clone(),
-                        &socket,
-                        data.clone(),
-                        addr,
-                    )
-                    .await
-                    {
-                        // dbg!(&e);
-                        error!("{:?}", e);
+            loop {
+                if let Err(e) = run_subscription::<T>(
+                    packet.clone(),
+                    buffer.clone(),
+                    &socket,
+                    data.clone(),
+                    &codec,
+                    addr,
+                )
+                .await
+                {
+                    // dbg!(&e);
+                    error!("{:?}", e);
+                    if e.is_access_denied() {
+                        break;
                     }
                 }
             }
@@ -2,11 +2,14 @@ mod active;
 mod idle;
 mod subscription;
 
-use crate::msg::{GenericMsg, Message, Msg};
+use crate::host::Credentials;
+use crate::msg::{GenericMsg, Message, Msg, MsgType};
+use crate::node::PayloadCodec;
 use std::convert::TryInto;
 use std::sync::Arc;
 use tokio::net::UdpSocket;
 use tokio::sync::Mutex as TokioMutex;
+use tokio::time::{timeout, Duration};
 
 use tracing::*;
 
@@ -14,11 +17,15 @@ use crate::error::Error;
 use std::io::{Error as IoError, ErrorKind};
 use std::net::SocketAddr;
 
+/// Longest a Node waits for the Host to answer its credentials, as UDP datagrams may be lost
+const AUTH_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[inline]
-#[tracing::instrument(skip(buffer))]
+#[tracing::instrument(skip(buffer, codec))]
 pub async fn await_response<T: Message>(
     socket: &UdpSocket,
     buffer: Arc<TokioMutex<Vec<u8>>>,
+    codec: &PayloadCodec,
 ) -> Result<Msg<T>, Error> {
     socket.readable().await?;
     loop {
@@ -34,8 +41,7 @@ pub async fn await_response<T: Message>(
                 let bytes = &buf[..n];
 
                 let generic = postcard::from_bytes::<GenericMsg>(bytes)?;
-                let msg: Msg<T> = generic.try_into()?;
-                return Ok(msg);
+                return codec.decode(generic);
             }
             Err(e) => {
                 if e.kind() == std::io::ErrorKind::WouldBlock {
@@ -58,3 +64,27 @@ async fn send_msg(
     let n = socket.send_to(&packet, host_addr).await?;
     Ok(n)
 }
+
+/// Present the Node's credentials to the Host, which associates them with the socket's address
+#[inline]
+async fn authenticate(
+    socket: &UdpSocket,
+    host_addr: SocketAddr,
+    topic: &str,
+    credentials: &Credentials,
+    buffer: Arc<TokioMutex<Vec<u8>>>,
+) -> Result<(), Error> {
+    let packet = GenericMsg::auth(topic, Some(credentials))?.as_bytes()?;
+    send_msg(socket, packet, host_addr).await?;
+
+    let mut buf = buffer.lock().await;
+    let n = match timeout(AUTH_TIMEOUT, socket.recv(&mut buf)).await {
+        Ok(n) => n?,
+        Err(_elapsed) => return Err(IoError::from(ErrorKind::TimedOut).into()),
+    };
+    let reply = postcard::from_bytes::<GenericMsg>(&buf[..n])?;
+    match reply.msg_type {
+        MsgType::Error(e) => Err(e),
+        _ => Ok(()),
+    }
+}
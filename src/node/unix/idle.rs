@@ -0,0 +1,315 @@
+extern crate alloc;
+use crate::prelude::*;
+
+use crate::node::network_config::{Blocking, Nonblocking, Unix};
+use crate::node::unix::{await_response, handshake, send_msg, try_unix_connection};
+use crate::node::*;
+
+use tokio::net::UnixStream;
+use tokio::sync::Mutex as TokioMutex;
+use tokio::time::Duration;
+
+use tracing::*;
+
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::result::Result;
+use std::sync::Arc;
+
+use alloc::vec::Vec;
+
+impl<T: Message> From<Node<Nonblocking, Unix, Idle, T>> for Node<Nonblocking, Unix, Active, T> {
+    fn from(node: Node<Nonblocking, Unix, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            #[cfg(feature = "quic")]
+            endpoint: node.endpoint,
+            #[cfg(feature = "quic")]
+            connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message> From<Node<Nonblocking, Unix, Idle, T>>
+    for Node<Nonblocking, Unix, Subscription, T>
+{
+    fn from(node: Node<Nonblocking, Unix, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            #[cfg(feature = "quic")]
+            endpoint: node.endpoint,
+            #[cfg(feature = "quic")]
+            connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message, B: Block> Node<B, Unix, Idle, T> {
+    /// Path of the Host's Unix domain socket
+    fn socket_path(&self) -> Result<PathBuf, Error> {
+        match &self.cfg.network_cfg.interface_cfg {
+            Some(path) => Ok(path.clone()),
+            None => Err(Error::StreamConnection),
+        }
+    }
+}
+
+impl<T: Message + 'static> Node<Nonblocking, Unix, Idle, T> {
+    /// Attempt a connection from the Node to the Host's Unix domain socket
+    #[tracing::instrument(skip_all)]
+    pub async fn activate(mut self) -> Result<Node<Nonblocking, Unix, Active, T>, Error> {
+        let path = self.socket_path()?;
+        let topic = self.topic.clone();
+
+        let stream = try_unix_connection(&path).await?;
+        let stream = handshake(stream, topic, self.cfg.credentials.as_ref()).await?;
+        debug!("Established Node<=>Host Unix stream: {:?}", path);
+        self.unix_stream = Some(TokioMutex::new(stream));
+
+        Ok(Node::<Nonblocking, Unix, Active, T>::from(self))
+    }
+
+    #[tracing::instrument]
+    pub async fn subscribe(
+        mut self,
+        rate: Duration,
+    ) -> Result<Node<Nonblocking, Unix, Subscription, T>, Error> {
+        let path = self.socket_path()?;
+        let topic = self.topic.clone();
+
+        let subscription_data: Arc<TokioMutex<Option<Msg<T>>>> = Arc::new(TokioMutex::new(None));
+        let data = Arc::clone(&subscription_data);
+
+        let buffer = self.buffer.clone();
+        let packet = GenericMsg::subscribe(&topic, rate)?;
+
+        let stream = try_unix_connection(&path).await?;
+        let mut stream = handshake(stream, topic, self.cfg.credentials.as_ref()).await?;
+
+        let codec = self.cfg.codec();
+        let task_subscribe = tokio::spawn(async move {
+            loop {
+                if let Err(e) = run_subscription::<T>(
+                    packet.clone(),
+                    buffer.clone(),
+                    &mut stream,
+                    data.clone(),
+                    &codec,
+                )
+                .await
+                {
+                    error!("{:?}", e);
+                    if e.is_access_denied() || e == Error::StreamConnection {
+                        break;
+                    }
+                }
+            }
+        });
+        self.task_subscribe = Some(task_subscribe);
+
+        let mut subscription_node = Node::<Nonblocking, Unix, Subscription, T>::from(self);
+        subscription_node.subscription_data = subscription_data;
+
+        Ok(subscription_node)
+    }
+}
+
+async fn run_subscription<T: Message>(
+    packet: GenericMsg,
+    buffer: Arc<TokioMutex<Vec<u8>>>,
+    stream: &mut UnixStream,
+    data: Arc<TokioMutex<Option<Msg<T>>>>,
+    codec: &PayloadCodec,
+) -> Result<(), Error> {
+    send_msg(stream, packet.as_bytes()?).await?;
+
+    let mut buffer = buffer.lock().await;
+    loop {
+        match await_response::<T>(stream, &mut buffer, codec).await {
+            Ok(msg) => {
+                let mut data = data.lock().await;
+                if let Some(existing) = data.as_ref() {
+                    if !msg.is_newer_than(existing) {
+                        continue;
+                    }
+                }
+                *data = Some(msg);
+            }
+            Err(e) if e.is_access_denied() || e == Error::StreamConnection => return Err(e),
+            Err(e) => {
+                error!("Subscription Error: {:?}", e);
+                continue;
+            }
+        };
+    }
+}
+
+//------
+
+impl<T: Message> From<Node<Blocking, Unix, Idle, T>> for Node<Blocking, Unix, Active, T> {
+    fn from(node: Node<Blocking, Unix, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            #[cfg(feature = "quic")]
+            endpoint: node.endpoint,
+            #[cfg(feature = "quic")]
+            connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message> From<Node<Blocking, Unix, Idle, T>> for Node<Blocking, Unix, Subscription, T> {
+    fn from(node: Node<Blocking, Unix, Idle, T>) -> Self {
+        Self {
+            __state: PhantomData,
+            __data_type: PhantomData,
+            cfg: node.cfg,
+            runtime: node.runtime,
+            rt_handle: node.rt_handle,
+            stream: node.stream,
+            topic: node.topic,
+            socket: node.socket,
+            buffer: node.buffer,
+            #[cfg(feature = "quic")]
+            endpoint: node.endpoint,
+            #[cfg(feature = "quic")]
+            connection: node.connection,
+            #[cfg(feature = "tls")]
+            tls_stream: node.tls_stream,
+            unix_stream: node.unix_stream,
+            in_process: node.in_process,
+            #[cfg(feature = "serial")]
+            serial_port: node.serial_port,
+            framed_stream: node.framed_stream,
+            subscription_data: node.subscription_data,
+            task_subscribe: None,
+        }
+    }
+}
+
+impl<T: Message + 'static> Node<Blocking, Unix, Idle, T> {
+    /// Attempt a connection from the Node to the Host's Unix domain socket
+    #[tracing::instrument(skip_all)]
+    pub fn activate(mut self) -> Result<Node<Blocking, Unix, Active, T>, Error> {
+        let path = self.socket_path()?;
+        let topic = self.topic.clone();
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+
+        let credentials = self.cfg.credentials.clone();
+        let stream: UnixStream = handle.block_on(async {
+            let stream = try_unix_connection(&path).await?;
+            handshake(stream, topic, credentials.as_ref()).await
+        })?;
+        debug!("Established Node<=>Host Unix stream: {:?}", path);
+        self.unix_stream = Some(TokioMutex::new(stream));
+
+        Ok(Node::<Blocking, Unix, Active, T>::from(self))
+    }
+
+    #[tracing::instrument]
+    pub fn subscribe(
+        mut self,
+        rate: Duration,
+    ) -> Result<Node<Blocking, Unix, Subscription, T>, Error> {
+        let path = self.socket_path()?;
+        let topic = self.topic.clone();
+
+        let subscription_data: Arc<TokioMutex<Option<Msg<T>>>> = Arc::new(TokioMutex::new(None));
+        let data = Arc::clone(&subscription_data);
+
+        let buffer = self.buffer.clone();
+        let packet = GenericMsg::subscribe(&topic, rate)?;
+
+        let handle = match &self.rt_handle {
+            Some(handle) => handle,
+            None => return Err(Error::HandleAccess),
+        };
+
+        let credentials = self.cfg.credentials.clone();
+        let mut stream: UnixStream = handle.block_on(async {
+            let stream = try_unix_connection(&path).await?;
+            handshake(stream, topic, credentials.as_ref()).await
+        })?;
+
+        let codec = self.cfg.codec();
+        let task_subscribe = handle.spawn(async move {
+            loop {
+                if let Err(e) = run_subscription::<T>(
+                    packet.clone(),
+                    buffer.clone(),
+                    &mut stream,
+                    data.clone(),
+                    &codec,
+                )
+                .await
+                {
+                    error!("{:?}", e);
+                    if e.is_access_denied() || e == Error::StreamConnection {
+                        break;
+                    }
+                }
+            }
+        });
+        self.task_subscribe = Some(task_subscribe);
+
+        let mut subscription_node = Node::<Blocking, Unix, Subscription, T>::from(self);
+        subscription_node.subscription_data = subscription_data;
+
+        Ok(subscription_node)
+    }
+}
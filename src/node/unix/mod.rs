@@ -0,0 +1,99 @@
+mod active;
+mod idle;
+mod subscription;
+
+extern crate alloc;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixStream;
+use tokio::time::{sleep, Duration};
+
+use tracing::*;
+
+use std::path::Path;
+use std::result::Result;
+
+use alloc::vec::Vec;
+use postcard::from_bytes;
+
+use crate::host::Credentials;
+use crate::msg::{GenericMsg, Message, Msg, MsgType};
+use crate::node::PayloadCodec;
+use crate::Error;
+
+/// Attempts to create an async `UnixStream` connection with a Host at the specified socket path
+pub async fn try_unix_connection(path: &Path) -> Result<UnixStream, Error> {
+    let mut connection_attempts = 0;
+    while connection_attempts < 5 {
+        match UnixStream::connect(path).await {
+            Ok(stream) => return Ok(stream),
+            // Permission to the socket file is how the Host restricts access, so don't retry
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => return Err(e.into()),
+            Err(e) => {
+                connection_attempts += 1;
+                sleep(Duration::from_millis(1_000)).await;
+                warn!("{:?}", e);
+            }
+        }
+    }
+    Err(Error::StreamConnection)
+}
+
+/// Run the initial Node <=> Host connection handshake, presenting any credentials to the Host
+pub async fn handshake(
+    mut stream: UnixStream,
+    topic: String,
+    credentials: Option<&Credentials>,
+) -> Result<UnixStream, Error> {
+    let packet = GenericMsg::auth(&topic, credentials)?.as_bytes()?;
+    send_msg(&mut stream, packet).await?;
+    debug!("{}: Wrote Unix handshake to host", topic);
+
+    let mut buf = vec![0u8; 1024];
+    let reply = await_generic(&mut stream, &mut buf).await?;
+    if let MsgType::Error(e) = reply.msg_type {
+        error!("{}: Host rejected handshake: {}", topic, e);
+        return Err(e);
+    }
+    debug!("{}: Successfully connected to host", topic);
+
+    Ok(stream)
+}
+
+/// Send a `GenericMsg` of `MsgType` from the Node to the Host
+#[inline]
+pub async fn send_msg(stream: &mut UnixStream, packet: Vec<u8>) -> Result<(), Error> {
+    stream.write_all(&packet).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+/// Set Node to wait for response from Host, with data to be deserialized into `Msg<T>`-type
+#[inline]
+pub async fn await_response<T: Message>(
+    stream: &mut UnixStream,
+    buf: &mut [u8],
+    codec: &PayloadCodec,
+) -> Result<Msg<T>, Error> {
+    let generic = await_generic(stream, buf).await?;
+    codec.decode(generic)
+}
+
+/// Set Node to wait for a `GenericMsg` response from Host
+#[inline]
+pub async fn await_generic(stream: &mut UnixStream, buf: &mut [u8]) -> Result<GenericMsg, Error> {
+    match stream.read(buf).await? {
+        // The Host has closed the connection
+        0 => Err(Error::StreamConnection),
+        n => Ok(from_bytes::<GenericMsg>(&buf[..n])?),
+    }
+}
+
+/// Set Node to wait for the Host's acknowledgement of a published message
+#[inline]
+pub async fn await_publish_ack(stream: &mut UnixStream, buf: &mut [u8]) -> Result<(), Error> {
+    match stream.read(buf).await? {
+        0 => Err(Error::StreamConnection),
+        n => crate::node::check_publish_ack(&buf[..n]),
+    }
+}
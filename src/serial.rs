@@ -0,0 +1,183 @@
+use crate::Error;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::unix::AsyncFd;
+use tracing::*;
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::AsRawFd;
+use std::path::PathBuf;
+
+/// Configuration of a serial device, such as a UART or telemetry radio
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SerialConfig {
+    /// Path of the serial device, e.g. `/dev/ttyUSB0`
+    pub path: PathBuf,
+    /// Line rate of the device in bits per second
+    pub baud_rate: u32,
+    /// Largest encoded frame accepted from the link
+    pub max_buffer_size: usize,
+    pub max_name_size: usize,
+}
+
+impl SerialConfig {
+    /// Create a new `SerialConfig` for the device at `path`, at 57600 baud
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        SerialConfig {
+            path: path.into(),
+            baud_rate: 57_600,
+            max_buffer_size: 10_000,
+            max_name_size: 100,
+        }
+    }
+
+    /// Set the line rate of the device in bits per second
+    pub fn set_baud_rate(mut self, baud_rate: u32) -> SerialConfig {
+        self.baud_rate = baud_rate;
+        self
+    }
+
+    /// Set the largest encoded frame accepted from the link
+    pub fn set_max_buffer_size(mut self, max_buffer_size: usize) -> SerialConfig {
+        self.max_buffer_size = max_buffer_size;
+        self
+    }
+}
+
+/// Payload carried in a single COBS-encoded frame, checked against its CRC-32 on receipt
+#[derive(Debug, Serialize, Deserialize)]
+struct Frame {
+    payload: Vec<u8>,
+    crc: u32,
+}
+
+/// Encode a payload into a zero-delimited COBS frame with a CRC-32 checksum
+pub fn encode_frame(payload: &[u8]) -> Result<Vec<u8>, Error> {
+    let frame = Frame {
+        payload: payload.to_vec(),
+        crc: crc32fast::hash(payload),
+    };
+    Ok(postcard::to_allocvec_cobs(&frame)?)
+}
+
+/// Decode a frame received from the link, which may include its zero delimiter
+pub fn decode_frame(frame: &mut [u8]) -> Result<Vec<u8>, Error> {
+    let frame: Frame = postcard::from_bytes_cobs(frame)?;
+    if crc32fast::hash(&frame.payload) != frame.crc {
+        return Err(Error::CorruptFrame);
+    }
+    Ok(frame.payload)
+}
+
+/// Terminal speed constant for a baud rate
+fn speed(baud_rate: u32) -> Result<libc::speed_t, Error> {
+    let speed = match baud_rate {
+        1_200 => libc::B1200,
+        2_400 => libc::B2400,
+        4_800 => libc::B4800,
+        9_600 => libc::B9600,
+        19_200 => libc::B19200,
+        38_400 => libc::B38400,
+        57_600 => libc::B57600,
+        115_200 => libc::B115200,
+        230_400 => libc::B230400,
+        460_800 => libc::B460800,
+        921_600 => libc::B921600,
+        _ => return Err(std::io::Error::from(std::io::ErrorKind::InvalidInput).into()),
+    };
+    Ok(speed)
+}
+
+/// Asynchronous serial device exchanging framed payloads
+#[derive(Debug)]
+pub struct SerialPort {
+    device: AsyncFd<File>,
+    /// Bytes received after the last complete frame
+    pending: Vec<u8>,
+    max_buffer_size: usize,
+}
+
+impl SerialPort {
+    /// Open the serial device in raw mode at the configured baud rate
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn open(cfg: &SerialConfig) -> Result<Self, Error> {
+        let speed = speed(cfg.baud_rate)?;
+        let device = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NOCTTY | libc::O_NONBLOCK)
+            .open(&cfg.path)?;
+
+        let fd = device.as_raw_fd();
+        // SAFETY: `fd` is a valid open file descriptor for the lifetime of `device`, and the
+        // `termios` struct is initialised by `tcgetattr` before it is read
+        unsafe {
+            let mut termios: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(fd, &mut termios) != 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+            libc::cfmakeraw(&mut termios);
+            termios.c_cflag |= libc::CLOCAL | libc::CREAD;
+            if libc::cfsetispeed(&mut termios, speed) != 0
+                || libc::cfsetospeed(&mut termios, speed) != 0
+                || libc::tcsetattr(fd, libc::TCSANOW, &termios) != 0
+            {
+                return Err(std::io::Error::last_os_error().into());
+            }
+        }
+
+        Ok(SerialPort {
+            device: AsyncFd::new(device)?,
+            pending: Vec::new(),
+            max_buffer_size: cfg.max_buffer_size,
+        })
+    }
+
+    /// Send a payload over the link as a single frame
+    pub async fn write_frame(&mut self, payload: &[u8]) -> Result<(), Error> {
+        let frame = encode_frame(payload)?;
+        let mut written = 0;
+        while written < frame.len() {
+            let mut guard = self.device.writable().await?;
+            match guard.try_io(|device| device.get_ref().write(&frame[written..])) {
+                Ok(result) => written += result?,
+                Err(_would_block) => continue,
+            }
+        }
+        Ok(())
+    }
+
+    /// Wait for the next intact frame from the link and return its payload
+    ///
+    /// Frames that fail to decode or don't match their CRC, e.g. due to line noise, are dropped.
+    pub async fn read_frame(&mut self) -> Result<Vec<u8>, Error> {
+        let mut buf = [0u8; 1024];
+        loop {
+            while let Some(end) = self.pending.iter().position(|b| *b == 0) {
+                let mut frame: Vec<u8> = self.pending.drain(..=end).collect();
+                match decode_frame(&mut frame) {
+                    Ok(payload) => return Ok(payload),
+                    Err(e) => warn!("Dropping serial frame of {} bytes: {}", frame.len(), e),
+                }
+            }
+            if self.pending.len() > self.max_buffer_size {
+                warn!(
+                    "Dropping {} bytes without a frame delimiter",
+                    self.pending.len()
+                );
+                self.pending.clear();
+            }
+
+            let mut guard = self.device.readable().await?;
+            match guard.try_io(|device| device.get_ref().read(&mut buf)) {
+                Ok(Ok(0)) => return Err(Error::StreamConnection),
+                Ok(Ok(n)) => self.pending.extend_from_slice(&buf[..n]),
+                Ok(Err(e)) => return Err(e.into()),
+                Err(_would_block) => continue,
+            }
+        }
+    }
+}
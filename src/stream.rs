@@ -0,0 +1,132 @@
+use crate::Error;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use std::convert::TryFrom;
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+/// Bidirectional byte stream that a Host and Node can exchange messages over
+///
+/// Implemented for any `AsyncRead + AsyncWrite` type, such as an SSH port-forward. Separate
+/// read and write halves, e.g. a child process's stdout and stdin, can be combined with
+/// `tokio::io::join`.
+pub trait AsyncStream: AsyncRead + AsyncWrite + Send + Unpin {}
+impl<S: AsyncRead + AsyncWrite + Send + Unpin> AsyncStream for S {}
+
+type ConnectFuture = Pin<Box<dyn Future<Output = std::io::Result<Box<dyn AsyncStream>>> + Send>>;
+
+/// Opens a new stream to the Host each time a Node connects
+///
+/// Nodes open one stream when activated and another for each subscription.
+#[derive(Clone)]
+pub struct StreamConnector {
+    connect: Arc<dyn Fn() -> ConnectFuture + Send + Sync>,
+}
+
+impl Debug for StreamConnector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamConnector").finish_non_exhaustive()
+    }
+}
+
+impl StreamConnector {
+    /// Create a connector from a function that opens a new stream to the Host
+    pub fn new<F, Fut, S>(connect: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = std::io::Result<S>> + Send + 'static,
+        S: AsyncStream + 'static,
+    {
+        StreamConnector {
+            connect: Arc::new(move || {
+                let fut = connect();
+                Box::pin(async move { Ok(Box::new(fut.await?) as Box<dyn AsyncStream>) })
+            }),
+        }
+    }
+
+    /// Create a connector for a single, already-open stream
+    ///
+    /// Only the first connection succeeds, so this suits links that can't be reopened, such as
+    /// the stdin/stdout of a child process.
+    pub fn once<S: AsyncStream + 'static>(stream: S) -> Self {
+        let stream: Arc<Mutex<Option<Box<dyn AsyncStream>>>> =
+            Arc::new(Mutex::new(Some(Box::new(stream))));
+        StreamConnector {
+            connect: Arc::new(move || {
+                let stream = stream.lock().ok().and_then(|mut stream| stream.take());
+                Box::pin(async move {
+                    stream.ok_or_else(|| std::io::Error::from(std::io::ErrorKind::NotConnected))
+                })
+            }),
+        }
+    }
+
+    /// Open a new stream to the Host
+    pub(crate) async fn connect(&self, max_frame_size: usize) -> Result<FramedStream, Error> {
+        let stream = (self.connect)().await?;
+        Ok(FramedStream::new(stream, max_frame_size))
+    }
+}
+
+/// Stream exchanging length-prefixed frames, each holding a single serialized `GenericMsg`
+pub struct FramedStream {
+    stream: Box<dyn AsyncStream>,
+    max_frame_size: usize,
+}
+
+impl Debug for FramedStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FramedStream")
+            .field("max_frame_size", &self.max_frame_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FramedStream {
+    /// Frame a stream, rejecting incoming frames larger than `max_frame_size`
+    pub fn new(stream: Box<dyn AsyncStream>, max_frame_size: usize) -> Self {
+        FramedStream {
+            stream,
+            max_frame_size,
+        }
+    }
+
+    /// Send a payload as a single frame, prefixed with its big-endian `u32` length
+    pub async fn write_frame(&mut self, payload: &[u8]) -> Result<(), Error> {
+        let len = u32::try_from(payload.len())
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::InvalidInput))?;
+        self.stream.write_all(&len.to_be_bytes()).await?;
+        self.stream.write_all(payload).await?;
+        self.stream.flush().await?;
+        Ok(())
+    }
+
+    /// Wait for the next frame and return its payload
+    ///
+    /// Returns `Error::StreamConnection` once the other end closes the stream.
+    pub async fn read_frame(&mut self) -> Result<Vec<u8>, Error> {
+        let mut len = [0u8; 4];
+        match self.stream.read_exact(&mut len).await {
+            Ok(_) => (),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                return Err(Error::StreamConnection)
+            }
+            Err(e) => return Err(e.into()),
+        }
+        let len = u32::from_be_bytes(len) as usize;
+        if len > self.max_frame_size {
+            return Err(std::io::Error::from(std::io::ErrorKind::InvalidData).into());
+        }
+
+        let mut payload = vec![0u8; len];
+        match self.stream.read_exact(&mut payload).await {
+            Ok(_) => Ok(payload),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Err(Error::StreamConnection),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
@@ -0,0 +1,364 @@
+#[cfg(feature = "otel")]
+mod otlp;
+#[cfg(feature = "otel")]
+pub use crate::trace::otlp::OtlpConfig;
+#[cfg(feature = "otel")]
+use crate::trace::otlp::{Exporter, SpanRecord};
+
+use serde::{Deserialize, Serialize};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{info_span, Dispatch, Span, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use std::any::TypeId;
+use std::collections::hash_map::RandomState;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{BuildHasher, Hasher};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::msg::GenericMsg;
+use crate::Error;
+
+/// Span field holding the `traceparent` of the remote span that a span continues
+const TRACEPARENT: &str = "traceparent";
+
+/// Trace flag marking a trace as sampled, i.e. recorded
+const SAMPLED: u8 = 0x01;
+
+/// W3C Trace Context of a span, which messages carry between Nodes and the Host
+///
+/// It is written as a [`traceparent`](https://www.w3.org/TR/trace-context/#traceparent-header)
+/// header, e.g. `00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TraceContext {
+    /// Identifier of the trace the span belongs to
+    pub trace_id: [u8; 16],
+    /// Identifier of the span
+    pub span_id: [u8; 8],
+    /// Trace flags, whose lowest bit marks the trace as sampled
+    pub flags: u8,
+}
+
+impl TraceContext {
+    /// Start a new, sampled trace
+    pub fn new_root() -> Self {
+        let mut trace_id = [0u8; 16];
+        trace_id[..8].copy_from_slice(&random_id());
+        trace_id[8..].copy_from_slice(&random_id());
+        TraceContext {
+            trace_id,
+            span_id: random_id(),
+            flags: SAMPLED,
+        }
+    }
+
+    /// Context of a new span in the same trace, as a child of this one
+    pub fn child(&self) -> Self {
+        TraceContext {
+            span_id: random_id(),
+            ..*self
+        }
+    }
+
+    /// Whether the trace is sampled, so its spans are exported
+    pub fn is_sampled(&self) -> bool {
+        self.flags & SAMPLED != 0
+    }
+
+    /// Context of the current `tracing` span, if it is tracked by a [`TraceLayer`]
+    pub fn current() -> Option<Self> {
+        Self::of(&Span::current())
+    }
+
+    /// Context of a `tracing` span, if it is tracked by a [`TraceLayer`]
+    pub fn of(span: &Span) -> Option<Self> {
+        span.with_subscriber(|(id, dispatch)| {
+            let with_context = dispatch.downcast_ref::<WithContext>()?;
+            (with_context.0)(dispatch, id)
+        })
+        .flatten()
+    }
+}
+
+impl Display for TraceContext {
+    /// Write the context as a version `00` `traceparent`
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "00-")?;
+        for byte in self.trace_id {
+            write!(f, "{:02x}", byte)?;
+        }
+        write!(f, "-")?;
+        for byte in self.span_id {
+            write!(f, "{:02x}", byte)?;
+        }
+        write!(f, "-{:02x}", self.flags)
+    }
+}
+
+impl FromStr for TraceContext {
+    type Err = Error;
+
+    /// Parse a `traceparent`, ignoring any fields added by versions after `00`
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidTraceContext(s.to_string());
+        let parts: Vec<&str> = s.split('-').collect();
+        let version = match parts.first().and_then(|version| hex::<1>(version)) {
+            Some([version]) if version != 0xff => version,
+            _ => return Err(invalid()),
+        };
+        if parts.len() < 4 || (version == 0 && parts.len() > 4) {
+            return Err(invalid());
+        }
+        let trace_id = hex::<16>(parts[1]).ok_or_else(invalid)?;
+        let span_id = hex::<8>(parts[2]).ok_or_else(invalid)?;
+        let [flags] = hex::<1>(parts[3]).ok_or_else(invalid)?;
+        // All-zero identifiers are reserved as invalid
+        if trace_id == [0; 16] || span_id == [0; 8] {
+            return Err(invalid());
+        }
+        Ok(TraceContext {
+            trace_id,
+            span_id,
+            flags,
+        })
+    }
+}
+
+/// Decode exactly `N` bytes from lowercase hexadecimal
+fn hex<const N: usize>(s: &str) -> Option<[u8; N]> {
+    if s.len() != 2 * N || s.bytes().any(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+    let mut bytes = [0u8; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(s.get(2 * i..2 * i + 2)?, 16).ok()?;
+    }
+    Some(bytes)
+}
+
+/// Random, non-zero span identifier
+///
+/// Identifiers only need to be unique rather than unpredictable, so they are drawn from the
+/// randomly-keyed hasher of the standard library.
+fn random_id() -> [u8; 8] {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    loop {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+        if let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) {
+            hasher.write_u128(now.as_nanos());
+        }
+        let id = hasher.finish();
+        if id != 0 {
+            return id.to_be_bytes();
+        }
+    }
+}
+
+/// Looks up the context of a span in the subscriber that a [`TraceLayer`] is layered onto,
+/// which is found by downcasting the current dispatcher
+struct WithContext(fn(&Dispatch, &Id) -> Option<TraceContext>);
+
+fn context_of<S>(dispatch: &Dispatch, id: &Id) -> Option<TraceContext>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let subscriber = dispatch.downcast_ref::<S>()?;
+    let span = subscriber.span(id)?;
+    let extensions = span.extensions();
+    extensions.get::<SpanTrace>().map(|trace| trace.0)
+}
+
+/// Trace context of a span, kept in its extensions
+struct SpanTrace(TraceContext);
+
+/// `tracing_subscriber` layer giving each span a W3C trace context
+///
+/// Spans continue the trace of their parent span, or of a remote span if they have a
+/// `traceparent` field, and otherwise start a new trace. Nodes fill in the context of the
+/// current span on the messages they send, and the Host continues it in the span handling
+/// each message.
+///
+/// ```no_run
+/// use tracing_subscriber::prelude::*;
+///
+/// tracing_subscriber::registry()
+///     .with(meadow::trace::TraceLayer::new())
+///     .with(tracing_subscriber::fmt::layer())
+///     .init();
+/// ```
+pub struct TraceLayer {
+    with_context: WithContext,
+    #[cfg(feature = "otel")]
+    exporter: Option<Exporter>,
+}
+
+impl Debug for TraceLayer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TraceLayer").finish_non_exhaustive()
+    }
+}
+
+impl Default for TraceLayer {
+    fn default() -> Self {
+        TraceLayer::new()
+    }
+}
+
+impl TraceLayer {
+    /// Create a layer that tracks the trace context of each span, without exporting them
+    pub fn new() -> Self {
+        TraceLayer {
+            // Replaced with a lookup in the subscriber once the layer is added to one
+            with_context: WithContext(|_dispatch, _id| None),
+            #[cfg(feature = "otel")]
+            exporter: None,
+        }
+    }
+
+    /// Export every sampled span to an OpenTelemetry collector once it closes
+    #[cfg(feature = "otel")]
+    pub fn with_exporter(mut self, cfg: OtlpConfig) -> Self {
+        self.exporter = Some(Exporter::start(cfg));
+        self
+    }
+}
+
+impl<S> Layer<S> for TraceLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_layer(&mut self, _subscriber: &mut S) {
+        self.with_context = WithContext(context_of::<S>);
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let span = match ctx.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+        #[cfg(feature = "otel")]
+        let exporting = self.exporter.is_some();
+        #[cfg(not(feature = "otel"))]
+        let exporting = false;
+        let mut fields = Fields::new(exporting);
+        attrs.record(&mut fields);
+
+        let parent = match fields.remote {
+            Some(remote) => Some(remote),
+            None => span
+                .parent()
+                .and_then(|parent| parent.extensions().get::<SpanTrace>().map(|trace| trace.0)),
+        };
+        let context = match parent {
+            Some(parent) => parent.child(),
+            None => TraceContext::new_root(),
+        };
+
+        let mut extensions = span.extensions_mut();
+        extensions.insert(SpanTrace(context));
+        #[cfg(feature = "otel")]
+        if let Some(attributes) = fields.attributes {
+            extensions.insert(SpanRecord::new(parent, attributes));
+        }
+    }
+
+    #[cfg(feature = "otel")]
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(record) = span.extensions_mut().get_mut::<SpanRecord>() {
+                let mut fields = Fields::new(true);
+                values.record(&mut fields);
+                record
+                    .attributes
+                    .extend(fields.attributes.unwrap_or_default());
+            }
+        }
+    }
+
+    #[cfg(feature = "otel")]
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        if let (Some(exporter), Some(span)) = (&self.exporter, ctx.span(&id)) {
+            let mut extensions = span.extensions_mut();
+            if let (Some(SpanTrace(context)), Some(record)) = (
+                extensions.remove::<SpanTrace>(),
+                extensions.remove::<SpanRecord>(),
+            ) {
+                if context.is_sampled() {
+                    exporter.export(span.name(), context, record);
+                }
+            }
+        }
+    }
+
+    unsafe fn downcast_raw(&self, id: TypeId) -> Option<*const ()> {
+        if id == TypeId::of::<Self>() {
+            Some(self as *const Self as *const ())
+        } else if id == TypeId::of::<WithContext>() {
+            Some(&self.with_context as *const WithContext as *const ())
+        } else {
+            None
+        }
+    }
+}
+
+/// Visits a span's fields for the `traceparent` it continues, and collects them as
+/// attributes if the span is exported
+struct Fields {
+    remote: Option<TraceContext>,
+    attributes: Option<Vec<(&'static str, String)>>,
+}
+
+impl Fields {
+    fn new(exporting: bool) -> Self {
+        Fields {
+            remote: None,
+            attributes: exporting.then(Vec::new),
+        }
+    }
+}
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == TRACEPARENT {
+            self.remote = value.parse().ok();
+        } else if let Some(attributes) = &mut self.attributes {
+            attributes.push((field.name(), value.to_string()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == TRACEPARENT {
+            self.remote = format!("{:?}", value).parse().ok();
+        } else if let Some(attributes) = &mut self.attributes {
+            attributes.push((field.name(), format!("{:?}", value)));
+        }
+    }
+}
+
+/// Span of the Host handling a message, which continues the trace the message was sent in
+///
+/// If the Host tracks spans with a [`TraceLayer`], the message's context is replaced with the
+/// span's own, so that subscribers continue the trace from the Host. The message should be
+/// handled within the span, so that the Host's events and spans are traced as part of it.
+pub(crate) fn host_span(msg: &mut GenericMsg) -> Span {
+    let context = match msg.trace {
+        Some(context) => context,
+        None => return Span::none(),
+    };
+    let span = info_span!(
+        "meadow.host",
+        topic = %msg.topic,
+        msg_type = ?msg.msg_type,
+        traceparent = %context
+    );
+    if let Some(own) = TraceContext::of(&span) {
+        msg.trace = Some(own);
+    }
+    span
+}
@@ -0,0 +1,264 @@
+use hyper::header::{CONTENT_TYPE, HOST};
+use hyper::{Body, Request};
+use serde_json::{json, Value};
+use tokio::net::TcpStream;
+use tracing::*;
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::mpsc::{self, RecvTimeoutError, SyncSender};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::trace::TraceContext;
+
+/// Configuration of the exporter sending spans to an OpenTelemetry collector over OTLP/HTTP
+///
+/// Spans are queued as they close and sent in batches as JSON to the collector's
+/// `/v1/traces` endpoint from a background thread. Spans are dropped rather than slowing the
+/// application down if the queue fills up.
+#[derive(Debug, Clone)]
+pub struct OtlpConfig {
+    /// Address of the collector's OTLP/HTTP receiver
+    pub collector_addr: SocketAddr,
+    /// Name of the service the spans are reported as coming from
+    pub service_name: String,
+    /// Largest number of spans sent in one request
+    pub batch_size: usize,
+    /// Longest time spans wait in the queue before they're sent
+    pub interval: Duration,
+    /// Number of spans queued before new ones are dropped
+    pub max_queue_size: usize,
+}
+
+impl Default for OtlpConfig {
+    /// Export to `127.0.0.1:4318` as the `meadow` service
+    fn default() -> Self {
+        OtlpConfig {
+            collector_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 4318),
+            service_name: "meadow".into(),
+            batch_size: 512,
+            interval: Duration::from_secs(5),
+            max_queue_size: 2048,
+        }
+    }
+}
+
+impl OtlpConfig {
+    /// Set the address of the collector's OTLP/HTTP receiver
+    pub fn set_collector_addr(mut self, collector_addr: impl Into<SocketAddr>) -> Self {
+        self.collector_addr = collector_addr.into();
+        self
+    }
+
+    /// Set the name of the service the spans are reported as coming from
+    pub fn set_service_name(mut self, service_name: impl Into<String>) -> Self {
+        self.service_name = service_name.into();
+        self
+    }
+
+    /// Set the largest number of spans sent in one request
+    pub fn set_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Set the longest time spans wait in the queue before they're sent
+    pub fn set_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Set the number of spans queued before new ones are dropped
+    pub fn set_max_queue_size(mut self, max_queue_size: usize) -> Self {
+        self.max_queue_size = max_queue_size;
+        self
+    }
+}
+
+/// Timing, parent and fields of an exported span, kept in its extensions until it closes
+pub(crate) struct SpanRecord {
+    parent_id: Option<[u8; 8]>,
+    start: SystemTime,
+    pub(crate) attributes: Vec<(&'static str, String)>,
+}
+
+impl SpanRecord {
+    pub(crate) fn new(
+        parent: Option<TraceContext>,
+        attributes: Vec<(&'static str, String)>,
+    ) -> Self {
+        SpanRecord {
+            parent_id: parent.map(|parent| parent.span_id),
+            start: SystemTime::now(),
+            attributes,
+        }
+    }
+}
+
+/// Span that has closed, waiting to be exported
+struct FinishedSpan {
+    name: &'static str,
+    context: TraceContext,
+    record: SpanRecord,
+    end: SystemTime,
+}
+
+/// Queue of finished spans, sent to the collector by a background thread
+///
+/// The thread sends any spans left in the queue and exits once the exporter is dropped.
+pub(crate) struct Exporter {
+    sender: SyncSender<FinishedSpan>,
+}
+
+impl Exporter {
+    pub(crate) fn start(cfg: OtlpConfig) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(cfg.max_queue_size);
+        let spawned = thread::Builder::new()
+            .name("meadow-otlp".into())
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                {
+                    Ok(runtime) => runtime,
+                    Err(e) => {
+                        error!("Unable to create a runtime for the OTLP exporter: {}", e);
+                        return;
+                    }
+                };
+
+                let mut batch = Vec::new();
+                let mut deadline = Instant::now() + cfg.interval;
+                loop {
+                    let open = match receiver
+                        .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                    {
+                        Ok(span) => {
+                            batch.push(span);
+                            if batch.len() < cfg.batch_size {
+                                continue;
+                            }
+                            true
+                        }
+                        Err(RecvTimeoutError::Timeout) => true,
+                        Err(RecvTimeoutError::Disconnected) => false,
+                    };
+                    if !batch.is_empty() {
+                        // Spans opened while sending aren't traced, or they would be exported
+                        // in turn
+                        let sent = dispatcher::with_default(&Dispatch::none(), || {
+                            runtime.block_on(send(&cfg, &batch))
+                        });
+                        if let Err(e) = sent {
+                            warn!(
+                                "Unable to export {} spans to {}: {}",
+                                batch.len(),
+                                cfg.collector_addr,
+                                e
+                            );
+                        }
+                        batch.clear();
+                    }
+                    if !open {
+                        return;
+                    }
+                    deadline = Instant::now() + cfg.interval;
+                }
+            });
+        if let Err(e) = spawned {
+            error!("Unable to start the OTLP exporter: {}", e);
+        }
+        Exporter { sender }
+    }
+
+    /// Queue a closed span for export, dropping it if the queue is full
+    pub(crate) fn export(&self, name: &'static str, context: TraceContext, record: SpanRecord) {
+        let span = FinishedSpan {
+            name,
+            context,
+            record,
+            end: SystemTime::now(),
+        };
+        if self.sender.try_send(span).is_err() {
+            debug!("OTLP export queue is full, dropping span {}", name);
+        }
+    }
+}
+
+/// Post a batch of spans to the collector
+async fn send(
+    cfg: &OtlpConfig,
+    batch: &[FinishedSpan],
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let body = serde_json::to_vec(&request_body(cfg, batch))?;
+    let stream = TcpStream::connect(cfg.collector_addr).await?;
+    let (mut sender, connection) = hyper::client::conn::handshake(stream).await?;
+    tokio::spawn(connection);
+
+    let request = Request::post("/v1/traces")
+        .header(HOST, cfg.collector_addr.to_string())
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))?;
+    let response = sender.send_request(request).await?;
+    if !response.status().is_success() {
+        return Err(format!("Collector replied {}", response.status()).into());
+    }
+    Ok(())
+}
+
+/// `ExportTraceServiceRequest` in the OTLP/JSON encoding
+fn request_body(cfg: &OtlpConfig, batch: &[FinishedSpan]) -> Value {
+    let spans: Vec<Value> = batch
+        .iter()
+        .map(|span| {
+            let attributes: Vec<Value> = span
+                .record
+                .attributes
+                .iter()
+                .map(|(key, value)| attribute(key, value))
+                .collect();
+            json!({
+                "traceId": hex(&span.context.trace_id),
+                "spanId": hex(&span.context.span_id),
+                "parentSpanId": span.record.parent_id.as_ref().map(|id| hex(id)).unwrap_or_default(),
+                "flags": span.context.flags,
+                "name": span.name,
+                // SPAN_KIND_INTERNAL
+                "kind": 1,
+                "startTimeUnixNano": unix_nanos(span.record.start),
+                "endTimeUnixNano": unix_nanos(span.end),
+                "attributes": attributes,
+            })
+        })
+        .collect();
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [attribute("service.name", &cfg.service_name)],
+            },
+            "scopeSpans": [{
+                "scope": {
+                    "name": "meadow",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+                "spans": spans,
+            }],
+        }],
+    })
+}
+
+fn attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Nanoseconds since the Unix epoch, which OTLP/JSON writes as a string
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .map(|since| since.as_nanos())
+        .unwrap_or_default()
+        .to_string()
+}
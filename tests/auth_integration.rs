@@ -0,0 +1,177 @@
+#![deny(unused_must_use)]
+
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use std::thread;
+use std::time::Duration;
+
+fn start_host() -> Host {
+    let auth = AuthConfig::new()
+        .with_identity(
+            Identity::new("planner", "planner-token")
+                .allow("motor/*", &[Permission::Publish])
+                .allow_all("pose"),
+        )
+        .with_identity(
+            Identity::new("observer", "observer-token")
+                .allow("*", &[Permission::Request, Permission::Subscribe]),
+        );
+
+    let sc = SledConfig::new().temporary(true);
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_udp_config(Some(UdpConfig::default("lo")))
+        .with_auth_config(Some(auth));
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+    host
+}
+
+#[test]
+fn tcp_authorized_publish_and_request() {
+    let _host = start_host();
+
+    let node = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_credentials(Credentials::new("planner", "planner-token"))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    let pose = Pose { x: 1.0, y: 2.0 };
+    node.publish(pose.clone()).unwrap();
+    assert_eq!(node.request().unwrap().data, pose);
+}
+
+#[test]
+fn tcp_rejects_invalid_credentials() {
+    let _host = start_host();
+
+    let result = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_credentials(Credentials::new("planner", "wrong-token"))
+        .build()
+        .unwrap()
+        .activate();
+    assert_eq!(result.unwrap_err(), Error::Unauthenticated);
+
+    let result = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .build()
+        .unwrap()
+        .activate();
+    assert_eq!(result.unwrap_err(), Error::Unauthenticated);
+}
+
+#[test]
+fn tcp_denies_unpermitted_operations() {
+    let _host = start_host();
+
+    let observer = NodeConfig::<Blocking, Tcp, f32>::new("motor/left")
+        .with_credentials(Credentials::new("observer", "observer-token"))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    assert_eq!(
+        observer.publish(1.0).unwrap_err(),
+        Error::PermissionDenied {
+            identity: "observer".into(),
+            permission: Permission::Publish,
+            topic: "motor/left".into(),
+        }
+    );
+
+    let planner = NodeConfig::<Blocking, Tcp, f32>::new("motor/left")
+        .with_credentials(Credentials::new("planner", "planner-token"))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    planner.publish(1.0).unwrap();
+    assert!(matches!(
+        planner.request(),
+        Err(Error::PermissionDenied {
+            permission: Permission::Request,
+            ..
+        })
+    ));
+    assert_eq!(observer.request().unwrap().data, 1.0);
+}
+
+#[test]
+fn udp_denies_unpermitted_request() {
+    let _host = start_host();
+
+    let planner = NodeConfig::<Blocking, Tcp, f32>::new("motor/right")
+        .with_credentials(Credentials::new("planner", "planner-token"))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    planner.publish(2.0).unwrap();
+
+    let anonymous = NodeConfig::<Blocking, Udp, f32>::new("motor/right")
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    assert_eq!(anonymous.request().unwrap_err(), Error::Unauthenticated);
+
+    let planner = NodeConfig::<Blocking, Udp, f32>::new("motor/right")
+        .with_credentials(Credentials::new("planner", "planner-token"))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    assert!(planner.request().unwrap_err().is_access_denied());
+
+    let observer = NodeConfig::<Blocking, Udp, f32>::new("motor/right")
+        .with_credentials(Credentials::new("observer", "observer-token"))
+        .build()
+        .unwrap()
+        .subscribe(Duration::from_millis(10))
+        .unwrap();
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(observer.get_subscribed_data().unwrap().data, 2.0);
+}
+
+#[test]
+fn udp_authentication_times_out() {
+    // Nothing answers datagrams sent to this socket
+    let silent = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let result = NodeConfig::<Blocking, Udp, f32>::new("motor/right")
+        .with_config(
+            NetworkConfig::<Blocking, Udp>::default().set_host_addr(silent.local_addr().unwrap()),
+        )
+        .with_credentials(Credentials::new("planner", "planner-token"))
+        .build()
+        .unwrap()
+        .activate();
+    assert!(result.unwrap_err().is_connection_lost());
+}
+
+#[test]
+fn udp_publish_refused_with_auth() {
+    let _host = start_host();
+
+    // Publishes over UDP could come from anyone spoofing the planner's address
+    let planner = NodeConfig::<Blocking, Udp, f32>::new("motor/left")
+        .with_credentials(Credentials::new("planner", "planner-token"))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    planner.publish(2.0).unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    let observer = NodeConfig::<Blocking, Tcp, f32>::new("motor/left")
+        .with_credentials(Credentials::new("observer", "observer-token"))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    assert!(observer.request().is_err());
+}
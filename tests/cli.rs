@@ -0,0 +1,165 @@
+#![deny(unused_must_use)]
+#![cfg(feature = "cli")]
+
+use meadow::host::TcpConfig;
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use std::io::{BufRead, BufReader};
+use std::net::SocketAddr;
+use std::process::{Command, Output, Stdio};
+use std::thread;
+use std::time::Duration;
+
+const POSE_SCHEMA: &str = "{x: f32, y: f32}";
+
+fn start_host() -> (Host, SocketAddr) {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let config = HostConfig::default()
+        .with_sled_config(SledConfig::new().temporary(true))
+        .with_tcp_config(Some(TcpConfig::default("lo").set_bind_addr(addr)))
+        .with_udp_config(None);
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+    (host, addr)
+}
+
+fn meadow(addr: SocketAddr, args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_meadow"));
+    command.args(args).args(["--host", &addr.to_string()]);
+    command
+}
+
+fn run(addr: SocketAddr, args: &[&str]) -> String {
+    let Output {
+        status,
+        stdout,
+        stderr,
+    } = meadow(addr, args).output().unwrap();
+    assert!(status.success(), "{}", String::from_utf8_lossy(&stderr));
+    String::from_utf8(stdout).unwrap()
+}
+
+/// Parse each line printed by `echo` or `history` as JSON
+fn json_lines(output: &str) -> Vec<serde_json::Value> {
+    output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn cli_list_and_history() {
+    let (mut host, addr) = start_host();
+    for i in 0..3 {
+        host.insert("count", i as usize).unwrap();
+    }
+    host.insert("pose", Pose { x: 1.0, y: -2.0 }).unwrap();
+    assert_eq!(run(addr, &["list"]), "count\npose\n");
+
+    // Standard types are decoded from their data type names, oldest message first
+    let history = json_lines(&run(addr, &["history", "count", "-n", "2"]));
+    let data: Vec<_> = history.iter().map(|line| line["data"].clone()).collect();
+    assert_eq!(data, vec![1, 2]);
+    assert_eq!(history[0]["data_type"], "usize");
+
+    // Other types are decoded with a schema, or printed as bytes
+    let pose = &json_lines(&run(addr, &["history", "pose", "--schema", POSE_SCHEMA]))[0];
+    assert_eq!(pose["data"], serde_json::json!({"x": 1.0, "y": -2.0}));
+    let pose = &json_lines(&run(addr, &["history", "pose"]))[0];
+    assert_eq!(
+        pose["data"],
+        serde_json::json!([0, 0, 128, 63, 0, 0, 0, 192])
+    );
+}
+
+#[test]
+fn cli_pub() {
+    let (host, addr) = start_host();
+    run(
+        addr,
+        &[
+            "pub",
+            "pose",
+            r#"{"x": 3.0, "y": -4.5}"#,
+            "--schema",
+            POSE_SCHEMA,
+        ],
+    );
+    let pose = host.get::<Pose>("pose").unwrap();
+    assert_eq!(pose.data, Pose { x: 3.0, y: -4.5 });
+    assert_eq!(pose.data_type, POSE_SCHEMA);
+
+    // Messages without a schema are set as raw bytes
+    run(addr, &["pub", "raw", "[1, 2, 3]", "--type", "Bytes"]);
+    let raw = host.get::<RawData>("raw").unwrap();
+    assert_eq!(raw.data, RawData(vec![1, 2, 3]));
+    assert_eq!(raw.data_type, "Bytes");
+
+    let output = meadow(
+        addr,
+        &["pub", "pose", r#"{"x": 3.0}"#, "--schema", POSE_SCHEMA],
+    )
+    .output()
+    .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("missing field \"y\""));
+}
+
+#[test]
+fn cli_echo() {
+    let (mut host, addr) = start_host();
+    host.insert("count", 0usize).unwrap();
+    let mut child = meadow(addr, &["echo", "count", "--count", "3"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut lines = BufReader::new(child.stdout.take().unwrap()).lines();
+
+    // The latest message is printed first, followed by each new message
+    let line = lines.next().unwrap().unwrap();
+    assert_eq!(json_lines(&line)[0]["data"], 0);
+    for i in 1..3 {
+        host.insert("count", i as usize).unwrap();
+        thread::sleep(Duration::from_millis(5));
+    }
+    let data: Vec<_> = lines
+        .map(|line| json_lines(&line.unwrap())[0]["data"].clone())
+        .collect();
+    assert_eq!(data, vec![1, 2]);
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn cli_hz_and_info() {
+    let (mut host, addr) = start_host();
+    host.insert("count", 0usize).unwrap();
+    let child = meadow(addr, &["hz", "count", "--count", "2"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    for i in 1..100 {
+        host.insert("count", i as usize).unwrap();
+        thread::sleep(Duration::from_millis(20));
+    }
+    let output = String::from_utf8(child.wait_with_output().unwrap().stdout).unwrap();
+    let rate: f64 = output
+        .lines()
+        .filter_map(|line| line.strip_prefix("average rate: "))
+        .next_back()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(rate > 25.0 && rate < 55.0, "{}", rate);
+
+    let info = run(addr, &["info", "count", "--window", "10"]);
+    assert!(info.contains("data type: usize\n"), "{}", info);
+    assert!(info.contains("size: 1.00B\n"), "{}", info);
+    assert!(info.contains("over 10 messages"), "{}", info);
+}
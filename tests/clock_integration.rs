@@ -0,0 +1,141 @@
+#![deny(unused_must_use)]
+
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+fn start_host() -> Host {
+    let sc = SledConfig::new().temporary(true);
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_udp_config(Some(UdpConfig::default("lo")));
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+    host
+}
+
+/// The Node and Host share a clock here, so the offset is bounded by the round trip
+fn assert_synced(sync: ClockSync) {
+    assert!(sync.round_trip >= Duration::zero());
+    assert!(
+        sync.round_trip < Duration::seconds(1),
+        "{}",
+        sync.round_trip
+    );
+    assert!(
+        sync.offset.num_microseconds().unwrap().abs()
+            <= sync.round_trip.num_microseconds().unwrap()
+    );
+}
+
+#[test]
+fn clock_sync_tcp() {
+    let _host = start_host();
+    let node = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    assert_synced(node.sync_clock().unwrap());
+}
+
+#[test]
+fn clock_sync_udp() {
+    let _host = start_host();
+    let node = NodeConfig::<Blocking, Udp, Pose>::new("pose")
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    assert_synced(node.sync_clock().unwrap());
+}
+
+#[test]
+fn clock_sync_in_process() {
+    let sc = SledConfig::new().temporary(true);
+    let host = HostConfig::default().with_sled_config(sc).build().unwrap();
+    let node = NodeConfig::<Blocking, InProcess, Pose>::new("pose")
+        .with_config(
+            NetworkConfig::<Blocking, InProcess>::default()
+                .set_host_handle(host.in_process_handle()),
+        )
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    assert_synced(node.sync_clock().unwrap());
+}
+
+#[test]
+fn host_records_receive_time_tcp() {
+    let _host = start_host();
+    let node = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    let before = Utc::now();
+    node.publish(Pose::default()).unwrap();
+    let after = Utc::now();
+    let received = node.request().unwrap().received.unwrap();
+    assert!(before <= received && received <= after);
+}
+
+#[test]
+fn host_records_receive_time_in_process() {
+    let sc = SledConfig::new().temporary(true);
+    let host = HostConfig::default().with_sled_config(sc).build().unwrap();
+    let node = NodeConfig::<Blocking, InProcess, Pose>::new("pose")
+        .with_config(
+            NetworkConfig::<Blocking, InProcess>::default()
+                .set_host_handle(host.in_process_handle()),
+        )
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    let before = Utc::now();
+    node.publish(Pose::default()).unwrap();
+    let after = Utc::now();
+    let received = node.request().unwrap().received.unwrap();
+    assert!(before <= received && received <= after);
+}
+
+#[test]
+fn clock_correction_tcp() {
+    let _host = start_host();
+    let corrected = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_clock_correction(true)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    let uncorrected = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    let publish = |node: &Node<Blocking, Tcp, Active, Pose>, timestamp| {
+        let mut msg = Msg::new(MsgType::Set, "pose", Pose::default());
+        msg.set_timestamp(timestamp);
+        node.publish_msg(msg).unwrap();
+        node.request().unwrap().timestamp
+    };
+
+    // Timestamps aren't corrected until an offset has been measured
+    let timestamp = Utc::now() - Duration::seconds(10);
+    assert_eq!(publish(&corrected, timestamp), timestamp);
+
+    let sync = corrected.sync_clock().unwrap();
+    let timestamp = timestamp + Duration::seconds(1);
+    assert_eq!(publish(&corrected, timestamp), timestamp + sync.offset);
+
+    // Nodes only correct their timestamps if configured to
+    uncorrected.sync_clock().unwrap();
+    let timestamp = timestamp + Duration::seconds(1);
+    assert_eq!(publish(&uncorrected, timestamp), timestamp);
+}
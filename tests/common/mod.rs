@@ -1,7 +1,14 @@
 #![deny(unused_must_use)]
+// Each test binary only uses some of these helpers
+#![allow(dead_code)]
 
+use meadow::host::TcpConfig;
 use meadow::prelude::*;
 
+use std::net::{Ipv4Addr, SocketAddr};
+use std::thread;
+use std::time::{Duration, Instant};
+
 /// Example test struct for docs and tests
 #[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
 #[repr(C)]
@@ -15,3 +22,32 @@ pub struct Pose {
 pub struct NotPose {
     a: isize,
 }
+
+/// Local address whose port is assigned by the OS once a Host binds it, which the Host then
+/// reports with `Host::local_addr`
+pub fn any_port() -> SocketAddr {
+    SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0)
+}
+
+/// Configuration of a Host with a temporary store that only listens for TCP Nodes, at `addr` if
+/// it has one
+pub fn tcp_host_config(addr: Option<SocketAddr>) -> HostConfig {
+    let sc = SledConfig::new().temporary(true);
+    let tcp_cfg = addr.map(|addr| TcpConfig::default("lo").set_bind_addr(addr));
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_tcp_config(tcp_cfg)
+        .with_udp_config(None);
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    config
+}
+
+/// Poll until a condition holds, failing the test if it doesn't within a few seconds
+pub fn wait_for(mut condition: impl FnMut() -> bool) {
+    let start = Instant::now();
+    while !condition() {
+        assert!(start.elapsed() < Duration::from_secs(5), "timed out");
+        thread::sleep(Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,195 @@
+#![deny(unused_must_use)]
+
+use meadow::discovery::{discover, DiscoveryConfig, Transport};
+use meadow::host::TcpConfig;
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::time::Duration;
+
+fn discovery_config() -> DiscoveryConfig {
+    DiscoveryConfig::new("lo")
+        .with_interval(Duration::from_millis(100))
+        .with_timeout(Duration::from_secs(2))
+}
+
+fn start_host(name: &str, socket_num: u16) -> Host {
+    let sc = SledConfig::new().temporary(true);
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_name(name)
+        .with_tcp_config(Some(TcpConfig::default("lo").set_socket_num(socket_num)))
+        .with_udp_config(Some(UdpConfig::default("lo").set_socket_num(socket_num)))
+        .with_discovery_config(Some(discovery_config()));
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+    host
+}
+
+#[test]
+fn discover_host_beacon() {
+    let _host = start_host("discovery-beacon", 25_020);
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let beacon = runtime
+        .block_on(discover("discovery-beacon", &discovery_config()))
+        .unwrap();
+    assert_eq!(beacon.name, "discovery-beacon");
+    let addr = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 25_020);
+    assert_eq!(beacon.addr(Transport::Tcp), Some(addr));
+    assert_eq!(beacon.addr(Transport::Udp), Some(addr));
+    assert_eq!(beacon.addr(Transport::Quic), None);
+    assert_eq!(beacon.fingerprint, None);
+}
+
+#[test]
+fn tcp_node_discovers_host_by_name() {
+    let _other = start_host("discovery-other", 25_030);
+    let host = start_host("discovery-tcp", 25_040);
+
+    // The configured address is replaced by the one the named Host announces
+    let cfg = NetworkConfig::<Blocking, Tcp>::default()
+        .set_host_addr(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 1)))
+        .set_host_name("discovery-tcp")
+        .set_discovery_config(discovery_config());
+    let node = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_config(cfg)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    let pose = Pose { x: 1.0, y: 2.0 };
+    node.publish(pose.clone()).unwrap();
+    assert_eq!(node.request().unwrap().data, pose);
+    assert_eq!(host.topics(), vec!["pose".to_string()]);
+}
+
+#[test]
+fn udp_node_discovers_host_by_name() {
+    let host = start_host("discovery-udp", 25_050);
+
+    let cfg = NetworkConfig::<Blocking, Udp>::default()
+        .set_host_name("discovery-udp")
+        .set_discovery_config(discovery_config());
+    let node = NodeConfig::<Blocking, Udp, Pose>::new("pose")
+        .with_config(cfg)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    let pose = Pose { x: 3.0, y: 4.0 };
+    node.publish(pose.clone()).unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+    assert_eq!(host.get::<Pose>("pose").unwrap().data, pose);
+}
+
+#[test]
+fn undiscovered_host_times_out() {
+    let _host = start_host("discovery-present", 25_060);
+
+    let cfg = NetworkConfig::<Blocking, Tcp>::default()
+        .set_host_name("discovery-absent")
+        .set_discovery_config(discovery_config().with_timeout(Duration::from_millis(500)));
+    let result = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_config(cfg)
+        .build()
+        .unwrap()
+        .activate();
+    assert_eq!(
+        result.unwrap_err(),
+        Error::HostNotFound("discovery-absent".into())
+    );
+}
+
+#[cfg(feature = "quic")]
+#[test]
+fn quic_beacon_includes_certificate_fingerprint() {
+    use meadow::host::pki;
+    use meadow::host::quic::QuicCertGenConfig;
+
+    generate_certs(QuicCertGenConfig::default()).unwrap();
+    let sc = SledConfig::new().temporary(true);
+    let mut host = HostConfig::default()
+        .with_sled_config(sc)
+        .with_name("discovery-quic")
+        .with_tcp_config(None)
+        .with_quic_config(Some(QuicConfig::default()))
+        .with_discovery_config(Some(discovery_config()))
+        .build()
+        .unwrap();
+    host.start().unwrap();
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let beacon = runtime
+        .block_on(discover("discovery-quic", &discovery_config()))
+        .unwrap();
+    assert_eq!(
+        beacon.fingerprint,
+        Some(pki::fingerprint("target/cert.pem").unwrap())
+    );
+
+    let cfg = NetworkConfig::<Blocking, Quic>::default()
+        .set_host_name("discovery-quic")
+        .set_discovery_config(discovery_config());
+    let node = NodeConfig::<Blocking, Quic, f32>::new("speed")
+        .with_config(cfg)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    node.publish(1.5).unwrap();
+    assert_eq!(node.request().unwrap().data, 1.5);
+}
+
+#[cfg(feature = "quic")]
+#[test]
+fn quic_node_ignores_untrusted_beacon() {
+    use meadow::discovery::Beacon;
+    use meadow::host::quic::QuicCertGenConfig;
+
+    generate_certs(QuicCertGenConfig::default()).unwrap();
+    let group = SocketAddrV4::new(Ipv4Addr::new(239, 255, 25, 0), 25_098);
+    let discovery_cfg = discovery_config()
+        .with_group(group)
+        .with_timeout(Duration::from_millis(500));
+
+    // A beacon in the Host's name, which doesn't announce the Host's certificate
+    let spoofed = Beacon {
+        name: "discovery-spoofed".into(),
+        transports: vec![(
+            Transport::Quic,
+            SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1),
+        )],
+        fingerprint: Some("00:11:22:33".into()),
+    };
+    let bytes = postcard::to_allocvec(&spoofed).unwrap();
+    let spoofer = std::thread::spawn(move || {
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        for _ in 0..10 {
+            socket
+                .send_to(&bytes, (Ipv4Addr::LOCALHOST, group.port()))
+                .unwrap();
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    });
+
+    let cfg = NetworkConfig::<Blocking, Quic>::default()
+        .set_host_name("discovery-spoofed")
+        .set_discovery_config(discovery_cfg);
+    let result = NodeConfig::<Blocking, Quic, f32>::new("speed")
+        .with_config(cfg)
+        .build()
+        .unwrap()
+        .activate();
+    assert_eq!(
+        result.unwrap_err(),
+        Error::HostNotFound("discovery-spoofed".into())
+    );
+    spoofer.join().unwrap();
+}
@@ -0,0 +1,173 @@
+#![deny(unused_must_use)]
+
+use meadow::prelude::*;
+mod common;
+use common::{any_port, tcp_host_config, wait_for, Pose};
+
+use std::net::SocketAddr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn start_host(replication_cfg: Option<ReplicationConfig>, auth_cfg: Option<AuthConfig>) -> Host {
+    let mut host = tcp_host_config(Some(any_port()))
+        .with_replication_config(replication_cfg)
+        .with_auth_config(auth_cfg)
+        .build()
+        .unwrap();
+    host.start().unwrap();
+    host
+}
+
+fn start_standby(primary_addr: SocketAddr, failover_timeout: Option<Duration>) -> Host {
+    let replication_cfg =
+        ReplicationConfig::new(primary_addr).set_failover_timeout(failover_timeout);
+    start_host(Some(replication_cfg), None)
+}
+
+fn len(host: &Host, topic: &str) -> usize {
+    host.db().open_tree(topic).unwrap().len()
+}
+
+#[test]
+fn standby_replicates_primary() {
+    let mut primary = start_host(None, None);
+    for i in 0..5 {
+        primary.insert("count", i as usize).unwrap();
+    }
+    let standby = start_standby(primary.local_addr(Transport::Tcp).unwrap(), None);
+    assert_eq!(primary.role(), HostRole::Primary);
+    assert_eq!(standby.role(), HostRole::Standby);
+
+    // Existing messages are replicated, followed by each message as it's set
+    wait_for(|| len(&standby, "count") == 5);
+    primary.insert("count", 5usize).unwrap();
+    primary.insert("pose", Pose { x: 1.0, y: 2.0 }).unwrap();
+    wait_for(|| len(&standby, "count") == 6 && len(&standby, "pose") == 1);
+    assert_eq!(
+        standby.get::<Pose>("pose").unwrap(),
+        primary.get::<Pose>("pose").unwrap()
+    );
+    let count = standby.get::<usize>("count").unwrap();
+    assert_eq!(count, primary.get::<usize>("count").unwrap());
+    assert_eq!(count.data, 5);
+}
+
+#[test]
+fn standby_promotes_itself() {
+    let primary = start_host(None, None);
+    let standby = start_standby(
+        primary.local_addr(Transport::Tcp).unwrap(),
+        Some(Duration::from_millis(500)),
+    );
+    thread::sleep(Duration::from_millis(1_000));
+    assert_eq!(standby.role(), HostRole::Standby);
+
+    drop(primary);
+    wait_for(|| standby.role() == HostRole::Primary);
+}
+
+#[test]
+fn standby_promoted_manually() {
+    let primary = start_host(None, None);
+    let mut standby = start_standby(primary.local_addr(Transport::Tcp).unwrap(), None);
+
+    drop(primary);
+    thread::sleep(Duration::from_millis(1_500));
+    assert_eq!(standby.role(), HostRole::Standby);
+    standby.promote();
+    assert_eq!(standby.role(), HostRole::Primary);
+}
+
+#[test]
+fn replication_requires_access_to_every_topic() {
+    let auth = AuthConfig::new()
+        .with_identity(Identity::new("standby", "standby-token").allow("*", &[Permission::Request]))
+        .with_identity(
+            Identity::new("reader", "reader-token").allow("pose", &[Permission::Request]),
+        );
+    let mut primary = start_host(None, Some(auth));
+    primary.insert("pose", Pose { x: 1.0, y: 2.0 }).unwrap();
+    let primary_addr = primary.local_addr(Transport::Tcp).unwrap();
+
+    let replication_cfg = |identity: &str, token: &str| {
+        ReplicationConfig::new(primary_addr)
+            .set_failover_timeout(Some(Duration::from_millis(500)))
+            .set_credentials(Credentials::new(identity, token))
+    };
+    let reader = start_host(Some(replication_cfg("reader", "reader-token")), None);
+    let standby = start_host(Some(replication_cfg("standby", "standby-token")), None);
+    wait_for(|| len(&standby, "pose") == 1);
+
+    // A refused standby doesn't replicate the primary, nor take over from it
+    thread::sleep(Duration::from_millis(1_000));
+    assert_eq!(len(&reader, "pose"), 0);
+    assert_eq!(reader.role(), HostRole::Standby);
+}
+
+#[test]
+fn node_fails_over_to_standby() {
+    let primary = start_host(None, None);
+    let primary_addr = primary.local_addr(Transport::Tcp).unwrap();
+    let standby = start_standby(primary_addr, Some(Duration::from_millis(500)));
+    let standby_addr = standby.local_addr(Transport::Tcp).unwrap();
+
+    let network_cfg =
+        NetworkConfig::<Blocking, Tcp>::default().set_host_addrs(vec![primary_addr, standby_addr]);
+    let node = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_config(network_cfg.clone())
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    let subscriber = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_config(network_cfg)
+        .build()
+        .unwrap()
+        .subscribe(Duration::from_millis(10))
+        .unwrap();
+
+    node.publish(Pose { x: 1.0, y: 1.0 }).unwrap();
+    wait_for(|| len(&standby, "pose") == 1);
+    wait_for(|| subscriber.get_subscribed_data().is_ok());
+
+    // Nodes reconnect to the standby, which already holds the primary's messages
+    drop(primary);
+    assert_eq!(node.request().unwrap().data, Pose { x: 1.0, y: 1.0 });
+    node.publish(Pose { x: 2.0, y: 2.0 }).unwrap();
+    assert_eq!(
+        standby.get::<Pose>("pose").unwrap().data,
+        Pose { x: 2.0, y: 2.0 }
+    );
+    wait_for(|| subscriber.get_subscribed_data().unwrap().data == Pose { x: 2.0, y: 2.0 });
+    wait_for(|| standby.role() == HostRole::Primary);
+}
+
+#[test]
+fn standby_rejects_oversized_frames() {
+    use std::io::{Read, Write};
+
+    let primary = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let replication_cfg = ReplicationConfig::new(primary.local_addr().unwrap())
+        .set_failover_timeout(None)
+        .set_retry_interval(Duration::from_millis(10));
+    let _standby = start_host(Some(replication_cfg), None);
+
+    // A primary that accepts the standby, then announces a frame of 4 GiB
+    let (mut stream, _addr) = primary.accept().unwrap();
+    let mut buf = [0u8; 1024];
+    assert!(stream.read(&mut buf).unwrap() > 0);
+    let auth = GenericMsg::auth("primary", None).unwrap();
+    stream.write_all(&auth.as_bytes().unwrap()).unwrap();
+    assert!(stream.read(&mut buf).unwrap() > 0);
+    let authorized = postcard::to_allocvec(&Ok::<(), Error>(())).unwrap();
+    stream
+        .write_all(&(authorized.len() as u32).to_le_bytes())
+        .unwrap();
+    stream.write_all(&authorized).unwrap();
+    stream.write_all(&u32::MAX.to_le_bytes()).unwrap();
+
+    // The standby drops the connection without waiting for the frame, and reconnects
+    let start = Instant::now();
+    let _reconnected = primary.accept().unwrap();
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
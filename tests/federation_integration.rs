@@ -0,0 +1,174 @@
+#![deny(unused_must_use)]
+
+use meadow::prelude::*;
+mod common;
+use common::{any_port, tcp_host_config, wait_for, Pose};
+
+use std::net::SocketAddr;
+use std::thread;
+use std::time::Duration;
+
+fn start_host(addr: Option<SocketAddr>, peers: Vec<PeerConfig>) -> Host {
+    let mut config = tcp_host_config(addr);
+    for peer in peers {
+        config = config.with_peer(peer);
+    }
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+    host
+}
+
+fn peer(addr: SocketAddr) -> PeerConfig {
+    PeerConfig::new(addr).set_retry_interval(Duration::from_millis(100))
+}
+
+#[test]
+fn federation_push_namespaced() {
+    let base = start_host(Some(any_port()), vec![]);
+    let addr = base.local_addr(Transport::Tcp).unwrap();
+    let mut robot = start_host(
+        None,
+        vec![peer(addr)
+            .set_namespace("robot1")
+            .with_bridge(TopicBridge::push("pose", "pose"))],
+    );
+
+    robot.insert("pose", Pose { x: 1.0, y: 2.0 }).unwrap();
+    wait_for(|| base.get::<Pose>("robot1/pose").is_ok());
+    assert_eq!(
+        base.get::<Pose>("robot1/pose").unwrap().data,
+        Pose { x: 1.0, y: 2.0 }
+    );
+
+    // Mirrored messages keep their original timestamps
+    robot.insert("pose", Pose { x: 3.0, y: 4.0 }).unwrap();
+    wait_for(|| base.get::<Pose>("robot1/pose").unwrap().data.x == 3.0);
+    assert_eq!(
+        base.get::<Pose>("robot1/pose").unwrap().timestamp,
+        robot.get::<Pose>("pose").unwrap().timestamp
+    );
+    assert!(base.get::<Pose>("pose").is_err());
+}
+
+#[test]
+fn federation_pull_renamed() {
+    let mut robot = start_host(Some(any_port()), vec![]);
+    let addr = robot.local_addr(Transport::Tcp).unwrap();
+    let base = start_host(
+        None,
+        vec![peer(addr).with_bridge(TopicBridge::pull("pose", "robot_pose"))],
+    );
+
+    // Topics are pulled once the peer has them
+    thread::sleep(Duration::from_millis(200));
+    assert!(base.get::<Pose>("robot_pose").is_err());
+    for i in 0..3 {
+        robot
+            .insert(
+                "pose",
+                Pose {
+                    x: i as f32,
+                    y: 0.0,
+                },
+            )
+            .unwrap();
+        wait_for(|| matches!(base.get::<Pose>("robot_pose"), Ok(msg) if msg.data.x == i as f32));
+    }
+    assert_eq!(base.topics(), vec!["robot_pose".to_string()]);
+}
+
+#[test]
+fn federation_rate_limit() {
+    let base = start_host(Some(any_port()), vec![]);
+    let addr = base.local_addr(Transport::Tcp).unwrap();
+    let mut robot = start_host(
+        None,
+        vec![peer(addr).with_bridge(
+            TopicBridge::push("count", "count").set_min_interval(Duration::from_millis(500)),
+        )],
+    );
+    robot.insert("count", 0usize).unwrap();
+    wait_for(|| base.get::<usize>("count").is_ok());
+
+    // Messages set within the interval are skipped, apart from the latest
+    for i in 1..=20 {
+        robot.insert("count", i as usize).unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+    wait_for(|| base.get::<usize>("count").unwrap().data == 20);
+    assert!(base.db().open_tree("count").unwrap().len() <= 3);
+}
+
+#[test]
+fn federation_cycle_does_not_loop() {
+    let bridges = |addr| {
+        peer(addr)
+            .with_bridge(TopicBridge::push("shared", "shared"))
+            .with_bridge(TopicBridge::pull("pulled", "pulled"))
+    };
+    // Each Host needs the other's address, so host_b is restarted at its address once host_a
+    // has one
+    let addr_b = start_host(Some(any_port()), vec![])
+        .local_addr(Transport::Tcp)
+        .unwrap();
+    let mut host_a = start_host(Some(any_port()), vec![bridges(addr_b)]);
+    let addr_a = host_a.local_addr(Transport::Tcp).unwrap();
+    let mut host_b = start_host(Some(addr_b), vec![bridges(addr_a)]);
+    thread::sleep(Duration::from_millis(200));
+
+    host_a.insert("shared", 1usize).unwrap();
+    host_b.insert("pulled", 2usize).unwrap();
+    wait_for(|| host_b.get::<usize>("shared").is_ok() && host_a.get::<usize>("pulled").is_ok());
+
+    // Once mirrored, messages aren't set over and over again on either Host
+    thread::sleep(Duration::from_millis(300));
+    let subscribers: Vec<sled::Subscriber> = [&host_a, &host_b]
+        .iter()
+        .flat_map(|host| ["shared", "pulled"].map(|t| host.db().open_tree(t).unwrap()))
+        .map(|tree| tree.watch_prefix(vec![]))
+        .collect();
+    thread::sleep(Duration::from_millis(500));
+    for mut subscriber in subscribers {
+        assert!(subscriber.next_timeout(Duration::from_millis(1)).is_err());
+    }
+    for host in [&host_a, &host_b] {
+        assert_eq!(host.db().open_tree("shared").unwrap().len(), 1);
+        assert_eq!(host.db().open_tree("pulled").unwrap().len(), 1);
+    }
+}
+
+#[test]
+fn federation_survives_restart() {
+    let base = start_host(Some(any_port()), vec![]);
+    let addr = base.local_addr(Transport::Tcp).unwrap();
+    let mut robot = start_host(
+        None,
+        vec![peer(addr)
+            .with_bridge(TopicBridge::push("pose", "pose"))
+            .with_bridge(TopicBridge::pull("cmd", "cmd"))],
+    );
+    robot.insert("pose", Pose { x: 1.0, y: 1.0 }).unwrap();
+    wait_for(|| base.get::<Pose>("pose").is_ok());
+
+    // Messages set while the peer is down reach it once it's back
+    drop(base);
+    robot.insert("pose", Pose { x: 2.0, y: 2.0 }).unwrap();
+    thread::sleep(Duration::from_millis(300));
+    let mut base = start_host(Some(addr), vec![]);
+    wait_for(|| base.get::<Pose>("pose").is_ok());
+    assert_eq!(
+        base.get::<Pose>("pose").unwrap().data,
+        Pose { x: 2.0, y: 2.0 }
+    );
+    base.insert("cmd", "stop".to_string()).unwrap();
+    wait_for(|| robot.get::<String>("cmd").is_ok());
+
+    // A restarted Host links back up to its peer
+    drop(robot);
+    let robot = start_host(
+        None,
+        vec![peer(addr).with_bridge(TopicBridge::pull("cmd", "cmd"))],
+    );
+    base.insert("cmd", "go".to_string()).unwrap();
+    wait_for(|| matches!(robot.get::<String>("cmd"), Ok(msg) if msg.data == "go"));
+}
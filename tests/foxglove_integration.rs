@@ -0,0 +1,268 @@
+#![deny(unused_must_use)]
+#![cfg(feature = "foxglove")]
+
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::convert::TryInto;
+use tokio::net::TcpStream;
+use tokio::time::{sleep, timeout, Duration};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::WebSocketStream;
+
+const POSE_SCHEMA: &str =
+    r#"{"type":"object","properties":{"x":{"type":"number"},"y":{"type":"number"}}}"#;
+
+fn start_host(foxglove_cfg: FoxgloveConfig) -> Host {
+    let sc = SledConfig::new().temporary(true);
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_tcp_config(None)
+        .with_udp_config(None)
+        .with_foxglove_config(Some(foxglove_cfg.set_bind_addr(([127, 0, 0, 1], 0))));
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+    host
+}
+
+/// Minimal Foxglove client
+struct Client {
+    ws: WebSocketStream<TcpStream>,
+}
+
+impl Client {
+    async fn connect(host: &Host) -> Client {
+        let addr = host.foxglove_addr().unwrap();
+        let mut request = format!("ws://{}", addr).into_client_request().unwrap();
+        request.headers_mut().insert(
+            "Sec-WebSocket-Protocol",
+            HeaderValue::from_static("foxglove.websocket.v1"),
+        );
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let (ws, response) = tokio_tungstenite::client_async(request, stream)
+            .await
+            .unwrap();
+        assert_eq!(
+            response.headers()["Sec-WebSocket-Protocol"],
+            "foxglove.websocket.v1"
+        );
+        Client { ws }
+    }
+
+    async fn next(&mut self) -> WsMessage {
+        timeout(Duration::from_secs(2), self.ws.next())
+            .await
+            .expect("timed out waiting for the server")
+            .unwrap()
+            .unwrap()
+    }
+
+    /// Wait for the next JSON message with the given op
+    async fn op(&mut self, op: &str) -> Value {
+        loop {
+            if let WsMessage::Text(text) = self.next().await {
+                let msg: Value = serde_json::from_str(&text).unwrap();
+                if msg["op"] == op {
+                    return msg;
+                }
+            }
+        }
+    }
+
+    /// Wait for the next message data, returning its subscription ID and JSON payload
+    async fn message_data(&mut self) -> (u32, Value) {
+        loop {
+            if let WsMessage::Binary(bytes) = self.next().await {
+                assert_eq!(bytes[0], 0x01);
+                let id = u32::from_le_bytes(bytes[1..5].try_into().unwrap());
+                return (id, serde_json::from_slice(&bytes[13..]).unwrap());
+            }
+        }
+    }
+
+    /// ID of the channel advertised for a topic
+    async fn channel(&mut self, topic: &str) -> (u64, Value) {
+        loop {
+            let advertise = self.op("advertise").await;
+            for channel in advertise["channels"].as_array().unwrap() {
+                if channel["topic"] == topic {
+                    return (channel["id"].as_u64().unwrap(), channel.clone());
+                }
+            }
+        }
+    }
+
+    async fn send(&mut self, msg: Value) {
+        self.ws
+            .send(WsMessage::Text(msg.to_string()))
+            .await
+            .unwrap();
+    }
+
+    async fn publish(&mut self, channel_id: u32, payload: Value) {
+        let mut frame = vec![0x01];
+        frame.extend_from_slice(&channel_id.to_le_bytes());
+        frame.extend_from_slice(payload.to_string().as_bytes());
+        self.ws.send(WsMessage::Binary(frame)).await.unwrap();
+    }
+}
+
+#[test]
+fn foxglove_advertise_and_stream() {
+    let mut host =
+        start_host(FoxgloveConfig::default().with_schema::<Pose>("pose", "Pose", POSE_SCHEMA));
+    host.insert("pose", Pose { x: 0.0, y: 0.0 }).unwrap();
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let mut client = Client::connect(&host).await;
+        let info = client.op("serverInfo").await;
+        assert_eq!(info["supportedEncodings"], json!(["json"]));
+        assert_eq!(info["capabilities"], json!([]));
+
+        let (channel_id, channel) = client.channel("pose").await;
+        assert_eq!(channel["encoding"], "json");
+        assert_eq!(channel["schemaName"], "Pose");
+        assert_eq!(channel["schema"], POSE_SCHEMA);
+        client
+            .send(json!({"op": "subscribe", "subscriptions": [{"id": 7, "channelId": channel_id}]}))
+            .await;
+        sleep(Duration::from_millis(100)).await;
+
+        for i in 1..4 {
+            host.insert(
+                "pose",
+                Pose {
+                    x: i as f32,
+                    y: 2.0,
+                },
+            )
+            .unwrap();
+            let (id, payload) = client.message_data().await;
+            assert_eq!(id, 7);
+            assert_eq!(payload, json!({"x": i as f32, "y": 2.0}));
+        }
+    });
+}
+
+#[test]
+fn foxglove_streams_node_publishes() {
+    let mut host = start_host(FoxgloveConfig::default());
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let mut client = Client::connect(&host).await;
+
+        // Topics created after the client connects are advertised as they appear
+        let handle = host.in_process_handle();
+        std::thread::spawn(move || {
+            let node = NodeConfig::<Blocking, InProcess, usize>::new("count")
+                .with_config(
+                    NetworkConfig::<Blocking, InProcess>::default().set_host_handle(handle),
+                )
+                .build()
+                .unwrap()
+                .activate()
+                .unwrap();
+            node.publish(0).unwrap();
+        })
+        .join()
+        .unwrap();
+
+        // Topics without a registered schema are sent as their raw bytes
+        let (channel_id, channel) = client.channel("count").await;
+        assert_eq!(channel["schemaName"], "meadow.GenericMsg");
+        client
+            .send(json!({"op": "subscribe", "subscriptions": [{"id": 1, "channelId": channel_id}]}))
+            .await;
+        sleep(Duration::from_millis(100)).await;
+
+        host.insert("count", 5usize).unwrap();
+        let (id, payload) = client.message_data().await;
+        assert_eq!(id, 1);
+        assert_eq!(payload["data"], json!([5]));
+
+        // No more data arrives once unsubscribed
+        client
+            .send(json!({"op": "unsubscribe", "subscriptionIds": [1]}))
+            .await;
+        sleep(Duration::from_millis(100)).await;
+        host.insert("count", 6usize).unwrap();
+        let next = timeout(Duration::from_millis(700), client.message_data()).await;
+        assert!(next.is_err());
+    });
+}
+
+#[test]
+fn foxglove_client_publish() {
+    let host = start_host(
+        FoxgloveConfig::default()
+            .with_schema::<Pose>("pose", "Pose", POSE_SCHEMA)
+            .set_client_publish(true),
+    );
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let mut client = Client::connect(&host).await;
+        let info = client.op("serverInfo").await;
+        assert_eq!(info["capabilities"], json!(["clientPublish"]));
+
+        client
+            .send(json!({"op": "advertise", "channels": [
+                {"id": 3, "topic": "pose", "encoding": "json", "schemaName": "Pose"},
+                {"id": 4, "topic": "unknown", "encoding": "json", "schemaName": "Unknown"},
+            ]}))
+            .await;
+        client.publish(3, json!({"x": 1.0, "y": 2.0})).await;
+        sleep(Duration::from_millis(100)).await;
+        assert_eq!(
+            host.get::<Pose>("pose").unwrap().data,
+            Pose { x: 1.0, y: 2.0 }
+        );
+
+        // Payloads must match the topic's schema, and topics must have one
+        client.publish(3, json!({"z": 1.0})).await;
+        assert_eq!(client.op("status").await["level"], 2);
+        client.publish(4, json!({"x": 1.0})).await;
+        assert_eq!(client.op("status").await["level"], 2);
+        assert!(host.get::<Pose>("unknown").is_err());
+    });
+}
+
+#[test]
+fn foxglove_client_publish_disabled() {
+    let host =
+        start_host(FoxgloveConfig::default().with_schema::<Pose>("pose", "Pose", POSE_SCHEMA));
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let mut client = Client::connect(&host).await;
+        client
+            .send(json!({"op": "advertise", "channels": [
+                {"id": 3, "topic": "pose", "encoding": "json", "schemaName": "Pose"},
+            ]}))
+            .await;
+        assert_eq!(client.op("status").await["level"], 2);
+        client.publish(3, json!({"x": 1.0, "y": 2.0})).await;
+        assert_eq!(client.op("status").await["level"], 2);
+        assert!(host.get::<Pose>("pose").is_err());
+    });
+}
+
+#[test]
+fn foxglove_refused_with_auth() {
+    let auth = AuthConfig::new().with_identity(Identity::new("viewer", "viewer-token"));
+    let result = HostConfig::default()
+        .with_sled_config(SledConfig::new().temporary(true))
+        .with_auth_config(Some(auth))
+        .with_foxglove_config(Some(FoxgloveConfig::default()))
+        .build();
+    assert_eq!(result.err(), Some(Error::UnsupportedOperation));
+}
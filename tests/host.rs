@@ -1,12 +1,13 @@
+use meadow::host::TcpConfig;
 use meadow::prelude::*;
-use rand::{random, Rng};
+use rand::Rng;
 
 #[test]
 fn host_only_ops() {
     let sc = SledConfig::new().temporary(true);
     let mut host = HostConfig::default().with_sled_config(sc).build().unwrap();
     let mut rng = rand::thread_rng();
-    for i in 0..10 {
+    for _ in 0..10 {
         let data: usize = rng.gen();
         host.insert("test", data).unwrap();
         let back: usize = host.get("test").unwrap().data;
@@ -14,3 +15,77 @@ fn host_only_ops() {
         assert_eq!(data, back);
     }
 }
+
+fn localhost(port: u16) -> std::net::SocketAddr {
+    std::net::SocketAddr::new(std::net::Ipv4Addr::LOCALHOST.into(), port)
+}
+
+fn ephemeral_host() -> Host {
+    let sc = SledConfig::new().temporary(true);
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_tcp_config(Some(TcpConfig::default("lo").set_bind_addr(localhost(0))))
+        .with_udp_config(Some(UdpConfig::default("lo").set_bind_addr(localhost(0))));
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+    host
+}
+
+#[test]
+fn host_reports_assigned_ports() {
+    let host_a = ephemeral_host();
+    let host_b = ephemeral_host();
+
+    for host in [&host_a, &host_b] {
+        let addrs = host.local_addrs();
+        assert_eq!(addrs.len(), 2);
+        for (_transport, addr) in addrs {
+            assert_eq!(addr.ip(), localhost(0).ip());
+            assert_ne!(addr.port(), 0);
+        }
+    }
+    let tcp_a = host_a.local_addr(Transport::Tcp).unwrap();
+    let tcp_b = host_b.local_addr(Transport::Tcp).unwrap();
+    assert_ne!(tcp_a, tcp_b);
+
+    // Each Host only receives the data published to its own addresses
+    for (i, addr) in [tcp_a, tcp_b].iter().enumerate() {
+        let node = NodeConfig::<Blocking, Tcp, usize>::new("index")
+            .with_config(NetworkConfig::<Blocking, Tcp>::default().set_host_addr(*addr))
+            .build()
+            .unwrap()
+            .activate()
+            .unwrap();
+        node.publish(i).unwrap();
+    }
+    let node = NodeConfig::<Blocking, Udp, usize>::new("index")
+        .with_config(
+            NetworkConfig::<Blocking, Udp>::default()
+                .set_host_addr(host_b.local_addr(Transport::Udp).unwrap()),
+        )
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    assert_eq!(node.request().unwrap().data, 1);
+    assert_eq!(host_a.get::<usize>("index").unwrap().data, 0);
+}
+
+#[test]
+fn host_start_fails_when_address_in_use() {
+    let taken = std::net::TcpListener::bind(localhost(0)).unwrap();
+    let addr = taken.local_addr().unwrap();
+
+    let sc = SledConfig::new().temporary(true);
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_tcp_config(Some(TcpConfig::default("lo").set_bind_addr(addr)))
+        .with_udp_config(None);
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    assert!(matches!(host.start(), Err(Error::Io { .. })));
+    assert!(host.local_addrs().is_empty());
+}
@@ -0,0 +1,253 @@
+#![deny(unused_must_use)]
+#![cfg(feature = "http")]
+
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use serde_json::{json, Value};
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+fn start_host(http_cfg: HttpConfig, auth: Option<AuthConfig>) -> Host {
+    let sc = SledConfig::new().temporary(true);
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_tcp_config(None)
+        .with_udp_config(None)
+        .with_auth_config(auth)
+        .with_http_config(Some(http_cfg.set_bind_addr(([127, 0, 0, 1], 0))));
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+    host
+}
+
+/// Send a request, returning the response's status code and body
+fn request(
+    addr: SocketAddr,
+    method: &str,
+    path: &str,
+    body: Option<&str>,
+    authorization: Option<&str>,
+) -> (u16, String) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    let mut request = format!(
+        "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n",
+        method, path, addr
+    );
+    if let Some(authorization) = authorization {
+        request += &format!("Authorization: {}\r\n", authorization);
+    }
+    let body = body.unwrap_or("");
+    request += &format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+    stream.write_all(request.as_bytes()).unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let status = response[9..12].parse().unwrap();
+    let body = response.split_once("\r\n\r\n").unwrap().1.to_string();
+    (status, body)
+}
+
+fn get_json(addr: SocketAddr, path: &str) -> (u16, Value) {
+    let (status, body) = request(addr, "GET", path, None, None);
+    (status, serde_json::from_str(&body).unwrap())
+}
+
+#[test]
+fn http_get_topics_and_messages() {
+    let mut host = start_host(HttpConfig::default().with_type::<Pose>("pose"), None);
+    let addr = host.http_addr().unwrap();
+    for i in 0..5 {
+        host.insert(
+            "pose",
+            Pose {
+                x: i as f32,
+                y: 0.0,
+            },
+        )
+        .unwrap();
+    }
+    host.insert("count", 3usize).unwrap();
+
+    assert_eq!(get_json(addr, "/topics"), (200, json!(["count", "pose"])));
+
+    let (status, msg) = get_json(addr, "/topics/pose");
+    assert_eq!(status, 200);
+    assert_eq!(msg["topic"], "pose");
+    assert_eq!(msg["data"], json!({"x": 4.0, "y": 0.0}));
+
+    // Topics without a registered type are served as their raw bytes
+    let (status, msg) = get_json(addr, "/topics/count");
+    assert_eq!(status, 200);
+    assert_eq!(msg["data"], json!([3]));
+
+    let (status, history) = get_json(addr, "/topics/pose/history?n=3");
+    assert_eq!(status, 200);
+    let xs: Vec<Value> = history
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|msg| msg["data"]["x"].clone())
+        .collect();
+    assert_eq!(xs, vec![json!(2.0), json!(3.0), json!(4.0)]);
+
+    assert_eq!(get_json(addr, "/topics/doesnt_exist").0, 404);
+    assert_eq!(get_json(addr, "/topics/doesnt_exist/history").0, 404);
+    assert_eq!(get_json(addr, "/topics/pose/history?n=x").0, 400);
+    assert_eq!(get_json(addr, "/elsewhere").0, 404);
+    assert_eq!(request(addr, "DELETE", "/topics/pose", None, None).0, 405);
+}
+
+#[test]
+fn http_post_message() {
+    let host = start_host(HttpConfig::default().with_type::<Pose>("pose"), None);
+    let addr = host.http_addr().unwrap();
+
+    let body = r#"{"x": 1.0, "y": 2.0}"#;
+    assert_eq!(
+        request(addr, "POST", "/topics/pose", Some(body), None).0,
+        204
+    );
+    assert_eq!(
+        host.get::<Pose>("pose").unwrap().data,
+        Pose { x: 1.0, y: 2.0 }
+    );
+
+    // Payloads must be JSON matching the topic's registered type
+    let (status, _) = request(addr, "POST", "/topics/pose", Some("{"), None);
+    assert_eq!(status, 400);
+    let (status, _) = request(addr, "POST", "/topics/pose", Some(r#"{"z": 1}"#), None);
+    assert_eq!(status, 400);
+    let (status, _) = request(addr, "POST", "/topics/count", Some("1"), None);
+    assert_eq!(status, 415);
+}
+
+#[test]
+fn http_post_continues_trace() {
+    let host = start_host(HttpConfig::default().with_type::<Pose>("pose"), None);
+    let addr = host.http_addr().unwrap();
+
+    let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+    let body = r#"{"x": 1.0, "y": 2.0}"#;
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "POST /topics/pose HTTP/1.1\r\nHost: {}\r\nConnection: close\r\ntraceparent: {}\r\nContent-Length: {}\r\n\r\n{}",
+        addr,
+        traceparent,
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 204"), "{}", response);
+
+    // The Host doesn't track spans here, so the client's context is stored as it is
+    assert_eq!(
+        host.get::<Pose>("pose").unwrap().trace,
+        Some(traceparent.parse().unwrap())
+    );
+}
+
+#[test]
+fn http_post_body_limit() {
+    let host = start_host(
+        HttpConfig::default()
+            .with_type::<String>("log")
+            .set_max_body_size(64),
+        None,
+    );
+    let addr = host.http_addr().unwrap();
+
+    let body = json!("a".repeat(100)).to_string();
+    assert_eq!(
+        request(addr, "POST", "/topics/log", Some(&body), None).0,
+        413
+    );
+    assert_eq!(
+        request(addr, "POST", "/topics/log", Some("\"short\""), None).0,
+        204
+    );
+}
+
+#[test]
+fn http_server_sent_events() {
+    let mut host = start_host(HttpConfig::default().with_type::<Pose>("pose"), None);
+    let addr = host.http_addr().unwrap();
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(2)))
+        .unwrap();
+    write!(
+        stream,
+        "GET /topics/pose/events HTTP/1.1\r\nHost: {}\r\n\r\n",
+        addr
+    )
+    .unwrap();
+    let mut reader = BufReader::new(stream);
+    let mut status = String::new();
+    reader.read_line(&mut status).unwrap();
+    assert!(status.starts_with("HTTP/1.1 200"));
+    thread::sleep(Duration::from_millis(100));
+
+    for i in 0..3 {
+        host.insert(
+            "pose",
+            Pose {
+                x: i as f32,
+                y: 1.0,
+            },
+        )
+        .unwrap();
+        let event = loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if let Some(data) = line.strip_prefix("data: ") {
+                break serde_json::from_str::<Value>(data).unwrap();
+            }
+        };
+        assert_eq!(event["data"], json!({"x": i as f32, "y": 1.0}));
+    }
+}
+
+#[test]
+fn http_access_control() {
+    let auth = AuthConfig::new()
+        .with_identity(
+            Identity::new("dashboard", "dashboard-token").allow("*", &[Permission::Request]),
+        )
+        .with_identity(Identity::new("script", "script-token").allow_all("pose"));
+    let mut host = start_host(HttpConfig::default().with_type::<Pose>("pose"), Some(auth));
+    let addr = host.http_addr().unwrap();
+    host.insert("pose", Pose { x: 1.0, y: 2.0 }).unwrap();
+
+    // "dashboard:dashboard-token", "script:script-token" and "dashboard:wrong"
+    let dashboard = Some("Basic ZGFzaGJvYXJkOmRhc2hib2FyZC10b2tlbg==");
+    let script = Some("Basic c2NyaXB0OnNjcmlwdC10b2tlbg==");
+    let wrong = Some("Basic ZGFzaGJvYXJkOndyb25n");
+
+    // Listing topics doesn't require any permission
+    assert_eq!(request(addr, "GET", "/topics", None, None).0, 200);
+    assert_eq!(request(addr, "GET", "/topics/pose", None, None).0, 401);
+    assert_eq!(request(addr, "GET", "/topics/pose", None, wrong).0, 401);
+    assert_eq!(request(addr, "GET", "/topics/pose", None, dashboard).0, 200);
+
+    let body = Some(r#"{"x": 3.0, "y": 4.0}"#);
+    assert_eq!(
+        request(addr, "POST", "/topics/pose", body, dashboard).0,
+        403
+    );
+    assert_eq!(request(addr, "POST", "/topics/pose", body, script).0, 204);
+    assert_eq!(
+        host.get::<Pose>("pose").unwrap().data,
+        Pose { x: 3.0, y: 4.0 }
+    );
+}
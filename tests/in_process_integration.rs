@@ -0,0 +1,255 @@
+#![deny(unused_must_use)]
+
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use std::thread;
+use std::time::Duration;
+
+type N = InProcess;
+
+/// In-process Hosts don't need to be started, so no ports are bound
+fn build_host() -> Host {
+    let sc = SledConfig::new().temporary(true);
+    HostConfig::default().with_sled_config(sc).build().unwrap()
+}
+
+fn node_config<T: Message>(topic: &str, host: &Host) -> NodeConfig<Blocking, N, T> {
+    NodeConfig::new(topic).with_config(
+        NetworkConfig::<Blocking, N>::default().set_host_handle(host.in_process_handle()),
+    )
+}
+
+#[test]
+fn integrate_host_and_single_node_in_process() {
+    let host = build_host();
+
+    let node = node_config::<Pose>("pose", &host)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    for i in 0..5 {
+        let pose = Pose {
+            x: i as f32,
+            y: i as f32,
+        };
+
+        node.publish(pose.clone()).unwrap();
+        let result = node.request().unwrap();
+        assert_eq!(pose, result.data);
+        assert_eq!(host.get::<Pose>("pose").unwrap().data, pose);
+    }
+}
+
+#[test]
+fn request_non_existent_topic_in_process() {
+    let host = build_host();
+
+    let node = node_config::<Pose>("doesnt_exist", &host)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    assert_eq!(
+        node.request().unwrap_err(),
+        Error::NonExistentTopic("doesnt_exist".into())
+    );
+}
+
+#[test]
+fn missing_host_handle_in_process() {
+    let result = NodeConfig::<Blocking, N, Pose>::new("pose")
+        .build()
+        .unwrap()
+        .activate();
+    assert!(result.is_err());
+}
+
+#[test]
+fn subscription_usize_in_process() {
+    let host = build_host();
+
+    let writer = node_config::<usize>("subscription", &host)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    // Create a subscription node with a query rate of 100 Hz
+    let reader = writer
+        .config()
+        .clone()
+        .build()
+        .unwrap()
+        .subscribe(Duration::from_millis(10))
+        .unwrap();
+
+    for i in 0..5 {
+        let test_value = i as usize;
+        writer.publish(test_value).unwrap();
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(reader.get_subscribed_data().unwrap().data, test_value);
+    }
+}
+
+#[test]
+fn in_process_back_nth_operation() {
+    let host = build_host();
+
+    let node = node_config::<Pose>("pose", &host)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    let n = 5;
+    for i in 0..n {
+        let pose = Pose {
+            x: i as f32,
+            y: i as f32,
+        };
+        node.publish(pose.clone()).unwrap();
+        assert_eq!(node.request_nth_back(0).unwrap().data, pose);
+    }
+    let back = 3;
+    let pose = Pose {
+        x: (n - back) as f32,
+        y: (n - back) as f32,
+    };
+    assert_eq!(node.request_nth_back(back - 1).unwrap().data, pose);
+    assert_eq!(node.request_nth_back(10).unwrap_err(), Error::NoNthValue);
+}
+
+#[test]
+fn topics_list_in_process() {
+    let host = build_host();
+
+    let a = node_config::<usize>("a", &host)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    let b = node_config::<usize>("b", &host)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    a.publish(0).unwrap();
+    b.publish(1).unwrap();
+
+    assert_eq!(a.topics().unwrap().data, vec!["a", "b"]);
+}
+
+#[test]
+fn nonblocking_in_process() {
+    let host = build_host();
+
+    // The Node owns a runtime of its own, so it must be dropped outside of this one
+    let node = NodeConfig::<Nonblocking, N, Pose>::new("pose")
+        .with_config(
+            NetworkConfig::<Nonblocking, N>::default().set_host_handle(host.in_process_handle()),
+        )
+        .build()
+        .unwrap();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _node = runtime.block_on(async {
+        let node = node.activate().await.unwrap();
+
+        let pose = Pose { x: 1.0, y: 2.0 };
+        node.publish(pose.clone()).await.unwrap();
+        assert_eq!(node.request().await.unwrap().data, pose);
+        assert_eq!(node.request_nth_back(0).await.unwrap().data, pose);
+        node
+    });
+}
+
+#[test]
+fn in_process_and_tcp_nodes_share_host() {
+    let sc = SledConfig::new().temporary(true);
+    let tcp_cfg = meadow::host::TcpConfig::default("lo")
+        .set_bind_addr("127.0.0.1:0".parse::<std::net::SocketAddr>().unwrap());
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_tcp_config(Some(tcp_cfg));
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+
+    let local = node_config::<Pose>("pose", &host)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    let remote = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_config(
+            NetworkConfig::<Blocking, Tcp>::default()
+                .set_host_addr(host.local_addr(Transport::Tcp).unwrap()),
+        )
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    let pose = Pose { x: 1.0, y: 2.0 };
+    local.publish(pose.clone()).unwrap();
+    assert_eq!(remote.request().unwrap().data, pose);
+
+    let pose = Pose { x: 3.0, y: 4.0 };
+    remote.publish(pose.clone()).unwrap();
+    assert_eq!(local.request().unwrap().data, pose);
+}
+
+#[test]
+fn in_process_access_control() {
+    let auth = AuthConfig::new()
+        .with_identity(Identity::new("planner", "planner-token").allow_all("pose"))
+        .with_identity(
+            Identity::new("observer", "observer-token").allow("*", &[Permission::Request]),
+        );
+    let sc = SledConfig::new().temporary(true);
+    let host = HostConfig::default()
+        .with_sled_config(sc)
+        .with_auth_config(Some(auth))
+        .build()
+        .unwrap();
+
+    let result = node_config::<Pose>("pose", &host)
+        .with_credentials(Credentials::new("planner", "wrong-token"))
+        .build()
+        .unwrap()
+        .activate();
+    assert_eq!(result.unwrap_err(), Error::Unauthenticated);
+
+    let planner = node_config::<Pose>("pose", &host)
+        .with_credentials(Credentials::new("planner", "planner-token"))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    let pose = Pose { x: 1.0, y: 2.0 };
+    planner.publish(pose.clone()).unwrap();
+
+    let observer = node_config::<Pose>("pose", &host)
+        .with_credentials(Credentials::new("observer", "observer-token"))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    assert_eq!(observer.request().unwrap().data, pose);
+    assert!(observer
+        .publish(pose.clone())
+        .unwrap_err()
+        .is_access_denied());
+
+    let result = node_config::<Pose>("pose", &host)
+        .with_credentials(Credentials::new("observer", "observer-token"))
+        .build()
+        .unwrap()
+        .subscribe(Duration::from_millis(10));
+    assert!(result.unwrap_err().is_access_denied());
+}
@@ -0,0 +1,194 @@
+#![deny(unused_must_use)]
+
+use meadow::host::{AddressFamily, TcpConfig};
+use meadow::networks::get_socket_addr;
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6};
+use std::time::Duration;
+
+fn start_host(address_family: AddressFamily, socket_num: u16) -> Host {
+    let sc = SledConfig::new().temporary(true);
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_tcp_config(Some(
+            TcpConfig::default("lo")
+                .set_socket_num(socket_num)
+                .set_address_family(address_family),
+        ))
+        .with_udp_config(Some(
+            UdpConfig::default("lo")
+                .set_socket_num(socket_num)
+                .set_address_family(address_family),
+        ));
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+    host
+}
+
+fn tcp_node(host_addr: SocketAddr) -> Node<Blocking, Tcp, Active, Pose> {
+    NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_config(NetworkConfig::<Blocking, Tcp>::default().set_host_addr(host_addr))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap()
+}
+
+#[test]
+fn resolve_interface_addresses() {
+    assert_eq!(
+        get_socket_addr("lo", AddressFamily::Ipv4, 25_000).unwrap(),
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 25_000)
+    );
+    assert_eq!(
+        get_socket_addr("lo", AddressFamily::Ipv6, 25_000).unwrap(),
+        SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 25_000)
+    );
+    assert_eq!(
+        get_socket_addr("lo", AddressFamily::DualStack, 25_000).unwrap(),
+        SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 25_000)
+    );
+    assert_eq!(
+        get_socket_addr("not-an-interface", AddressFamily::Ipv6, 25_000),
+        Err(Error::InvalidInterface)
+    );
+}
+
+#[test]
+fn ipv6_host() {
+    let host = start_host(AddressFamily::Ipv6, 25_100);
+    let host_addr = SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 25_100);
+
+    let node = tcp_node(host_addr);
+    let pose = Pose { x: 1.0, y: 2.0 };
+    node.publish(pose.clone()).unwrap();
+    assert_eq!(node.request().unwrap().data, pose);
+
+    let node = NodeConfig::<Blocking, Udp, Pose>::new("pose")
+        .with_config(NetworkConfig::<Blocking, Udp>::default().set_host_addr(host_addr))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    let pose = Pose { x: 3.0, y: 4.0 };
+    node.publish(pose.clone()).unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+    assert_eq!(host.get::<Pose>("pose").unwrap().data, pose);
+    assert_eq!(node.request().unwrap().data, pose);
+
+    // IPv6-only Hosts don't accept IPv4 connections
+    let result = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_config(
+            NetworkConfig::<Blocking, Tcp>::default()
+                .set_host_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 25_100)),
+        )
+        .build()
+        .unwrap()
+        .activate();
+    assert!(result.is_err());
+}
+
+#[test]
+fn dual_stack_host() {
+    let _host = start_host(AddressFamily::DualStack, 25_110);
+
+    let v4 = tcp_node(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 25_110));
+    let v6 = tcp_node(SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 25_110));
+
+    let pose = Pose { x: 5.0, y: 6.0 };
+    v4.publish(pose.clone()).unwrap();
+    assert_eq!(v6.request().unwrap().data, pose);
+
+    let udp = NodeConfig::<Blocking, Udp, Pose>::new("pose")
+        .with_config(
+            NetworkConfig::<Blocking, Udp>::default()
+                .set_host_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 25_110)),
+        )
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    assert_eq!(udp.request().unwrap().data, pose);
+}
+
+/// First scoped link-local IPv6 address on this machine, read from `/proc/net/if_inet6`
+fn link_local_addr(port: u16) -> Option<SocketAddrV6> {
+    let table = std::fs::read_to_string("/proc/net/if_inet6").ok()?;
+    table.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let segments: Vec<u16> = (0..8)
+            .map(|i| u16::from_str_radix(fields[0].get(i * 4..i * 4 + 4)?, 16).ok())
+            .collect::<Option<Vec<u16>>>()?;
+        if segments[0] & 0xffc0 != 0xfe80 {
+            return None;
+        }
+        let index = u32::from_str_radix(fields[1], 16).ok()?;
+        let ip = Ipv6Addr::new(
+            segments[0],
+            segments[1],
+            segments[2],
+            segments[3],
+            segments[4],
+            segments[5],
+            segments[6],
+            segments[7],
+        );
+        Some(SocketAddrV6::new(ip, port, 0, index))
+    })
+}
+
+#[test]
+fn scoped_link_local_host() {
+    let host_addr = match link_local_addr(25_120) {
+        Some(addr) => addr,
+        None => {
+            println!("No link-local IPv6 address available, skipping");
+            return;
+        }
+    };
+    let _host = start_host(AddressFamily::DualStack, 25_120);
+
+    let node = tcp_node(SocketAddr::V6(host_addr));
+    let pose = Pose { x: 7.0, y: 8.0 };
+    node.publish(pose.clone()).unwrap();
+    assert_eq!(node.request().unwrap().data, pose);
+}
+
+#[cfg(feature = "quic")]
+#[test]
+fn quic_ipv6_host() {
+    use meadow::host::quic::QuicCertGenConfig;
+
+    generate_certs(QuicCertGenConfig::default()).unwrap();
+    let sc = SledConfig::new().temporary(true);
+    let mut quic_cfg = QuicConfig::default();
+    quic_cfg.network_cfg = quic_cfg
+        .network_cfg
+        .set_socket_num(25_130u16)
+        .set_address_family(AddressFamily::Ipv6);
+    let mut host = HostConfig::default()
+        .with_sled_config(sc)
+        .with_tcp_config(None)
+        .with_quic_config(Some(quic_cfg))
+        .build()
+        .unwrap();
+    host.start().unwrap();
+
+    let node = NodeConfig::<Blocking, Quic, Pose>::new("pose")
+        .with_config(
+            NetworkConfig::<Blocking, Quic>::default()
+                .set_host_addr(SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 25_130)),
+        )
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    let pose = Pose { x: 9.0, y: 10.0 };
+    node.publish(pose.clone()).unwrap();
+    assert_eq!(node.request().unwrap().data, pose);
+}
@@ -0,0 +1,142 @@
+#![deny(unused_must_use)]
+
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use std::thread;
+use std::time::Duration;
+
+fn start_host() -> Host {
+    let sc = SledConfig::new().temporary(true);
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_udp_config(None);
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+    host
+}
+
+fn tcp_node(tracker: Option<LatencyTracker>) -> Node<Blocking, Tcp, Active, Pose> {
+    let mut config = NodeConfig::<Blocking, Tcp, Pose>::new("pose");
+    if let Some(tracker) = tracker {
+        config = config.with_latency_tracker(tracker);
+    }
+    config.build().unwrap().activate().unwrap()
+}
+
+fn assert_recorded(report: &LatencyReport, count: u64) {
+    for histogram in [
+        &report.end_to_end,
+        &report.to_host,
+        &report.on_host,
+        &report.from_host,
+    ] {
+        assert_eq!(histogram.count(), count);
+        assert!(histogram.p50() <= histogram.p99());
+        assert!(histogram.p99() <= histogram.max());
+        assert!(histogram.max() < Duration::from_secs(1));
+        let bucketed: u64 = histogram
+            .buckets()
+            .iter()
+            .map(|(_bound, count)| count)
+            .sum();
+        assert_eq!(bucketed, count);
+    }
+}
+
+#[test]
+fn latency_tcp() {
+    let _host = start_host();
+    let publisher = tcp_node(Some(LatencyTracker::new()));
+    let requester = tcp_node(Some(LatencyTracker::new()));
+
+    for i in 0..10 {
+        publisher
+            .publish(Pose {
+                x: i as f32,
+                y: 0.0,
+            })
+            .unwrap();
+        let msg = requester.request().unwrap();
+
+        // The Node and Host share a clock here, so each hop follows the last
+        let latency = msg.latency.unwrap();
+        let sent = latency.sent.unwrap();
+        let received = msg.received.unwrap();
+        let forwarded = latency.forwarded.unwrap();
+        let delivered = latency.delivered.unwrap();
+        assert!(sent <= received && received <= forwarded && forwarded <= delivered);
+    }
+    // Requesting the last message again doesn't count it twice
+    requester.request().unwrap();
+
+    assert_recorded(&requester.latency().unwrap(), 10);
+    // The publisher only stamps its messages
+    assert_eq!(publisher.latency(), None);
+}
+
+#[test]
+fn latency_subscription_tcp() {
+    let _host = start_host();
+    let tracker = LatencyTracker::new();
+    let publisher = tcp_node(Some(tracker.clone()));
+    let subscriber = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_latency_tracker(tracker.clone())
+        .build()
+        .unwrap()
+        .subscribe(Duration::from_millis(5))
+        .unwrap();
+
+    for i in 0..5 {
+        publisher
+            .publish(Pose {
+                x: i as f32,
+                y: 0.0,
+            })
+            .unwrap();
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    let report = subscriber.latency().unwrap();
+    assert_recorded(&report, 5);
+    // Subscriptions are polled, so messages wait on the Host for up to the subscription's rate
+    assert!(report.on_host.max() < Duration::from_millis(100));
+    assert_eq!(tracker.reports().len(), 1);
+    assert_eq!(tracker.report("pose"), Some(report));
+}
+
+#[test]
+fn latency_in_process() {
+    let sc = SledConfig::new().temporary(true);
+    let host = HostConfig::default().with_sled_config(sc).build().unwrap();
+    let node = NodeConfig::<Blocking, InProcess, Pose>::new("pose")
+        .with_config(
+            NetworkConfig::<Blocking, InProcess>::default()
+                .set_host_handle(host.in_process_handle()),
+        )
+        .with_latency_tracker(LatencyTracker::new())
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    for _ in 0..3 {
+        node.publish(Pose::default()).unwrap();
+        node.request().unwrap();
+    }
+    assert_recorded(&node.latency().unwrap(), 3);
+}
+
+#[test]
+fn untracked_msgs_carry_no_latency() {
+    let _host = start_host();
+    let publisher = tcp_node(None);
+    let requester = tcp_node(Some(LatencyTracker::new()));
+
+    publisher.publish(Pose::default()).unwrap();
+    assert_eq!(requester.request().unwrap().latency, None);
+    assert_eq!(requester.latency(), None);
+}
@@ -0,0 +1,217 @@
+#![deny(unused_must_use)]
+#![cfg(feature = "mcap")]
+
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const POSE_SCHEMA: &str =
+    r#"{"type":"object","properties":{"x":{"type":"number"},"y":{"type":"number"}}}"#;
+const MAGIC: &[u8] = b"\x89MCAP0\r\n";
+
+fn start_host(sled_cfg: SledConfig, recorder_cfg: Option<RecorderConfig>) -> Host {
+    let config = HostConfig::default()
+        .with_sled_config(sled_cfg)
+        .with_tcp_config(None)
+        .with_udp_config(None)
+        .with_recorder_config(recorder_cfg);
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+    host
+}
+
+fn temporary_host() -> Host {
+    start_host(SledConfig::new().temporary(true), None)
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("meadow_{}_{}", std::process::id(), name))
+}
+
+/// Check a played message against the original, which it matches other than being received again
+fn assert_replayed<T: Message + PartialEq>(played: Msg<T>, original: &Msg<T>) {
+    assert!(played.received > original.received);
+    assert_eq!(
+        &Msg {
+            received: original.received,
+            ..played
+        },
+        original
+    );
+}
+
+/// Record the messages set by `set` on a Host, finishing the recording once the Host is dropped
+fn record(recorder_cfg: RecorderConfig, set: impl FnOnce(&mut Host)) {
+    let mut host = start_host(SledConfig::new().temporary(true), Some(recorder_cfg));
+    // Give the recorder a moment to start watching the Host
+    thread::sleep(Duration::from_millis(100));
+    set(&mut host);
+    thread::sleep(Duration::from_millis(100));
+}
+
+#[test]
+fn mcap_record_and_play() {
+    let path = temp_path("record_and_play.mcap");
+    let recorder_cfg = RecorderConfig::new(&path).with_schema::<Pose>("pose", "Pose", POSE_SCHEMA);
+    let mut originals = Vec::new();
+    record(recorder_cfg, |host| {
+        for i in 0..3 {
+            host.insert(
+                "pose",
+                Pose {
+                    x: i as f32,
+                    y: 1.0,
+                },
+            )
+            .unwrap();
+            host.insert("count", i as usize).unwrap();
+            originals.push((
+                host.get::<Pose>("pose").unwrap(),
+                host.get::<usize>("count").unwrap(),
+            ));
+        }
+    });
+
+    // The recording is a complete MCAP file, with the JSON schema of `pose`
+    let bytes = std::fs::read(&path).unwrap();
+    assert!(bytes.starts_with(MAGIC) && bytes.ends_with(MAGIC));
+    let contains = |needle: &[u8]| bytes.windows(needle.len()).any(|w| w == needle);
+    assert!(contains(POSE_SCHEMA.as_bytes()));
+    assert!(contains(br#"{"x":2.0,"y":1.0}"#));
+
+    // Messages are played back with their data type, and their timestamps if kept
+    let host = temporary_host();
+    let player_cfg = PlayerConfig::new(&path)
+        .with_type::<Pose>("pose")
+        .set_original_timestamps(true);
+    host.play(player_cfg).unwrap();
+    for (n, (pose, count)) in originals.iter().rev().enumerate() {
+        assert_replayed(host.db().get_nth_back::<Pose>("pose", n).unwrap(), pose);
+        assert_replayed(host.db().get_nth_back::<usize>("count", n).unwrap(), count);
+    }
+
+    // Messages recorded as JSON can't be played without the topic's type
+    let host = temporary_host();
+    host.play(PlayerConfig::new(&path)).unwrap();
+    assert_eq!(host.topics(), vec!["count".to_string()]);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn mcap_topic_filters() {
+    let path = temp_path("topic_filters.mcap");
+    let recorder_cfg = RecorderConfig::new(&path).with_topic("a").with_topic("b");
+    record(recorder_cfg, |host| {
+        for topic in ["a", "b", "c"] {
+            host.insert(topic, topic.to_string()).unwrap();
+        }
+    });
+
+    let host = temporary_host();
+    host.play(PlayerConfig::new(&path)).unwrap();
+    let mut topics = host.topics();
+    topics.sort();
+    assert_eq!(topics, vec!["a".to_string(), "b".to_string()]);
+
+    let host = temporary_host();
+    host.play(PlayerConfig::new(&path).with_topic("b")).unwrap();
+    assert_eq!(host.topics(), vec!["b".to_string()]);
+    assert_eq!(host.get::<String>("b").unwrap().data, "b");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn mcap_play_timing() {
+    let path = temp_path("timing.mcap");
+    record(RecorderConfig::new(&path), |host| {
+        for i in 0..3 {
+            host.insert("count", i as usize).unwrap();
+            thread::sleep(Duration::from_millis(200));
+        }
+    });
+
+    // Messages are set at the intervals they were recorded at, scaled by the speed
+    for (speed, expected) in [(1.0, 400), (2.0, 200)] {
+        let host = temporary_host();
+        let start = Instant::now();
+        host.play(PlayerConfig::new(&path).set_speed(speed))
+            .unwrap();
+        let elapsed = start.elapsed().as_millis();
+        assert!(
+            elapsed >= expected && elapsed < expected + 150,
+            "{}ms",
+            elapsed
+        );
+        assert_eq!(host.db().open_tree("count").unwrap().len(), 3);
+    }
+    let host = temporary_host();
+    let start = Instant::now();
+    host.play(PlayerConfig::new(&path).set_speed(f64::INFINITY))
+        .unwrap();
+    assert!(start.elapsed() < Duration::from_millis(100));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn mcap_play_looping() {
+    let path = temp_path("looping.mcap");
+    record(RecorderConfig::new(&path), |host| {
+        for i in 0..2 {
+            host.insert("count", i as usize).unwrap();
+            thread::sleep(Duration::from_millis(20));
+        }
+    });
+
+    // Looped messages are stamped as they're set, so every pass adds to the topic
+    let player_cfg = PlayerConfig::new(&path).set_looping(true);
+    let config = HostConfig::default()
+        .with_sled_config(SledConfig::new().temporary(true))
+        .with_tcp_config(None)
+        .with_udp_config(None)
+        .with_player_config(Some(player_cfg));
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+    thread::sleep(Duration::from_millis(300));
+    assert!(host.db().open_tree("count").unwrap().len() > 4);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn play_sled_log() {
+    let path = temp_path("log.sled");
+    let mut host = start_host(SledConfig::new().path(&path), None);
+    host.insert("pose", Pose { x: 1.0, y: 2.0 }).unwrap();
+    host.insert("count", 1usize).unwrap();
+    let (pose, count) = (
+        host.get::<Pose>("pose").unwrap(),
+        host.get::<usize>("count").unwrap(),
+    );
+    drop(host);
+
+    let host = temporary_host();
+    host.play(PlayerConfig::new(&path).set_original_timestamps(true))
+        .unwrap();
+    assert_replayed(host.get::<Pose>("pose").unwrap(), &pose);
+    assert_replayed(host.get::<usize>("count").unwrap(), &count);
+    std::fs::remove_dir_all(&path).unwrap();
+}
+
+#[test]
+fn play_invalid_recording() {
+    let path = temp_path("invalid.mcap");
+    std::fs::write(&path, b"not a recording").unwrap();
+    let host = temporary_host();
+    assert!(matches!(
+        host.play(PlayerConfig::new(&path)),
+        Err(Error::InvalidRecording(_))
+    ));
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,275 @@
+#![deny(unused_must_use)]
+#![cfg(feature = "metrics")]
+
+use meadow::host::{MetricsConfig, TcpConfig};
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+fn start_host(auth: Option<AuthConfig>) -> Host {
+    let sc = SledConfig::new().temporary(true);
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_tcp_config(Some(
+            TcpConfig::default("lo").set_bind_addr(([127, 0, 0, 1], 0)),
+        ))
+        .with_udp_config(Some(
+            UdpConfig::default("lo").set_bind_addr(([127, 0, 0, 1], 0)),
+        ))
+        .with_auth_config(auth)
+        .with_metrics_config(Some(
+            MetricsConfig::default().set_bind_addr(([127, 0, 0, 1], 0)),
+        ));
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+    host
+}
+
+fn node_config(host: &Host) -> NodeConfig<Blocking, Tcp, Pose> {
+    let addr = host.local_addr(Transport::Tcp).unwrap();
+    NodeConfig::new("pose")
+        .with_config(NetworkConfig::<Blocking, Tcp>::default().set_host_addr(addr))
+}
+
+/// Send a request, returning the response's status code and body
+fn request(addr: SocketAddr, method: &str, path: &str) -> (u16, String) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    let request = format!(
+        "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nContent-Length: 0\r\n\r\n",
+        method, path, addr
+    );
+    stream.write_all(request.as_bytes()).unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let status = response[9..12].parse().unwrap();
+    let body = response.split_once("\r\n\r\n").unwrap().1.to_string();
+    (status, body)
+}
+
+fn scrape(host: &Host) -> String {
+    let (status, body) = request(host.metrics_addr().unwrap(), "GET", "/metrics");
+    assert_eq!(status, 200);
+    body
+}
+
+/// Value of the sample with the given name and labels
+fn value(metrics: &str, sample: &str) -> f64 {
+    metrics
+        .lines()
+        .find_map(|line| line.strip_prefix(sample)?.strip_prefix(' '))
+        .unwrap_or_else(|| panic!("No sample {} in:\n{}", sample, metrics))
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn metrics_count_requests() {
+    let host = start_host(None);
+
+    let node = node_config(&host).build().unwrap().activate().unwrap();
+    for i in 0..3 {
+        node.publish(Pose {
+            x: i as f32,
+            y: 0.0,
+        })
+        .unwrap();
+    }
+    node.request().unwrap();
+    let _subscriber = node_config(&host)
+        .build()
+        .unwrap()
+        .subscribe(Duration::from_millis(10))
+        .unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    let metrics = scrape(&host);
+    assert_eq!(
+        value(&metrics, r#"meadow_connections{transport="tcp"}"#),
+        2.0
+    );
+    assert!(!metrics.contains(r#"meadow_connections{transport="udp"}"#));
+
+    assert_eq!(
+        value(
+            &metrics,
+            r#"meadow_requests_total{topic="pose",type="set"}"#
+        ),
+        3.0
+    );
+    assert_eq!(
+        value(
+            &metrics,
+            r#"meadow_requests_total{topic="pose",type="get"}"#
+        ),
+        1.0
+    );
+    assert_eq!(
+        value(
+            &metrics,
+            r#"meadow_requests_total{topic="pose",type="subscribe"}"#
+        ),
+        1.0
+    );
+    assert_eq!(
+        value(&metrics, r#"meadow_messages_total{topic="pose"}"#),
+        3.0
+    );
+    // Poses are always two `f32`s
+    assert_eq!(
+        value(&metrics, r#"meadow_message_bytes_total{topic="pose"}"#),
+        24.0
+    );
+    assert_eq!(
+        value(&metrics, r#"meadow_subscriptions{topic="pose"}"#),
+        1.0
+    );
+    assert!(value(&metrics, "meadow_store_size_bytes") > 0.0);
+
+    assert_eq!(
+        value(
+            &metrics,
+            r#"meadow_request_duration_seconds_count{type="set"}"#
+        ),
+        3.0
+    );
+    assert_eq!(
+        value(
+            &metrics,
+            r#"meadow_request_duration_seconds_bucket{type="get",le="+Inf"}"#
+        ),
+        1.0
+    );
+    assert!(!metrics.contains(r#"meadow_request_duration_seconds_count{type="subscribe"}"#));
+}
+
+#[test]
+fn metrics_subscription_ends_with_node() {
+    let host = start_host(None);
+
+    let node = node_config(&host).build().unwrap().activate().unwrap();
+    node.publish(Pose::default()).unwrap();
+    drop(node);
+    let subscriber = node_config(&host)
+        .build()
+        .unwrap()
+        .subscribe(Duration::from_millis(10))
+        .unwrap();
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(
+        value(&scrape(&host), r#"meadow_connections{transport="tcp"}"#),
+        1.0
+    );
+
+    // The subscription's task ends along with its connection once the Node disconnects
+    drop(subscriber);
+    thread::sleep(Duration::from_millis(100));
+    let metrics = scrape(&host);
+    assert_eq!(
+        value(&metrics, r#"meadow_connections{transport="tcp"}"#),
+        0.0
+    );
+    assert_eq!(
+        value(&metrics, r#"meadow_subscriptions{topic="pose"}"#),
+        0.0
+    );
+}
+
+#[test]
+fn metrics_count_failures() {
+    let auth = AuthConfig::new().with_identity(Identity::new("planner", "planner-token"));
+    let host = start_host(Some(auth));
+
+    let result = node_config(&host)
+        .with_credentials(Credentials::new("planner", "wrong-token"))
+        .build()
+        .unwrap()
+        .activate();
+    assert!(result.is_err());
+
+    let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket
+        .send_to(&[0xff; 4], host.local_addr(Transport::Udp).unwrap())
+        .unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    let metrics = scrape(&host);
+    assert_eq!(
+        value(
+            &metrics,
+            r#"meadow_handshake_failures_total{transport="tcp"}"#
+        ),
+        1.0
+    );
+    assert_eq!(
+        value(&metrics, r#"meadow_decode_errors_total{transport="udp"}"#),
+        1.0
+    );
+    assert_eq!(
+        value(&metrics, r#"meadow_connections{transport="tcp"}"#),
+        0.0
+    );
+}
+
+#[test]
+fn metrics_count_only_authorized_requests() {
+    let auth = AuthConfig::new().with_identity(
+        Identity::new("planner", "planner-token")
+            .allow("pose", &[Permission::Publish])
+            .allow("missing/*", &[Permission::Request]),
+    );
+    let host = start_host(Some(auth));
+
+    let node = node_config(&host)
+        .with_credentials(Credentials::new("planner", "planner-token"))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    node.publish(Pose::default()).unwrap();
+    // Denied, as the planner can only publish to `pose`
+    assert!(node.request().is_err());
+    for i in 0..3 {
+        let missing = NodeConfig::<Blocking, Tcp, Pose>::new(format!("missing/{}", i))
+            .with_config(node_config(&host).network_cfg)
+            .with_credentials(Credentials::new("planner", "planner-token"))
+            .build()
+            .unwrap()
+            .activate()
+            .unwrap();
+        assert!(missing.request().is_err());
+    }
+
+    let metrics = scrape(&host);
+    assert_eq!(
+        value(
+            &metrics,
+            r#"meadow_requests_total{topic="pose",type="set"}"#
+        ),
+        1.0
+    );
+    assert!(!metrics.contains(r#"meadow_requests_total{topic="pose",type="get"}"#));
+    assert_eq!(
+        value(
+            &metrics,
+            r#"meadow_requests_total{topic="other",type="get"}"#
+        ),
+        3.0
+    );
+    assert!(!metrics.contains("missing"));
+}
+
+#[test]
+fn metrics_endpoint_paths() {
+    let host = start_host(None);
+    let addr = host.metrics_addr().unwrap();
+    assert_eq!(request(addr, "GET", "/").0, 404);
+    assert_eq!(request(addr, "POST", "/metrics").0, 405);
+}
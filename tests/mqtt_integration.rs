@@ -0,0 +1,308 @@
+#![deny(unused_must_use)]
+#![cfg(feature = "mqtt")]
+
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use bytes::BytesMut;
+use rumqttc::mqttbytes::v4::{self, ConnAck, ConnectReturnCode, Packet, PubAck, Publish, SubAck};
+use rumqttc::mqttbytes::v4::{PingResp, SubscribeReasonCode};
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+use std::convert::TryInto;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Broker state shared between client connections
+#[derive(Default)]
+struct State {
+    /// Topic filters of each client, and the channel delivering publishes to it
+    clients: Vec<(Vec<String>, mpsc::UnboundedSender<Publish>)>,
+    /// Every publish received from a client
+    published: Vec<Publish>,
+}
+
+/// Minimal MQTT 3.1.1 broker, standing in for a real one
+struct Broker {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    _runtime: tokio::runtime::Runtime,
+}
+
+impl Broker {
+    fn start() -> Broker {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let state: Arc<Mutex<State>> = Arc::default();
+        let listener = runtime.block_on(TcpListener::bind("127.0.0.1:0")).unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let shared = state.clone();
+        runtime.spawn(async move {
+            while let Ok((stream, _addr)) = listener.accept().await {
+                tokio::spawn(serve_client(stream, shared.clone()));
+            }
+        });
+        Broker {
+            addr,
+            state,
+            _runtime: runtime,
+        }
+    }
+
+    /// Deliver a message to subscribed clients, as if published by another client
+    fn inject(&self, topic: &str, payload: impl Into<Vec<u8>>) {
+        let publish = Publish::new(topic, rumqttc::QoS::AtMostOnce, payload);
+        route(&self.state, publish);
+    }
+
+    fn subscribed(&self, filter: &str) -> bool {
+        let state = self.state.lock().unwrap();
+        state
+            .clients
+            .iter()
+            .any(|(filters, _tx)| filters.iter().any(|f| f == filter))
+    }
+
+    fn published(&self, topic: &str) -> Vec<Publish> {
+        let state = self.state.lock().unwrap();
+        state
+            .published
+            .iter()
+            .filter(|publish| publish.topic == topic)
+            .cloned()
+            .collect()
+    }
+}
+
+fn route(state: &Mutex<State>, publish: Publish) {
+    let state = state.lock().unwrap();
+    for (filters, tx) in &state.clients {
+        // Exact topics are enough for these tests, apart from single-level wildcards
+        let matches = filters.iter().any(|filter| {
+            let (f, t): (Vec<&str>, Vec<&str>) = (
+                filter.split('/').collect(),
+                publish.topic.split('/').collect(),
+            );
+            f.len() == t.len() && f.iter().zip(&t).all(|(f, t)| *f == "+" || f == t)
+        });
+        if matches {
+            let mut delivered = publish.clone();
+            delivered.qos = rumqttc::QoS::AtMostOnce;
+            delivered.pkid = 0;
+            let _ = tx.send(delivered);
+        }
+    }
+}
+
+async fn serve_client(mut stream: tokio::net::TcpStream, state: Arc<Mutex<State>>) {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let index = {
+        let mut state = state.lock().unwrap();
+        state.clients.push((Vec::new(), tx));
+        state.clients.len() - 1
+    };
+
+    let mut buf = BytesMut::new();
+    loop {
+        let mut out = BytesMut::new();
+        tokio::select! {
+            read = stream.read_buf(&mut buf) => {
+                if !matches!(read, Ok(n) if n > 0) {
+                    break;
+                }
+                while let Ok(packet) = v4::read(&mut buf, 1 << 20) {
+                    match packet {
+                        Packet::Connect(_) => {
+                            ConnAck::new(ConnectReturnCode::Success, false).write(&mut out).unwrap();
+                        }
+                        Packet::Subscribe(subscribe) => {
+                            let mut codes = Vec::new();
+                            let mut state = state.lock().unwrap();
+                            for filter in subscribe.filters {
+                                codes.push(SubscribeReasonCode::Success(filter.qos));
+                                state.clients[index].0.push(filter.path);
+                            }
+                            SubAck::new(subscribe.pkid, codes).write(&mut out).unwrap();
+                        }
+                        Packet::Publish(publish) => {
+                            if publish.qos == rumqttc::QoS::AtLeastOnce {
+                                PubAck::new(publish.pkid).write(&mut out).unwrap();
+                            }
+                            state.lock().unwrap().published.push(publish.clone());
+                            route(&state, publish);
+                        }
+                        Packet::PingReq => {
+                            PingResp.write(&mut out).unwrap();
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            Some(publish) = rx.recv() => {
+                publish.write(&mut out).unwrap();
+            }
+        }
+        if stream.write_all(&out).await.is_err() {
+            break;
+        }
+    }
+    state.lock().unwrap().clients[index].0.clear();
+}
+
+fn start_host(mqtt_cfg: MqttConfig) -> Host {
+    let sc = SledConfig::new().temporary(true);
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_tcp_config(None)
+        .with_udp_config(None)
+        .with_mqtt_config(Some(mqtt_cfg));
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+    host
+}
+
+fn mqtt_config(broker: &Broker) -> MqttConfig {
+    MqttConfig::new(broker.addr.ip().to_string(), broker.addr.port())
+}
+
+/// Poll until a condition holds, failing the test if it doesn't within a couple of seconds
+fn wait_for(mut condition: impl FnMut() -> bool) {
+    let start = Instant::now();
+    while !condition() {
+        assert!(start.elapsed() < Duration::from_secs(3), "timed out");
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Wait until the bridge has connected, so that messages set afterwards are exported
+fn wait_for_bridge(broker: &Broker) {
+    wait_for(|| !broker.state.lock().unwrap().clients.is_empty());
+    thread::sleep(Duration::from_millis(100));
+}
+
+#[test]
+fn mqtt_export_json() {
+    let broker = Broker::start();
+    let mut host = start_host(
+        mqtt_config(&broker)
+            .with_type::<Pose>("pose")
+            .with_export(TopicMapping::new("pose", "robot/pose").set_encoding(MqttEncoding::Json))
+            .with_export(
+                TopicMapping::new("count", "robot/count").set_encoding(MqttEncoding::Json),
+            ),
+    );
+    wait_for_bridge(&broker);
+
+    host.insert("pose", Pose { x: 1.0, y: 2.0 }).unwrap();
+    host.insert("count", 3usize).unwrap();
+    wait_for(|| !broker.published("robot/pose").is_empty());
+    let publish = &broker.published("robot/pose")[0];
+    assert_eq!(publish.qos, MqttQoS::AtLeastOnce);
+    let payload: Value = serde_json::from_slice(&publish.payload).unwrap();
+    assert_eq!(payload, json!({"x": 1.0, "y": 2.0}));
+
+    // Topics without a registered type are exported with their raw bytes
+    wait_for(|| !broker.published("robot/count").is_empty());
+    let payload: Value =
+        serde_json::from_slice(&broker.published("robot/count")[0].payload).unwrap();
+    assert_eq!(payload["data"], json!([3]));
+}
+
+#[test]
+fn mqtt_export_postcard_qos() {
+    let broker = Broker::start();
+    let mut host = start_host(
+        mqtt_config(&broker)
+            .with_export(TopicMapping::new("pose", "robot/pose").set_qos(MqttQoS::AtMostOnce)),
+    );
+    wait_for_bridge(&broker);
+
+    for i in 0..3 {
+        host.insert(
+            "pose",
+            Pose {
+                x: i as f32,
+                y: 0.0,
+            },
+        )
+        .unwrap();
+    }
+    wait_for(|| broker.published("robot/pose").len() == 3);
+    for (i, publish) in broker.published("robot/pose").iter().enumerate() {
+        assert_eq!(publish.qos, MqttQoS::AtMostOnce);
+        let generic: GenericMsg = postcard::from_bytes(&publish.payload).unwrap();
+        let msg: Msg<Pose> = generic.try_into().unwrap();
+        assert_eq!(
+            msg.data,
+            Pose {
+                x: i as f32,
+                y: 0.0
+            }
+        );
+    }
+}
+
+#[test]
+fn mqtt_import() {
+    let broker = Broker::start();
+    let host = start_host(
+        mqtt_config(&broker)
+            .with_type::<String>("cmd")
+            .with_import(TopicMapping::new("cmd", "cloud/+/cmd").set_encoding(MqttEncoding::Json))
+            .with_import(TopicMapping::new("pose", "cloud/pose")),
+    );
+    wait_for(|| broker.subscribed("cloud/+/cmd") && broker.subscribed("cloud/pose"));
+
+    broker.inject("cloud/r1/cmd", json!("stop").to_string());
+    wait_for(|| host.get::<String>("cmd").is_ok());
+    assert_eq!(host.get::<String>("cmd").unwrap().data, "stop");
+
+    // Postcard payloads are whole messages, whose topic is replaced with the mapped one
+    let msg = Msg::new(MsgType::Set, "elsewhere", Pose { x: 1.0, y: 2.0 });
+    let bytes = postcard::to_allocvec(&msg.to_generic().unwrap()).unwrap();
+    broker.inject("cloud/pose", bytes);
+    wait_for(|| host.get::<Pose>("pose").is_ok());
+    assert_eq!(
+        host.get::<Pose>("pose").unwrap().data,
+        Pose { x: 1.0, y: 2.0 }
+    );
+
+    // Malformed payloads are dropped
+    broker.inject("cloud/r1/cmd", "not json");
+    broker.inject("cloud/r2/cmd", json!("go").to_string());
+    wait_for(|| host.get::<String>("cmd").unwrap().data == "go");
+}
+
+#[test]
+fn mqtt_bridge_does_not_echo() {
+    let broker = Broker::start();
+    let mapping = TopicMapping::new("shared", "robot/shared").set_encoding(MqttEncoding::Json);
+    let mut host = start_host(
+        mqtt_config(&broker)
+            .with_type::<usize>("shared")
+            .with_export(mapping.clone())
+            .with_import(mapping),
+    );
+    wait_for(|| broker.subscribed("robot/shared"));
+
+    // Imported messages aren't published back to the broker
+    broker.inject("robot/shared", "1");
+    wait_for(|| host.get::<usize>("shared").is_ok());
+    thread::sleep(Duration::from_millis(200));
+    assert!(broker.published("robot/shared").is_empty());
+
+    // Messages set on the Host are published once, even though the broker delivers them back
+    host.insert("shared", 2usize).unwrap();
+    wait_for(|| !broker.published("robot/shared").is_empty());
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(broker.published("robot/shared").len(), 1);
+    assert_eq!(host.db().open_tree("shared").unwrap().len(), 3);
+}
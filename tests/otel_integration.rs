@@ -0,0 +1,143 @@
+#![deny(unused_must_use)]
+#![cfg(feature = "otel")]
+
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use serde_json::Value;
+use tracing::info_span;
+use tracing_subscriber::prelude::*;
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Stand-in for an OpenTelemetry collector, passing on the body of each export request
+fn start_collector() -> (SocketAddr, Receiver<(String, Value)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0u8; content_length];
+            reader.read_exact(&mut body).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            let body = serde_json::from_slice(&body).unwrap();
+            if sender.send((request_line, body)).is_err() {
+                break;
+            }
+        }
+    });
+    (addr, receiver)
+}
+
+/// Spans received by the collector, keyed by name, once all of `names` have arrived
+fn receive_spans(receiver: &Receiver<(String, Value)>, names: &[&str]) -> Vec<Value> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut spans = Vec::new();
+    while !names
+        .iter()
+        .all(|name| spans.iter().any(|span: &Value| span["name"] == *name))
+    {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        let (request_line, body) = receiver.recv_timeout(timeout).unwrap();
+        assert!(request_line.starts_with("POST /v1/traces "));
+        let resource = &body["resourceSpans"][0];
+        assert_eq!(
+            resource["resource"]["attributes"][0],
+            serde_json::json!({"key": "service.name", "value": {"stringValue": "planner"}})
+        );
+        for scope in resource["scopeSpans"].as_array().unwrap() {
+            spans.extend(scope["spans"].as_array().unwrap().iter().cloned());
+        }
+    }
+    spans
+}
+
+fn span<'a>(spans: &'a [Value], name: &str) -> &'a Value {
+    spans.iter().find(|span| span["name"] == name).unwrap()
+}
+
+#[test]
+fn otel_export_spans() {
+    let (collector_addr, receiver) = start_collector();
+    let exporter = OtlpConfig::default()
+        .set_collector_addr(collector_addr)
+        .set_service_name("planner")
+        .set_interval(Duration::from_millis(50));
+    let subscriber = tracing_subscriber::registry().with(TraceLayer::new().with_exporter(exporter));
+    let _default = tracing::subscriber::set_default(subscriber);
+
+    let sc = SledConfig::new().temporary(true);
+    let host = HostConfig::default().with_sled_config(sc).build().unwrap();
+    let node = NodeConfig::<Blocking, InProcess, Pose>::new("pose")
+        .with_config(
+            NetworkConfig::<Blocking, InProcess>::default()
+                .set_host_handle(host.in_process_handle()),
+        )
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    let command = info_span!("command", goal = "dock");
+    let context = TraceContext::of(&command).unwrap();
+    command.in_scope(|| node.publish(Pose::default())).unwrap();
+    drop(command);
+    let msg = node.request().unwrap();
+    msg.span().in_scope(|| ());
+
+    let spans = receive_spans(&receiver, &["command", "meadow.host", "meadow.receive"]);
+    let trace_id: String = context
+        .trace_id
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    let command = span(&spans, "command");
+    assert_eq!(command["traceId"], trace_id);
+    assert_eq!(command["parentSpanId"], "");
+    assert_eq!(
+        command["attributes"][0],
+        serde_json::json!({"key": "goal", "value": {"stringValue": "dock"}})
+    );
+
+    // The Host's span continues the trace, and the receiving Node's span follows on from it
+    let host_span = span(&spans, "meadow.host");
+    assert_eq!(host_span["traceId"], trace_id);
+    let receive = span(&spans, "meadow.receive");
+    assert_eq!(receive["traceId"], trace_id);
+    assert_eq!(receive["parentSpanId"], host_span["spanId"]);
+    let start: u128 = host_span["startTimeUnixNano"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let end: u128 = host_span["endTimeUnixNano"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(start <= end);
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "quic")]
+
+use meadow::host::pki::{self, CertificateAuthority};
+use meadow::host::quic::QuicCertGenConfig;
+use meadow::prelude::*;
+
+use std::fs;
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+
+fn out_dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from("target").join("pki_tests").join(name);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn pki_issue_and_validate() {
+    let dir = out_dir("issue");
+    let ca = CertificateAuthority::generate("test-ca", Some(365)).unwrap();
+    ca.write(dir.join("ca.pem"), dir.join("ca_key.pem"))
+        .unwrap();
+
+    let host_cfg = QuicCertGenConfig::default()
+        .with_dns_names(vec!["meadow-host".into()])
+        .with_ip_address(Ipv4Addr::new(10, 0, 0, 1))
+        .with_common_name("host")
+        .with_validity_days(30)
+        .with_cert_path(dir.join("host.pem"))
+        .with_key_path(dir.join("host_key.pem"));
+    ca.issue(&host_cfg).unwrap();
+
+    // The chain holds the issued certificate followed by the CA certificate
+    let certs = pki::inspect(dir.join("host.pem")).unwrap();
+    assert_eq!(certs.len(), 2);
+    assert_eq!(certs[0].name, Some("host".to_string()));
+    assert_eq!(certs[0].dns_names, vec!["meadow-host".to_string()]);
+    assert_eq!(certs[0].ip_addresses, vec![Ipv4Addr::new(10, 0, 0, 1)]);
+    assert!(!certs[0].is_ca);
+    assert!(certs[1].is_ca);
+    assert!(certs[0].expires_in() <= chrono::Duration::days(30));
+
+    let ca_path = Some(dir.join("ca.pem"));
+    pki::validate(dir.join("host.pem"), ca_path.clone(), Some("meadow-host")).unwrap();
+    pki::validate(dir.join("host.pem"), ca_path.clone(), None).unwrap();
+    assert!(pki::validate(dir.join("host.pem"), ca_path, Some("localhost")).is_err());
+
+    // Certificates from another authority aren't trusted
+    let other = CertificateAuthority::generate("other-ca", None).unwrap();
+    other
+        .write(dir.join("other.pem"), dir.join("other_key.pem"))
+        .unwrap();
+    assert!(pki::validate(dir.join("host.pem"), Some(dir.join("other.pem")), None).is_err());
+}
+
+#[test]
+fn pki_load_existing_authority() {
+    let dir = out_dir("load");
+    CertificateAuthority::generate("loaded-ca", None)
+        .unwrap()
+        .write(dir.join("ca.pem"), dir.join("ca_key.pem"))
+        .unwrap();
+
+    let ca = CertificateAuthority::load(dir.join("ca.pem"), dir.join("ca_key.pem")).unwrap();
+    assert_eq!(
+        ca.cert_pem(),
+        fs::read_to_string(dir.join("ca.pem")).unwrap()
+    );
+
+    let node_cfg = QuicCertGenConfig::default()
+        .with_dns_names(vec!["node-a".into()])
+        .with_common_name("node-a")
+        .with_cert_path(dir.join("node-a.pem"))
+        .with_key_path(dir.join("node-a_key.pem"));
+    ca.issue(&node_cfg).unwrap();
+
+    let info = pki::validate(dir.join("node-a.pem"), Some(dir.join("ca.pem")), None).unwrap();
+    assert_eq!(info.name, Some("node-a".to_string()));
+
+    // Only certificate authorities can be loaded to issue certificates
+    assert!(matches!(
+        CertificateAuthority::load(dir.join("node-a.pem"), dir.join("node-a_key.pem")),
+        Err(Error::Quic(_))
+    ));
+}
+
+#[test]
+fn pki_names_certificate_without_common_name_by_dns_name() {
+    let dir = out_dir("unnamed");
+    let ca = CertificateAuthority::generate("unnamed-ca", None).unwrap();
+    ca.write(dir.join("ca.pem"), dir.join("ca_key.pem"))
+        .unwrap();
+
+    let node_cfg = QuicCertGenConfig::default()
+        .with_dns_names(vec!["node-b".into()])
+        .with_cert_path(dir.join("node-b.pem"))
+        .with_key_path(dir.join("node-b_key.pem"));
+    ca.issue(&node_cfg).unwrap();
+
+    let info = pki::validate(dir.join("node-b.pem"), Some(dir.join("ca.pem")), None).unwrap();
+    assert_eq!(info.name, Some("node-b".to_string()));
+    assert!(!info.subject.contains("rcgen"));
+}
@@ -21,7 +21,7 @@ static INIT: Once = Once::new();
 #[cfg(feature = "quic")]
 pub fn initialize() {
     INIT.call_once(|| {
-        generate_certs(QuicCertGenConfig::default());
+        generate_certs(QuicCertGenConfig::default()).unwrap();
     });
 }
 
@@ -31,7 +31,7 @@ type N = Quic;
 #[cfg(feature = "quic")]
 #[test]
 fn integrate_host_and_single_node_quic() {
-    generate_certs(QuicCertGenConfig::default());
+    generate_certs(QuicCertGenConfig::default()).unwrap();
     let sc = SledConfig::new().temporary(true);
     let mut host = HostConfig::default()
         .with_sled_config(sc)
@@ -64,7 +64,7 @@ fn integrate_host_and_single_node_quic() {
 #[cfg(feature = "quic")]
 #[test]
 fn request_non_existent_topic_quic() {
-    generate_certs(QuicCertGenConfig::default());
+    generate_certs(QuicCertGenConfig::default()).unwrap();
 
     let sc = SledConfig::new().temporary(true);
     let mut host = HostConfig::default()
@@ -91,7 +91,7 @@ fn request_non_existent_topic_quic() {
 #[cfg(feature = "quic")]
 #[test]
 fn node_send_options_quic() {
-    generate_certs(QuicCertGenConfig::default());
+    generate_certs(QuicCertGenConfig::default()).unwrap();
 
     let sc = SledConfig::new().temporary(true);
     let mut host = HostConfig::default()
@@ -129,7 +129,7 @@ fn node_send_options_quic() {
 #[cfg(feature = "quic")]
 #[test]
 fn subscription_usize_quic() {
-    generate_certs(QuicCertGenConfig::default());
+    generate_certs(QuicCertGenConfig::default()).unwrap();
 
     let sc = SledConfig::new().temporary(true);
     let mut host = HostConfig::default()
@@ -167,7 +167,7 @@ fn subscription_usize_quic() {
 #[test]
 #[should_panic]
 fn no_subscribed_value_quic() {
-    generate_certs(QuicCertGenConfig::default());
+    generate_certs(QuicCertGenConfig::default()).unwrap();
 
     let sc = SledConfig::new().temporary(true);
     let mut host = HostConfig::default()
@@ -191,7 +191,7 @@ fn no_subscribed_value_quic() {
 #[cfg(feature = "quic")]
 #[test]
 fn topics_list_quic() {
-    generate_certs(QuicCertGenConfig::default());
+    generate_certs(QuicCertGenConfig::default()).unwrap();
 
     let sc = SledConfig::new().temporary(true);
     let mut host = HostConfig::default()
@@ -264,7 +264,6 @@ fn quic_back_nth_operation() {
         y: (n - back) as f32,
     };
     // We use "back + 1" because we're zero-indexed
-    let b = node.request_nth_back(back - 1).unwrap().data;
     assert_eq!(node.request_nth_back(back - 1).unwrap().data, pose);
 }
 
@@ -298,8 +297,56 @@ fn quic_back_nth_operation_fallible() {
         y: (n - back) as f32,
     };
     // We use "back + 1" because we're zero-indexed
-    let b = node.request_nth_back(back - 1).unwrap().data;
     assert_eq!(node.request_nth_back(back - 1).unwrap().data, pose);
     println!("Asking for a value we know doesn't exist");
-    let result = node.request_nth_back(10).unwrap();
+    node.request_nth_back(10).unwrap();
+}
+
+#[cfg(feature = "quic")]
+#[test]
+fn quic_custom_server_name() {
+    use std::net::Ipv4Addr;
+
+    let cert_cfg = QuicCertGenConfig::default()
+        .with_dns_names(vec!["meadow-host".into()])
+        .with_ip_address(Ipv4Addr::LOCALHOST)
+        .with_cert_path("target/server_name_cert.pem")
+        .with_key_path("target/server_name_key.pem")
+        .with_validity_days(30);
+    generate_certs(cert_cfg.clone()).unwrap();
+
+    let quic_cfg = QuicConfig {
+        cert_path: cert_cfg.cert_pem_path.clone(),
+        key_path: cert_cfg.priv_key_pem_path.clone(),
+        ..QuicConfig::default()
+    };
+    let sc = SledConfig::new().temporary(true);
+    let mut host = HostConfig::default()
+        .with_sled_config(sc)
+        .with_udp_config(None)
+        .with_quic_config(Some(quic_cfg))
+        .build()
+        .unwrap();
+    host.start().unwrap();
+
+    let network_cfg = NetworkConfig::<Blocking, N>::default()
+        .set_cert_path(Some(cert_cfg.cert_pem_path.clone()))
+        .set_server_name("meadow-host");
+    let node = NodeConfig::<Blocking, N, usize>::new("server_name")
+        .with_config(network_cfg.clone())
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    node.publish(1).unwrap();
+    assert_eq!(node.request().unwrap().data, 1);
+
+    // The certificate isn't valid for the default "localhost" server name
+    let result = NodeConfig::<Blocking, N, usize>::new("server_name")
+        .with_config(network_cfg.set_server_name("localhost"))
+        .build()
+        .unwrap()
+        .activate()
+        .and_then(|node| node.request());
+    assert!(result.is_err());
 }
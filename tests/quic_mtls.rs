@@ -0,0 +1,141 @@
+#![deny(unused_must_use)]
+#![cfg(feature = "quic")]
+
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use rcgen::{BasicConstraints, Certificate, CertificateParams, DnType, IsCa};
+use std::fs;
+use std::path::PathBuf;
+
+type N = Quic;
+
+/// Certificate and private key paths for a single mutual TLS identity
+struct Identity {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+}
+
+fn generate_ca(common_name: &str) -> Certificate {
+    let mut params = CertificateParams::new(vec![]);
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params
+        .distinguished_name
+        .push(DnType::CommonName, common_name);
+    Certificate::from_params(params).unwrap()
+}
+
+/// Issue a certificate signed by `ca`, writing it (followed by the CA certificate) and its key to disk
+fn issue(ca: &Certificate, name: &str, sans: Vec<String>, file_stem: &str) -> Identity {
+    let mut params = CertificateParams::new(sans);
+    params.distinguished_name.push(DnType::CommonName, name);
+    let cert = Certificate::from_params(params).unwrap();
+
+    let chain = format!(
+        "{}{}",
+        cert.serialize_pem_with_signer(ca).unwrap(),
+        ca.serialize_pem().unwrap()
+    );
+    let cert_path = PathBuf::from(format!("target/{}_cert.pem", file_stem));
+    let key_path = PathBuf::from(format!("target/{}_key.pem", file_stem));
+    fs::write(&cert_path, chain).unwrap();
+    fs::write(&key_path, cert.serialize_private_key_pem()).unwrap();
+    Identity {
+        cert_path,
+        key_path,
+    }
+}
+
+fn start_host(ca: &Certificate) -> Host {
+    let ca_path = PathBuf::from("target/mtls_ca.pem");
+    fs::write(&ca_path, ca.serialize_pem().unwrap()).unwrap();
+    let host_id = issue(ca, "host", vec!["localhost".into()], "mtls_host");
+
+    let quic_cfg = QuicConfig {
+        cert_path: host_id.cert_path,
+        key_path: host_id.key_path,
+        ..QuicConfig::default()
+    }
+    .with_client_ca(ca_path);
+
+    let sc = SledConfig::new().temporary(true);
+    let mut host = HostConfig::default()
+        .with_sled_config(sc)
+        .with_udp_config(None)
+        .with_tcp_config(None)
+        .with_quic_config(Some(quic_cfg))
+        .build()
+        .unwrap();
+    host.start().unwrap();
+    host
+}
+
+fn node_config(cert_path: PathBuf, key_path: Option<PathBuf>) -> NodeConfig<Blocking, N, Pose> {
+    let network_cfg = NetworkConfig::<Blocking, N>::default()
+        .set_cert_path(Some(cert_path))
+        .set_key_path(key_path);
+    NodeConfig::new("pose").with_config(network_cfg)
+}
+
+#[test]
+fn quic_mtls_authenticated_node() {
+    let ca = generate_ca("meadow-test-ca");
+    let host = start_host(&ca);
+    let node_id = issue(&ca, "node-a", vec!["node".into()], "mtls_node_a");
+
+    let node = node_config(node_id.cert_path, Some(node_id.key_path))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    let pose = Pose { x: 1.0, y: 2.0 };
+    node.publish(pose.clone()).unwrap();
+    assert_eq!(node.request().unwrap().data, pose);
+
+    // Nodes verified over mutual TLS are named by their certificate
+    assert!(host
+        .connection_names()
+        .unwrap()
+        .contains(&"node-a".to_string()));
+}
+
+#[test]
+fn quic_mtls_rejects_node_without_certificate() {
+    let ca = generate_ca("meadow-test-ca");
+    let _host = start_host(&ca);
+    let node_id = issue(&ca, "node-b", vec!["node".into()], "mtls_node_b");
+
+    let result = node_config(node_id.cert_path, None)
+        .build()
+        .unwrap()
+        .activate()
+        .and_then(|node| node.request());
+    // The Host rejects the handshake, so the first stream fails rather than reporting a missing topic
+    assert!(matches!(result, Err(Error::Quic(_))));
+}
+
+#[test]
+fn quic_mtls_rejects_untrusted_certificate() {
+    let ca = generate_ca("meadow-test-ca");
+    let _host = start_host(&ca);
+
+    // The Node trusts the Host's CA, but its own certificate is issued by another CA
+    let rogue_ca = generate_ca("rogue-ca");
+    let node_id = issue(&rogue_ca, "node-c", vec!["node".into()], "mtls_node_c");
+    let chain = format!(
+        "{}{}",
+        fs::read_to_string(&node_id.cert_path).unwrap(),
+        ca.serialize_pem().unwrap()
+    );
+    fs::write(&node_id.cert_path, chain).unwrap();
+
+    let result = node_config(node_id.cert_path, Some(node_id.key_path))
+        .build()
+        .unwrap()
+        .activate()
+        .and_then(|node| node.request());
+    // The Host rejects the handshake, so the first stream fails rather than reporting a missing topic
+    assert!(matches!(result, Err(Error::Quic(_))));
+}
@@ -0,0 +1,183 @@
+#![cfg(feature = "crypto")]
+#![deny(unused_must_use)]
+
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+fn start_host() -> Host {
+    let sc = SledConfig::new().temporary(true);
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_udp_config(Some(UdpConfig::default("lo")));
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+    host
+}
+
+#[test]
+fn tcp_encrypted_round_trip() {
+    let _host = start_host();
+    let security = SecurityConfig::new().with_key(SecurityConfig::generate_key());
+
+    let node = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_security(security.clone())
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    let pose = Pose { x: 1.0, y: 2.0 };
+    node.publish(pose.clone()).unwrap();
+    assert_eq!(node.request().unwrap().data, pose);
+
+    // The Host only stores the sealed payload, which can't be read without the key
+    let plain = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    assert_ne!(plain.request().map(|msg| msg.data).ok(), Some(pose.clone()));
+
+    let wrong_key = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_security(SecurityConfig::new().with_key(SecurityConfig::generate_key()))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    assert_eq!(wrong_key.request().unwrap_err(), Error::Crypto);
+
+    // Nodes with a key don't accept unencrypted payloads
+    plain.publish(pose).unwrap();
+    assert_eq!(node.request().unwrap_err(), Error::Crypto);
+}
+
+#[test]
+fn latency_not_recorded_for_unopened_msgs() {
+    let _host = start_host();
+    let security = SecurityConfig::new().with_key(SecurityConfig::generate_key());
+
+    let node = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_security(security)
+        .with_latency_tracker(LatencyTracker::new())
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    node.publish(Pose::default()).unwrap();
+
+    let wrong_key = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_security(SecurityConfig::new().with_key(SecurityConfig::generate_key()))
+        .with_latency_tracker(LatencyTracker::new())
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    assert_eq!(wrong_key.request().unwrap_err(), Error::Crypto);
+    assert_eq!(wrong_key.latency(), None);
+
+    assert_eq!(node.request().unwrap().data, Pose::default());
+    assert_eq!(node.latency().unwrap().end_to_end.count(), 1);
+}
+
+#[test]
+fn udp_encrypted_round_trip() {
+    let _host = start_host();
+    let security = SecurityConfig::new().with_key(SecurityConfig::generate_key());
+
+    let node = NodeConfig::<Blocking, Udp, Pose>::new("pose")
+        .with_security(security)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    let pose = Pose { x: 3.0, y: 4.0 };
+    node.publish(pose.clone()).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    assert_eq!(node.request().unwrap().data, pose);
+
+    let wrong_key = NodeConfig::<Blocking, Udp, Pose>::new("pose")
+        .with_security(SecurityConfig::new().with_key(SecurityConfig::generate_key()))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    assert_eq!(wrong_key.request().unwrap_err(), Error::Crypto);
+}
+
+#[test]
+fn tcp_trusted_publishers() {
+    let _host = start_host();
+    let key = SecurityConfig::generate_key();
+    let publisher = SecurityConfig::new()
+        .with_key(key)
+        .with_signing_key(SecurityConfig::generate_key());
+    let impostor = SecurityConfig::new()
+        .with_key(key)
+        .with_signing_key(SecurityConfig::generate_key());
+    let subscriber = SecurityConfig::new()
+        .with_key(key)
+        .with_trusted_publisher(publisher.public_key().unwrap());
+
+    let publisher = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_security(publisher)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    let impostor = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_security(impostor)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    let subscriber = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_security(subscriber)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    let pose = Pose { x: 5.0, y: 6.0 };
+    publisher.publish(pose.clone()).unwrap();
+    assert_eq!(subscriber.request().unwrap().data, pose);
+
+    // Payloads sharing the encryption key are still rejected without a trusted signature
+    impostor.publish(Pose { x: 0.0, y: 0.0 }).unwrap();
+    assert_eq!(subscriber.request().unwrap_err(), Error::UntrustedPublisher);
+}
+
+#[test]
+fn signed_payloads_without_encryption() {
+    let _host = start_host();
+    let publisher = SecurityConfig::new().with_signing_key(SecurityConfig::generate_key());
+    let subscriber = SecurityConfig::new().with_trusted_publisher(publisher.public_key().unwrap());
+
+    let publisher = NodeConfig::<Blocking, Tcp, f32>::new("speed")
+        .with_security(publisher)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    let subscriber = NodeConfig::<Blocking, Tcp, f32>::new("speed")
+        .with_security(subscriber)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    publisher.publish(1.5).unwrap();
+    assert_eq!(subscriber.request().unwrap().data, 1.5);
+
+    let unsigned = NodeConfig::<Blocking, Tcp, f32>::new("speed")
+        .with_security(SecurityConfig::new())
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    unsigned.publish(2.5).unwrap();
+    assert_eq!(subscriber.request().unwrap_err(), Error::UntrustedPublisher);
+}
@@ -0,0 +1,340 @@
+#![deny(unused_must_use)]
+#![cfg(all(feature = "serial", target_os = "linux"))]
+
+use meadow::prelude::*;
+use meadow::serial::{decode_frame, encode_frame};
+mod common;
+use common::Pose;
+
+use std::ffi::CStr;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+type N = Serial;
+
+/// Pseudo-terminal pair, whose slave end stands in for a serial device
+struct Pty {
+    master: File,
+    // Holding the slave open keeps reads on the master from failing before a device is opened
+    _slave: File,
+    path: PathBuf,
+}
+
+fn pty() -> Pty {
+    unsafe {
+        let fd = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY);
+        assert!(fd >= 0);
+        assert_eq!(libc::grantpt(fd), 0);
+        assert_eq!(libc::unlockpt(fd), 0);
+        let mut name = [0 as libc::c_char; 128];
+        assert_eq!(libc::ptsname_r(fd, name.as_mut_ptr(), name.len()), 0);
+        let path = PathBuf::from(CStr::from_ptr(name.as_ptr()).to_str().unwrap());
+        let master = File::from_raw_fd(fd);
+
+        let slave = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NOCTTY)
+            .open(&path)
+            .unwrap();
+        // Relayed bytes must not be echoed or translated before either end opens its device
+        let mut termios: libc::termios = std::mem::zeroed();
+        assert_eq!(libc::tcgetattr(slave.as_raw_fd(), &mut termios), 0);
+        libc::cfmakeraw(&mut termios);
+        assert_eq!(
+            libc::tcsetattr(slave.as_raw_fd(), libc::TCSANOW, &termios),
+            0
+        );
+
+        Pty {
+            master,
+            _slave: slave,
+            path,
+        }
+    }
+}
+
+/// Simulated serial link, relaying bytes between the Host's and the Node's pseudo-terminals
+struct Link {
+    host: Pty,
+    node: Pty,
+}
+
+impl Link {
+    fn new() -> Link {
+        let (host, node) = (pty(), pty());
+        relay(&host.master, &node.master);
+        relay(&node.master, &host.master);
+        Link { host, node }
+    }
+
+    /// Inject bytes into the link as if they had been sent by the Node
+    fn inject_to_host(&self, bytes: &[u8]) {
+        let mut to_host = &self.host.master;
+        to_host.write_all(bytes).unwrap();
+    }
+}
+
+fn relay(from: &File, to: &File) {
+    let (mut from, mut to) = (from.try_clone().unwrap(), to.try_clone().unwrap());
+    thread::spawn(move || {
+        let mut buf = [0u8; 1024];
+        while let Ok(n) = from.read(&mut buf) {
+            if n == 0 || to.write_all(&buf[..n]).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+fn start_host(link: &Link, auth: Option<AuthConfig>) -> Result<Host, Error> {
+    let sc = SledConfig::new().temporary(true);
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_tcp_config(None)
+        .with_udp_config(None)
+        .with_auth_config(auth)
+        .with_serial_config(Some(SerialConfig::new(&link.host.path)));
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build()?;
+    host.start()?;
+    Ok(host)
+}
+
+fn node_config<T: Message>(topic: &str, link: &Link) -> NodeConfig<Blocking, N, T> {
+    NodeConfig::new(topic).with_config(
+        NetworkConfig::<Blocking, N>::default()
+            .set_serial_config(SerialConfig::new(&link.node.path).set_baud_rate(115_200)),
+    )
+}
+
+#[test]
+fn serial_frame_checksum() {
+    let payload = [1u8, 0, 2, 3];
+    let mut frame = encode_frame(&payload).unwrap();
+    // Frames are delimited by the only zero byte they contain
+    assert_eq!(frame.iter().filter(|b| **b == 0).count(), 1);
+    assert_eq!(*frame.last().unwrap(), 0);
+    assert_eq!(decode_frame(&mut frame.clone()).unwrap(), payload);
+
+    // COBS overhead and payload length precede the payload's first byte
+    frame[2] = 9;
+    assert_eq!(decode_frame(&mut frame).unwrap_err(), Error::CorruptFrame);
+}
+
+#[test]
+fn integrate_host_and_single_node_serial() {
+    let link = Link::new();
+    let host = start_host(&link, None).unwrap();
+
+    let node = node_config::<Pose>("pose", &link)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    for i in 0..5 {
+        let pose = Pose {
+            x: i as f32,
+            y: i as f32,
+        };
+
+        node.publish(pose.clone()).unwrap();
+        let result = node.request().unwrap();
+        assert_eq!(pose, result.data);
+        assert_eq!(host.get::<Pose>("pose").unwrap().data, pose);
+    }
+}
+
+#[test]
+fn request_non_existent_topic_serial() {
+    let link = Link::new();
+    let _host = start_host(&link, None).unwrap();
+
+    let node = node_config::<Pose>("doesnt_exist", &link)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    assert_eq!(
+        node.request().unwrap_err(),
+        Error::NonExistentTopic("doesnt_exist".into())
+    );
+}
+
+#[test]
+fn missing_serial_config() {
+    let result = NodeConfig::<Blocking, N, Pose>::new("pose")
+        .build()
+        .unwrap()
+        .activate();
+    assert!(result.is_err());
+}
+
+#[test]
+fn subscription_usize_serial() {
+    let link = Link::new();
+    let mut host = start_host(&link, None).unwrap();
+
+    // Create a subscription node with a query rate of 100 Hz
+    let reader = node_config::<usize>("subscription", &link)
+        .build()
+        .unwrap()
+        .subscribe(Duration::from_millis(10))
+        .unwrap();
+
+    for i in 0..5 {
+        let test_value = i as usize;
+        host.insert("subscription", test_value).unwrap();
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(reader.get_subscribed_data().unwrap().data, test_value);
+    }
+}
+
+#[test]
+fn serial_back_nth_operation() {
+    let link = Link::new();
+    let _host = start_host(&link, None).unwrap();
+
+    let node = node_config::<Pose>("pose", &link)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    let n = 5;
+    for i in 0..n {
+        let pose = Pose {
+            x: i as f32,
+            y: i as f32,
+        };
+        node.publish(pose.clone()).unwrap();
+        assert_eq!(node.request_nth_back(0).unwrap().data, pose);
+    }
+    let back = 3;
+    let pose = Pose {
+        x: (n - back) as f32,
+        y: (n - back) as f32,
+    };
+    assert_eq!(node.request_nth_back(back - 1).unwrap().data, pose);
+    assert_eq!(node.request_nth_back(10).unwrap_err(), Error::NoNthValue);
+}
+
+#[test]
+fn topics_list_serial() {
+    let link = Link::new();
+    let mut host = start_host(&link, None).unwrap();
+    host.insert("a", 0usize).unwrap();
+
+    let b = node_config::<usize>("b", &link)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    b.publish(1).unwrap();
+
+    assert_eq!(b.topics().unwrap().data, vec!["a", "b"]);
+}
+
+#[test]
+fn nonblocking_serial() {
+    let link = Link::new();
+    let _host = start_host(&link, None).unwrap();
+
+    // The Node owns a runtime of its own, so it must be dropped outside of this one
+    let node = NodeConfig::<Nonblocking, N, Pose>::new("pose")
+        .with_config(
+            NetworkConfig::<Nonblocking, N>::default()
+                .set_serial_config(SerialConfig::new(&link.node.path)),
+        )
+        .build()
+        .unwrap();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _node = runtime.block_on(async {
+        let node = node.activate().await.unwrap();
+
+        let pose = Pose { x: 1.0, y: 2.0 };
+        node.publish(pose.clone()).await.unwrap();
+        assert_eq!(node.request().await.unwrap().data, pose);
+        assert_eq!(node.request_nth_back(0).await.unwrap().data, pose);
+        node
+    });
+}
+
+#[test]
+fn serial_line_noise_dropped() {
+    let link = Link::new();
+    let host = start_host(&link, None).unwrap();
+
+    // Garbage, a truncated frame, and a frame that fails its checksum
+    let mut corrupt = encode_frame(&[1, 2, 3]).unwrap();
+    corrupt[2] = 9;
+    link.inject_to_host(&[0x55, 0xaa, 0x00]);
+    link.inject_to_host(&corrupt);
+
+    let node = node_config::<f32>("speed", &link)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    node.publish(1.5).unwrap();
+    assert_eq!(host.get::<f32>("speed").unwrap().data, 1.5);
+}
+
+#[test]
+fn serial_node_restart() {
+    let link = Link::new();
+    let _host = start_host(&link, None).unwrap();
+
+    let node = node_config::<f32>("speed", &link)
+        .build()
+        .unwrap()
+        .subscribe(Duration::from_millis(10))
+        .unwrap();
+    thread::sleep(Duration::from_millis(50));
+    drop(node);
+
+    // The Host is still pushing the subscription when the next Node takes over the link
+    let node = node_config::<f32>("speed", &link)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    node.publish(2.5).unwrap();
+    assert_eq!(node.request().unwrap().data, 2.5);
+}
+
+#[test]
+fn serial_access_control() {
+    let auth = AuthConfig::new()
+        .with_identity(Identity::new("radio", "radio-token").allow("*", &[Permission::Request]));
+    let link = Link::new();
+    let mut host = start_host(&link, Some(auth)).unwrap();
+    host.insert("pose", Pose { x: 1.0, y: 2.0 }).unwrap();
+
+    let result = node_config::<Pose>("pose", &link)
+        .with_credentials(Credentials::new("radio", "wrong-token"))
+        .build()
+        .unwrap()
+        .activate();
+    assert_eq!(result.unwrap_err(), Error::Unauthenticated);
+
+    let node = node_config::<Pose>("pose", &link)
+        .with_credentials(Credentials::new("radio", "radio-token"))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    assert_eq!(node.request().unwrap().data, Pose { x: 1.0, y: 2.0 });
+    assert!(node
+        .publish(Pose::default())
+        .unwrap_err()
+        .is_access_denied());
+}
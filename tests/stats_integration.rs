@@ -0,0 +1,173 @@
+#![deny(unused_must_use)]
+
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use std::thread;
+use std::time::Duration;
+
+fn start_host(auth: Option<AuthConfig>) -> Host {
+    let sc = SledConfig::new().temporary(true);
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_udp_config(Some(UdpConfig::default("lo")))
+        .with_auth_config(auth);
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+    host
+}
+
+#[test]
+fn stats_tcp() {
+    let _host = start_host(None);
+
+    let node = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    for i in 0..10 {
+        node.publish(Pose {
+            x: i as f32,
+            y: i as f32,
+        })
+        .unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let stats = node.stats().unwrap();
+    assert_eq!(stats.msg_type, MsgType::Stats);
+    let stats = stats.data;
+    assert_eq!(stats.topic, "pose");
+    assert_eq!(stats.count, 10);
+    assert!(stats.rate > 10.0 && stats.rate < 110.0, "{}", stats.rate);
+    assert!(stats.bytes_per_sec > 0.0);
+    assert!(stats.last_publisher.unwrap().starts_with("127.0.0.1:"));
+    assert!(stats.last_update.is_some());
+    // Poses are always two `f32`s
+    let sizes = stats.payload_sizes;
+    assert_eq!((sizes.p50, sizes.p90, sizes.p99, sizes.max), (8, 8, 8, 8));
+    assert_eq!(stats.subscribers, 0);
+}
+
+#[test]
+fn stats_count_subscribers_tcp() {
+    let _host = start_host(None);
+
+    let node = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    node.publish(Pose::default()).unwrap();
+
+    let subscriber = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .build()
+        .unwrap()
+        .subscribe(Duration::from_millis(10))
+        .unwrap();
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(node.stats().unwrap().data.subscribers, 1);
+
+    // The subscription ends once the Host fails to write to the closed connection
+    drop(subscriber);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(node.stats().unwrap().data.subscribers, 0);
+}
+
+#[test]
+fn stats_udp() {
+    let _host = start_host(None);
+
+    let node = NodeConfig::<Blocking, Udp, usize>::new("count")
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    for i in 0..3 {
+        node.publish(i).unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let stats = node.stats().unwrap().data;
+    assert_eq!(stats.count, 3);
+    assert!(stats.last_publisher.unwrap().starts_with("127.0.0.1:"));
+}
+
+#[test]
+fn stats_non_existent_topic() {
+    let _host = start_host(None);
+
+    let node = NodeConfig::<Blocking, Tcp, Pose>::new("doesnt_exist")
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    assert_eq!(
+        node.stats().unwrap_err(),
+        Error::NonExistentTopic("doesnt_exist".into())
+    );
+}
+
+#[test]
+fn stats_in_process_and_host() {
+    let sc = SledConfig::new().temporary(true);
+    let mut host = HostConfig::default().with_sled_config(sc).build().unwrap();
+
+    let node = NodeConfig::<Blocking, InProcess, Pose>::new("pose")
+        .with_config(
+            NetworkConfig::<Blocking, InProcess>::default()
+                .set_host_handle(host.in_process_handle()),
+        )
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    node.publish(Pose::default()).unwrap();
+    host.insert("count", 0usize).unwrap();
+    host.insert("count", 1usize).unwrap();
+
+    let stats = node.stats().unwrap().data;
+    assert_eq!(stats.count, 1);
+    assert_eq!(stats.last_publisher.as_deref(), Some("in-process"));
+
+    let stats = host.topic_stats();
+    let topics: Vec<_> = stats.iter().map(|stats| stats.topic.as_str()).collect();
+    assert_eq!(topics, vec!["count", "pose"]);
+    assert_eq!(stats[0].count, 2);
+    assert_eq!(stats[0].last_publisher.as_deref(), Some("host"));
+}
+
+#[test]
+fn stats_require_request_permission() {
+    let auth = AuthConfig::new()
+        .with_identity(Identity::new("planner", "planner-token").allow_all("pose"))
+        .with_identity(Identity::new("motor", "motor-token").allow("pose", &[Permission::Publish]));
+    let _host = start_host(Some(auth));
+
+    let planner = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_credentials(Credentials::new("planner", "planner-token"))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    let motor = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_credentials(Credentials::new("motor", "motor-token"))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    // Authenticated Nodes are named by their identity
+    motor.publish(Pose::default()).unwrap();
+    let stats = planner.stats().unwrap().data;
+    assert_eq!(stats.last_publisher.as_deref(), Some("motor"));
+
+    assert!(matches!(
+        motor.stats().unwrap_err(),
+        Error::PermissionDenied { .. }
+    ));
+}
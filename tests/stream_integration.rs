@@ -0,0 +1,269 @@
+#![deny(unused_must_use)]
+
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+type N = Stream;
+
+/// Streams to user-supplied Hosts don't need any of the Host's listeners
+fn build_host(auth: Option<AuthConfig>) -> Host {
+    let sc = SledConfig::new().temporary(true);
+    HostConfig::default()
+        .with_sled_config(sc)
+        .with_auth_config(auth)
+        .build()
+        .unwrap()
+}
+
+/// Connector handing out the Node ends of `n` in-memory streams served by the Host
+fn duplex_connector(host: &Host, n: usize) -> StreamConnector {
+    let mut streams = Vec::new();
+    for i in 0..n {
+        let (node_end, host_end) = tokio::io::duplex(64 * 1024);
+        host.serve_stream(format!("duplex-{}", i), host_end);
+        streams.push(node_end);
+    }
+    let streams = Arc::new(Mutex::new(streams));
+    StreamConnector::new(move || {
+        let stream = streams.lock().unwrap().pop();
+        async move { stream.ok_or_else(|| std::io::Error::from(std::io::ErrorKind::NotConnected)) }
+    })
+}
+
+fn node_config<T: Message>(topic: &str, connector: StreamConnector) -> NodeConfig<Blocking, N, T> {
+    NodeConfig::new(topic)
+        .with_config(NetworkConfig::<Blocking, N>::default().set_connector(connector))
+}
+
+#[test]
+fn integrate_host_and_single_node_stream() {
+    let host = build_host(None);
+
+    let node = node_config::<Pose>("pose", duplex_connector(&host, 1))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    for i in 0..5 {
+        let pose = Pose {
+            x: i as f32,
+            y: i as f32,
+        };
+
+        node.publish(pose.clone()).unwrap();
+        let result = node.request().unwrap();
+        assert_eq!(pose, result.data);
+        assert_eq!(host.get::<Pose>("pose").unwrap().data, pose);
+    }
+}
+
+#[test]
+fn request_non_existent_topic_stream() {
+    let host = build_host(None);
+
+    let node = node_config::<Pose>("doesnt_exist", duplex_connector(&host, 1))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    assert_eq!(
+        node.request().unwrap_err(),
+        Error::NonExistentTopic("doesnt_exist".into())
+    );
+}
+
+#[test]
+fn missing_connector_stream() {
+    let result = NodeConfig::<Blocking, N, Pose>::new("pose")
+        .build()
+        .unwrap()
+        .activate();
+    assert!(result.is_err());
+}
+
+#[test]
+fn subscription_usize_stream() {
+    let host = build_host(None);
+
+    let writer = node_config::<usize>("subscription", duplex_connector(&host, 2))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    // Create a subscription node with a query rate of 100 Hz, over the second stream
+    let reader = writer
+        .config()
+        .clone()
+        .build()
+        .unwrap()
+        .subscribe(Duration::from_millis(10))
+        .unwrap();
+
+    for i in 0..5 {
+        let test_value = i as usize;
+        writer.publish(test_value).unwrap();
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(reader.get_subscribed_data().unwrap().data, test_value);
+    }
+}
+
+#[test]
+fn stream_back_nth_operation() {
+    let host = build_host(None);
+
+    let node = node_config::<Pose>("pose", duplex_connector(&host, 1))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    let n = 5;
+    for i in 0..n {
+        let pose = Pose {
+            x: i as f32,
+            y: i as f32,
+        };
+        node.publish(pose.clone()).unwrap();
+        assert_eq!(node.request_nth_back(0).unwrap().data, pose);
+    }
+    let back = 3;
+    let pose = Pose {
+        x: (n - back) as f32,
+        y: (n - back) as f32,
+    };
+    assert_eq!(node.request_nth_back(back - 1).unwrap().data, pose);
+    assert_eq!(node.request_nth_back(10).unwrap_err(), Error::NoNthValue);
+}
+
+#[test]
+fn topics_list_stream() {
+    let host = build_host(None);
+    let connector = duplex_connector(&host, 2);
+
+    let a = node_config::<usize>("a", connector.clone())
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    let b = node_config::<usize>("b", connector)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    a.publish(0).unwrap();
+    b.publish(1).unwrap();
+
+    assert_eq!(a.topics().unwrap().data, vec!["a", "b"]);
+}
+
+#[test]
+fn nonblocking_stream() {
+    let host = build_host(None);
+
+    // The Node owns a runtime of its own, so it must be dropped outside of this one
+    let node = NodeConfig::<Nonblocking, N, Pose>::new("pose")
+        .with_config(
+            NetworkConfig::<Nonblocking, N>::default().set_connector(duplex_connector(&host, 1)),
+        )
+        .build()
+        .unwrap();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _node = runtime.block_on(async {
+        let node = node.activate().await.unwrap();
+
+        let pose = Pose { x: 1.0, y: 2.0 };
+        node.publish(pose.clone()).await.unwrap();
+        assert_eq!(node.request().await.unwrap().data, pose);
+        assert_eq!(node.request_nth_back(0).await.unwrap().data, pose);
+        node
+    });
+}
+
+#[test]
+fn stream_once_connector() {
+    let host = build_host(None);
+    let (node_end, host_end) = tokio::io::duplex(64 * 1024);
+    host.serve_stream("once", host_end);
+    let connector = StreamConnector::once(node_end);
+
+    let node = node_config::<f32>("speed", connector.clone())
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    node.publish(1.5).unwrap();
+    assert_eq!(node.request().unwrap().data, 1.5);
+
+    // The stream has already been handed to the first Node
+    let result = node_config::<f32>("speed", connector)
+        .build()
+        .unwrap()
+        .activate();
+    assert!(result.is_err());
+}
+
+#[test]
+fn stream_over_tcp_forward() {
+    let host = build_host(None);
+
+    // Stands in for a port-forward, whose far end the Host is handed once it's accepted
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let connector = StreamConnector::new(move || tokio::net::TcpStream::connect(addr));
+
+    thread::scope(|s| {
+        s.spawn(|| {
+            let (socket, peer) = listener.accept().unwrap();
+            socket.set_nonblocking(true).unwrap();
+            let _guard = host.runtime().enter();
+            let socket = tokio::net::TcpStream::from_std(socket).unwrap();
+            host.serve_stream(peer.to_string(), socket);
+        });
+
+        let node = node_config::<Pose>("pose", connector)
+            .build()
+            .unwrap()
+            .activate()
+            .unwrap();
+        let pose = Pose { x: 1.0, y: 2.0 };
+        node.publish(pose.clone()).unwrap();
+        assert_eq!(node.request().unwrap().data, pose);
+    });
+}
+
+#[test]
+fn stream_access_control() {
+    let auth = AuthConfig::new().with_identity(
+        Identity::new("observer", "observer-token").allow("*", &[Permission::Request]),
+    );
+    let mut host = build_host(Some(auth));
+    host.insert("pose", Pose { x: 1.0, y: 2.0 }).unwrap();
+    let connector = duplex_connector(&host, 2);
+
+    let result = node_config::<Pose>("pose", connector.clone())
+        .with_credentials(Credentials::new("observer", "wrong-token"))
+        .build()
+        .unwrap()
+        .activate();
+    assert_eq!(result.unwrap_err(), Error::Unauthenticated);
+
+    let node = node_config::<Pose>("pose", connector)
+        .with_credentials(Credentials::new("observer", "observer-token"))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    assert_eq!(node.request().unwrap().data, Pose { x: 1.0, y: 2.0 });
+    assert!(node
+        .publish(Pose::default())
+        .unwrap_err()
+        .is_access_denied());
+}
@@ -2,8 +2,7 @@
 
 use meadow::prelude::*;
 mod common;
-use common::Pose;
-use rand::thread_rng;
+use common::{any_port, tcp_host_config, Pose};
 
 use std::thread;
 use std::time::Duration;
@@ -233,7 +232,6 @@ fn tcp_back_nth_operation() {
         y: (n - back) as f32,
     };
     // We use "back + 1" because we're zero-indexed
-    let b = node.request_nth_back(back - 1).unwrap().data;
     assert_eq!(node.request_nth_back(back - 1).unwrap().data, pose);
 }
 
@@ -266,8 +264,63 @@ fn tcp_back_nth_operation_fallible() {
         y: (n - back) as f32,
     };
     // We use "back + 1" because we're zero-indexed
-    let b = node.request_nth_back(back - 1).unwrap().data;
     assert_eq!(node.request_nth_back(back - 1).unwrap().data, pose);
     println!("Asking for a value we know doesn't exist");
-    let result = node.request_nth_back(10).unwrap();
+    node.request_nth_back(10).unwrap();
+}
+
+/// Start a Host listening for TCP Nodes on a port assigned by the OS
+fn start_local_host() -> (Host, std::net::SocketAddr) {
+    let mut host = tcp_host_config(Some(any_port())).build().unwrap();
+    host.start().unwrap();
+    let addr = host.local_addr(Transport::Tcp).unwrap();
+    (host, addr)
+}
+
+#[test]
+fn stalled_handshake_doesnt_block_tcp() {
+    let (_host, addr) = start_local_host();
+
+    // A client which never sends its name
+    let _stalled = std::net::TcpStream::connect(addr).unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    let node = NodeConfig::<Blocking, N, Pose>::new("pose")
+        .with_config(NetworkConfig::<Blocking, N>::default().set_host_addr(addr))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    node.publish(Pose::default()).unwrap();
+    assert_eq!(node.request().unwrap().data, Pose::default());
+}
+
+#[test]
+fn malformed_msg_drops_connection_tcp() {
+    use std::io::{Read, Write};
+
+    let (_host, addr) = start_local_host();
+
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    let auth = GenericMsg::auth("malformed", None).unwrap();
+    stream.write_all(&auth.as_bytes().unwrap()).unwrap();
+    let mut buf = [0u8; 1024];
+    assert!(stream.read(&mut buf).unwrap() > 0);
+
+    // The Host answers with an error and closes the connection
+    stream.write_all(&[0xff; 16]).unwrap();
+    let n = stream.read(&mut buf).unwrap();
+    let reply: GenericMsg = postcard::from_bytes(&buf[..n]).unwrap();
+    assert!(matches!(reply.msg_type, MsgType::Error(_)));
+    assert_eq!(stream.read(&mut buf).unwrap(), 0);
+
+    // Other Nodes are still served
+    let node = NodeConfig::<Blocking, N, Pose>::new("pose")
+        .with_config(NetworkConfig::<Blocking, N>::default().set_host_addr(addr))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    node.publish(Pose::default()).unwrap();
+    assert_eq!(node.request().unwrap().data, Pose::default());
 }
@@ -0,0 +1,150 @@
+#![deny(unused_must_use)]
+#![cfg(feature = "tls")]
+
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use meadow::host::quic::generate_certs;
+use meadow::host::quic::QuicCertGenConfig;
+
+use std::thread;
+use std::time::Duration;
+
+type N = Tls;
+
+fn start_host() -> Host {
+    generate_certs(QuicCertGenConfig::default()).unwrap();
+    let sc = SledConfig::new().temporary(true);
+    let mut host = HostConfig::default()
+        .with_sled_config(sc)
+        .with_tcp_config(None)
+        .with_udp_config(None)
+        .with_quic_config(None)
+        .with_tls_config(Some(TlsConfig::default()))
+        .build()
+        .unwrap();
+    host.start().unwrap();
+    host
+}
+
+#[test]
+fn integrate_host_and_single_node_tls() {
+    let _host = start_host();
+
+    let node: Node<Blocking, N, Idle, Pose> = NodeConfig::new("pose").build().unwrap();
+    let node = node.activate().unwrap();
+
+    for i in 0..5 {
+        let pose = Pose {
+            x: i as f32,
+            y: i as f32,
+        };
+
+        node.publish(pose.clone()).unwrap();
+        let result = node.request().unwrap();
+        assert_eq!(pose, result.data);
+    }
+}
+
+#[test]
+fn request_non_existent_topic_tls() {
+    let _host = start_host();
+
+    let node: Node<Blocking, N, Idle, Pose> = NodeConfig::new("doesnt_exist").build().unwrap();
+    let node = node.activate().unwrap();
+
+    assert_eq!(
+        node.request().unwrap_err(),
+        Error::NonExistentTopic("doesnt_exist".into())
+    );
+}
+
+#[test]
+fn subscription_usize_tls() {
+    let _host = start_host();
+
+    let writer = NodeConfig::<Blocking, N, usize>::new("subscription")
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    // Create a subscription node with a query rate of 100 Hz
+    let reader = writer
+        .config()
+        .clone()
+        .build()
+        .unwrap()
+        .subscribe(Duration::from_millis(10))
+        .unwrap();
+
+    for i in 0..5 {
+        let test_value = i as usize;
+        writer.publish(test_value).unwrap();
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(reader.get_subscribed_data().unwrap().data, test_value);
+    }
+}
+
+#[test]
+fn tls_back_nth_operation() {
+    let _host = start_host();
+
+    let node: Node<Blocking, N, Idle, Pose> = NodeConfig::new("pose").build().unwrap();
+    let node = node.activate().unwrap();
+
+    let n = 5;
+    for i in 0..n {
+        let pose = Pose {
+            x: i as f32,
+            y: i as f32,
+        };
+        node.publish(pose.clone()).unwrap();
+        assert_eq!(node.request_nth_back(0).unwrap().data, pose);
+    }
+    let back = 3;
+    let pose = Pose {
+        x: (n - back) as f32,
+        y: (n - back) as f32,
+    };
+    assert_eq!(node.request_nth_back(back - 1).unwrap().data, pose);
+    assert_eq!(node.request_nth_back(10).unwrap_err(), Error::NoNthValue);
+}
+
+#[test]
+fn topics_list_tls() {
+    let _host = start_host();
+
+    let a = NodeConfig::<Blocking, N, usize>::new("a")
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    let b = NodeConfig::<Blocking, N, usize>::new("b")
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    a.publish(0).unwrap();
+    b.publish(1).unwrap();
+
+    assert_eq!(a.topics().unwrap().data, vec!["a", "b"]);
+}
+
+#[test]
+fn stalled_handshake_doesnt_block_tls() {
+    let host = start_host();
+
+    // A client which never starts its TLS session
+    let _stalled = std::net::TcpStream::connect(host.local_addr(Transport::Tls).unwrap()).unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    let node = NodeConfig::<Blocking, N, Pose>::new("pose")
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    node.publish(Pose::default()).unwrap();
+    assert_eq!(node.request().unwrap().data, Pose::default());
+}
@@ -0,0 +1,203 @@
+#![deny(unused_must_use)]
+
+use meadow::host::TcpConfig;
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use tracing::{info_span, Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::registry::LookupSpan;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn start_host() -> Host {
+    let sc = SledConfig::new().temporary(true);
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_tcp_config(Some(
+            TcpConfig::default("lo").set_bind_addr(([127, 0, 0, 1], 0)),
+        ))
+        .with_udp_config(None);
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build().unwrap();
+    host.start().unwrap();
+    host
+}
+
+fn tcp_node(host: &Host) -> Node<Blocking, Tcp, Active, Pose> {
+    let addr = host.local_addr(Transport::Tcp).unwrap();
+    NodeConfig::new("pose")
+        .with_config(NetworkConfig::<Blocking, Tcp>::default().set_host_addr(addr))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap()
+}
+
+#[test]
+fn traceparent_round_trip() {
+    let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+    let context: TraceContext = traceparent.parse().unwrap();
+    assert_eq!(context.trace_id[..2], [0x4b, 0xf9]);
+    assert_eq!(context.span_id[7], 0xb7);
+    assert!(context.is_sampled());
+    assert_eq!(context.to_string(), traceparent);
+
+    // Later versions may append fields
+    let context: TraceContext = "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00-extra"
+        .parse()
+        .unwrap();
+    assert!(!context.is_sampled());
+
+    for invalid in [
+        "",
+        "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+        "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+        "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+    ] {
+        assert_eq!(
+            invalid.parse::<TraceContext>(),
+            Err(Error::InvalidTraceContext(invalid.into()))
+        );
+    }
+
+    let root = TraceContext::new_root();
+    let child = root.child();
+    assert_eq!(child.trace_id, root.trace_id);
+    assert_ne!(child.span_id, root.span_id);
+}
+
+#[test]
+fn trace_propagates_to_subscribers_tcp() {
+    let subscriber = tracing_subscriber::registry().with(TraceLayer::new());
+    let _default = tracing::subscriber::set_default(subscriber);
+    let host = start_host();
+    let publisher = tcp_node(&host);
+    let requester = tcp_node(&host);
+
+    let span = info_span!("command");
+    let context = TraceContext::of(&span).unwrap();
+    span.in_scope(|| publisher.publish(Pose::default()))
+        .unwrap();
+
+    // The Host doesn't track spans here, so it passes the publisher's context on as it is
+    let msg = requester.request().unwrap();
+    let trace = msg.trace.unwrap();
+    assert_eq!(trace.trace_id, context.trace_id);
+    assert_ne!(trace.span_id, context.span_id);
+
+    // Handling the message continues its trace
+    let handled = TraceContext::of(&msg.span()).unwrap();
+    assert_eq!(handled.trace_id, context.trace_id);
+    assert_ne!(handled.span_id, trace.span_id);
+
+    let subscription = NodeConfig::<Blocking, Tcp, Pose>::new("pose")
+        .with_config(
+            NetworkConfig::<Blocking, Tcp>::default()
+                .set_host_addr(host.local_addr(Transport::Tcp).unwrap()),
+        )
+        .build()
+        .unwrap()
+        .subscribe(Duration::from_millis(10))
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+    let msg = subscription.get_subscribed_data().unwrap();
+    assert_eq!(msg.trace.unwrap().trace_id, context.trace_id);
+}
+
+#[test]
+fn trace_continues_on_host_in_process() {
+    let subscriber = tracing_subscriber::registry().with(TraceLayer::new());
+    let _default = tracing::subscriber::set_default(subscriber);
+    let sc = SledConfig::new().temporary(true);
+    let host = HostConfig::default().with_sled_config(sc).build().unwrap();
+    let node = NodeConfig::<Blocking, InProcess, Pose>::new("pose")
+        .with_config(
+            NetworkConfig::<Blocking, InProcess>::default()
+                .set_host_handle(host.in_process_handle()),
+        )
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    let mut msg = Msg::new(MsgType::Set, "pose", Pose::default());
+    let context = TraceContext::new_root();
+    msg.trace = Some(context);
+    node.publish_msg(msg).unwrap();
+
+    // In-process Nodes are served on the calling thread, so the Host's span is traced too and
+    // takes over as the message's context
+    let trace = node.request().unwrap().trace.unwrap();
+    assert_eq!(trace.trace_id, context.trace_id);
+    assert_ne!(trace.span_id, context.span_id);
+}
+
+#[test]
+fn untraced_msgs_carry_no_context() {
+    let host = start_host();
+    let node = tcp_node(&host);
+
+    // Without a `TraceLayer`, spans have no trace context
+    info_span!("command").in_scope(|| node.publish(Pose::default()).unwrap());
+    let msg = node.request().unwrap();
+    assert_eq!(msg.trace, None);
+    assert!(msg.span().is_none());
+}
+
+/// Records the name of the span each event was emitted in
+#[derive(Clone, Default)]
+struct EventSpans(Arc<Mutex<Vec<Option<String>>>>);
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for EventSpans {
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let span = ctx.event_span(event).map(|span| span.name().to_string());
+        self.0.lock().unwrap().push(span);
+    }
+}
+
+#[test]
+fn host_handles_message_within_its_span() {
+    let events = EventSpans::default();
+    let subscriber = tracing_subscriber::registry()
+        .with(TraceLayer::new())
+        .with(events.clone());
+    let _default = tracing::subscriber::set_default(subscriber);
+
+    let auth = AuthConfig::new().with_identity(
+        Identity::new("observer", "observer-token").allow("*", &[Permission::Request]),
+    );
+    let sc = SledConfig::new().temporary(true);
+    let host = HostConfig::default()
+        .with_sled_config(sc)
+        .with_auth_config(Some(auth))
+        .build()
+        .unwrap();
+    let node = NodeConfig::<Blocking, InProcess, Pose>::new("pose")
+        .with_config(
+            NetworkConfig::<Blocking, InProcess>::default()
+                .set_host_handle(host.in_process_handle()),
+        )
+        .with_credentials(Credentials::new("observer", "observer-token"))
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    // The Host warns of the rejected publish while handling it
+    let mut msg = Msg::new(MsgType::Set, "pose", Pose::default());
+    msg.trace = Some(TraceContext::new_root());
+    assert!(node.publish_msg(msg).is_err());
+    assert!(events
+        .0
+        .lock()
+        .unwrap()
+        .contains(&Some("meadow.host".to_string())));
+}
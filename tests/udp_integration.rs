@@ -198,7 +198,6 @@ fn udp_back_nth_operation() {
         y: (n - back) as f32,
     };
     // We use "back + 1" because we're zero-indexed
-    let b = node.request_nth_back(back - 1).unwrap().data;
     assert_eq!(node.request_nth_back(back - 1).unwrap().data, pose);
 }
 
@@ -231,8 +230,7 @@ fn udp_back_nth_operation_fallible() {
         y: (n - back) as f32,
     };
     // We use "back + 1" because we're zero-indexed
-    let b = node.request_nth_back(back - 1).unwrap().data;
     assert_eq!(node.request_nth_back(back - 1).unwrap().data, pose);
     println!("Asking for a value we know doesn't exist");
-    let result = node.request_nth_back(10).unwrap();
+    node.request_nth_back(10).unwrap();
 }
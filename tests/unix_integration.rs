@@ -0,0 +1,252 @@
+#![deny(unused_must_use)]
+#![cfg(unix)]
+
+use meadow::prelude::*;
+mod common;
+use common::Pose;
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+type N = Unix;
+
+fn socket_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("meadow-{}.sock", name))
+}
+
+fn start_host(unix_cfg: UnixConfig) -> Result<Host, Error> {
+    let sc = SledConfig::new().temporary(true);
+    let config = HostConfig::default()
+        .with_sled_config(sc)
+        .with_tcp_config(None)
+        .with_udp_config(None)
+        .with_unix_config(Some(unix_cfg));
+    #[cfg(feature = "quic")]
+    let config = config.with_quic_config(None);
+    let mut host = config.build()?;
+    host.start()?;
+    Ok(host)
+}
+
+fn node_config<T: Message>(topic: &str, path: &PathBuf) -> NodeConfig<Blocking, N, T> {
+    NodeConfig::new(topic)
+        .with_config(NetworkConfig::<Blocking, N>::default().set_socket_path(path))
+}
+
+#[test]
+fn integrate_host_and_single_node_unix() {
+    let path = socket_path("single");
+    let _host = start_host(UnixConfig::new(&path)).unwrap();
+
+    let node = node_config::<Pose>("pose", &path)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    for i in 0..5 {
+        let pose = Pose {
+            x: i as f32,
+            y: i as f32,
+        };
+
+        node.publish(pose.clone()).unwrap();
+        let result = node.request().unwrap();
+        assert_eq!(pose, result.data);
+    }
+}
+
+#[test]
+fn request_non_existent_topic_unix() {
+    let path = socket_path("non-existent");
+    let _host = start_host(UnixConfig::new(&path)).unwrap();
+
+    let node = node_config::<Pose>("doesnt_exist", &path)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    assert_eq!(
+        node.request().unwrap_err(),
+        Error::NonExistentTopic("doesnt_exist".into())
+    );
+}
+
+#[test]
+fn subscription_usize_unix() {
+    let path = socket_path("subscription");
+    let _host = start_host(UnixConfig::new(&path)).unwrap();
+
+    let writer = node_config::<usize>("subscription", &path)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    // Create a subscription node with a query rate of 100 Hz
+    let reader = writer
+        .config()
+        .clone()
+        .build()
+        .unwrap()
+        .subscribe(Duration::from_millis(10))
+        .unwrap();
+
+    for i in 0..5 {
+        let test_value = i as usize;
+        writer.publish(test_value).unwrap();
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(reader.get_subscribed_data().unwrap().data, test_value);
+    }
+}
+
+#[test]
+fn unix_back_nth_operation() {
+    let path = socket_path("nth");
+    let _host = start_host(UnixConfig::new(&path)).unwrap();
+
+    let node = node_config::<Pose>("pose", &path)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+
+    let n = 5;
+    for i in 0..n {
+        let pose = Pose {
+            x: i as f32,
+            y: i as f32,
+        };
+        node.publish(pose.clone()).unwrap();
+        assert_eq!(node.request_nth_back(0).unwrap().data, pose);
+    }
+    let back = 3;
+    let pose = Pose {
+        x: (n - back) as f32,
+        y: (n - back) as f32,
+    };
+    assert_eq!(node.request_nth_back(back - 1).unwrap().data, pose);
+    assert_eq!(node.request_nth_back(10).unwrap_err(), Error::NoNthValue);
+}
+
+#[test]
+fn topics_list_unix() {
+    let path = socket_path("topics");
+    let _host = start_host(UnixConfig::new(&path)).unwrap();
+
+    let a = node_config::<usize>("a", &path)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    let b = node_config::<usize>("b", &path)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    a.publish(0).unwrap();
+    b.publish(1).unwrap();
+
+    assert_eq!(a.topics().unwrap().data, vec!["a", "b"]);
+}
+
+#[test]
+fn nonblocking_unix() {
+    let path = socket_path("nonblocking");
+    let _host = start_host(UnixConfig::new(&path)).unwrap();
+
+    // The Node owns a runtime of its own, so it must be dropped outside of this one
+    let node = NodeConfig::<Nonblocking, N, Pose>::new("pose")
+        .with_config(NetworkConfig::<Nonblocking, N>::default().set_socket_path(&path))
+        .build()
+        .unwrap();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _node = runtime.block_on(async {
+        let node = node.activate().await.unwrap();
+
+        let pose = Pose { x: 1.0, y: 2.0 };
+        node.publish(pose.clone()).await.unwrap();
+        assert_eq!(node.request().await.unwrap().data, pose);
+        assert_eq!(node.request_nth_back(0).await.unwrap().data, pose);
+        node
+    });
+}
+
+#[test]
+fn unix_socket_permissions() {
+    let path = socket_path("permissions");
+    let host = start_host(UnixConfig::new(&path).set_mode(0o600)).unwrap();
+
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    // The directory the socket was bound in is removed once it's moved into place
+    let staged = std::fs::read_dir(std::env::temp_dir())
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .any(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(".meadow-permissions.sock.")
+        });
+    assert!(!staged);
+
+    // The socket file is removed once the Host is dropped
+    drop(host);
+    assert!(!path.exists());
+}
+
+#[test]
+fn unix_socket_in_use() {
+    let path = socket_path("in-use");
+    let host = start_host(UnixConfig::new(&path)).unwrap();
+
+    // A second Host can't take over the socket of a running Host
+    assert!(start_host(UnixConfig::new(&path)).is_err());
+    let node = node_config::<f32>("speed", &path)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    node.publish(1.5).unwrap();
+    assert_eq!(host.get::<f32>("speed").unwrap().data, 1.5);
+}
+
+#[test]
+fn unix_stale_socket_replaced() {
+    let path = socket_path("stale");
+    let _ = std::fs::remove_file(&path);
+    // Dropping a listener leaves its socket file behind, as a crashed Host would
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+
+    let _host = start_host(UnixConfig::new(&path)).unwrap();
+    let node = node_config::<f32>("speed", &path)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    node.publish(2.5).unwrap();
+    assert_eq!(node.request().unwrap().data, 2.5);
+}
+
+#[test]
+fn stalled_handshake_doesnt_block_unix() {
+    let path = socket_path("stalled");
+    let _host = start_host(UnixConfig::new(&path)).unwrap();
+
+    // A client which never sends its name
+    let _stalled = std::os::unix::net::UnixStream::connect(&path).unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    let node = node_config::<f32>("speed", &path)
+        .build()
+        .unwrap()
+        .activate()
+        .unwrap();
+    node.publish(1.5).unwrap();
+    assert_eq!(node.request().unwrap().data, 1.5);
+}
@@ -89,6 +89,66 @@ fn main() {
     )
     .run()
     .expect("Please fix failing tests in output above.");
+    cmd!(
+        sh,
+        "cargo test --workspace --features=tls tls -- --nocapture --test-threads=1"
+    )
+    .run()
+    .expect("Please fix failing tests in output above.");
+    cmd!(
+        sh,
+        "cargo test --workspace --features=crypto --test security -- --nocapture --test-threads=1"
+    )
+    .run()
+    .expect("Please fix failing tests in output above.");
+    cmd!(
+        sh,
+        "cargo test --workspace --features=serial --test serial_integration -- --nocapture --test-threads=1"
+    )
+    .run()
+    .expect("Please fix failing tests in output above.");
+    cmd!(
+        sh,
+        "cargo test --workspace --features=foxglove --test foxglove_integration -- --nocapture --test-threads=1"
+    )
+    .run()
+    .expect("Please fix failing tests in output above.");
+    cmd!(
+        sh,
+        "cargo test --workspace --features=http --test http_integration -- --nocapture --test-threads=1"
+    )
+    .run()
+    .expect("Please fix failing tests in output above.");
+    cmd!(
+        sh,
+        "cargo test --workspace --features=metrics --test metrics_integration -- --nocapture --test-threads=1"
+    )
+    .run()
+    .expect("Please fix failing tests in output above.");
+    cmd!(
+        sh,
+        "cargo test --workspace --features=otel --test otel_integration -- --nocapture --test-threads=1"
+    )
+    .run()
+    .expect("Please fix failing tests in output above.");
+    cmd!(
+        sh,
+        "cargo test --workspace --features=mqtt --test mqtt_integration -- --nocapture --test-threads=1"
+    )
+    .run()
+    .expect("Please fix failing tests in output above.");
+    cmd!(
+        sh,
+        "cargo test --workspace --features=mcap --test mcap_integration -- --nocapture --test-threads=1"
+    )
+    .run()
+    .expect("Please fix failing tests in output above.");
+    cmd!(
+        sh,
+        "cargo test --workspace --features=cli --test cli --bin meadow -- --nocapture --test-threads=1"
+    )
+    .run()
+    .expect("Please fix failing tests in output above.");
 
     // Run doc tests: these are ignored by `cargo test`
     cmd!(sh, "cargo test --doc --workspace")